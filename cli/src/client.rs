@@ -3,10 +3,29 @@
 use anyhow::{anyhow, Result};
 use futures::{SinkExt, StreamExt};
 use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use uuid::Uuid;
 
-use crate::messages::{BlockStatus, ClientMessage, Journal, ServerMessage};
+use crate::messages::{
+    ApprovalRequest, Block, BlockStatus, ClientMessage, Journal, ServerMessage, WorkItem,
+};
+
+/// How a streamed response ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamOutcome {
+    Complete,
+    Error,
+    Cancelled,
+}
+
+/// Where to reach the server and how to authenticate
+pub struct Server {
+    pub url: String,
+    /// Sent as an `Authorization: Bearer` header when set
+    pub token: Option<String>,
+}
 
 /// WebSocket client for Outer.sh
 pub struct OuterClient {
@@ -18,10 +37,17 @@ pub struct OuterClient {
 
 impl OuterClient {
     /// Connect to an Outer.sh server
-    pub async fn connect(url: &str) -> Result<Self> {
-        tracing::info!("Connecting to {}", url);
-
-        let (ws_stream, _) = connect_async(url).await?;
+    pub async fn connect(server: &Server) -> Result<Self> {
+        tracing::info!("Connecting to {}", server.url);
+
+        let mut request = server.url.as_str().into_client_request()?;
+        if let Some(token) = &server.token {
+            request.headers_mut().insert(
+                "Authorization",
+                HeaderValue::from_str(&format!("Bearer {}", token))?,
+            );
+        }
+        let (ws_stream, _) = connect_async(request).await?;
         let (mut write, mut read) = ws_stream.split();
 
         // Channel for outgoing messages
@@ -91,6 +117,29 @@ impl OuterClient {
         self.rx.recv().await
     }
 
+    /// Send `msg` and wait for the reply that `reply` picks out
+    ///
+    /// Messages `reply` returns `None` for (broadcasts, replies to other
+    /// requests) are skipped. A server error fails the request.
+    async fn request<T>(
+        &mut self,
+        msg: ClientMessage,
+        mut reply: impl FnMut(ServerMessage) -> Option<T>,
+    ) -> Result<T> {
+        self.send(msg).await?;
+
+        while let Some(msg) = self.recv().await {
+            if let ServerMessage::Error { message } = msg {
+                return Err(anyhow!("Server error: {}", message));
+            }
+            if let Some(value) = reply(msg) {
+                return Ok(value);
+            }
+        }
+
+        Err(anyhow!("Connection closed"))
+    }
+
     /// Try to receive a message without blocking
     pub fn try_recv(&mut self) -> Option<ServerMessage> {
         self.rx.try_recv().ok()
@@ -98,45 +147,25 @@ impl OuterClient {
 
     /// Create a new journal
     pub async fn create_journal(&mut self, title: Option<String>) -> Result<Journal> {
-        self.send(ClientMessage::CreateJournal { title }).await?;
-
-        while let Some(msg) = self.recv().await {
-            match msg {
-                ServerMessage::JournalCreated { journal_id, title } => {
-                    return Ok(Journal {
-                        id: journal_id,
-                        title,
-                        created_at: chrono::Utc::now(),
-                        updated_at: chrono::Utc::now(),
-                    });
-                }
-                ServerMessage::Error { message } => {
-                    return Err(anyhow!("Server error: {}", message));
-                }
-                _ => continue,
-            }
-        }
-
-        Err(anyhow!("Connection closed"))
+        self.request(ClientMessage::CreateJournal { title }, |msg| match msg {
+            ServerMessage::JournalCreated { journal_id, title } => Some(Journal {
+                id: journal_id,
+                title,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            }),
+            _ => None,
+        })
+        .await
     }
 
     /// List all journals
     pub async fn list_journals(&mut self) -> Result<Vec<Journal>> {
-        self.send(ClientMessage::ListJournals).await?;
-
-        while let Some(msg) = self.recv().await {
-            match msg {
-                ServerMessage::Journals { journals } => {
-                    return Ok(journals);
-                }
-                ServerMessage::Error { message } => {
-                    return Err(anyhow!("Server error: {}", message));
-                }
-                _ => continue,
-            }
-        }
-
-        Err(anyhow!("Connection closed"))
+        self.request(ClientMessage::ListJournals, |msg| match msg {
+            ServerMessage::Journals { journals } => Some(journals),
+            _ => None,
+        })
+        .await
     }
 
     /// Get a journal with its blocks
@@ -144,21 +173,13 @@ impl OuterClient {
         &mut self,
         journal_id: Uuid,
     ) -> Result<(Journal, Vec<crate::messages::Block>)> {
-        self.send(ClientMessage::GetJournal { journal_id }).await?;
-
-        while let Some(msg) = self.recv().await {
-            match msg {
-                ServerMessage::Journal { journal, blocks } => {
-                    return Ok((journal, blocks));
-                }
-                ServerMessage::Error { message } => {
-                    return Err(anyhow!("Server error: {}", message));
-                }
-                _ => continue,
+        self.request(ClientMessage::GetJournal { journal_id }, |msg| match msg {
+            ServerMessage::Journal { journal, blocks } if journal.id == journal_id => {
+                Some((journal, blocks))
             }
-        }
-
-        Err(anyhow!("Connection closed"))
+            _ => None,
+        })
+        .await
     }
 
     /// Subscribe to a journal for real-time updates
@@ -171,39 +192,33 @@ impl OuterClient {
         crate::messages::Participant,
         Vec<crate::messages::Participant>,
     )> {
-        self.send(ClientMessage::Subscribe {
+        let msg = ClientMessage::Subscribe {
             journal_id,
             name,
             kind,
+            known_participants: Vec::new(),
+        };
+        self.request(msg, |msg| match msg {
+            ServerMessage::Subscribed {
+                journal_id: subscribed,
+                participant,
+                participants,
+                ..
+            } if subscribed == journal_id => Some((participant, participants)),
+            _ => None,
         })
-        .await?;
-
-        while let Some(msg) = self.recv().await {
-            match msg {
-                ServerMessage::Subscribed {
-                    participant,
-                    participants,
-                    ..
-                } => {
-                    return Ok((participant, participants));
-                }
-                ServerMessage::Error { message } => {
-                    return Err(anyhow!("Server error: {}", message));
-                }
-                _ => continue,
-            }
-        }
-
-        Err(anyhow!("Connection closed"))
+        .await
     }
 
     /// Submit a message and stream the response
+    ///
+    /// Returns the assistant block's ID and how its stream ended.
     pub async fn submit_and_stream<F>(
         &mut self,
         journal_id: Uuid,
         content: String,
         mut callback: F,
-    ) -> Result<()>
+    ) -> Result<(Uuid, StreamOutcome)>
     where
         F: FnMut(ServerMessage),
     {
@@ -227,10 +242,19 @@ impl OuterClient {
                 }
                 ServerMessage::BlockStatusChanged { block_id, status } => {
                     callback(msg.clone());
-                    if Some(*block_id) == assistant_block_id
-                        && (*status == BlockStatus::Complete || *status == BlockStatus::Error)
-                    {
-                        return Ok(());
+                    if let Some(id) = assistant_block_id.filter(|id| id == block_id) {
+                        match status {
+                            BlockStatus::Complete => return Ok((id, StreamOutcome::Complete)),
+                            BlockStatus::Error => return Ok((id, StreamOutcome::Error)),
+                            _ => {}
+                        }
+                    }
+                }
+                ServerMessage::BlockCancelled { block_id } => {
+                    let cancelled = assistant_block_id.filter(|id| id == block_id);
+                    callback(msg);
+                    if let Some(id) = cancelled {
+                        return Ok((id, StreamOutcome::Cancelled));
                     }
                 }
                 ServerMessage::Error { message } => {
@@ -245,6 +269,15 @@ impl OuterClient {
         Err(anyhow!("Connection closed"))
     }
 
+    /// Fetch a single block
+    pub async fn get_block(&mut self, block_id: Uuid) -> Result<Block> {
+        self.request(ClientMessage::GetBlock { block_id }, |msg| match msg {
+            ServerMessage::Block { block } if block.id == block_id => Some(block),
+            _ => None,
+        })
+        .await
+    }
+
     /// Fork a block and stream the response
     pub async fn fork_and_stream<F>(&mut self, block_id: Uuid, mut callback: F) -> Result<()>
     where
@@ -305,6 +338,160 @@ impl OuterClient {
         self.send(ClientMessage::Cancel { block_id }).await
     }
 
+    /// Register with the delegation system for a journal
+    ///
+    /// Returns the participant ID other participants delegate to.
+    pub async fn register_participant(
+        &mut self,
+        journal_id: Uuid,
+        name: String,
+        kind: Option<String>,
+    ) -> Result<Uuid> {
+        let msg = ClientMessage::RegisterParticipant {
+            journal_id,
+            name,
+            kind,
+        };
+        self.request(msg, |msg| match msg {
+            ServerMessage::ParticipantRegistered { participant_id, .. } => Some(participant_id),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Delegate work to another registered participant
+    ///
+    /// The connection must have registered for `journal_id` first.
+    ///
+    /// ```no_run
+    /// # async fn example(client: &mut OuterClient, journal_id: Uuid, reviewer_id: Uuid) -> anyhow::Result<()> {
+    /// client.register_participant(journal_id, "Alice".into(), None).await?;
+    /// let item = client
+    ///     .delegate(journal_id, "Review the draft".into(), reviewer_id, true)
+    ///     .await?;
+    /// println!("Delegated {}", item.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delegate(
+        &mut self,
+        journal_id: Uuid,
+        description: String,
+        assignee_id: Uuid,
+        requires_approval: bool,
+    ) -> Result<WorkItem> {
+        let msg = ClientMessage::Delegate {
+            journal_id,
+            description: description.clone(),
+            assignee_id,
+            requires_approval,
+        };
+        self.request(msg, |msg| match msg {
+            ServerMessage::WorkDelegated { work_item }
+                if work_item.journal_id == journal_id
+                    && work_item.assignee_id == assignee_id
+                    && work_item.description == description =>
+            {
+                Some(work_item)
+            }
+            _ => None,
+        })
+        .await
+    }
+
+    /// Accept work delegated to this participant
+    // No subcommand drives the assignee side yet; these are for code using the client
+    #[allow(dead_code)]
+    pub async fn accept_work(&mut self, work_item_id: Uuid) -> Result<()> {
+        self.request(
+            ClientMessage::AcceptWork { work_item_id },
+            |msg| match msg {
+                ServerMessage::WorkAccepted {
+                    work_item_id: accepted,
+                    ..
+                } if accepted == work_item_id => Some(()),
+                _ => None,
+            },
+        )
+        .await
+    }
+
+    /// Submit the result of accepted work
+    ///
+    /// Returns the pending approval request if the work needs sign-off, or
+    /// `None` if submitting completed it.
+    ///
+    /// ```no_run
+    /// # async fn example(client: &mut OuterClient, work_item_id: Uuid) -> anyhow::Result<()> {
+    /// client.accept_work(work_item_id).await?;
+    /// if let Some(approval) = client.submit_work(work_item_id, "Done".into()).await? {
+    ///     println!("Waiting on {}", approval.approver_id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(dead_code)]
+    pub async fn submit_work(
+        &mut self,
+        work_item_id: Uuid,
+        result: String,
+    ) -> Result<Option<ApprovalRequest>> {
+        let msg = ClientMessage::SubmitWork {
+            work_item_id,
+            result,
+        };
+        self.request(msg, |msg| match msg {
+            ServerMessage::ApprovalRequested { approval, .. }
+                if approval.work_item_id == work_item_id =>
+            {
+                Some(Some(approval))
+            }
+            ServerMessage::WorkApproved {
+                work_item_id: approved,
+                ..
+            } if approved == work_item_id => Some(None),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Approve submitted work
+    ///
+    /// Returns true once the work item is approved, or false if it still
+    /// waits on other approvers.
+    ///
+    /// ```no_run
+    /// # async fn example(client: &mut OuterClient, approval: ApprovalRequest) -> anyhow::Result<()> {
+    /// if client.approve(&approval, Some("Looks good".into())).await? {
+    ///     println!("{} is approved", approval.work_item_id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(dead_code)]
+    pub async fn approve(
+        &mut self,
+        approval: &ApprovalRequest,
+        feedback: Option<String>,
+    ) -> Result<bool> {
+        let msg = ClientMessage::ApproveWork {
+            approval_id: approval.id,
+            feedback,
+        };
+        self.request(msg, |msg| match msg {
+            ServerMessage::WorkApproved { work_item_id, .. }
+                if work_item_id == approval.work_item_id =>
+            {
+                Some(true)
+            }
+            ServerMessage::ApprovalGranted { approval_id, .. } if approval_id == approval.id => {
+                Some(false)
+            }
+            _ => None,
+        })
+        .await
+    }
+
     /// Listen for events until callback returns false
     pub async fn listen<F>(&mut self, mut callback: F) -> Result<()>
     where
@@ -330,6 +517,19 @@ mod tests {
         assert!(json.contains("list_journals"));
     }
 
+    #[test]
+    fn test_delegate_message_serialization() {
+        let msg = ClientMessage::Delegate {
+            journal_id: Uuid::nil(),
+            description: "Review".to_string(),
+            assignee_id: Uuid::nil(),
+            requires_approval: true,
+        };
+        let json: serde_json::Value = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["type"], "delegate");
+        assert_eq!(json["requires_approval"], true);
+    }
+
     #[test]
     fn test_submit_message_serialization() {
         let msg = ClientMessage::Submit {
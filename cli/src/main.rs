@@ -3,8 +3,13 @@
 mod client;
 mod messages;
 mod tui;
+mod watch;
 
-use anyhow::Result;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
 use clap::{Parser, Subcommand};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -17,6 +22,10 @@ struct Cli {
     #[arg(short, long, default_value = "ws://localhost:3000/ws")]
     server: String,
 
+    /// Bearer token to present when the server requires authentication
+    #[arg(long, env = "OUTER_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -50,6 +59,10 @@ enum Commands {
         /// Message content
         #[arg(short, long)]
         message: String,
+
+        /// Write the response to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 
     /// Fork a block to create a new branch
@@ -73,6 +86,36 @@ enum Commands {
         #[arg(long)]
         once: bool,
     },
+
+    /// Delegate work to another participant and print the work item ID
+    Delegate {
+        /// Journal ID
+        #[arg(short, long)]
+        journal: String,
+
+        /// What needs doing
+        #[arg(short, long)]
+        description: String,
+
+        /// Participant ID to assign the work to
+        #[arg(short, long)]
+        assignee: String,
+
+        /// Have the work come back to you for approval
+        #[arg(long)]
+        requires_approval: bool,
+
+        /// Your display name
+        #[arg(short = 'n', long, default_value = "CLI User")]
+        name: String,
+    },
+
+    /// Print a journal's events as they happen, one line each
+    Watch {
+        /// Journal ID to watch
+        #[arg(short, long)]
+        journal: String,
+    },
 }
 
 #[tokio::main]
@@ -87,24 +130,48 @@ async fn main() -> Result<()> {
         .init();
 
     let cli = Cli::parse();
+    let server = client::Server {
+        url: cli.server,
+        token: cli.token,
+    };
 
     match cli.command {
-        Commands::Connect { journal, new, name } => {
-            run_connect(&cli.server, journal, new, &name).await
-        }
-        Commands::List => run_list(&cli.server).await,
-        Commands::Submit { journal, message } => run_submit(&cli.server, &journal, &message).await,
-        Commands::Fork { block } => run_fork(&cli.server, &block).await,
+        Commands::Connect { journal, new, name } => run_connect(&server, journal, new, &name).await,
+        Commands::List => run_list(&server).await,
+        Commands::Submit {
+            journal,
+            message,
+            output,
+        } => run_submit(&server, &journal, &message, output).await,
+        Commands::Fork { block } => run_fork(&server, &block).await,
         Commands::Agent {
             journal,
             name,
             once,
-        } => run_agent(&cli.server, &journal, &name, once).await,
+        } => run_agent(&server, &journal, &name, once).await,
+        Commands::Delegate {
+            journal,
+            description,
+            assignee,
+            requires_approval,
+            name,
+        } => {
+            run_delegate(
+                &server,
+                &journal,
+                description,
+                &assignee,
+                requires_approval,
+                name,
+            )
+            .await
+        }
+        Commands::Watch { journal } => run_watch(&server, &journal).await,
     }
 }
 
 async fn run_connect(
-    server: &str,
+    server: &client::Server,
     journal_id: Option<String>,
     new: bool,
     name: &str,
@@ -133,7 +200,7 @@ async fn run_connect(
     tui::run(client, journal_id).await
 }
 
-async fn run_list(server: &str) -> Result<()> {
+async fn run_list(server: &client::Server) -> Result<()> {
     let mut client = client::OuterClient::connect(server).await?;
     let journals = client.list_journals().await?;
 
@@ -155,32 +222,75 @@ async fn run_list(server: &str) -> Result<()> {
     Ok(())
 }
 
-async fn run_submit(server: &str, journal_id: &str, message: &str) -> Result<()> {
+async fn run_submit(
+    server: &client::Server,
+    journal_id: &str,
+    message: &str,
+    output: Option<PathBuf>,
+) -> Result<()> {
     let mut client = client::OuterClient::connect(server).await?;
     let journal_id: uuid::Uuid = journal_id.parse()?;
 
+    let mut file = output
+        .as_ref()
+        .map(|path| File::create(path).map(BufWriter::new))
+        .transpose()?;
+    let mut write_error = None;
+
     println!("Submitting message to journal {}...", journal_id);
 
     // Submit and stream response
-    client
+    let (block_id, outcome) = client
         .submit_and_stream(journal_id, message.to_string(), |event| match event {
-            messages::ServerMessage::BlockContentDelta { delta, .. } => {
-                print!("{}", delta);
-                std::io::Write::flush(&mut std::io::stdout()).ok();
-            }
-            messages::ServerMessage::BlockStatusChanged { status, .. } => {
-                if status == messages::BlockStatus::Complete {
-                    println!();
+            messages::ServerMessage::BlockContentDelta { delta, .. } => match file.as_mut() {
+                Some(file) => {
+                    if let Err(e) = file.write_all(delta.as_bytes()) {
+                        write_error.get_or_insert(e);
+                    }
                 }
+                None => {
+                    print!("{}", delta);
+                    std::io::stdout().flush().ok();
+                }
+            },
+            messages::ServerMessage::BlockStatusChanged { status, .. }
+                if status == messages::BlockStatus::Complete && file.is_none() =>
+            {
+                println!();
+            }
+            messages::ServerMessage::QueuePosition { position, .. } => {
+                eprintln!("Queued: {} in line", position);
             }
             _ => {}
         })
         .await?;
 
-    Ok(())
+    if let Some(e) = write_error {
+        return Err(e.into());
+    }
+    if let Some(mut file) = file {
+        file.flush()?;
+    }
+
+    // Failed responses exit non-zero so scripts can tell
+    match outcome {
+        client::StreamOutcome::Complete => Ok(()),
+        client::StreamOutcome::Cancelled => bail!("Response {} was cancelled", block_id),
+        client::StreamOutcome::Error => {
+            let detail = client
+                .get_block(block_id)
+                .await
+                .map(|block| block.content)
+                .unwrap_or_default();
+            if detail.is_empty() {
+                bail!("Response {} failed", block_id)
+            }
+            bail!("Response {} failed: {}", block_id, detail)
+        }
+    }
 }
 
-async fn run_fork(server: &str, block_id: &str) -> Result<()> {
+async fn run_fork(server: &client::Server, block_id: &str) -> Result<()> {
     let mut client = client::OuterClient::connect(server).await?;
     let block_id: uuid::Uuid = block_id.parse()?;
 
@@ -207,7 +317,12 @@ async fn run_fork(server: &str, block_id: &str) -> Result<()> {
     Ok(())
 }
 
-async fn run_agent(server: &str, journal_id: &str, name: &str, once: bool) -> Result<()> {
+async fn run_agent(
+    server: &client::Server,
+    journal_id: &str,
+    name: &str,
+    once: bool,
+) -> Result<()> {
     let mut client = client::OuterClient::connect(server).await?;
     let journal_id: uuid::Uuid = journal_id.parse()?;
 
@@ -248,3 +363,44 @@ async fn run_agent(server: &str, journal_id: &str, name: &str, once: bool) -> Re
 
     Ok(())
 }
+
+async fn run_delegate(
+    server: &client::Server,
+    journal_id: &str,
+    description: String,
+    assignee_id: &str,
+    requires_approval: bool,
+    name: String,
+) -> Result<()> {
+    let mut client = client::OuterClient::connect(server).await?;
+    let journal_id: uuid::Uuid = journal_id.parse()?;
+    let assignee_id: uuid::Uuid = assignee_id.parse()?;
+
+    let delegator_id = client.register_participant(journal_id, name, None).await?;
+    tracing::info!("Registered as {}", delegator_id);
+
+    let work_item = client
+        .delegate(journal_id, description, assignee_id, requires_approval)
+        .await?;
+
+    // Just the ID on stdout so scripts can capture it
+    println!("{}", work_item.id);
+    Ok(())
+}
+
+async fn run_watch(server: &client::Server, journal_id: &str) -> Result<()> {
+    let mut client = client::OuterClient::connect(server).await?;
+    let journal_id: uuid::Uuid = journal_id.parse()?;
+
+    // Observers don't appear as users or agents to everyone else
+    client
+        .subscribe(
+            journal_id,
+            "Watcher".to_string(),
+            Some("observer".to_string()),
+        )
+        .await?;
+    eprintln!("Watching journal {} (Ctrl-C to stop)", journal_id);
+
+    watch::run(client, journal_id).await
+}
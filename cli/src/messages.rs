@@ -30,6 +30,8 @@ pub struct Block {
     pub parent_id: Option<Uuid>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub forked_from_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<f64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -40,6 +42,7 @@ pub struct Block {
 pub enum BlockType {
     User,
     Assistant,
+    System,
 }
 
 /// Status of a block
@@ -47,6 +50,7 @@ pub enum BlockType {
 #[serde(rename_all = "snake_case")]
 pub enum BlockStatus {
     Pending,
+    Queued,
     Streaming,
     Complete,
     Error,
@@ -63,6 +67,10 @@ pub struct Participant {
     pub cursor_block_id: Option<Uuid>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cursor_offset: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor_anchor: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor_head: Option<u32>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -70,6 +78,7 @@ pub struct Participant {
 pub enum ParticipantKind {
     User,
     Agent,
+    Observer,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -80,6 +89,46 @@ pub enum ParticipantStatus {
     Thinking,
 }
 
+/// Status of a delegated work item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkItemStatus {
+    Pending,
+    InProgress,
+    Paused,
+    AwaitingApproval,
+    Approved,
+    Rejected,
+    Declined,
+    Cancelled,
+}
+
+/// Work delegated from one participant to another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkItem {
+    pub id: Uuid,
+    pub journal_id: Uuid,
+    pub description: String,
+    pub delegator_id: Uuid,
+    pub assignee_id: Uuid,
+    pub status: WorkItemStatus,
+    pub requires_approval: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A request for an approver to sign off on submitted work
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub id: Uuid,
+    pub work_item_id: Uuid,
+    pub requester_id: Uuid,
+    pub approver_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Messages from client to server
 #[derive(Debug, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -98,6 +147,8 @@ pub enum ClientMessage {
     },
     /// Get a journal with its blocks
     GetJournal { journal_id: Uuid },
+    /// Get a single block
+    GetBlock { block_id: Uuid },
     /// List all journals
     ListJournals,
     /// Fork a block
@@ -120,6 +171,8 @@ pub enum ClientMessage {
         name: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         kind: Option<String>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        known_participants: Vec<Uuid>,
     },
     /// Unsubscribe from a journal
     Unsubscribe { journal_id: Uuid },
@@ -133,6 +186,30 @@ pub enum ClientMessage {
     },
     /// Request presence information
     GetPresence { journal_id: Uuid },
+    /// Register with the delegation system for a journal
+    RegisterParticipant {
+        journal_id: Uuid,
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        kind: Option<String>,
+    },
+    /// Delegate work to another participant
+    Delegate {
+        journal_id: Uuid,
+        description: String,
+        assignee_id: Uuid,
+        requires_approval: bool,
+    },
+    /// Accept delegated work
+    AcceptWork { work_item_id: Uuid },
+    /// Submit completed work (for approval if the item requires it)
+    SubmitWork { work_item_id: Uuid, result: String },
+    /// Approve submitted work
+    ApproveWork {
+        approval_id: Uuid,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        feedback: Option<String>,
+    },
 }
 
 /// Messages from server to client
@@ -141,6 +218,10 @@ pub enum ClientMessage {
 pub enum ServerMessage {
     /// Journal was created
     JournalCreated { journal_id: Uuid, title: String },
+    /// Journal metadata changed
+    JournalUpdated { journal: Journal },
+    /// Journal title changed
+    JournalRenamed { journal_id: Uuid, title: String },
     /// Journal with blocks
     Journal {
         journal: Journal,
@@ -151,9 +232,21 @@ pub enum ServerMessage {
     /// Block was created
     BlockCreated { block: Block },
     /// Block content delta (streaming)
-    BlockContentDelta { block_id: Uuid, delta: String },
+    BlockContentDelta {
+        block_id: Uuid,
+        delta: String,
+        /// Byte offset of `delta` within the block's accumulated content
+        #[serde(default)]
+        offset: usize,
+    },
     /// Block status changed
     BlockStatusChanged { block_id: Uuid, status: BlockStatus },
+    /// Position of a queued block in its journal's submit queue
+    QueuePosition {
+        journal_id: Uuid,
+        block_id: Uuid,
+        position: usize,
+    },
     /// Block was forked
     BlockForked {
         original_block_id: Uuid,
@@ -161,6 +254,8 @@ pub enum ServerMessage {
     },
     /// Block was cancelled
     BlockCancelled { block_id: Uuid },
+    /// A single block
+    Block { block: Block },
     /// Error occurred
     Error { message: String },
     /// Successfully subscribed
@@ -168,6 +263,9 @@ pub enum ServerMessage {
         journal_id: Uuid,
         participant: Participant,
         participants: Vec<Participant>,
+        /// Observers left out of `participants` when the server collapsed them
+        #[serde(default)]
+        observer_count: Option<usize>,
     },
     /// Unsubscribed
     Unsubscribed { journal_id: Uuid },
@@ -187,6 +285,10 @@ pub enum ServerMessage {
         participant_id: Uuid,
         block_id: Option<Uuid>,
         offset: Option<u32>,
+        #[serde(default)]
+        anchor: Option<u32>,
+        #[serde(default)]
+        head: Option<u32>,
     },
     /// Participant status changed
     ParticipantStatusChanged {
@@ -198,6 +300,8 @@ pub enum ServerMessage {
     Presence {
         journal_id: Uuid,
         participants: Vec<Participant>,
+        #[serde(default)]
+        observer_count: Option<usize>,
     },
     /// CRDT update
     CrdtUpdate {
@@ -205,8 +309,46 @@ pub enum ServerMessage {
         source: Option<Uuid>,
         update: String,
     },
+    /// Registered with the delegation system
+    ParticipantRegistered {
+        participant_id: Uuid,
+        name: String,
+        kind: String,
+    },
+    /// Work was delegated
+    WorkDelegated { work_item: WorkItem },
+    /// Work was accepted by its assignee
+    WorkAccepted {
+        work_item_id: Uuid,
+        assignee_id: Uuid,
+    },
+    /// Submitted work is waiting for approval
+    ApprovalRequested {
+        approval: ApprovalRequest,
+        work_item: WorkItem,
+    },
+    /// An approval was recorded; other approvers still need to sign off
+    ApprovalGranted {
+        approval_id: Uuid,
+        work_item_id: Uuid,
+        approver_id: Uuid,
+    },
+    /// Work was approved
+    WorkApproved {
+        work_item_id: Uuid,
+        approver_id: Uuid,
+        feedback: Option<String>,
+    },
     /// Sync state
-    SyncState { journal_id: Uuid, state: String },
+    SyncState {
+        journal_id: Uuid,
+        state: String,
+        /// Chunk index when the state is split across messages
+        #[serde(default)]
+        seq: Option<u32>,
+        #[serde(default, rename = "final")]
+        last: Option<bool>,
+    },
 }
 
 #[cfg(test)]
@@ -261,12 +403,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_server_message_work_delegated_deserialization() {
+        let json = r#"{
+            "type": "work_delegated",
+            "work_item": {
+                "id": "00000000-0000-0000-0000-000000000001",
+                "journal_id": "00000000-0000-0000-0000-000000000000",
+                "description": "Review the draft",
+                "delegator_id": "00000000-0000-0000-0000-000000000002",
+                "assignee_id": "00000000-0000-0000-0000-000000000003",
+                "status": "pending",
+                "priority": "normal",
+                "requires_approval": true,
+                "required_approvals": 1,
+                "auto_execute": false,
+                "created_at": "2024-01-01T00:00:00Z",
+                "updated_at": "2024-01-01T00:00:00Z"
+            }
+        }"#;
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ServerMessage::WorkDelegated { work_item } => {
+                assert_eq!(work_item.description, "Review the draft");
+                assert_eq!(work_item.status, WorkItemStatus::Pending);
+                assert!(work_item.requires_approval);
+            }
+            _ => panic!("Expected WorkDelegated"),
+        }
+    }
+
     #[test]
     fn test_block_status_values() {
         assert_eq!(
             serde_json::to_string(&BlockStatus::Pending).unwrap(),
             "\"pending\""
         );
+        assert_eq!(
+            serde_json::to_string(&BlockStatus::Queued).unwrap(),
+            "\"queued\""
+        );
         assert_eq!(
             serde_json::to_string(&BlockStatus::Streaming).unwrap(),
             "\"streaming\""
@@ -79,8 +79,20 @@ impl App {
                 }
                 self.blocks.push(block);
             }
-            ServerMessage::BlockContentDelta { block_id, delta } => {
+            ServerMessage::BlockContentDelta {
+                block_id,
+                delta,
+                offset,
+            } => {
                 if Some(block_id) == self.streaming_block_id {
+                    // Skip duplicates and flag gaps rather than garbling the text
+                    if offset < self.streaming_content.len() {
+                        return;
+                    }
+                    if offset > self.streaming_content.len() {
+                        self.status = "Missed part of the response".to_string();
+                        return;
+                    }
                     self.streaming_content.push_str(&delta);
                     // Update the block content in our list
                     if let Some(block) = self.blocks.iter_mut().find(|b| b.id == block_id) {
@@ -248,12 +260,14 @@ fn draw_conversation(f: &mut Frame, app: &App, area: Rect) {
         let (prefix, style) = match block.block_type {
             BlockType::User => ("You: ", Style::default().fg(Color::Cyan)),
             BlockType::Assistant => ("AI: ", Style::default().fg(Color::Green)),
+            BlockType::System => ("System: ", Style::default().fg(Color::Magenta)),
         };
 
         // Show status indicator for streaming blocks
         let status_indicator = match block.status {
             BlockStatus::Streaming => " [streaming...]",
             BlockStatus::Pending => " [pending]",
+            BlockStatus::Queued => " [queued]",
             BlockStatus::Error => " [error]",
             BlockStatus::Complete => "",
         };
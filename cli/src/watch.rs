@@ -0,0 +1,153 @@
+//! Watch mode: print a journal's live events one line at a time
+//!
+//! Lines are written as they arrive, so the output can be piped to a file or
+//! another process. Colors are only used when stdout is a terminal.
+
+use std::io::{self, IsTerminal, Write};
+
+use anyhow::Result;
+use crossterm::style::{Color, Stylize};
+use uuid::Uuid;
+
+use crate::client::OuterClient;
+use crate::messages::{BlockStatus, ServerMessage};
+
+/// Longest preview of block content or deltas shown on a line
+const PREVIEW_CHARS: usize = 60;
+
+/// Print events for `journal_id` until the connection closes or Ctrl-C is pressed
+pub async fn run(mut client: OuterClient, journal_id: Uuid) -> Result<()> {
+    let color = io::stdout().is_terminal();
+
+    let listen = client.listen(|msg| {
+        if let Some((tint, line)) = summarize(&msg) {
+            let mut stdout = io::stdout().lock();
+            let written = if color {
+                writeln!(stdout, "{}", line.with(tint))
+            } else {
+                writeln!(stdout, "{}", line)
+            };
+            // Stop once the reader has gone away (e.g. `| head`)
+            if written.and_then(|_| stdout.flush()).is_err() {
+                return false;
+            }
+        }
+        true
+    });
+
+    tokio::select! {
+        result = listen => result?,
+        _ = tokio::signal::ctrl_c() => {}
+    }
+
+    eprintln!("Stopped watching journal {}", journal_id);
+    Ok(())
+}
+
+/// One-line description of an event, or `None` for events not worth showing
+fn summarize(msg: &ServerMessage) -> Option<(Color, String)> {
+    let summary = match msg {
+        ServerMessage::BlockCreated { block } => (
+            Color::Green,
+            format!(
+                "block created  {} {:?}: {}",
+                block.id,
+                block.block_type,
+                preview(&block.content)
+            ),
+        ),
+        ServerMessage::BlockContentDelta {
+            block_id, delta, ..
+        } => (
+            Color::DarkGrey,
+            format!("delta          {} {}", block_id, preview(delta)),
+        ),
+        ServerMessage::BlockStatusChanged { block_id, status } => (
+            match status {
+                BlockStatus::Complete => Color::Green,
+                BlockStatus::Error => Color::Red,
+                _ => Color::Yellow,
+            },
+            format!("status         {} {:?}", block_id, status),
+        ),
+        ServerMessage::BlockForked {
+            original_block_id,
+            new_block,
+        } => (
+            Color::Green,
+            format!("block forked   {} -> {}", original_block_id, new_block.id),
+        ),
+        ServerMessage::BlockCancelled { block_id } => {
+            (Color::Red, format!("cancelled      {}", block_id))
+        }
+        ServerMessage::QueuePosition {
+            block_id, position, ..
+        } => (
+            Color::Yellow,
+            format!("queued         {} at {}", block_id, position),
+        ),
+        ServerMessage::ParticipantJoined { participant, .. } => (
+            Color::Cyan,
+            format!(
+                "joined         {} {} ({:?})",
+                participant.id, participant.name, participant.kind
+            ),
+        ),
+        ServerMessage::ParticipantLeft { participant_id, .. } => {
+            (Color::Cyan, format!("left           {}", participant_id))
+        }
+        ServerMessage::ParticipantStatusChanged {
+            participant_id,
+            status,
+            ..
+        } => (
+            Color::DarkCyan,
+            format!("presence       {} {:?}", participant_id, status),
+        ),
+        ServerMessage::JournalRenamed { title, .. } => {
+            (Color::Magenta, format!("renamed        {}", title))
+        }
+        ServerMessage::Error { message } => (Color::Red, format!("error          {}", message)),
+        _ => return None,
+    };
+    Some(summary)
+}
+
+/// First line of `text`, shortened and with newlines escaped
+fn preview(text: &str) -> String {
+    let escaped = text.escape_debug().to_string();
+    match escaped.char_indices().nth(PREVIEW_CHARS) {
+        Some((end, _)) => format!("{}…", &escaped[..end]),
+        None => escaped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_stays_on_one_line() {
+        assert_eq!(preview("Hello\nworld"), "Hello\\nworld");
+        let long = "x".repeat(PREVIEW_CHARS + 10);
+        assert_eq!(preview(&long).chars().count(), PREVIEW_CHARS + 1);
+    }
+
+    #[test]
+    fn test_summarize_skips_sync_traffic() {
+        let msg = ServerMessage::CrdtUpdate {
+            journal_id: Uuid::nil(),
+            source: None,
+            update: String::new(),
+        };
+        assert!(summarize(&msg).is_none());
+
+        let msg = ServerMessage::BlockStatusChanged {
+            block_id: Uuid::nil(),
+            status: BlockStatus::Error,
+        };
+        let (color, line) = summarize(&msg).unwrap();
+        assert_eq!(color, Color::Red);
+        assert!(line.contains("Error"));
+    }
+}
@@ -0,0 +1,117 @@
+//! `outer-cli submit` against a scripted websocket server
+
+use futures::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+use uuid::Uuid;
+
+fn block_json(id: Uuid, journal_id: Uuid, content: &str, status: &str) -> serde_json::Value {
+    let now = chrono::Utc::now();
+    serde_json::json!({
+        "id": id,
+        "journal_id": journal_id,
+        "block_type": "assistant",
+        "content": content,
+        "status": status,
+        "created_at": now,
+        "updated_at": now
+    })
+}
+
+/// Serve one connection: answer a submit with `deltas`, finish with `final_status`,
+/// then answer any `get_block` with an errored block
+async fn mock_server(deltas: &'static [&'static str], final_status: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        let submit = match ws.next().await {
+            Some(Ok(Message::Text(text))) => {
+                serde_json::from_str::<serde_json::Value>(&text).unwrap()
+            }
+            other => panic!("Expected submit, got {:?}", other),
+        };
+        assert_eq!(submit["type"], "submit");
+        let journal_id: Uuid = submit["journal_id"].as_str().unwrap().parse().unwrap();
+        let block_id = Uuid::new_v4();
+
+        let mut replies = vec![serde_json::json!({
+            "type": "block_created",
+            "block": block_json(block_id, journal_id, "", "pending")
+        })];
+        for delta in deltas {
+            replies.push(serde_json::json!({
+                "type": "block_content_delta",
+                "block_id": block_id,
+                "delta": delta
+            }));
+        }
+        replies.push(serde_json::json!({
+            "type": "block_status_changed",
+            "block_id": block_id,
+            "status": final_status
+        }));
+        for reply in replies {
+            ws.send(Message::Text(reply.to_string().into()))
+                .await
+                .unwrap();
+        }
+
+        while let Some(Ok(Message::Text(text))) = ws.next().await {
+            let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+            if request["type"] == "get_block" {
+                let content = "The model provider is rate limiting requests.";
+                let reply = serde_json::json!({
+                    "type": "block",
+                    "block": block_json(block_id, journal_id, content, "error")
+                });
+                ws.send(Message::Text(reply.to_string().into()))
+                    .await
+                    .unwrap();
+            }
+        }
+    });
+
+    format!("ws://{}", addr)
+}
+
+async fn run_submit(server: &str, output: &std::path::Path) -> std::process::Output {
+    tokio::process::Command::new(env!("CARGO_BIN_EXE_outer-cli"))
+        .args(["--server", server, "submit", "--journal"])
+        .arg(Uuid::new_v4().to_string())
+        .args(["--message", "Hello", "--output"])
+        .arg(output)
+        .output()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_submit_writes_response_to_file() {
+    let server = mock_server(&["Hello, ", "world!"], "complete").await;
+    let path = std::env::temp_dir().join(format!("outer-cli-{}.txt", Uuid::new_v4()));
+
+    let output = run_submit(&server, &path).await;
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(written, "Hello, world!");
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Hello, world!"));
+}
+
+#[tokio::test]
+async fn test_submit_exits_non_zero_on_error() {
+    let server = mock_server(&["partial"], "error").await;
+    let path = std::env::temp_dir().join(format!("outer-cli-{}.txt", Uuid::new_v4()));
+
+    let output = run_submit(&server, &path).await;
+    std::fs::remove_file(&path).ok();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("rate limiting"), "stderr: {}", stderr);
+}
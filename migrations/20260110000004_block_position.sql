@@ -0,0 +1,6 @@
+-- Optional manual ordering of blocks within a journal
+
+-- Fractional index; NULL keeps the block in chronological order
+ALTER TABLE blocks ADD COLUMN position REAL;
+
+CREATE INDEX IF NOT EXISTS idx_blocks_journal_position ON blocks(journal_id, position);
@@ -0,0 +1,46 @@
+-- Persist delegation work items and approvals
+--
+-- Nothing wrote to these tables before, so they are recreated rather than
+-- migrated: work items gained a `paused` status and the `auto_execute` flag.
+
+DROP TABLE IF EXISTS approval_requests;
+DROP TABLE IF EXISTS work_items;
+
+CREATE TABLE work_items (
+    id TEXT PRIMARY KEY NOT NULL,
+    journal_id TEXT NOT NULL REFERENCES journals(id),
+    description TEXT NOT NULL,
+    block_id TEXT REFERENCES blocks(id),
+    delegator_id TEXT NOT NULL,
+    assignee_id TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN (
+        'pending', 'in_progress', 'paused', 'awaiting_approval',
+        'approved', 'rejected', 'declined', 'cancelled'
+    )),
+    priority TEXT NOT NULL DEFAULT 'normal' CHECK (priority IN ('low', 'normal', 'high', 'urgent')),
+    requires_approval BOOLEAN NOT NULL DEFAULT 0,
+    approver_id TEXT,
+    auto_execute BOOLEAN NOT NULL DEFAULT 0,
+    result TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE approval_requests (
+    id TEXT PRIMARY KEY NOT NULL,
+    work_item_id TEXT NOT NULL REFERENCES work_items(id),
+    requester_id TEXT NOT NULL,
+    approver_id TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    feedback TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_work_items_journal_id ON work_items(journal_id);
+CREATE INDEX IF NOT EXISTS idx_work_items_assignee_id ON work_items(assignee_id);
+CREATE INDEX IF NOT EXISTS idx_work_items_delegator_id ON work_items(delegator_id);
+CREATE INDEX IF NOT EXISTS idx_work_items_status ON work_items(status);
+CREATE INDEX IF NOT EXISTS idx_approval_requests_work_item_id ON approval_requests(work_item_id);
+CREATE INDEX IF NOT EXISTS idx_approval_requests_approver_id ON approval_requests(approver_id);
+CREATE INDEX IF NOT EXISTS idx_approval_requests_status ON approval_requests(status);
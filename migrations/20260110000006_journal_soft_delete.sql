@@ -0,0 +1,6 @@
+-- Soft deletion of journals
+
+-- Set when the journal is deleted; deleted journals are hidden from listings
+ALTER TABLE journals ADD COLUMN deleted_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_journals_deleted_at ON journals(deleted_at);
@@ -0,0 +1,3 @@
+-- Optional deadlines for delegated work
+
+ALTER TABLE work_items ADD COLUMN due_at DATETIME;
@@ -0,0 +1,7 @@
+-- Latest collaborative document state per journal, so rooms survive restarts
+
+CREATE TABLE crdt_snapshots (
+    journal_id TEXT PRIMARY KEY NOT NULL REFERENCES journals(id) ON DELETE CASCADE,
+    state BLOB NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
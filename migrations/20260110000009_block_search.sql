@@ -0,0 +1,22 @@
+-- Full-text index over block content
+--
+-- External-content table: the text lives in `blocks`, the index is kept in
+-- step by triggers.
+
+CREATE VIRTUAL TABLE blocks_fts USING fts5(content, content='blocks', content_rowid='rowid');
+
+CREATE TRIGGER blocks_fts_insert AFTER INSERT ON blocks BEGIN
+    INSERT INTO blocks_fts(rowid, content) VALUES (new.rowid, new.content);
+END;
+
+CREATE TRIGGER blocks_fts_update AFTER UPDATE OF content ON blocks BEGIN
+    INSERT INTO blocks_fts(blocks_fts, rowid, content) VALUES ('delete', old.rowid, old.content);
+    INSERT INTO blocks_fts(rowid, content) VALUES (new.rowid, new.content);
+END;
+
+CREATE TRIGGER blocks_fts_delete AFTER DELETE ON blocks BEGIN
+    INSERT INTO blocks_fts(blocks_fts, rowid, content) VALUES ('delete', old.rowid, old.content);
+END;
+
+-- Index blocks written before this migration
+INSERT INTO blocks_fts(blocks_fts) VALUES ('rebuild');
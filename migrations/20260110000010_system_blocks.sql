@@ -0,0 +1,58 @@
+-- Allow system prompt blocks
+--
+-- SQLite can't change a CHECK constraint in place, so the blocks table is
+-- rebuilt. This runs inside the migrator's transaction, where toggling
+-- `foreign_keys` has no effect; deferring the checks to commit instead lets
+-- the old table be dropped while rows still point into it, and putting the
+-- rows back satisfies them again. Row ids are kept because the full-text
+-- index refers to blocks by rowid.
+
+PRAGMA defer_foreign_keys = ON;
+
+-- Set the rows aside in a table without constraints while blocks is rebuilt
+CREATE TABLE blocks_old AS SELECT rowid AS old_rowid, * FROM blocks;
+
+DROP TABLE blocks;
+
+CREATE TABLE blocks (
+    id TEXT PRIMARY KEY NOT NULL,
+    journal_id TEXT NOT NULL REFERENCES journals(id),
+    block_type TEXT NOT NULL CHECK (block_type IN ('user', 'assistant', 'system')),
+    content TEXT NOT NULL DEFAULT '',
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'streaming', 'complete', 'error')),
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    parent_id TEXT REFERENCES blocks(id),
+    forked_from_id TEXT REFERENCES blocks(id),
+    position REAL
+);
+
+INSERT INTO blocks (
+    rowid, id, journal_id, block_type, content, status, created_at, updated_at,
+    parent_id, forked_from_id, position
+)
+SELECT old_rowid, id, journal_id, block_type, content, status, created_at, updated_at,
+       parent_id, forked_from_id, position
+FROM blocks_old;
+
+DROP TABLE blocks_old;
+
+CREATE INDEX idx_blocks_journal_id ON blocks(journal_id);
+CREATE INDEX idx_blocks_created_at ON blocks(created_at);
+CREATE INDEX idx_blocks_parent_id ON blocks(parent_id);
+CREATE INDEX idx_blocks_forked_from_id ON blocks(forked_from_id);
+CREATE INDEX idx_blocks_journal_position ON blocks(journal_id, position);
+
+-- Dropping the old table took the search triggers with it
+CREATE TRIGGER blocks_fts_insert AFTER INSERT ON blocks BEGIN
+    INSERT INTO blocks_fts(rowid, content) VALUES (new.rowid, new.content);
+END;
+
+CREATE TRIGGER blocks_fts_update AFTER UPDATE OF content ON blocks BEGIN
+    INSERT INTO blocks_fts(blocks_fts, rowid, content) VALUES ('delete', old.rowid, old.content);
+    INSERT INTO blocks_fts(rowid, content) VALUES (new.rowid, new.content);
+END;
+
+CREATE TRIGGER blocks_fts_delete AFTER DELETE ON blocks BEGIN
+    INSERT INTO blocks_fts(blocks_fts, rowid, content) VALUES ('delete', old.rowid, old.content);
+END;
@@ -0,0 +1,2 @@
+-- Content version for optimistic concurrency: bumped on every content write
+ALTER TABLE blocks ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
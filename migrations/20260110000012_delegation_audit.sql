@@ -0,0 +1,21 @@
+-- Append-only record of every delegation event, for compliance review
+CREATE TABLE delegation_audit (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_type TEXT NOT NULL,
+    actor_id TEXT,
+    work_item_id TEXT,
+    details TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_delegation_audit_work_item_id ON delegation_audit(work_item_id);
+
+CREATE TRIGGER delegation_audit_no_update BEFORE UPDATE ON delegation_audit
+BEGIN
+    SELECT RAISE(ABORT, 'delegation_audit is append-only');
+END;
+
+CREATE TRIGGER delegation_audit_no_delete BEFORE DELETE ON delegation_audit
+BEGIN
+    SELECT RAISE(ABORT, 'delegation_audit is append-only');
+END;
@@ -0,0 +1,3 @@
+-- Work items approved by several reviewers
+ALTER TABLE work_items ADD COLUMN approver_ids TEXT NOT NULL DEFAULT '[]'; -- JSON array of participant IDs
+ALTER TABLE work_items ADD COLUMN required_approvals INTEGER NOT NULL DEFAULT 1;
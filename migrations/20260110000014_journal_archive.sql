@@ -0,0 +1,5 @@
+-- Archiving journals
+
+-- Set when the journal is archived; archived journals stay readable but are
+-- left out of listings unless asked for
+ALTER TABLE journals ADD COLUMN archived_at DATETIME;
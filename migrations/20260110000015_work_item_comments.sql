@@ -0,0 +1,11 @@
+-- Comments left on delegated work items by their delegator, assignee and approvers
+
+CREATE TABLE IF NOT EXISTS work_item_comments (
+    id TEXT PRIMARY KEY NOT NULL,
+    work_item_id TEXT NOT NULL REFERENCES work_items(id),
+    author_id TEXT NOT NULL,
+    text TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_work_item_comments_work_item_id ON work_item_comments(work_item_id);
@@ -0,0 +1,12 @@
+-- Earlier content of user blocks, saved each time a prompt is edited
+
+CREATE TABLE IF NOT EXISTS block_edits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    block_id TEXT NOT NULL REFERENCES blocks(id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    -- The block's version while it held this content
+    version INTEGER NOT NULL,
+    edited_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_block_edits_block_id ON block_edits(block_id);
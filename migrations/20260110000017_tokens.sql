@@ -0,0 +1,10 @@
+-- Bearer tokens accepted when opening a websocket, and who each one belongs to
+--
+-- Only a SHA-256 digest of each token is kept, so reading the database
+-- doesn't hand out working credentials.
+
+CREATE TABLE IF NOT EXISTS tokens (
+    token_hash TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
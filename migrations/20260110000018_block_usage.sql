@@ -0,0 +1,4 @@
+-- Token counts OpenCode reported for an assistant block; NULL if it never said
+
+ALTER TABLE blocks ADD COLUMN prompt_tokens INTEGER;
+ALTER TABLE blocks ADD COLUMN completion_tokens INTEGER;
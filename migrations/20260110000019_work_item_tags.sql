@@ -0,0 +1,2 @@
+-- Free-form labels on work items
+ALTER TABLE work_items ADD COLUMN tags TEXT NOT NULL DEFAULT '[]'; -- JSON array of lowercase strings
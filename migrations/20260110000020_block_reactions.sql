@@ -0,0 +1,9 @@
+-- Emoji reactions participants leave on blocks without editing them
+
+CREATE TABLE IF NOT EXISTS block_reactions (
+    block_id TEXT NOT NULL REFERENCES blocks(id) ON DELETE CASCADE,
+    participant_id TEXT NOT NULL,
+    emoji TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (block_id, participant_id, emoji)
+);
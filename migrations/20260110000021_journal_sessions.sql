@@ -0,0 +1,7 @@
+-- OpenCode session each journal's submits continue, so context carries over
+
+CREATE TABLE IF NOT EXISTS journal_sessions (
+    journal_id TEXT PRIMARY KEY NOT NULL REFERENCES journals(id) ON DELETE CASCADE,
+    session_id TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,3 @@
+-- Registered participants are written through so work and approvals keep
+-- their owners across restarts; webhooks came after the table was created
+ALTER TABLE registered_participants ADD COLUMN webhook_url TEXT;
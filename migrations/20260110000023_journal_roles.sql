@@ -0,0 +1,11 @@
+-- Who holds which role in each journal, so ownership outlives the live room
+-- and can't be claimed by whoever subscribes first after a restart.
+-- `member` is the connection's token identity, or its participant ID when
+-- auth is disabled.
+
+CREATE TABLE IF NOT EXISTS journal_roles (
+    journal_id TEXT NOT NULL REFERENCES journals(id) ON DELETE CASCADE,
+    member TEXT NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('owner', 'member', 'guest')),
+    PRIMARY KEY (journal_id, member)
+);
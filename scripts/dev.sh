@@ -6,6 +6,9 @@ set -e
 
 PROJECT_ROOT="$( cd "$( dirname "${BASH_SOURCE[0]}" )/.." && pwd )"
 
+# The web UI doesn't send a token; let it connect unless told otherwise
+export OUTER_AUTH_DISABLED="${OUTER_AUTH_DISABLED:-true}"
+
 # Check for cargo-watch
 if ! command -v cargo-watch &> /dev/null; then
     echo "Installing cargo-watch..."
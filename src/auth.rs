@@ -0,0 +1,165 @@
+//! Bearer token checks for websocket upgrades and event streams
+//!
+//! A client presents its token in an `Authorization: Bearer <token>` header,
+//! or as `?token=<token>` for browsers that can't set headers on a websocket.
+//! The token is accepted if it matches the configured shared secret or a row
+//! in the `tokens` table; the name it belongs to becomes the connection's
+//! identity.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use axum::http::{header, HeaderMap, StatusCode, Uri};
+use serde::Deserialize;
+
+use crate::store::Store;
+
+/// Identity given to connections that present the shared secret
+pub const SECRET_IDENTITY: &str = "admin";
+
+/// Query parameters the upgrade handler looks at
+#[derive(Debug, Default, Deserialize)]
+pub struct TokenQuery {
+    pub token: Option<String>,
+}
+
+/// Decides whether a connection may open
+pub struct Authenticator {
+    enabled: AtomicBool,
+    secret: RwLock<Option<String>>,
+}
+
+impl Default for Authenticator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Authenticator {
+    /// Create an authenticator that lets everyone in
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            secret: RwLock::new(None),
+        }
+    }
+
+    /// Require a token on new connections, accepting `secret` as well as the `tokens` table
+    pub fn enable(&self, secret: Option<String>) {
+        *self.secret.write().unwrap() = secret.filter(|s| !s.is_empty());
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Check the token on an incoming request
+    ///
+    /// Returns the caller's identity, `None` if auth is off, or the status
+    /// to reject the request with.
+    pub async fn authenticate(
+        &self,
+        store: &Store,
+        headers: &HeaderMap,
+        query: &TokenQuery,
+    ) -> Result<Option<String>, StatusCode> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+        let token = bearer_token(headers)
+            .or(query.token.as_deref())
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+
+        let secret = self.secret.read().unwrap().clone();
+        if secret.is_some_and(|secret| constant_time_eq(secret.as_bytes(), token.as_bytes())) {
+            return Ok(Some(SECRET_IDENTITY.to_string()));
+        }
+
+        match store.token_identity(token).await {
+            Ok(Some(name)) => Ok(Some(name)),
+            Ok(None) => Err(StatusCode::UNAUTHORIZED),
+            Err(e) => {
+                tracing::error!("Failed to look up token: {}", e);
+                Err(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+}
+
+/// Token from an `Authorization: Bearer` header, if there is one
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+/// `uri` with any `?token=` value masked, so request logs don't record credentials
+pub fn redacted_uri(uri: &Uri) -> String {
+    let Some(query) = uri.query() else {
+        return uri.to_string();
+    };
+    let query: Vec<&str> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some(("token", _)) => "token=[redacted]",
+            _ => pair,
+        })
+        .collect();
+    format!("{}?{}", uri.path(), query.join("&"))
+}
+
+/// Compare without bailing out at the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_bearer_token_parsing() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer abc"),
+        );
+        assert_eq!(bearer_token(&headers), Some("abc"));
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("bearer  abc "),
+        );
+        assert_eq!(bearer_token(&headers), Some("abc"));
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic abc"));
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer "));
+        assert_eq!(bearer_token(&headers), None);
+    }
+
+    #[test]
+    fn test_redacted_uri() {
+        let uri: Uri = "/ws?token=abc&x=1".parse().unwrap();
+        assert_eq!(redacted_uri(&uri), "/ws?token=[redacted]&x=1");
+
+        let uri: Uri = "/journals/1/stream?tokens=1".parse().unwrap();
+        assert_eq!(redacted_uri(&uri), "/journals/1/stream?tokens=1");
+
+        let uri: Uri = "/ws".parse().unwrap();
+        assert_eq!(redacted_uri(&uri), "/ws");
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secrets"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Message encodings for the websocket protocol
+//!
+//! JSON text frames are the default. A client may also send any message as a
+//! MessagePack binary frame, and after `{"type": "hello", "msgpack": true}`
+//! every reply comes back as MessagePack too.
+//!
+//! MessagePack messages are always maps, so their first byte (`0x80`-`0x8f`,
+//! `0xde` or `0xdf`) never clashes with the opcode that starts a binary CRDT
+//! frame (see [`crate::frame`]).
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use axum::extract::ws::Message;
+use futures::Sink;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// How a message is laid out on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// Text frames holding JSON
+    #[default]
+    Json,
+    /// Binary frames holding MessagePack, with the same field names as JSON
+    MessagePack,
+}
+
+impl Encoding {
+    /// Serialize `msg` into a frame
+    pub fn encode<T: Serialize>(self, msg: &T) -> Result<Message, String> {
+        match self {
+            Encoding::Json => serde_json::to_string(msg)
+                .map(Message::Text)
+                .map_err(|e| e.to_string()),
+            Encoding::MessagePack => {
+                let mut bytes = Vec::new();
+                let mut serializer = rmp_serde::Serializer::new(&mut bytes)
+                    .with_struct_map()
+                    .with_human_readable();
+                msg.serialize(&mut serializer).map_err(|e| e.to_string())?;
+                Ok(Message::Binary(bytes))
+            }
+        }
+    }
+
+    /// Parse a frame's payload: UTF-8 text for JSON, raw bytes for MessagePack
+    pub fn decode<T: DeserializeOwned>(self, payload: &[u8]) -> Result<T, String> {
+        match self {
+            Encoding::Json => serde_json::from_slice(payload).map_err(|e| e.to_string()),
+            Encoding::MessagePack => {
+                let mut deserializer = rmp_serde::Deserializer::new(payload).with_human_readable();
+                T::deserialize(&mut deserializer).map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+/// Whether a binary frame holds a MessagePack message rather than a CRDT frame
+pub fn is_message_pack(data: &[u8]) -> bool {
+    matches!(data.first(), Some(0x80..=0x8f | 0xde | 0xdf))
+}
+
+/// Outgoing half of a connection that re-encodes JSON text frames as
+/// MessagePack once the client has asked for it
+///
+/// Handlers keep sending `Message::Text` JSON; binary CRDT frames and
+/// control frames pass through untouched.
+pub struct EncodingSink<S> {
+    inner: S,
+    msgpack: Arc<AtomicBool>,
+}
+
+impl<S> EncodingSink<S> {
+    /// Wrap `inner`; replies switch to MessagePack while `msgpack` is set
+    pub fn new(inner: S, msgpack: Arc<AtomicBool>) -> Self {
+        Self { inner, msgpack }
+    }
+
+    fn transcode(&self, item: Message) -> Message {
+        let Message::Text(text) = item else {
+            return item;
+        };
+        if !self.msgpack.load(Ordering::Relaxed) {
+            return Message::Text(text);
+        }
+        let reencoded = serde_json::from_str::<serde_json::Value>(&text)
+            .map_err(|e| e.to_string())
+            .and_then(|value| Encoding::MessagePack.encode(&value));
+        match reencoded {
+            Ok(frame) => frame,
+            Err(e) => {
+                tracing::warn!("Sending frame as JSON; MessagePack encoding failed: {}", e);
+                Message::Text(text)
+            }
+        }
+    }
+}
+
+impl<S> Sink<Message> for EncodingSink<S>
+where
+    S: Sink<Message> + Unpin,
+{
+    type Error = S::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Message) -> Result<(), Self::Error> {
+        let item = self.transcode(item);
+        Pin::new(&mut self.inner).start_send(item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::websocket::{ClientMessage, ServerMessage};
+    use uuid::Uuid;
+
+    fn payload(frame: Message) -> Vec<u8> {
+        match frame {
+            Message::Text(text) => text.into_bytes(),
+            Message::Binary(bytes) => bytes,
+            other => panic!("Unexpected frame {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cursor_moved_round_trips_through_message_pack() {
+        let msg = ServerMessage::CursorMoved {
+            journal_id: Uuid::new_v4(),
+            participant_id: Uuid::new_v4(),
+            block_id: Some(Uuid::new_v4()),
+            offset: Some(42),
+            anchor: Some(40),
+            head: Some(42),
+        };
+
+        let frame = Encoding::MessagePack.encode(&msg).unwrap();
+        assert!(matches!(frame, Message::Binary(_)));
+        let bytes = payload(frame);
+        assert!(is_message_pack(&bytes));
+
+        // Same content as the JSON encoding, only smaller
+        let decoded: serde_json::Value = Encoding::MessagePack.decode(&bytes).unwrap();
+        let json = payload(Encoding::Json.encode(&msg).unwrap());
+        assert_eq!(
+            decoded,
+            serde_json::from_slice::<serde_json::Value>(&json).unwrap()
+        );
+        assert!(bytes.len() < json.len());
+    }
+
+    #[test]
+    fn test_client_message_decodes_from_message_pack() {
+        let journal_id = Uuid::new_v4();
+        let value = serde_json::json!({
+            "type": "cursor",
+            "journal_id": journal_id,
+            "offset": 7
+        });
+        let bytes = payload(Encoding::MessagePack.encode(&value).unwrap());
+
+        let msg: ClientMessage = Encoding::MessagePack.decode(&bytes).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::Cursor { journal_id: id, block_id: None, offset: Some(7), .. } if id == journal_id
+        ));
+    }
+
+    #[test]
+    fn test_crdt_frames_are_not_mistaken_for_messages() {
+        use crate::frame::{BinaryFrame, Opcode};
+
+        let frame = BinaryFrame::new(Opcode::CrdtUpdate, Uuid::new_v4(), vec![1, 2, 3]);
+        assert!(!is_message_pack(&frame.encode()));
+        assert!(!is_message_pack(&[]));
+    }
+}
@@ -7,5 +7,5 @@ pub mod participant;
 pub mod room;
 
 pub use journal_doc::JournalDoc;
-pub use participant::{Participant, ParticipantKind, ParticipantStatus};
-pub use room::{JournalRoom, RoomEvent};
+pub use participant::{JournalRole, Participant, ParticipantKind, ParticipantStatus};
+pub use room::{CapacityExceeded, DuplicateNamePolicy, JournalRoom, NameTaken, RoomEvent};
@@ -26,6 +26,13 @@ impl ParticipantKind {
             ParticipantKind::Observer => "observer",
         }
     }
+
+    /// Parse a kind the client may have left out; a missing kind means a user
+    ///
+    /// A kind that is present but misspelled is an error rather than a user.
+    pub fn parse_or_user(kind: Option<&str>) -> Result<Self, String> {
+        kind.map_or(Ok(ParticipantKind::User), str::parse)
+    }
 }
 
 impl std::str::FromStr for ParticipantKind {
@@ -49,6 +56,8 @@ pub enum ParticipantStatus {
     Active,
     /// Connected but idle
     Idle,
+    /// Heads-down on accepted delegated work
+    Busy,
     /// Connection lost, may reconnect
     Disconnected,
 }
@@ -58,9 +67,30 @@ impl ParticipantStatus {
         match self {
             ParticipantStatus::Active => "active",
             ParticipantStatus::Idle => "idle",
+            ParticipantStatus::Busy => "busy",
             ParticipantStatus::Disconnected => "disconnected",
         }
     }
+
+    /// Whether presence may move from this status to `next`
+    ///
+    /// Staying put is always allowed. Otherwise:
+    ///
+    /// - Active -> Idle, Busy, Disconnected
+    /// - Idle -> Active, Busy, Disconnected
+    /// - Busy -> Active, Disconnected (a participant working isn't idle)
+    /// - Disconnected -> Active (on reconnect, before anything else)
+    pub fn can_transition_to(&self, next: ParticipantStatus) -> bool {
+        use ParticipantStatus::*;
+
+        matches!(
+            (self, next),
+            (Active, Active | Idle | Busy | Disconnected)
+                | (Idle, Idle | Active | Busy | Disconnected)
+                | (Busy, Busy | Active | Disconnected)
+                | (Disconnected, Disconnected | Active)
+        )
+    }
 }
 
 impl std::str::FromStr for ParticipantStatus {
@@ -70,12 +100,63 @@ impl std::str::FromStr for ParticipantStatus {
         match s {
             "active" => Ok(ParticipantStatus::Active),
             "idle" => Ok(ParticipantStatus::Idle),
+            "busy" => Ok(ParticipantStatus::Busy),
             "disconnected" => Ok(ParticipantStatus::Disconnected),
             _ => Err(format!("Invalid participant status: {}", s)),
         }
     }
 }
 
+/// What a participant may do to a journal, beyond editing its blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalRole {
+    /// May rename, archive and delete the journal, and change others' roles
+    Owner,
+    /// May rename the journal and delete its blocks
+    Member,
+    /// May follow along but not change the journal itself
+    Guest,
+}
+
+impl JournalRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JournalRole::Owner => "owner",
+            JournalRole::Member => "member",
+            JournalRole::Guest => "guest",
+        }
+    }
+
+    /// Whether this role may retitle the journal
+    pub fn can_rename(&self) -> bool {
+        matches!(self, JournalRole::Owner | JournalRole::Member)
+    }
+
+    /// Whether this role may delete the journal's blocks
+    pub fn can_delete_blocks(&self) -> bool {
+        matches!(self, JournalRole::Owner | JournalRole::Member)
+    }
+
+    /// Whether this role may delete or archive the journal, or assign roles
+    pub fn can_manage(&self) -> bool {
+        matches!(self, JournalRole::Owner)
+    }
+}
+
+impl std::str::FromStr for JournalRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "owner" => Ok(JournalRole::Owner),
+            "member" => Ok(JournalRole::Member),
+            "guest" => Ok(JournalRole::Guest),
+            _ => Err(format!("Invalid journal role: {}", s)),
+        }
+    }
+}
+
 /// Predefined colors for participant cursors
 const PARTICIPANT_COLORS: [&str; 8] = [
     "#FF6B6B", // Red
@@ -103,8 +184,21 @@ pub struct Participant {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cursor_block_id: Option<Uuid>,
     /// Character offset within the block (if applicable)
+    ///
+    /// Always the head of the selection when one is set.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cursor_offset: Option<u32>,
+    /// Offset where the current selection started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor_anchor: Option<u32>,
+    /// Offset where the current selection ends, i.e. where the caret is
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor_head: Option<u32>,
+    /// Whether the participant is composing a message right now
+    ///
+    /// Cleared by the room if the client stops refreshing it.
+    #[serde(default)]
+    pub typing: bool,
     /// Color for displaying this participant's cursor/presence
     pub color: String,
     /// When this participant joined
@@ -129,6 +223,9 @@ impl Participant {
             status: ParticipantStatus::Active,
             cursor_block_id: None,
             cursor_offset: None,
+            cursor_anchor: None,
+            cursor_head: None,
+            typing: false,
             color: PARTICIPANT_COLORS[color_index].to_string(),
             joined_at: now,
             last_seen_at: now,
@@ -147,16 +244,31 @@ impl Participant {
             status: ParticipantStatus::Active,
             cursor_block_id: None,
             cursor_offset: None,
+            cursor_anchor: None,
+            cursor_head: None,
+            typing: false,
             color: PARTICIPANT_COLORS[color_index].to_string(),
             joined_at: now,
             last_seen_at: now,
         }
     }
 
-    /// Update cursor position
+    /// Update cursor position, clearing any selection
     pub fn set_cursor(&mut self, block_id: Option<Uuid>, offset: Option<u32>) {
+        self.set_selection(block_id, offset, offset);
+    }
+
+    /// Update the selected range; `anchor == head` is a plain cursor
+    pub fn set_selection(
+        &mut self,
+        block_id: Option<Uuid>,
+        anchor: Option<u32>,
+        head: Option<u32>,
+    ) {
         self.cursor_block_id = block_id;
-        self.cursor_offset = offset;
+        self.cursor_offset = head;
+        self.cursor_anchor = anchor;
+        self.cursor_head = head;
         self.touch();
     }
 
@@ -168,11 +280,32 @@ impl Participant {
         }
     }
 
+    /// Move to `status`, refusing moves the state machine doesn't allow
+    ///
+    /// See [`ParticipantStatus::can_transition_to`].
+    pub fn set_status(&mut self, status: ParticipantStatus) -> Result<(), String> {
+        if !self.status.can_transition_to(status) {
+            return Err(format!(
+                "Cannot change participant status from {} to {}",
+                self.status.as_str(),
+                status.as_str()
+            ));
+        }
+        self.status = status;
+        self.last_seen_at = Utc::now();
+        Ok(())
+    }
+
     /// Mark as idle
     pub fn mark_idle(&mut self) {
         self.status = ParticipantStatus::Idle;
     }
 
+    /// Mark as busy with delegated work
+    pub fn mark_busy(&mut self) {
+        self.status = ParticipantStatus::Busy;
+    }
+
     /// Mark as disconnected
     pub fn mark_disconnected(&mut self) {
         self.status = ParticipantStatus::Disconnected;
@@ -234,6 +367,14 @@ mod tests {
         assert_eq!(p.status, ParticipantStatus::Disconnected);
     }
 
+    #[test]
+    fn test_touch_keeps_busy() {
+        let mut p = Participant::new("Test", ParticipantKind::Agent);
+        p.mark_busy();
+        p.touch();
+        assert_eq!(p.status, ParticipantStatus::Busy);
+    }
+
     #[test]
     fn test_touch_reactivates_idle() {
         let mut p = Participant::new("Test", ParticipantKind::User);
@@ -244,6 +385,31 @@ mod tests {
         assert_eq!(p.status, ParticipantStatus::Active);
     }
 
+    #[test]
+    fn test_set_status_active_to_idle() {
+        let mut p = Participant::new("Test", ParticipantKind::User);
+        assert!(ParticipantStatus::Active.can_transition_to(ParticipantStatus::Idle));
+
+        p.set_status(ParticipantStatus::Idle).unwrap();
+        assert_eq!(p.status, ParticipantStatus::Idle);
+    }
+
+    #[test]
+    fn test_set_status_rejects_invalid_transition() {
+        let mut p = Participant::new("Test", ParticipantKind::Agent);
+        p.set_status(ParticipantStatus::Disconnected).unwrap();
+        assert!(!ParticipantStatus::Disconnected.can_transition_to(ParticipantStatus::Busy));
+
+        // A disconnected participant has to come back before taking on work
+        let err = p.set_status(ParticipantStatus::Busy).unwrap_err();
+        assert!(err.contains("disconnected to busy"));
+        assert_eq!(p.status, ParticipantStatus::Disconnected);
+
+        p.set_status(ParticipantStatus::Active).unwrap();
+        p.set_status(ParticipantStatus::Busy).unwrap();
+        assert!(p.set_status(ParticipantStatus::Idle).is_err());
+    }
+
     #[test]
     fn test_participant_kind_as_str() {
         assert_eq!(ParticipantKind::User.as_str(), "user");
@@ -273,10 +439,35 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_participant_kind_round_trip() {
+        for kind in [
+            ParticipantKind::User,
+            ParticipantKind::Agent,
+            ParticipantKind::Observer,
+        ] {
+            assert_eq!(kind.as_str().parse::<ParticipantKind>(), Ok(kind));
+        }
+    }
+
+    #[test]
+    fn test_parse_or_user() {
+        assert_eq!(
+            ParticipantKind::parse_or_user(None),
+            Ok(ParticipantKind::User)
+        );
+        assert_eq!(
+            ParticipantKind::parse_or_user(Some("agent")),
+            Ok(ParticipantKind::Agent)
+        );
+        assert!(ParticipantKind::parse_or_user(Some("agnet")).is_err());
+    }
+
     #[test]
     fn test_participant_status_as_str() {
         assert_eq!(ParticipantStatus::Active.as_str(), "active");
         assert_eq!(ParticipantStatus::Idle.as_str(), "idle");
+        assert_eq!(ParticipantStatus::Busy.as_str(), "busy");
         assert_eq!(ParticipantStatus::Disconnected.as_str(), "disconnected");
     }
 
@@ -290,6 +481,10 @@ mod tests {
             "idle".parse::<ParticipantStatus>().unwrap(),
             ParticipantStatus::Idle
         );
+        assert_eq!(
+            "busy".parse::<ParticipantStatus>().unwrap(),
+            ParticipantStatus::Busy
+        );
         assert_eq!(
             "disconnected".parse::<ParticipantStatus>().unwrap(),
             ParticipantStatus::Disconnected
@@ -3,13 +3,26 @@
 //! A room represents all clients subscribed to a particular journal,
 //! managing CRDT updates and presence information.
 
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::{broadcast, RwLock};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::{broadcast, watch, RwLock};
+use tokio::time::Instant;
 use uuid::Uuid;
 
 use super::journal_doc::JournalDoc;
-use super::participant::{Participant, ParticipantKind};
+use super::participant::{JournalRole, Participant, ParticipantKind, ParticipantStatus};
+use crate::snapshot_store::SnapshotStore;
+
+/// How long a room's document must go unedited before it is snapshotted
+pub const SNAPSHOT_QUIET_PERIOD: Duration = Duration::from_secs(5);
+
+/// How long a typing indicator lasts without being refreshed
+pub const TYPING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many recent CRDT updates a room keeps for replaying to clients
+pub const CRDT_REPLAY_UPDATES: usize = 256;
 
 /// Events that can occur in a journal room
 #[derive(Debug, Clone)]
@@ -18,21 +31,27 @@ pub enum RoomEvent {
     ParticipantJoined(Participant),
     /// A participant left the room
     ParticipantLeft { participant_id: Uuid },
-    /// A participant's cursor position changed
+    /// A participant's cursor position or selection changed
     CursorMoved {
         participant_id: Uuid,
         block_id: Option<Uuid>,
         offset: Option<u32>,
+        anchor: Option<u32>,
+        head: Option<u32>,
     },
     /// A participant's status changed (active/idle/disconnected)
     StatusChanged {
         participant_id: Uuid,
-        status: super::participant::ParticipantStatus,
+        status: ParticipantStatus,
     },
+    /// A participant started or stopped typing
+    TypingChanged { participant_id: Uuid, typing: bool },
     /// CRDT update received (binary update to apply)
     CrdtUpdate {
         /// The participant who made the change (None for server-originated)
         source: Option<Uuid>,
+        /// Position in the room's update sequence, starting at 1
+        seq: u64,
         /// The binary update data
         update: Vec<u8>,
     },
@@ -41,6 +60,26 @@ pub enum RoomEvent {
         /// Full document state
         state: Vec<u8>,
     },
+    /// A block was created or changed by a submit, fork or rerun
+    BlockEvent {
+        /// Connection that caused it; that client is sent the event directly
+        origin: Uuid,
+        event: crate::models::BlockEvent,
+    },
+    /// A participant's role in the journal changed
+    RoleChanged {
+        /// The participant who changed it
+        source: Option<Uuid>,
+        participant_id: Uuid,
+        role: JournalRole,
+    },
+    /// The journal was given a new title
+    JournalRenamed {
+        /// Connection that renamed it, which was answered directly (None if
+        /// the server did)
+        origin: Option<Uuid>,
+        title: String,
+    },
 }
 
 /// A room for a journal, managing subscribers and CRDT sync
@@ -48,7 +87,88 @@ pub struct JournalRoom {
     journal_id: Uuid,
     doc: Arc<JournalDoc>,
     participants: RwLock<HashMap<Uuid, Participant>>,
+    /// When each participant last showed signs of life, on the monotonic clock
+    ///
+    /// Only written while holding the `participants` write lock.
+    heartbeats: std::sync::Mutex<HashMap<Uuid, Instant>>,
+    /// Each participant's role, kept after they leave so a rejoin restores it
+    ///
+    /// Only written while holding the `participants` write lock.
+    roles: std::sync::Mutex<HashMap<Uuid, JournalRole>>,
+    /// Who each participant's role is stored under, once [`Self::adopt_role`] has run
+    ///
+    /// Only written while holding the `participants` write lock.
+    members: std::sync::Mutex<HashMap<Uuid, String>>,
+    /// When each typing participant last said so
+    ///
+    /// Only written while holding the `participants` write lock.
+    typing_refreshed: std::sync::Mutex<HashMap<Uuid, Instant>>,
+    /// When the room was last looked up through its manager
+    last_used: std::sync::Mutex<Instant>,
+    /// Recent CRDT updates, held while broadcasting so sequence numbers go
+    /// out in order
+    crdt_log: std::sync::Mutex<CrdtLog>,
+    /// What happens when someone joins under a name that's already present
+    name_policy: DuplicateNamePolicy,
     event_tx: broadcast::Sender<RoomEvent>,
+    snapshots: Option<SnapshotWriter>,
+}
+
+/// What a room does when someone joins under a name already in use there
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateNamePolicy {
+    /// Number the newcomer's name, e.g. "Alice (2)"
+    #[default]
+    Suffix,
+    /// Refuse the join with [`NameTaken`]
+    Reject,
+}
+
+/// Returned when joining under a name someone in the room already has
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Someone named {0:?} is already in this journal")]
+pub struct NameTaken(pub String);
+
+/// A CRDT update as broadcast, kept so missed ones can be sent again
+#[derive(Debug, Clone)]
+pub struct SequencedUpdate {
+    pub seq: u64,
+    pub source: Option<Uuid>,
+    pub update: Vec<u8>,
+}
+
+/// The last `CRDT_REPLAY_UPDATES` updates and the sequence number to use next
+#[derive(Default)]
+struct CrdtLog {
+    last_seq: u64,
+    recent: VecDeque<SequencedUpdate>,
+}
+
+/// Persists a room's document once edits have gone quiet
+struct SnapshotWriter {
+    store: SnapshotStore,
+    /// Poked on every edit; dropping it flushes any pending snapshot
+    changed: watch::Sender<()>,
+}
+
+impl SnapshotWriter {
+    fn spawn(doc: Arc<JournalDoc>, store: SnapshotStore) -> Self {
+        let (changed, mut edits) = watch::channel(());
+        let task_store = store.clone();
+        tokio::spawn(async move {
+            while edits.changed().await.is_ok() {
+                // Keep waiting while edits arrive; stop once quiet or the room is gone
+                while let Ok(Ok(())) =
+                    tokio::time::timeout(SNAPSHOT_QUIET_PERIOD, edits.changed()).await
+                {
+                }
+                if let Err(e) = task_store.save(doc.journal_id(), &doc.encode_state()).await {
+                    tracing::warn!("Failed to snapshot journal {}: {}", doc.journal_id(), e);
+                }
+            }
+        });
+        Self { store, changed }
+    }
 }
 
 impl JournalRoom {
@@ -59,7 +179,15 @@ impl JournalRoom {
             journal_id,
             doc: Arc::new(JournalDoc::new(journal_id)),
             participants: RwLock::new(HashMap::new()),
+            heartbeats: std::sync::Mutex::new(HashMap::new()),
+            roles: std::sync::Mutex::new(HashMap::new()),
+            members: std::sync::Mutex::new(HashMap::new()),
+            typing_refreshed: std::sync::Mutex::new(HashMap::new()),
+            last_used: std::sync::Mutex::new(Instant::now()),
+            crdt_log: std::sync::Mutex::new(CrdtLog::default()),
+            name_policy: DuplicateNamePolicy::default(),
             event_tx,
+            snapshots: None,
         }
     }
 
@@ -70,10 +198,27 @@ impl JournalRoom {
             journal_id: doc.journal_id(),
             doc,
             participants: RwLock::new(HashMap::new()),
+            heartbeats: std::sync::Mutex::new(HashMap::new()),
+            roles: std::sync::Mutex::new(HashMap::new()),
+            members: std::sync::Mutex::new(HashMap::new()),
+            typing_refreshed: std::sync::Mutex::new(HashMap::new()),
+            last_used: std::sync::Mutex::new(Instant::now()),
+            crdt_log: std::sync::Mutex::new(CrdtLog::default()),
+            name_policy: DuplicateNamePolicy::default(),
             event_tx,
+            snapshots: None,
         }
     }
 
+    /// Create a room whose document is snapshotted to `store` after edits
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn with_snapshot_store(doc: Arc<JournalDoc>, store: SnapshotStore) -> Self {
+        let mut room = Self::with_doc(doc);
+        room.snapshots = Some(SnapshotWriter::spawn(Arc::clone(&room.doc), store));
+        room
+    }
+
     /// Get the journal ID
     pub fn journal_id(&self) -> Uuid {
         self.journal_id
@@ -84,41 +229,190 @@ impl JournalRoom {
         &self.doc
     }
 
+    /// When the room was last looked up through its manager
+    pub fn last_used(&self) -> Instant {
+        *self.last_used.lock().unwrap()
+    }
+
+    fn touch(&self) {
+        *self.last_used.lock().unwrap() = Instant::now();
+    }
+
     /// Subscribe to room events
     pub fn subscribe(&self) -> broadcast::Receiver<RoomEvent> {
         self.event_tx.subscribe()
     }
 
+    /// Number of live event receivers (one per forwarding connection)
+    pub fn subscriber_count(&self) -> usize {
+        self.event_tx.receiver_count()
+    }
+
     /// Add a participant to the room
-    pub async fn join(&self, name: impl Into<String>, kind: ParticipantKind) -> Participant {
-        let participant = Participant::new(name, kind);
+    ///
+    /// A name already used by someone present is numbered or refused,
+    /// depending on the room's [`DuplicateNamePolicy`].
+    pub async fn join(
+        &self,
+        name: impl Into<String>,
+        kind: ParticipantKind,
+    ) -> Result<Participant, NameTaken> {
+        let mut participant = Participant::new(name, kind);
         let mut participants = self.participants.write().await;
+        participant.name = self.unique_name(&participants, participant.id, participant.name)?;
         participants.insert(participant.id, participant.clone());
+        self.assign_role(participant.id, kind);
+        self.heartbeats
+            .lock()
+            .unwrap()
+            .insert(participant.id, Instant::now());
 
         // Broadcast join event
         let _ = self
             .event_tx
             .send(RoomEvent::ParticipantJoined(participant.clone()));
 
-        participant
+        Ok(participant)
     }
 
     /// Add a participant with a specific ID (for reconnection)
-    pub async fn rejoin(&self, participant: Participant) -> Participant {
+    ///
+    /// Their name is checked against everyone else's as in [`Self::join`].
+    pub async fn rejoin(&self, mut participant: Participant) -> Result<Participant, NameTaken> {
         let mut participants = self.participants.write().await;
+        participant.name = self.unique_name(&participants, participant.id, participant.name)?;
         participants.insert(participant.id, participant.clone());
+        self.assign_role(participant.id, participant.kind);
+        self.heartbeats
+            .lock()
+            .unwrap()
+            .insert(participant.id, Instant::now());
 
         let _ = self
             .event_tx
             .send(RoomEvent::ParticipantJoined(participant.clone()));
 
-        participant
+        Ok(participant)
+    }
+
+    /// `name`, or a numbered variant if another connected participant has it
+    ///
+    /// Names are compared case-insensitively; disconnected participants
+    /// don't hold on to theirs.
+    fn unique_name(
+        &self,
+        participants: &HashMap<Uuid, Participant>,
+        id: Uuid,
+        name: String,
+    ) -> Result<String, NameTaken> {
+        let taken = |candidate: &str| {
+            let candidate = candidate.to_lowercase();
+            participants.values().any(|p| {
+                p.id != id
+                    && p.status != ParticipantStatus::Disconnected
+                    && p.name.to_lowercase() == candidate
+            })
+        };
+        if !taken(&name) {
+            return Ok(name);
+        }
+        match self.name_policy {
+            DuplicateNamePolicy::Reject => Err(NameTaken(name)),
+            DuplicateNamePolicy::Suffix => Ok((2..)
+                .map(|n| format!("{} ({})", name, n))
+                .find(|candidate| !taken(candidate))
+                .expect("some numbered name is free")),
+        }
+    }
+
+    /// Give a newcomer its role; someone who has been here before keeps theirs
+    ///
+    /// The first non-observer to join becomes the owner. Later arrivals are
+    /// members, and observers are always guests.
+    fn assign_role(&self, participant_id: Uuid, kind: ParticipantKind) -> JournalRole {
+        let mut roles = self.roles.lock().unwrap();
+        if let Some(&role) = roles.get(&participant_id) {
+            return role;
+        }
+        let role = if kind == ParticipantKind::Observer {
+            JournalRole::Guest
+        } else if roles.values().any(|role| *role == JournalRole::Owner) {
+            JournalRole::Member
+        } else {
+            JournalRole::Owner
+        };
+        roles.insert(participant_id, role);
+        role
+    }
+
+    /// Replace a participant's role with the one stored for `member`
+    ///
+    /// Storage outlives the room, so its answer wins over the first-come
+    /// role given on joining.
+    pub async fn adopt_role(&self, participant_id: Uuid, member: String, role: JournalRole) {
+        let _participants = self.participants.write().await;
+        self.roles.lock().unwrap().insert(participant_id, role);
+        self.members.lock().unwrap().insert(participant_id, member);
+    }
+
+    /// Who a participant's role is stored under, if it has been adopted
+    pub fn member(&self, participant_id: Uuid) -> Option<String> {
+        self.members.lock().unwrap().get(&participant_id).cloned()
+    }
+
+    /// A participant's role, if they have joined this room
+    pub fn role(&self, participant_id: Uuid) -> Option<JournalRole> {
+        self.roles.lock().unwrap().get(&participant_id).copied()
+    }
+
+    /// Change a present participant's role, broadcasting the change
+    ///
+    /// Fails if the participant is not in the room, or if it would leave the
+    /// journal without an owner.
+    pub async fn set_role(
+        &self,
+        participant_id: Uuid,
+        role: JournalRole,
+        source: Option<Uuid>,
+    ) -> crate::error::Result<()> {
+        let participants = self.participants.write().await;
+        if !participants.contains_key(&participant_id) {
+            return Err(crate::error::AppError::NotFound(format!(
+                "Participant {} is not in journal {}",
+                participant_id, self.journal_id
+            )));
+        }
+
+        let mut roles = self.roles.lock().unwrap();
+        let other_owners = roles
+            .iter()
+            .filter(|(id, r)| **id != participant_id && **r == JournalRole::Owner)
+            .count();
+        if role != JournalRole::Owner && other_owners == 0 {
+            return Err(crate::error::AppError::BadRequest(format!(
+                "Participant {} is the journal's only owner",
+                participant_id
+            )));
+        }
+        roles.insert(participant_id, role);
+
+        let _ = self.event_tx.send(RoomEvent::RoleChanged {
+            source,
+            participant_id,
+            role,
+        });
+        Ok(())
     }
 
     /// Remove a participant from the room
     pub async fn leave(&self, participant_id: Uuid) -> Option<Participant> {
         let mut participants = self.participants.write().await;
         let removed = participants.remove(&participant_id);
+        self.heartbeats.lock().unwrap().remove(&participant_id);
+        self.typing_refreshed
+            .lock()
+            .unwrap()
+            .remove(&participant_id);
 
         if removed.is_some() {
             let _ = self
@@ -135,15 +429,33 @@ impl JournalRoom {
         participant_id: Uuid,
         block_id: Option<Uuid>,
         offset: Option<u32>,
+    ) -> bool {
+        self.update_selection(participant_id, block_id, offset, offset)
+            .await
+    }
+
+    /// Update the range a participant has selected within a block
+    pub async fn update_selection(
+        &self,
+        participant_id: Uuid,
+        block_id: Option<Uuid>,
+        anchor: Option<u32>,
+        head: Option<u32>,
     ) -> bool {
         let mut participants = self.participants.write().await;
         if let Some(participant) = participants.get_mut(&participant_id) {
-            participant.set_cursor(block_id, offset);
+            participant.set_selection(block_id, anchor, head);
+            self.heartbeats
+                .lock()
+                .unwrap()
+                .insert(participant_id, Instant::now());
 
             let _ = self.event_tx.send(RoomEvent::CursorMoved {
                 participant_id,
                 block_id,
-                offset,
+                offset: head,
+                anchor,
+                head,
             });
 
             true
@@ -152,6 +464,147 @@ impl JournalRoom {
         }
     }
 
+    /// Set a participant's presence status, broadcasting the change
+    ///
+    /// Returns false if the participant is not in the room or the move isn't
+    /// allowed from its current status (which is logged and left unchanged).
+    pub async fn set_status(&self, participant_id: Uuid, status: ParticipantStatus) -> bool {
+        let mut participants = self.participants.write().await;
+        let Some(participant) = participants.get_mut(&participant_id) else {
+            return false;
+        };
+
+        if participant.status == status {
+            return true;
+        }
+        if let Err(e) = participant.set_status(status) {
+            tracing::warn!("Participant {}: {}", participant_id, e);
+            return false;
+        }
+        let _ = self.event_tx.send(RoomEvent::StatusChanged {
+            participant_id,
+            status,
+        });
+
+        true
+    }
+
+    /// Show or hide a participant's typing indicator, broadcasting changes
+    ///
+    /// An indicator that isn't refreshed within [`TYPING_TIMEOUT`] is cleared,
+    /// so a client that disappears mid-message doesn't leave it stuck on.
+    /// Returns false if the participant is not in the room.
+    pub async fn set_typing(self: &Arc<Self>, participant_id: Uuid, typing: bool) -> bool {
+        let mut participants = self.participants.write().await;
+        let Some(participant) = participants.get_mut(&participant_id) else {
+            return false;
+        };
+
+        let now = Instant::now();
+        self.heartbeats.lock().unwrap().insert(participant_id, now);
+        if typing {
+            self.typing_refreshed
+                .lock()
+                .unwrap()
+                .insert(participant_id, now);
+            let room = Arc::downgrade(self);
+            tokio::spawn(async move {
+                tokio::time::sleep(TYPING_TIMEOUT).await;
+                if let Some(room) = room.upgrade() {
+                    room.expire_typing(participant_id).await;
+                }
+            });
+        } else {
+            self.typing_refreshed
+                .lock()
+                .unwrap()
+                .remove(&participant_id);
+        }
+
+        if participant.typing != typing {
+            participant.typing = typing;
+            let _ = self.event_tx.send(RoomEvent::TypingChanged {
+                participant_id,
+                typing,
+            });
+        }
+        true
+    }
+
+    /// Clear a typing indicator that hasn't been refreshed in time
+    async fn expire_typing(&self, participant_id: Uuid) {
+        let mut participants = self.participants.write().await;
+        let stale = self
+            .typing_refreshed
+            .lock()
+            .unwrap()
+            .get(&participant_id)
+            .is_some_and(|refreshed| refreshed.elapsed() >= TYPING_TIMEOUT);
+        if !stale {
+            return;
+        }
+
+        self.typing_refreshed
+            .lock()
+            .unwrap()
+            .remove(&participant_id);
+        if let Some(participant) = participants.get_mut(&participant_id) {
+            participant.typing = false;
+            let _ = self.event_tx.send(RoomEvent::TypingChanged {
+                participant_id,
+                typing: false,
+            });
+        }
+    }
+
+    /// Record that a participant's client is still there
+    ///
+    /// Returns false if the participant is not in the room (e.g. it was reaped).
+    pub async fn heartbeat(&self, participant_id: Uuid) -> bool {
+        let mut participants = self.participants.write().await;
+        let Some(participant) = participants.get_mut(&participant_id) else {
+            return false;
+        };
+
+        participant.touch();
+        self.heartbeats
+            .lock()
+            .unwrap()
+            .insert(participant_id, Instant::now());
+        true
+    }
+
+    /// Remove participants not heard from within `timeout`
+    ///
+    /// Catches clients whose connection died without a close, which would
+    /// otherwise linger in presence. Returns the IDs removed.
+    pub async fn reap_silent_participants(&self, timeout: Duration) -> Vec<Uuid> {
+        let mut participants = self.participants.write().await;
+        let mut heartbeats = self.heartbeats.lock().unwrap();
+        let now = Instant::now();
+
+        let silent: Vec<Uuid> = participants
+            .keys()
+            .filter(|id| {
+                heartbeats
+                    .get(id)
+                    .is_none_or(|seen| now.duration_since(*seen) > timeout)
+            })
+            .copied()
+            .collect();
+
+        for participant_id in &silent {
+            participants.remove(participant_id);
+            heartbeats.remove(participant_id);
+            self.typing_refreshed.lock().unwrap().remove(participant_id);
+            let _ = self.event_tx.send(RoomEvent::ParticipantLeft {
+                participant_id: *participant_id,
+            });
+        }
+
+        silent
+    }
+
     /// Get all current participants
     pub async fn participants(&self) -> Vec<Participant> {
         let participants = self.participants.read().await;
@@ -183,14 +636,74 @@ impl JournalRoom {
         update: &[u8],
     ) -> Result<(), yrs::encoding::read::Error> {
         self.doc.apply_update(update)?;
+        self.mark_changed();
 
         // Broadcast to all other participants
+        self.broadcast_update(source, update.to_vec());
+
+        Ok(())
+    }
+
+    /// Number the update, remember it for replays and send it to subscribers
+    fn broadcast_update(&self, source: Option<Uuid>, update: Vec<u8>) {
+        let mut log = self.crdt_log.lock().unwrap();
+        log.last_seq += 1;
+        let seq = log.last_seq;
+        if log.recent.len() == CRDT_REPLAY_UPDATES {
+            log.recent.pop_front();
+        }
+        log.recent.push_back(SequencedUpdate {
+            seq,
+            source,
+            update: update.clone(),
+        });
         let _ = self.event_tx.send(RoomEvent::CrdtUpdate {
             source,
-            update: update.to_vec(),
+            seq,
+            update,
         });
+    }
 
-        Ok(())
+    /// The updates numbered `from_seq` onwards, oldest first
+    ///
+    /// Returns `None` if some of them have already been dropped from the
+    /// replay buffer, in which case the client needs a full sync instead.
+    pub fn updates_since(&self, from_seq: u64) -> Option<Vec<SequencedUpdate>> {
+        let log = self.crdt_log.lock().unwrap();
+        let oldest = log
+            .recent
+            .front()
+            .map(|u| u.seq)
+            .unwrap_or(log.last_seq + 1);
+        if from_seq.max(1) < oldest {
+            return None;
+        }
+        Some(
+            log.recent
+                .iter()
+                .filter(|u| u.seq >= from_seq)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Write the document to the snapshot store now, if the room has one
+    pub async fn persist(&self) -> crate::error::Result<()> {
+        match &self.snapshots {
+            Some(writer) => {
+                writer
+                    .store
+                    .save(self.journal_id, &self.doc.encode_state())
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn mark_changed(&self) {
+        if let Some(writer) = &self.snapshots {
+            writer.changed.send_modify(|_| {});
+        }
     }
 
     /// Get the full sync state for a new participant
@@ -204,6 +717,19 @@ impl JournalRoom {
         let _ = self.event_tx.send(RoomEvent::SyncState { state });
     }
 
+    /// Relay a block lifecycle event to every subscriber
+    pub fn broadcast_block_event(&self, origin: Uuid, event: crate::models::BlockEvent) {
+        let _ = self.event_tx.send(RoomEvent::BlockEvent { origin, event });
+    }
+
+    /// Tell every subscriber the journal's title changed
+    pub fn broadcast_rename(&self, title: impl Into<String>, origin: Option<Uuid>) {
+        let _ = self.event_tx.send(RoomEvent::JournalRenamed {
+            origin,
+            title: title.into(),
+        });
+    }
+
     /// Set content for a block and broadcast the update
     pub async fn set_block_content(&self, block_id: Uuid, content: &str, source: Option<Uuid>) {
         // Get state before
@@ -211,10 +737,11 @@ impl JournalRoom {
 
         // Apply the change
         self.doc.set_block_content(block_id, content);
+        self.mark_changed();
 
         // Compute the update (diff from before)
         if let Ok(update) = self.doc.encode_diff(&before_sv) {
-            let _ = self.event_tx.send(RoomEvent::CrdtUpdate { source, update });
+            self.broadcast_update(source, update);
         }
     }
 
@@ -223,9 +750,22 @@ impl JournalRoom {
         let before_sv = self.doc.state_vector();
 
         self.doc.append_block_content(block_id, delta);
+        self.mark_changed();
+
+        if let Ok(update) = self.doc.encode_diff(&before_sv) {
+            self.broadcast_update(source, update);
+        }
+    }
+
+    /// Remove a block from the document and broadcast the update
+    pub async fn delete_block(&self, block_id: Uuid, source: Option<Uuid>) {
+        let before_sv = self.doc.state_vector();
+
+        self.doc.delete_block(block_id);
+        self.mark_changed();
 
         if let Ok(update) = self.doc.encode_diff(&before_sv) {
-            let _ = self.event_tx.send(RoomEvent::CrdtUpdate { source, update });
+            self.broadcast_update(source, update);
         }
     }
 
@@ -238,69 +778,187 @@ impl JournalRoom {
         let mut participants = self.participants.write().await;
 
         for participant in participants.values_mut() {
-            if participant.is_stale(disconnect_timeout)
-                && participant.status != super::participant::ParticipantStatus::Disconnected
+            let next = if participant.is_stale(disconnect_timeout)
+                && participant.status != ParticipantStatus::Disconnected
             {
-                participant.mark_disconnected();
-                let _ = self.event_tx.send(RoomEvent::StatusChanged {
-                    participant_id: participant.id,
-                    status: participant.status,
-                });
+                ParticipantStatus::Disconnected
             } else if participant.is_stale(idle_timeout)
-                && participant.status == super::participant::ParticipantStatus::Active
+                && participant.status == ParticipantStatus::Active
             {
-                participant.mark_idle();
-                let _ = self.event_tx.send(RoomEvent::StatusChanged {
-                    participant_id: participant.id,
-                    status: participant.status,
-                });
+                ParticipantStatus::Idle
+            } else {
+                continue;
+            };
+
+            if let Err(e) = participant.set_status(next) {
+                tracing::warn!("Participant {}: {}", participant.id, e);
+                continue;
             }
+            let _ = self.event_tx.send(RoomEvent::StatusChanged {
+                participant_id: participant.id,
+                status: participant.status,
+            });
         }
     }
 }
 
+/// Returned when a new room is needed but the manager is at its limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Server is at capacity ({0} active journals); try again later")]
+pub struct CapacityExceeded(pub usize);
+
 /// Manager for all active journal rooms
 pub struct RoomManager {
-    rooms: RwLock<HashMap<Uuid, Arc<JournalRoom>>>,
+    /// Shared with the presence reaper, which stops once the manager is dropped
+    rooms: Arc<RwLock<HashMap<Uuid, Arc<JournalRoom>>>>,
+    /// Maximum number of live rooms (`usize::MAX` when unbounded)
+    max_rooms: AtomicUsize,
+    /// Sync states larger than this many bytes are sent in several frames
+    sync_chunk_bytes: AtomicUsize,
+    /// Where room documents are loaded from and snapshotted to, if anywhere
+    snapshot_store: std::sync::RwLock<Option<SnapshotStore>>,
+    /// How new rooms treat participants joining under a name already in use
+    name_policy: std::sync::RwLock<DuplicateNamePolicy>,
 }
 
+/// Default size above which sync states are split into chunks
+pub const DEFAULT_SYNC_CHUNK_BYTES: usize = 256 * 1024;
+
 impl RoomManager {
     pub fn new() -> Self {
         Self {
-            rooms: RwLock::new(HashMap::new()),
+            rooms: Arc::new(RwLock::new(HashMap::new())),
+            max_rooms: AtomicUsize::new(usize::MAX),
+            sync_chunk_bytes: AtomicUsize::new(DEFAULT_SYNC_CHUNK_BYTES),
+            snapshot_store: std::sync::RwLock::new(None),
+            name_policy: std::sync::RwLock::new(DuplicateNamePolicy::default()),
         }
     }
 
+    /// Persist room documents to `store`; affects rooms created afterwards
+    pub fn set_snapshot_store(&self, store: SnapshotStore) {
+        *self.snapshot_store.write().unwrap() = Some(store);
+    }
+
+    /// Choose how duplicate names are handled; affects rooms created afterwards
+    pub fn set_duplicate_name_policy(&self, policy: DuplicateNamePolicy) {
+        *self.name_policy.write().unwrap() = policy;
+    }
+
+    /// Cap the number of live rooms; existing rooms are unaffected
+    pub fn set_max_rooms(&self, max_rooms: usize) {
+        self.max_rooms.store(max_rooms, Ordering::Relaxed);
+    }
+
+    /// Size (in bytes of CRDT data) above which text sync states are chunked
+    pub fn sync_chunk_bytes(&self) -> usize {
+        self.sync_chunk_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Set the sync chunk threshold (minimum 1 byte)
+    pub fn set_sync_chunk_bytes(&self, bytes: usize) {
+        self.sync_chunk_bytes.store(bytes.max(1), Ordering::Relaxed);
+    }
+
     /// Get or create a room for a journal
-    pub async fn get_or_create(&self, journal_id: Uuid) -> Arc<JournalRoom> {
+    ///
+    /// At the room limit, the least recently used empty room is snapshotted
+    /// and evicted to make space; if every room is in use, rooms for new
+    /// journals are refused. With a snapshot store, a new room's document
+    /// starts from the journal's last snapshot.
+    pub async fn get_or_create(
+        &self,
+        journal_id: Uuid,
+    ) -> Result<Arc<JournalRoom>, CapacityExceeded> {
         {
             let rooms = self.rooms.read().await;
             if let Some(room) = rooms.get(&journal_id) {
-                return Arc::clone(room);
+                room.touch();
+                return Ok(Arc::clone(room));
             }
         }
 
+        // Load outside the lock so other journals aren't held up by the database
+        let mut room = self.open_room(journal_id).await;
+        room.name_policy = *self.name_policy.read().unwrap();
+
         let mut rooms = self.rooms.write().await;
         // Double-check after acquiring write lock
         if let Some(room) = rooms.get(&journal_id) {
-            return Arc::clone(room);
+            room.touch();
+            return Ok(Arc::clone(room));
+        }
+
+        let max_rooms = self.max_rooms.load(Ordering::Relaxed);
+        while rooms.len() >= max_rooms {
+            let mut oldest: Option<(Uuid, Instant)> = None;
+            for (id, room) in rooms.iter() {
+                if !room.is_empty().await {
+                    continue;
+                }
+                let last_used = room.last_used();
+                match oldest {
+                    Some((_, at)) if at <= last_used => {}
+                    _ => oldest = Some((*id, last_used)),
+                }
+            }
+            let Some((id, _)) = oldest else {
+                return Err(CapacityExceeded(max_rooms));
+            };
+            if let Some(evicted) = rooms.remove(&id) {
+                if let Err(e) = evicted.persist().await {
+                    tracing::warn!("Failed to snapshot evicted journal {}: {}", id, e);
+                }
+            }
         }
 
-        let room = Arc::new(JournalRoom::new(journal_id));
+        let room = Arc::new(room);
         rooms.insert(journal_id, Arc::clone(&room));
-        room
+        Ok(room)
+    }
+
+    /// Build a room, restoring its document from the snapshot store
+    ///
+    /// If the snapshot can't be read the room starts empty and is not
+    /// persisted, so the stored state is never overwritten with less.
+    async fn open_room(&self, journal_id: Uuid) -> JournalRoom {
+        let store = self.snapshot_store.read().unwrap().clone();
+        let Some(store) = store else {
+            return JournalRoom::new(journal_id);
+        };
+
+        let doc = match store.load(journal_id).await {
+            Ok(Some(state)) => match JournalDoc::from_update(journal_id, &state) {
+                Ok(doc) => doc,
+                Err(e) => {
+                    tracing::error!("Corrupt snapshot for journal {}: {}", journal_id, e);
+                    return JournalRoom::new(journal_id);
+                }
+            },
+            Ok(None) => JournalDoc::new(journal_id),
+            Err(e) => {
+                tracing::error!("Failed to load snapshot for journal {}: {}", journal_id, e);
+                return JournalRoom::new(journal_id);
+            }
+        };
+        JournalRoom::with_snapshot_store(Arc::new(doc), store)
     }
 
     /// Get a room if it exists
     pub async fn get(&self, journal_id: Uuid) -> Option<Arc<JournalRoom>> {
         let rooms = self.rooms.read().await;
-        rooms.get(&journal_id).cloned()
+        let room = rooms.get(&journal_id)?;
+        room.touch();
+        Some(Arc::clone(room))
     }
 
-    /// Remove a room (usually when empty)
+    /// Remove a room (usually when empty), snapshotting its document first
     pub async fn remove(&self, journal_id: Uuid) -> Option<Arc<JournalRoom>> {
-        let mut rooms = self.rooms.write().await;
-        rooms.remove(&journal_id)
+        let room = self.rooms.write().await.remove(&journal_id)?;
+        if let Err(e) = room.persist().await {
+            tracing::warn!("Failed to snapshot journal {}: {}", journal_id, e);
+        }
+        Some(room)
     }
 
     /// Remove empty rooms
@@ -321,6 +979,32 @@ impl RoomManager {
         }
     }
 
+    /// Periodically remove participants who haven't sent a heartbeat within `timeout`
+    ///
+    /// The task ends when the manager is dropped.
+    pub fn spawn_presence_reaper(&self, timeout: Duration) -> tokio::task::JoinHandle<()> {
+        let rooms = Arc::downgrade(&self.rooms);
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval((timeout / 4).max(Duration::from_secs(1)));
+            loop {
+                ticks.tick().await;
+                let Some(rooms) = Weak::upgrade(&rooms) else {
+                    break;
+                };
+                let rooms: Vec<_> = rooms.read().await.values().cloned().collect();
+                for room in rooms {
+                    for participant_id in room.reap_silent_participants(timeout).await {
+                        tracing::info!(
+                            "Reaped silent participant {} from journal {}",
+                            participant_id,
+                            room.journal_id()
+                        );
+                    }
+                }
+            }
+        })
+    }
+
     /// Get the number of active rooms
     pub async fn room_count(&self) -> usize {
         let rooms = self.rooms.read().await;
@@ -342,7 +1026,7 @@ mod tests {
     async fn test_room_join_and_leave() {
         let room = JournalRoom::new(Uuid::new_v4());
 
-        let participant = room.join("Alice", ParticipantKind::User).await;
+        let participant = room.join("Alice", ParticipantKind::User).await.unwrap();
         assert_eq!(room.participant_count().await, 1);
 
         room.leave(participant.id).await;
@@ -353,9 +1037,9 @@ mod tests {
     async fn test_room_multiple_participants() {
         let room = JournalRoom::new(Uuid::new_v4());
 
-        room.join("Alice", ParticipantKind::User).await;
-        room.join("Bob", ParticipantKind::User).await;
-        room.join("Agent", ParticipantKind::Agent).await;
+        room.join("Alice", ParticipantKind::User).await.unwrap();
+        room.join("Bob", ParticipantKind::User).await.unwrap();
+        room.join("Agent", ParticipantKind::Agent).await.unwrap();
 
         assert_eq!(room.participant_count().await, 3);
 
@@ -363,10 +1047,42 @@ mod tests {
         assert_eq!(participants.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_first_to_join_owns_the_room() {
+        let room = JournalRoom::new(Uuid::new_v4());
+
+        let watcher = room
+            .join("Monitor", ParticipantKind::Observer)
+            .await
+            .unwrap();
+        let alice = room.join("Alice", ParticipantKind::User).await.unwrap();
+        let bob = room.join("Bob", ParticipantKind::User).await.unwrap();
+        assert_eq!(room.role(watcher.id), Some(JournalRole::Guest));
+        assert_eq!(room.role(alice.id), Some(JournalRole::Owner));
+        assert_eq!(room.role(bob.id), Some(JournalRole::Member));
+
+        // The only owner can't step down, but can once someone else owns it
+        assert!(room
+            .set_role(alice.id, JournalRole::Member, Some(alice.id))
+            .await
+            .is_err());
+        room.set_role(bob.id, JournalRole::Owner, Some(alice.id))
+            .await
+            .unwrap();
+        room.set_role(alice.id, JournalRole::Guest, Some(alice.id))
+            .await
+            .unwrap();
+
+        // Roles survive leaving and rejoining
+        room.leave(alice.id).await;
+        let alice = room.rejoin(alice).await.unwrap();
+        assert_eq!(room.role(alice.id), Some(JournalRole::Guest));
+    }
+
     #[tokio::test]
     async fn test_room_update_cursor() {
         let room = JournalRoom::new(Uuid::new_v4());
-        let participant = room.join("Alice", ParticipantKind::User).await;
+        let participant = room.join("Alice", ParticipantKind::User).await.unwrap();
         let block_id = Uuid::new_v4();
 
         let updated = room
@@ -391,7 +1107,7 @@ mod tests {
         let room = JournalRoom::new(Uuid::new_v4());
         let mut receiver = room.subscribe();
 
-        room.join("Alice", ParticipantKind::User).await;
+        room.join("Alice", ParticipantKind::User).await.unwrap();
 
         let event = receiver.try_recv().unwrap();
         match event {
@@ -436,8 +1152,8 @@ mod tests {
         let manager = RoomManager::new();
         let journal_id = Uuid::new_v4();
 
-        let room1 = manager.get_or_create(journal_id).await;
-        let room2 = manager.get_or_create(journal_id).await;
+        let room1 = manager.get_or_create(journal_id).await.unwrap();
+        let room2 = manager.get_or_create(journal_id).await.unwrap();
 
         assert!(Arc::ptr_eq(&room1, &room2));
     }
@@ -448,13 +1164,65 @@ mod tests {
         let j1 = Uuid::new_v4();
         let j2 = Uuid::new_v4();
 
-        let room1 = manager.get_or_create(j1).await;
-        let room2 = manager.get_or_create(j2).await;
+        let room1 = manager.get_or_create(j1).await.unwrap();
+        let room2 = manager.get_or_create(j2).await.unwrap();
 
         assert!(!Arc::ptr_eq(&room1, &room2));
         assert_eq!(manager.room_count().await, 2);
     }
 
+    #[tokio::test]
+    async fn test_room_manager_max_rooms() {
+        let manager = RoomManager::new();
+        manager.set_max_rooms(2);
+
+        let mut occupants = Vec::new();
+        for _ in 0..2 {
+            let room = manager.get_or_create(Uuid::new_v4()).await.unwrap();
+            let participant = room.join("Alice", ParticipantKind::User).await.unwrap();
+            occupants.push((room, participant));
+        }
+
+        // Full of busy rooms: a new journal is refused, existing ones still resolve
+        let newcomer = Uuid::new_v4();
+        let Err(e) = manager.get_or_create(newcomer).await else {
+            panic!("Expected the new room to be refused");
+        };
+        assert_eq!(e, CapacityExceeded(2));
+        let existing = occupants[0].0.journal_id();
+        assert!(manager.get_or_create(existing).await.is_ok());
+
+        // Once a room empties it is evicted to make space
+        let (room, participant) = &occupants[0];
+        room.leave(participant.id).await;
+        assert!(manager.get_or_create(newcomer).await.is_ok());
+        assert_eq!(manager.room_count().await, 2);
+        assert!(manager.get(existing).await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_room_manager_evicts_least_recently_used_room() {
+        let manager = RoomManager::new();
+        manager.set_max_rooms(2);
+
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        manager.get_or_create(first).await.unwrap();
+        tokio::time::advance(Duration::from_secs(1)).await;
+        manager.get_or_create(second).await.unwrap();
+        tokio::time::advance(Duration::from_secs(1)).await;
+
+        // Looking the first room up again makes the second the stalest
+        manager.get(first).await.unwrap();
+        tokio::time::advance(Duration::from_secs(1)).await;
+
+        let third = Uuid::new_v4();
+        manager.get_or_create(third).await.unwrap();
+        assert_eq!(manager.room_count().await, 2);
+        assert!(manager.get(second).await.is_none());
+        assert!(manager.get(first).await.is_some());
+        assert!(manager.get(third).await.is_some());
+    }
+
     #[tokio::test]
     async fn test_room_manager_get_nonexistent() {
         let manager = RoomManager::new();
@@ -467,7 +1235,7 @@ mod tests {
         let manager = RoomManager::new();
         let journal_id = Uuid::new_v4();
 
-        manager.get_or_create(journal_id).await;
+        manager.get_or_create(journal_id).await.unwrap();
         assert_eq!(manager.room_count().await, 1);
 
         manager.remove(journal_id).await;
@@ -479,8 +1247,8 @@ mod tests {
         let manager = RoomManager::new();
         let journal_id = Uuid::new_v4();
 
-        let room = manager.get_or_create(journal_id).await;
-        let participant = room.join("Alice", ParticipantKind::User).await;
+        let room = manager.get_or_create(journal_id).await.unwrap();
+        let participant = room.join("Alice", ParticipantKind::User).await.unwrap();
 
         // Room has participant, shouldn't be cleaned up
         manager.cleanup_empty_rooms().await;
@@ -499,7 +1267,7 @@ mod tests {
         let room = JournalRoom::new(Uuid::new_v4());
         assert!(room.is_empty().await);
 
-        let participant = room.join("Alice", ParticipantKind::User).await;
+        let participant = room.join("Alice", ParticipantKind::User).await.unwrap();
         assert!(!room.is_empty().await);
 
         room.leave(participant.id).await;
@@ -509,7 +1277,7 @@ mod tests {
     #[tokio::test]
     async fn test_room_rejoin() {
         let room = JournalRoom::new(Uuid::new_v4());
-        let original = room.join("Alice", ParticipantKind::User).await;
+        let original = room.join("Alice", ParticipantKind::User).await.unwrap();
         let original_id = original.id;
 
         room.leave(original_id).await;
@@ -517,13 +1285,59 @@ mod tests {
 
         // Rejoin with same ID
         let participant = Participant::with_id(original_id, "Alice", ParticipantKind::User);
-        room.rejoin(participant).await;
+        room.rejoin(participant).await.unwrap();
 
         let p = room.get_participant(original_id).await;
         assert!(p.is_some());
         assert_eq!(p.unwrap().id, original_id);
     }
 
+    #[tokio::test]
+    async fn test_room_numbers_duplicate_names() {
+        let room = JournalRoom::new(Uuid::new_v4());
+        let first = room.join("Alice", ParticipantKind::User).await.unwrap();
+        let second = room.join("Alice", ParticipantKind::User).await.unwrap();
+        let third = room.join("alice", ParticipantKind::Agent).await.unwrap();
+
+        assert_eq!(first.name, "Alice");
+        assert_eq!(second.name, "Alice (2)");
+        assert_eq!(third.name, "alice (3)");
+        assert_eq!(
+            room.get_participant(second.id).await.unwrap().name,
+            "Alice (2)"
+        );
+
+        // Once the name is free again it is handed out as-is
+        room.leave(first.id).await;
+        let fourth = room.join("Alice", ParticipantKind::User).await.unwrap();
+        assert_eq!(fourth.name, "Alice");
+    }
+
+    #[tokio::test]
+    async fn test_room_manager_can_reject_duplicate_names() {
+        let manager = RoomManager::new();
+        manager.set_duplicate_name_policy(DuplicateNamePolicy::Reject);
+        let room = manager.get_or_create(Uuid::new_v4()).await.unwrap();
+
+        let alice = room.join("Alice", ParticipantKind::User).await.unwrap();
+        assert_eq!(
+            room.join("Alice", ParticipantKind::User).await.unwrap_err(),
+            NameTaken("Alice".to_string())
+        );
+        assert_eq!(room.participant_count().await, 1);
+
+        // Reconnecting under your own name isn't a clash
+        let rejoined = room
+            .rejoin(Participant::with_id(
+                alice.id,
+                "Alice",
+                ParticipantKind::User,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(rejoined.name, "Alice");
+    }
+
     #[tokio::test]
     async fn test_room_with_doc() {
         let journal_id = Uuid::new_v4();
@@ -563,7 +1377,7 @@ mod tests {
     #[tokio::test]
     async fn test_room_leave_event() {
         let room = JournalRoom::new(Uuid::new_v4());
-        let participant = room.join("Alice", ParticipantKind::User).await;
+        let participant = room.join("Alice", ParticipantKind::User).await.unwrap();
         let participant_id = participant.id;
 
         let mut receiver = room.subscribe();
@@ -586,7 +1400,7 @@ mod tests {
     #[tokio::test]
     async fn test_room_cursor_moved_event() {
         let room = JournalRoom::new(Uuid::new_v4());
-        let participant = room.join("Alice", ParticipantKind::User).await;
+        let participant = room.join("Alice", ParticipantKind::User).await.unwrap();
         let mut receiver = room.subscribe();
 
         // Clear the join event
@@ -602,12 +1416,194 @@ mod tests {
                 participant_id,
                 block_id: bid,
                 offset,
+                anchor,
+                head,
             } => {
                 assert_eq!(participant_id, participant.id);
                 assert_eq!(bid, Some(block_id));
                 assert_eq!(offset, Some(10));
+                assert_eq!((anchor, head), (Some(10), Some(10)));
             }
             _ => panic!("Expected CursorMoved event"),
         }
     }
+
+    #[tokio::test]
+    async fn test_room_set_status() {
+        let room = JournalRoom::new(Uuid::new_v4());
+        let participant = room.join("Bot", ParticipantKind::Agent).await.unwrap();
+        let mut receiver = room.subscribe();
+
+        let updated = room
+            .set_status(participant.id, ParticipantStatus::Busy)
+            .await;
+        assert!(updated);
+        let p = room.get_participant(participant.id).await.unwrap();
+        assert_eq!(p.status, ParticipantStatus::Busy);
+
+        match receiver.try_recv().unwrap() {
+            RoomEvent::StatusChanged {
+                participant_id,
+                status,
+            } => {
+                assert_eq!(participant_id, participant.id);
+                assert_eq!(status, ParticipantStatus::Busy);
+            }
+            _ => panic!("Expected StatusChanged event"),
+        }
+
+        // Unknown participants are reported, not inserted
+        let updated = room
+            .set_status(Uuid::new_v4(), ParticipantStatus::Busy)
+            .await;
+        assert!(!updated);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_presence_reaper_removes_silent_participants() {
+        let manager = RoomManager::new();
+        let room = manager.get_or_create(Uuid::new_v4()).await.unwrap();
+        let alice = room.join("Alice", ParticipantKind::User).await.unwrap();
+        let bob = room.join("Bob", ParticipantKind::User).await.unwrap();
+        let mut receiver = room.subscribe();
+
+        let _reaper = manager.spawn_presence_reaper(Duration::from_secs(60));
+
+        // Bob keeps heartbeating; Alice's connection has silently died
+        for _ in 0..4 {
+            tokio::time::sleep(Duration::from_secs(20)).await;
+            assert!(room.heartbeat(bob.id).await);
+        }
+
+        let participants = room.participants().await;
+        assert_eq!(participants.len(), 1);
+        assert_eq!(participants[0].id, bob.id);
+        assert!(!room.heartbeat(alice.id).await);
+
+        match receiver.try_recv().unwrap() {
+            RoomEvent::ParticipantLeft { participant_id } => assert_eq!(participant_id, alice.id),
+            other => panic!("Expected ParticipantLeft, got {:?}", other),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_typing_clears_without_refresh() {
+        let room = Arc::new(JournalRoom::new(Uuid::new_v4()));
+        let alice = room.join("Alice", ParticipantKind::User).await.unwrap();
+        let mut receiver = room.subscribe();
+
+        assert!(room.set_typing(alice.id, true).await);
+        assert!(room.get_participant(alice.id).await.unwrap().typing);
+
+        // A refresh halfway through keeps the indicator on past the first deadline
+        tokio::time::sleep(TYPING_TIMEOUT / 2).await;
+        assert!(room.set_typing(alice.id, true).await);
+        tokio::time::sleep(TYPING_TIMEOUT / 2 + Duration::from_millis(10)).await;
+        assert!(room.get_participant(alice.id).await.unwrap().typing);
+
+        tokio::time::sleep(TYPING_TIMEOUT).await;
+        assert!(!room.get_participant(alice.id).await.unwrap().typing);
+
+        let events: Vec<_> = std::iter::from_fn(|| receiver.try_recv().ok())
+            .map(|event| match event {
+                RoomEvent::TypingChanged {
+                    participant_id,
+                    typing,
+                } => {
+                    assert_eq!(participant_id, alice.id);
+                    typing
+                }
+                other => panic!("Expected TypingChanged, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(events, [true, false]);
+    }
+
+    #[tokio::test]
+    async fn test_resync_replays_a_missed_update() {
+        let journal_id = Uuid::new_v4();
+        let room = JournalRoom::new(journal_id);
+        let mut rx = room.subscribe();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let writer = JournalDoc::new(journal_id);
+        for (block_id, content) in [(a, "First"), (b, "Second")] {
+            let before = writer.state_vector();
+            writer.set_block_content(block_id, content);
+            let update = writer.encode_diff(&before).unwrap();
+            room.apply_update(None, &update).await.unwrap();
+        }
+
+        // The reader loses the first broadcast and only applies the second
+        let reader = JournalDoc::new(journal_id);
+        let mut seqs = Vec::new();
+        while let Ok(RoomEvent::CrdtUpdate { seq, update, .. }) = rx.try_recv() {
+            seqs.push(seq);
+            if seq > 1 {
+                reader.apply_update(&update).unwrap();
+            }
+        }
+        assert_eq!(seqs, [1, 2]);
+        assert_eq!(reader.get_block_content(a), None);
+
+        for missed in room.updates_since(1).unwrap() {
+            reader.apply_update(&missed.update).unwrap();
+        }
+        assert_eq!(reader.get_block_content(a), Some("First".to_string()));
+        assert_eq!(reader.get_block_content(b), Some("Second".to_string()));
+        assert_eq!(reader.state_vector(), room.doc().state_vector());
+    }
+
+    #[tokio::test]
+    async fn test_updates_since_an_evicted_seq_needs_full_sync() {
+        let room = JournalRoom::new(Uuid::new_v4());
+        assert!(room.updates_since(1).unwrap().is_empty());
+
+        for i in 0..=CRDT_REPLAY_UPDATES {
+            room.set_block_content(Uuid::new_v4(), &i.to_string(), None)
+                .await;
+        }
+        assert!(room.updates_since(1).is_none());
+        let replay = room.updates_since(2).unwrap();
+        assert_eq!(replay.len(), CRDT_REPLAY_UPDATES);
+        assert_eq!(replay[0].seq, 2);
+    }
+
+    #[tokio::test]
+    async fn test_room_restored_from_snapshot() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let journal = crate::store::Store::new(pool.clone())
+            .create_journal(None)
+            .await
+            .unwrap();
+
+        // A client's edit arrives as an update made against its own document
+        let block_id = Uuid::new_v4();
+        let client = JournalDoc::new(journal.id);
+        client.set_block_content(block_id, "Persisted");
+        let update = client.encode_state();
+
+        let manager = RoomManager::new();
+        manager.set_snapshot_store(SnapshotStore::new(pool.clone()));
+        let room = manager.get_or_create(journal.id).await.unwrap();
+        room.apply_update(None, &update).await.unwrap();
+        let state_vector = room.doc().state_vector();
+        drop(room);
+        manager.remove(journal.id).await;
+        drop(manager);
+
+        let manager = RoomManager::new();
+        manager.set_snapshot_store(SnapshotStore::new(pool));
+        let room = manager.get_or_create(journal.id).await.unwrap();
+        assert_eq!(room.doc().state_vector(), state_vector);
+        assert_eq!(
+            room.doc().get_block_content(block_id),
+            Some("Persisted".to_string())
+        );
+    }
 }
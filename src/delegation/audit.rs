@@ -0,0 +1,89 @@
+//! Audit trails for delegation
+//!
+//! Authorization failures are returned to the caller as usual, but repeated
+//! attempts are a security signal, so the manager also records them here.
+//!
+//! Separately, a manager backed by a database appends every event it emits to
+//! the `delegation_audit` table, read back as [`DelegationAuditEntry`] rows.
+
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::capability::Capability;
+
+/// Number of recent failures kept in memory
+const MAX_ENTRIES: usize = 1000;
+
+/// A delegation action refused for lack of authority
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorizationFailure {
+    /// Participant who attempted the action
+    pub actor_id: Uuid,
+    /// Manager operation that was attempted (e.g. `cancel_work`)
+    pub action: String,
+    /// Why it was refused
+    pub reason: String,
+    /// Capability the actor lacked, if that was the reason
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capability: Option<Capability>,
+    pub at: DateTime<Utc>,
+}
+
+/// One delegation event as recorded in the persistent audit table
+#[derive(Debug, Clone, Serialize)]
+pub struct DelegationAuditEntry {
+    pub id: i64,
+    /// Event name, e.g. `work_delegated`
+    pub event_type: String,
+    /// Participant who performed the action, when the event names one
+    pub actor_id: Option<Uuid>,
+    pub work_item_id: Option<Uuid>,
+    /// The full event as it was emitted
+    pub details: serde_json::Value,
+    pub at: DateTime<Utc>,
+}
+
+/// Running counters for delegation activity
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DelegationStats {
+    /// Total refused actions since startup
+    pub authorization_failures: u64,
+    /// Refusals for a missing capability, by capability
+    pub capability_failures: HashMap<Capability, u64>,
+}
+
+/// Bounded log of authorization failures plus lifetime counters
+#[derive(Debug, Default)]
+pub(crate) struct AuditLog {
+    entries: VecDeque<AuthorizationFailure>,
+    stats: DelegationStats,
+}
+
+impl AuditLog {
+    pub(crate) fn record(&mut self, failure: AuthorizationFailure) {
+        self.stats.authorization_failures += 1;
+        if let Some(capability) = failure.capability {
+            *self
+                .stats
+                .capability_failures
+                .entry(capability)
+                .or_default() += 1;
+        }
+
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(failure);
+    }
+
+    pub(crate) fn entries(&self) -> Vec<AuthorizationFailure> {
+        self.entries.iter().cloned().collect()
+    }
+
+    pub(crate) fn stats(&self) -> DelegationStats {
+        self.stats.clone()
+    }
+}
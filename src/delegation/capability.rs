@@ -19,6 +19,8 @@ pub enum Capability {
     Delegate,
     /// Can approve or reject work submitted by others
     Approve,
+    /// Can manage other participants' availability (e.g. take a team off duty)
+    Coordinate,
     /// Full administrative access (includes all other capabilities)
     Admin,
 }
@@ -32,6 +34,7 @@ impl Capability {
             Capability::Fork => "fork",
             Capability::Delegate => "delegate",
             Capability::Approve => "approve",
+            Capability::Coordinate => "coordinate",
             Capability::Admin => "admin",
         }
     }
@@ -44,6 +47,7 @@ impl Capability {
         caps.insert(Capability::Fork);
         caps.insert(Capability::Delegate);
         caps.insert(Capability::Approve);
+        caps.insert(Capability::Coordinate);
         caps.insert(Capability::Admin);
         caps
     }
@@ -88,6 +92,7 @@ impl std::str::FromStr for Capability {
             "fork" => Ok(Capability::Fork),
             "delegate" => Ok(Capability::Delegate),
             "approve" => Ok(Capability::Approve),
+            "coordinate" => Ok(Capability::Coordinate),
             "admin" => Ok(Capability::Admin),
             _ => Err(format!("Invalid capability: {}", s)),
         }
@@ -169,6 +174,7 @@ mod tests {
         assert_eq!(Capability::Fork.as_str(), "fork");
         assert_eq!(Capability::Delegate.as_str(), "delegate");
         assert_eq!(Capability::Approve.as_str(), "approve");
+        assert_eq!(Capability::Coordinate.as_str(), "coordinate");
         assert_eq!(Capability::Admin.as_str(), "admin");
     }
 
@@ -185,6 +191,10 @@ mod tests {
             "approve".parse::<Capability>().unwrap(),
             Capability::Approve
         );
+        assert_eq!(
+            "coordinate".parse::<Capability>().unwrap(),
+            Capability::Coordinate
+        );
         assert_eq!("admin".parse::<Capability>().unwrap(), Capability::Admin);
     }
 
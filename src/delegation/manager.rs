@@ -7,17 +7,27 @@
 //! - Approval request/response flows
 //! - Event broadcasting
 
+use serde::Serialize;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
 
+use super::audit::{AuditLog, AuthorizationFailure, DelegationAuditEntry, DelegationStats};
 use super::capability::{Capability, CapabilitySet};
+use super::notify::{AssignmentWebhooks, NotificationSink};
 use super::participant::RegisteredParticipant;
-use super::work_item::{ApprovalRequest, WorkItem, WorkItemStatus, WorkPriority};
+use super::work_item::{
+    ApprovalRequest, ApprovalStatus, WorkItem, WorkItemComment, WorkItemStatus, WorkPriority,
+    UNASSIGNED,
+};
 use crate::crdt::{Participant, ParticipantKind};
+use crate::delegation_store::DelegationStore;
+use crate::error::{AppError, ErrorCode};
 
 /// Events emitted by the delegation manager
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum DelegationEvent {
     /// A participant was registered
     ParticipantRegistered {
@@ -47,6 +57,16 @@ pub enum DelegationEvent {
         work_item_id: Uuid,
         assignee_id: Uuid,
     },
+    /// Work was paused by the assignee
+    WorkPaused {
+        work_item_id: Uuid,
+        assignee_id: Uuid,
+    },
+    /// Paused work was resumed by the assignee
+    WorkResumed {
+        work_item_id: Uuid,
+        assignee_id: Uuid,
+    },
     /// Work was submitted for approval
     ApprovalRequested {
         approval_id: Uuid,
@@ -54,6 +74,21 @@ pub enum DelegationEvent {
         requester_id: Uuid,
         approver_id: Uuid,
     },
+    /// A pending submission was withdrawn by the assignee
+    SubmissionWithdrawn {
+        work_item_id: Uuid,
+        assignee_id: Uuid,
+    },
+    /// One of several approvers signed off; the work still awaits the rest
+    ApprovalGranted {
+        approval_id: Uuid,
+        work_item_id: Uuid,
+        approver_id: Uuid,
+        /// Approvals so far for this submission
+        approvals: usize,
+        /// Approvals needed
+        required: usize,
+    },
     /// Work was approved
     WorkApproved {
         work_item_id: Uuid,
@@ -76,6 +111,32 @@ pub enum DelegationEvent {
         work_item_id: Uuid,
         claimed_by: Uuid,
     },
+    /// The assignee left before finishing, so the work went back to the unassigned pool
+    WorkUnassigned {
+        work_item_id: Uuid,
+        previous_assignee_id: Uuid,
+    },
+    /// Work was moved to a different assignee by its delegator
+    WorkReassigned {
+        work_item_id: Uuid,
+        previous_assignee_id: Uuid,
+        new_assignee_id: Uuid,
+        reassigned_by: Uuid,
+    },
+    /// A pending approval was handed to a different approver
+    ApproverReassigned {
+        approval_id: Uuid,
+        work_item_id: Uuid,
+        previous_approver_id: Uuid,
+        new_approver_id: Uuid,
+    },
+    /// A party to the work left a comment on it
+    WorkCommentAdded {
+        comment_id: Uuid,
+        work_item_id: Uuid,
+        author_id: Uuid,
+        text: String,
+    },
     /// Participant status changed (accepting work or not)
     ParticipantStatusChanged {
         participant_id: Uuid,
@@ -83,6 +144,91 @@ pub enum DelegationEvent {
     },
 }
 
+impl DelegationEvent {
+    /// Event name as used in serialized payloads (e.g. `work_delegated`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            DelegationEvent::ParticipantRegistered { .. } => "participant_registered",
+            DelegationEvent::CapabilitiesChanged { .. } => "capabilities_changed",
+            DelegationEvent::WorkDelegated { .. } => "work_delegated",
+            DelegationEvent::WorkAccepted { .. } => "work_accepted",
+            DelegationEvent::WorkDeclined { .. } => "work_declined",
+            DelegationEvent::WorkPaused { .. } => "work_paused",
+            DelegationEvent::WorkResumed { .. } => "work_resumed",
+            DelegationEvent::ApprovalRequested { .. } => "approval_requested",
+            DelegationEvent::SubmissionWithdrawn { .. } => "submission_withdrawn",
+            DelegationEvent::ApprovalGranted { .. } => "approval_granted",
+            DelegationEvent::WorkApproved { .. } => "work_approved",
+            DelegationEvent::WorkRejected { .. } => "work_rejected",
+            DelegationEvent::WorkCancelled { .. } => "work_cancelled",
+            DelegationEvent::WorkClaimed { .. } => "work_claimed",
+            DelegationEvent::WorkReassigned { .. } => "work_reassigned",
+            DelegationEvent::ApproverReassigned { .. } => "approver_reassigned",
+            DelegationEvent::WorkCommentAdded { .. } => "work_comment_added",
+            DelegationEvent::WorkUnassigned { .. } => "work_unassigned",
+            DelegationEvent::ParticipantStatusChanged { .. } => "participant_status_changed",
+        }
+    }
+
+    /// Participant who performed the action, if the event records one
+    pub fn actor_id(&self) -> Option<Uuid> {
+        match self {
+            DelegationEvent::ParticipantRegistered { participant_id, .. }
+            | DelegationEvent::ParticipantStatusChanged { participant_id, .. } => {
+                Some(*participant_id)
+            }
+            DelegationEvent::CapabilitiesChanged { .. } => None,
+            DelegationEvent::WorkDelegated { delegator_id, .. } => Some(*delegator_id),
+            DelegationEvent::WorkAccepted { assignee_id, .. }
+            | DelegationEvent::WorkDeclined { assignee_id, .. }
+            | DelegationEvent::WorkPaused { assignee_id, .. }
+            | DelegationEvent::WorkResumed { assignee_id, .. }
+            | DelegationEvent::SubmissionWithdrawn { assignee_id, .. } => Some(*assignee_id),
+            DelegationEvent::ApprovalRequested { requester_id, .. } => Some(*requester_id),
+            DelegationEvent::ApprovalGranted { approver_id, .. }
+            | DelegationEvent::WorkApproved { approver_id, .. }
+            | DelegationEvent::WorkRejected { approver_id, .. } => Some(*approver_id),
+            DelegationEvent::WorkCancelled { cancelled_by, .. } => Some(*cancelled_by),
+            DelegationEvent::WorkClaimed { claimed_by, .. } => Some(*claimed_by),
+            DelegationEvent::WorkReassigned { reassigned_by, .. } => Some(*reassigned_by),
+            DelegationEvent::WorkCommentAdded { author_id, .. } => Some(*author_id),
+            DelegationEvent::WorkUnassigned {
+                previous_assignee_id,
+                ..
+            } => Some(*previous_assignee_id),
+            DelegationEvent::ApproverReassigned {
+                previous_approver_id,
+                ..
+            } => Some(*previous_approver_id),
+        }
+    }
+
+    /// Work item the event concerns, if any
+    pub fn work_item_id(&self) -> Option<Uuid> {
+        match self {
+            DelegationEvent::ParticipantRegistered { .. }
+            | DelegationEvent::CapabilitiesChanged { .. }
+            | DelegationEvent::ParticipantStatusChanged { .. } => None,
+            DelegationEvent::WorkDelegated { work_item_id, .. }
+            | DelegationEvent::WorkAccepted { work_item_id, .. }
+            | DelegationEvent::WorkDeclined { work_item_id, .. }
+            | DelegationEvent::WorkPaused { work_item_id, .. }
+            | DelegationEvent::WorkResumed { work_item_id, .. }
+            | DelegationEvent::ApprovalRequested { work_item_id, .. }
+            | DelegationEvent::SubmissionWithdrawn { work_item_id, .. }
+            | DelegationEvent::ApprovalGranted { work_item_id, .. }
+            | DelegationEvent::WorkApproved { work_item_id, .. }
+            | DelegationEvent::WorkRejected { work_item_id, .. }
+            | DelegationEvent::WorkCancelled { work_item_id, .. }
+            | DelegationEvent::WorkClaimed { work_item_id, .. }
+            | DelegationEvent::WorkReassigned { work_item_id, .. }
+            | DelegationEvent::ApproverReassigned { work_item_id, .. }
+            | DelegationEvent::WorkCommentAdded { work_item_id, .. }
+            | DelegationEvent::WorkUnassigned { work_item_id, .. } => Some(*work_item_id),
+        }
+    }
+}
+
 /// Error types for delegation operations
 #[derive(Debug, Clone)]
 pub enum DelegationError {
@@ -103,6 +249,10 @@ pub enum DelegationError {
     InvalidStateTransition(String),
     /// Not authorized for this operation
     NotAuthorized(String),
+    /// The work item names a journal or block that doesn't exist
+    MissingReference(String),
+    /// The work item could not be saved
+    Storage(String),
 }
 
 impl std::fmt::Display for DelegationError {
@@ -137,12 +287,67 @@ impl std::fmt::Display for DelegationError {
             DelegationError::NotAuthorized(msg) => {
                 write!(f, "Not authorized: {}", msg)
             }
+            DelegationError::MissingReference(what) => {
+                write!(f, "Not found: {}", what)
+            }
+            DelegationError::Storage(msg) => {
+                write!(f, "Failed to save work: {}", msg)
+            }
         }
     }
 }
 
 impl std::error::Error for DelegationError {}
 
+impl DelegationError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            DelegationError::ParticipantNotFound(_)
+            | DelegationError::WorkItemNotFound(_)
+            | DelegationError::ApprovalNotFound(_)
+            | DelegationError::MissingReference(_) => ErrorCode::NotFound,
+            DelegationError::InsufficientCapability { .. } => ErrorCode::InsufficientCapability,
+            DelegationError::NotAcceptingWork(_) | DelegationError::InvalidStateTransition(_) => {
+                ErrorCode::InvalidState
+            }
+            DelegationError::NotAuthorized(_) => ErrorCode::NotAuthorized,
+            DelegationError::Storage(_) => ErrorCode::Internal,
+        }
+    }
+
+    /// Describe a failure to save new work, calling out references that don't exist
+    fn from_storage(items: &[WorkItem], e: AppError) -> Self {
+        match e {
+            AppError::Database(sqlx::Error::Database(db)) if db.is_foreign_key_violation() => {
+                let refs: Vec<String> = items
+                    .iter()
+                    .flat_map(|item| {
+                        std::iter::once(format!("journal {}", item.journal_id))
+                            .chain(item.block_id.map(|id| format!("block {}", id)))
+                    })
+                    .collect();
+                DelegationError::MissingReference(refs.join(" or "))
+            }
+            e => DelegationError::Storage(e.to_string()),
+        }
+    }
+}
+
+/// How much delegated work a participant has been involved in
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ParticipantStats {
+    /// Items the participant handed to someone else
+    pub delegated_out: usize,
+    /// Items ever assigned to the participant, in any status
+    pub assigned: usize,
+    /// Assigned items that were approved
+    pub completed: usize,
+    pub declined: usize,
+    pub cancelled: usize,
+    /// Approval requests waiting on the participant's decision
+    pub pending_approvals: usize,
+}
+
 /// Result type for delegation operations
 pub type DelegationResult<T> = Result<T, DelegationError>;
 
@@ -158,8 +363,21 @@ pub struct DelegationManager {
     work_queues: RwLock<HashMap<Uuid, Vec<Uuid>>>,
     /// Pending approvals per participant (approver_id -> approval_ids)
     approval_queues: RwLock<HashMap<Uuid, Vec<Uuid>>>,
+    /// Comments per work item, oldest first
+    comments: RwLock<HashMap<Uuid, Vec<WorkItemComment>>>,
     /// Event broadcaster
     event_tx: broadcast::Sender<DelegationEvent>,
+    /// External notification sinks
+    sinks: std::sync::RwLock<Vec<Arc<dyn NotificationSink>>>,
+    /// Delivery to participants' own assignment webhooks
+    assignment_webhooks: AssignmentWebhooks,
+    /// Refused actions, for spotting abuse
+    audit: std::sync::Mutex<AuditLog>,
+    /// Write-through storage for work items and approvals, if configured
+    persistence: Option<DelegationStore>,
+    /// How long a disconnected participant keeps its work before it is
+    /// released, or `None` to keep it until they unregister
+    release_grace: std::sync::Mutex<Option<std::time::Duration>>,
 }
 
 impl DelegationManager {
@@ -172,7 +390,121 @@ impl DelegationManager {
             approvals: RwLock::new(HashMap::new()),
             work_queues: RwLock::new(HashMap::new()),
             approval_queues: RwLock::new(HashMap::new()),
+            comments: RwLock::new(HashMap::new()),
             event_tx,
+            sinks: std::sync::RwLock::new(Vec::new()),
+            assignment_webhooks: AssignmentWebhooks::new(),
+            audit: std::sync::Mutex::new(AuditLog::default()),
+            persistence: None,
+            release_grace: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Create a manager that persists participants, work items and approvals to `pool`
+    ///
+    /// Participants, work items, approvals and the queues derived from them are
+    /// restored from the database. A client that registers again under its
+    /// earlier participant ID picks up where it left off.
+    pub async fn with_pool(pool: sqlx::SqlitePool) -> crate::error::Result<Self> {
+        let store = DelegationStore::new(pool);
+        let participants = store.load_participants().await?;
+        let items = store.load_work_items().await?;
+        let approvals = store.load_approvals().await?;
+        let comments = store.load_comments().await?;
+
+        let mut manager = Self::new();
+        for registered in &participants {
+            manager
+                .work_queues
+                .get_mut()
+                .entry(registered.id())
+                .or_default();
+            manager
+                .approval_queues
+                .get_mut()
+                .entry(registered.id())
+                .or_default();
+        }
+        *manager.participants.get_mut() = participants.into_iter().map(|p| (p.id(), p)).collect();
+        {
+            let queues = manager.work_queues.get_mut();
+            for item in items.iter().filter(|item| {
+                !item.is_unassigned()
+                    && matches!(
+                        item.status,
+                        WorkItemStatus::Pending
+                            | WorkItemStatus::InProgress
+                            | WorkItemStatus::Paused
+                            | WorkItemStatus::Rejected
+                    )
+            }) {
+                queues.entry(item.assignee_id).or_default().push(item.id);
+            }
+
+            let queues = manager.approval_queues.get_mut();
+            for approval in approvals
+                .iter()
+                .filter(|a| a.status == ApprovalStatus::Pending)
+            {
+                queues
+                    .entry(approval.approver_id)
+                    .or_default()
+                    .push(approval.id);
+            }
+        }
+        *manager.work_items.get_mut() = items.into_iter().map(|i| (i.id, i)).collect();
+        *manager.approvals.get_mut() = approvals.into_iter().map(|a| (a.id, a)).collect();
+        {
+            let by_item = manager.comments.get_mut();
+            for comment in comments {
+                by_item
+                    .entry(comment.work_item_id)
+                    .or_default()
+                    .push(comment);
+            }
+        }
+        manager.persistence = Some(store);
+
+        Ok(manager)
+    }
+
+    /// Write a participant through to storage, logging failures like [`Self::persist_item`]
+    async fn persist_participant(&self, registered: &RegisteredParticipant) {
+        if let Some(store) = &self.persistence {
+            if let Err(e) = store.save_participant(registered).await {
+                tracing::error!("Failed to persist participant {}: {}", registered.id(), e);
+            }
+        }
+    }
+
+    /// Write a work item through to storage. Failures are logged: the in-memory
+    /// state has already changed and stays authoritative until restart.
+    async fn persist_item(&self, item: &WorkItem) {
+        if let Some(store) = &self.persistence {
+            if let Err(e) = store.save_work_item(item).await {
+                tracing::error!("Failed to persist work item {}: {}", item.id, e);
+            }
+        }
+    }
+
+    async fn persist_approval(&self, approval: &ApprovalRequest) {
+        if let Some(store) = &self.persistence {
+            if let Err(e) = store.save_approval(approval).await {
+                tracing::error!("Failed to persist approval {}: {}", approval.id, e);
+            }
+        }
+    }
+
+    /// Post newly assigned work to the assignee's webhook, if it has one
+    async fn notify_assignee(&self, assignee_id: Uuid, event: &DelegationEvent, item: &WorkItem) {
+        let url = {
+            let participants = self.participants.read().await;
+            participants
+                .get(&assignee_id)
+                .and_then(|p| p.webhook_url.clone())
+        };
+        if let Some(url) = url {
+            self.assignment_webhooks.deliver(&url, event, item);
         }
     }
 
@@ -181,35 +513,70 @@ impl DelegationManager {
         self.event_tx.subscribe()
     }
 
-    /// Register a participant with the delegation system
-    pub async fn register_participant(&self, participant: Participant) -> RegisteredParticipant {
-        let registered = RegisteredParticipant::new(participant);
-        let id = registered.id();
-        let name = registered.name().to_string();
-        let kind = registered.kind();
+    /// Add a sink that is notified of every delegation event
+    pub fn add_sink(&self, sink: Arc<dyn NotificationSink>) {
+        self.sinks.write().unwrap().push(sink);
+    }
 
-        {
-            let mut participants = self.participants.write().await;
-            participants.insert(id, registered.clone());
-        }
+    /// Recent authorization failures, oldest first
+    pub fn audit_log(&self) -> Vec<AuthorizationFailure> {
+        self.audit.lock().unwrap().entries()
+    }
 
-        {
-            let mut queues = self.work_queues.write().await;
-            queues.entry(id).or_default();
-        }
+    /// Delegation counters
+    pub fn stats(&self) -> DelegationStats {
+        self.audit.lock().unwrap().stats()
+    }
 
-        {
-            let mut queues = self.approval_queues.write().await;
-            queues.entry(id).or_default();
+    /// Persisted delegation events for work in `journal_id`, oldest first
+    ///
+    /// Holds the last `limit` entries. Always empty for a manager without a database.
+    pub async fn get_audit_log(
+        &self,
+        journal_id: Uuid,
+        limit: u32,
+    ) -> crate::error::Result<Vec<DelegationAuditEntry>> {
+        match &self.persistence {
+            Some(store) => store.load_audit_log(journal_id, limit).await,
+            None => Ok(Vec::new()),
         }
+    }
 
-        let _ = self.event_tx.send(DelegationEvent::ParticipantRegistered {
-            participant_id: id,
-            name,
-            kind,
+    /// Record an authorization failure before handing it back to the caller
+    fn deny(&self, actor_id: Uuid, action: &str, error: DelegationError) -> DelegationError {
+        let capability = match &error {
+            DelegationError::InsufficientCapability { required, .. } => Some(*required),
+            _ => None,
+        };
+        tracing::warn!("Refused {} by {}: {}", action, actor_id, error);
+
+        self.audit.lock().unwrap().record(AuthorizationFailure {
+            actor_id,
+            action: action.to_string(),
+            reason: error.to_string(),
+            capability,
+            at: chrono::Utc::now(),
         });
+        error
+    }
 
-        registered
+    /// Broadcast an event to subscribers and notification sinks
+    async fn emit(&self, event: DelegationEvent) {
+        if let Some(store) = &self.persistence {
+            if let Err(e) = store.append_audit(&event).await {
+                tracing::error!("Failed to audit {} event: {}", event.name(), e);
+            }
+        }
+        for sink in self.sinks.read().unwrap().iter() {
+            sink.notify(&event);
+        }
+        let _ = self.event_tx.send(event);
+    }
+
+    /// Register a participant with the delegation system
+    pub async fn register_participant(&self, participant: Participant) -> RegisteredParticipant {
+        self.add_participant(RegisteredParticipant::new(participant))
+            .await
     }
 
     /// Register a participant with specific capabilities
@@ -218,15 +585,25 @@ impl DelegationManager {
         participant: Participant,
         capabilities: CapabilitySet,
     ) -> RegisteredParticipant {
-        let registered = RegisteredParticipant::with_capabilities(participant, capabilities);
+        self.add_participant(RegisteredParticipant::with_capabilities(
+            participant,
+            capabilities,
+        ))
+        .await
+    }
+
+    /// Store a registration, replacing any earlier one under the same ID
+    ///
+    /// Queues are kept, so re-registering picks up the work and approvals
+    /// already waiting for that ID.
+    async fn add_participant(&self, registered: RegisteredParticipant) -> RegisteredParticipant {
         let id = registered.id();
-        let name = registered.name().to_string();
-        let kind = registered.kind();
 
         {
             let mut participants = self.participants.write().await;
             participants.insert(id, registered.clone());
         }
+        self.persist_participant(&registered).await;
 
         {
             let mut queues = self.work_queues.write().await;
@@ -238,25 +615,111 @@ impl DelegationManager {
             queues.entry(id).or_default();
         }
 
-        let _ = self.event_tx.send(DelegationEvent::ParticipantRegistered {
+        self.emit(DelegationEvent::ParticipantRegistered {
             participant_id: id,
-            name,
-            kind,
-        });
+            name: registered.name().to_string(),
+            kind: registered.kind(),
+        })
+        .await;
 
         registered
     }
 
-    /// Get a participant by ID
     pub async fn get_participant(&self, id: Uuid) -> Option<RegisteredParticipant> {
         let participants = self.participants.read().await;
         participants.get(&id).cloned()
     }
 
+    /// Release a disconnected participant's work after `grace` unless they return
+    pub fn set_release_grace(&self, grace: std::time::Duration) {
+        *self.release_grace.lock().unwrap() = Some(grace);
+    }
+
+    /// How long a disconnected participant keeps its work, if limited
+    pub fn release_grace(&self) -> Option<std::time::Duration> {
+        *self.release_grace.lock().unwrap()
+    }
+
+    /// Unregister a participant who left at `departed_at` and hasn't registered since
+    ///
+    /// Returns `None`, leaving their work assigned, if they came back.
+    pub async fn unregister_if_departed(
+        &self,
+        id: Uuid,
+        departed_at: chrono::DateTime<chrono::Utc>,
+    ) -> Option<RegisteredParticipant> {
+        let returned = match self.participants.read().await.get(&id) {
+            Some(registered) => registered.registered_at > departed_at,
+            None => return None,
+        };
+        if returned {
+            return None;
+        }
+        self.unregister_participant(id).await
+    }
+
     /// Unregister a participant
+    ///
+    /// Unfinished work assigned to them goes back to the unassigned pool.
     pub async fn unregister_participant(&self, id: Uuid) -> Option<RegisteredParticipant> {
-        let mut participants = self.participants.write().await;
-        participants.remove(&id)
+        let removed = self.participants.write().await.remove(&id);
+        if removed.is_some() {
+            if let Some(store) = &self.persistence {
+                if let Err(e) = store.delete_participant(id).await {
+                    tracing::error!("Failed to delete participant {}: {}", id, e);
+                }
+            }
+            self.release_work(id).await;
+        }
+        removed
+    }
+
+    /// Reset a departed participant's unfinished work to pending and unassigned
+    ///
+    /// Work awaiting approval stays put, since only the approver acts on it.
+    async fn release_work(&self, participant_id: Uuid) {
+        let released: Vec<WorkItem> = {
+            let mut items = self.work_items.write().await;
+            let now = chrono::Utc::now();
+            items
+                .values_mut()
+                .filter(|item| {
+                    item.assignee_id == participant_id
+                        && matches!(
+                            item.status,
+                            WorkItemStatus::Pending
+                                | WorkItemStatus::InProgress
+                                | WorkItemStatus::Paused
+                                | WorkItemStatus::Rejected
+                        )
+                })
+                .map(|item| {
+                    item.assignee_id = UNASSIGNED;
+                    item.status = WorkItemStatus::Pending;
+                    item.updated_at = now;
+                    item.clone()
+                })
+                .collect()
+        };
+        if released.is_empty() {
+            return;
+        }
+
+        {
+            let mut queues = self.work_queues.write().await;
+            if let Some(queue) = queues.get_mut(&participant_id) {
+                queue.retain(|id| !released.iter().any(|item| item.id == *id));
+            }
+        }
+
+        for item in &released {
+            self.persist_item(item).await;
+            self.emit(DelegationEvent::WorkUnassigned {
+                work_item_id: item.id,
+                previous_assignee_id: participant_id,
+            })
+            .await;
+        }
     }
 
     /// Update participant capabilities
@@ -265,17 +728,21 @@ impl DelegationManager {
         participant_id: Uuid,
         capabilities: CapabilitySet,
     ) -> DelegationResult<()> {
-        let mut participants = self.participants.write().await;
-        let participant = participants
-            .get_mut(&participant_id)
-            .ok_or(DelegationError::ParticipantNotFound(participant_id))?;
-
-        participant.capabilities = capabilities.clone();
+        let participant = {
+            let mut participants = self.participants.write().await;
+            let participant = participants
+                .get_mut(&participant_id)
+                .ok_or(DelegationError::ParticipantNotFound(participant_id))?;
+            participant.capabilities = capabilities.clone();
+            participant.clone()
+        };
+        self.persist_participant(&participant).await;
 
-        let _ = self.event_tx.send(DelegationEvent::CapabilitiesChanged {
+        self.emit(DelegationEvent::CapabilitiesChanged {
             participant_id,
             capabilities: capabilities.to_vec(),
-        });
+        })
+        .await;
 
         Ok(())
     }
@@ -286,24 +753,84 @@ impl DelegationManager {
         participant_id: Uuid,
         accepting: bool,
     ) -> DelegationResult<()> {
-        let mut participants = self.participants.write().await;
-        let participant = participants
-            .get_mut(&participant_id)
-            .ok_or(DelegationError::ParticipantNotFound(participant_id))?;
+        let participant = {
+            let mut participants = self.participants.write().await;
+            let participant = participants
+                .get_mut(&participant_id)
+                .ok_or(DelegationError::ParticipantNotFound(participant_id))?;
+            participant.set_accepting_work(accepting);
+            participant.clone()
+        };
+        self.persist_participant(&participant).await;
 
-        participant.set_accepting_work(accepting);
+        self.emit(DelegationEvent::ParticipantStatusChanged {
+            participant_id,
+            accepting_work: accepting,
+        })
+        .await;
 
-        let _ = self
-            .event_tx
-            .send(DelegationEvent::ParticipantStatusChanged {
-                participant_id,
-                accepting_work: accepting,
-            });
+        Ok(())
+    }
 
+    /// Set or clear the URL that receives work assigned to a participant
+    pub async fn set_webhook_url(
+        &self,
+        participant_id: Uuid,
+        url: Option<String>,
+    ) -> DelegationResult<()> {
+        let participant = {
+            let mut participants = self.participants.write().await;
+            let participant = participants
+                .get_mut(&participant_id)
+                .ok_or(DelegationError::ParticipantNotFound(participant_id))?;
+            participant.set_webhook_url(url);
+            participant.clone()
+        };
+        self.persist_participant(&participant).await;
         Ok(())
     }
 
+    /// Set whether several participants are accepting work
+    ///
+    /// Each known participant is updated and gets its own status event.
+    /// Returns the IDs that aren't registered; they don't stop the others.
+    pub async fn bulk_set_accepting_work(
+        &self,
+        participant_ids: &[Uuid],
+        accepting: bool,
+    ) -> Vec<Uuid> {
+        let mut updated = Vec::new();
+        let mut unknown = Vec::new();
+        {
+            let mut participants = self.participants.write().await;
+            for &participant_id in participant_ids {
+                match participants.get_mut(&participant_id) {
+                    Some(participant) => {
+                        participant.set_accepting_work(accepting);
+                        updated.push(participant.clone());
+                    }
+                    None => unknown.push(participant_id),
+                }
+            }
+        }
+
+        for participant in updated {
+            self.persist_participant(&participant).await;
+            self.emit(DelegationEvent::ParticipantStatusChanged {
+                participant_id: participant.id(),
+                accepting_work: accepting,
+            })
+            .await;
+        }
+
+        unknown
+    }
+
     /// Delegate work to a participant
+    ///
+    /// When approval is required, each of `approver_ids` (or the delegator, if
+    /// there are none) is asked. Any one approval is enough; build the item
+    /// with [`WorkItem::with_required_approvals`] to need more.
     #[allow(clippy::too_many_arguments)]
     pub async fn delegate(
         &self,
@@ -313,45 +840,170 @@ impl DelegationManager {
         assignee_id: Uuid,
         priority: Option<WorkPriority>,
         requires_approval: bool,
-        approver_id: Option<Uuid>,
+        approver_ids: impl IntoIterator<Item = Uuid>,
     ) -> DelegationResult<WorkItem> {
+        let mut work_item = WorkItem::new(journal_id, description, delegator_id, assignee_id);
+        if let Some(p) = priority {
+            work_item = work_item.with_priority(p);
+        }
+        if requires_approval {
+            work_item = work_item.require_approval_from(approver_ids);
+        }
+
+        self.delegate_item(work_item).await
+    }
+
+    /// Delegate a fully built work item (e.g. one tied to a block or set to auto-execute)
+    pub async fn delegate_item(&self, work_item: WorkItem) -> DelegationResult<WorkItem> {
+        {
+            let participants = self.participants.read().await;
+            self.check_delegation(&participants, &work_item)?;
+        }
+
+        self.record_delegations(std::slice::from_ref(&work_item))
+            .await?;
+        Ok(work_item)
+    }
+
+    /// Delegate the same work to several participants at once
+    ///
+    /// Every assignee is checked as [`delegate`](Self::delegate) would before
+    /// anything is created, so if one is refused the whole batch is and no
+    /// work items exist afterwards. Repeated assignees get a single item.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn delegate_many(
+        &self,
+        journal_id: Uuid,
+        description: impl Into<String>,
+        delegator_id: Uuid,
+        assignee_ids: Vec<Uuid>,
+        priority: Option<WorkPriority>,
+        requires_approval: bool,
+        approver_ids: impl IntoIterator<Item = Uuid>,
+    ) -> DelegationResult<Vec<WorkItem>> {
         let description = description.into();
+        let approver_ids: Vec<Uuid> = approver_ids.into_iter().collect();
+        let mut seen = std::collections::HashSet::new();
+        let work_items: Vec<WorkItem> = assignee_ids
+            .into_iter()
+            .filter(|id| seen.insert(*id))
+            .map(|assignee_id| {
+                let mut work_item =
+                    WorkItem::new(journal_id, description.clone(), delegator_id, assignee_id);
+                if let Some(p) = priority {
+                    work_item = work_item.with_priority(p);
+                }
+                if requires_approval {
+                    work_item = work_item.require_approval_from(approver_ids.iter().copied());
+                }
+                work_item
+            })
+            .collect();
 
-        // Check delegator has delegate capability
         {
             let participants = self.participants.read().await;
-            let delegator = participants
-                .get(&delegator_id)
-                .ok_or(DelegationError::ParticipantNotFound(delegator_id))?;
+            for work_item in &work_items {
+                self.check_delegation(&participants, work_item)?;
+            }
+        }
+
+        self.record_delegations(&work_items).await?;
+        Ok(work_items)
+    }
+
+    /// Whether `work_item` may be delegated as built
+    fn check_delegation(
+        &self,
+        participants: &HashMap<Uuid, RegisteredParticipant>,
+        work_item: &WorkItem,
+    ) -> DelegationResult<()> {
+        let delegator_id = work_item.delegator_id;
+        let assignee_id = work_item.assignee_id;
+
+        // Check delegator has delegate capability
+        let delegator = participants
+            .get(&delegator_id)
+            .ok_or(DelegationError::ParticipantNotFound(delegator_id))?;
 
-            if !delegator.can_delegate() {
-                return Err(DelegationError::InsufficientCapability {
+        if !delegator.can_delegate() {
+            return Err(self.deny(
+                delegator_id,
+                "delegate",
+                DelegationError::InsufficientCapability {
                     participant_id: delegator_id,
                     required: Capability::Delegate,
-                });
-            }
+                },
+            ));
+        }
 
-            let assignee = participants
-                .get(&assignee_id)
-                .ok_or(DelegationError::ParticipantNotFound(assignee_id))?;
+        let assignee = participants
+            .get(&assignee_id)
+            .ok_or(DelegationError::ParticipantNotFound(assignee_id))?;
 
-            if !assignee.can_receive_work() {
-                return Err(DelegationError::NotAcceptingWork(assignee_id));
+        if !assignee.can_receive_work() {
+            return Err(DelegationError::NotAcceptingWork(assignee_id));
+        }
+
+        if work_item.auto_execute && assignee.kind() != ParticipantKind::Agent {
+            return Err(self.deny(
+                delegator_id,
+                "delegate",
+                DelegationError::NotAuthorized(
+                    "Only agent assignees can auto-execute work".to_string(),
+                ),
+            ));
+        }
+
+        // Explicit approvers must be able to act on their approval requests;
+        // without any the delegator approves, as checked above
+        let approver_ids = match work_item.approver_id {
+            Some(_) if work_item.requires_approval => work_item.get_approver_ids(),
+            _ => Vec::new(),
+        };
+        for approver_id in approver_ids {
+            let approver = participants
+                .get(&approver_id)
+                .ok_or(DelegationError::ParticipantNotFound(approver_id))?;
+
+            if !approver.can_approve() {
+                return Err(self.deny(
+                    delegator_id,
+                    "delegate",
+                    DelegationError::InsufficientCapability {
+                        participant_id: approver_id,
+                        required: Capability::Approve,
+                    },
+                ));
             }
         }
 
-        // Create work item
-        let mut work_item = WorkItem::new(journal_id, &description, delegator_id, assignee_id);
-        if let Some(p) = priority {
-            work_item = work_item.with_priority(p);
+        Ok(())
+    }
+
+    /// Save checked work items, then queue and announce each
+    ///
+    /// Nothing is recorded unless every item saves, so a bad journal or
+    /// block reference fails the whole call instead of leaving work that
+    /// would vanish on restart.
+    async fn record_delegations(&self, work_items: &[WorkItem]) -> DelegationResult<()> {
+        if let Some(store) = &self.persistence {
+            store
+                .save_work_items(work_items)
+                .await
+                .map_err(|e| DelegationError::from_storage(work_items, e))?;
         }
-        if requires_approval {
-            work_item = work_item.require_approval(approver_id);
+
+        for work_item in work_items {
+            self.record_delegation(work_item).await;
         }
+        Ok(())
+    }
 
+    /// Queue a saved work item for its assignee and announce it
+    async fn record_delegation(&self, work_item: &WorkItem) {
         let work_item_id = work_item.id;
+        let assignee_id = work_item.assignee_id;
 
-        // Store work item
         {
             let mut items = self.work_items.write().await;
             items.insert(work_item_id, work_item.clone());
@@ -363,14 +1015,14 @@ impl DelegationManager {
             queues.entry(assignee_id).or_default().push(work_item_id);
         }
 
-        let _ = self.event_tx.send(DelegationEvent::WorkDelegated {
+        let event = DelegationEvent::WorkDelegated {
             work_item_id,
-            delegator_id,
+            delegator_id: work_item.delegator_id,
             assignee_id,
-            description,
-        });
-
-        Ok(work_item)
+            description: work_item.description.clone(),
+        };
+        self.notify_assignee(assignee_id, &event, work_item).await;
+        self.emit(event).await;
     }
 
     /// Accept a delegated work item
@@ -379,27 +1031,35 @@ impl DelegationManager {
         work_item_id: Uuid,
         acceptor_id: Uuid,
     ) -> DelegationResult<WorkItem> {
-        let mut items = self.work_items.write().await;
-        let item = items
-            .get_mut(&work_item_id)
-            .ok_or(DelegationError::WorkItemNotFound(work_item_id))?;
-
-        // Verify acceptor is the assignee
-        if item.assignee_id != acceptor_id {
-            return Err(DelegationError::NotAuthorized(
-                "Only the assignee can accept work".to_string(),
-            ));
-        }
+        let item = {
+            let mut items = self.work_items.write().await;
+            let item = items
+                .get_mut(&work_item_id)
+                .ok_or(DelegationError::WorkItemNotFound(work_item_id))?;
 
-        item.accept()
-            .map_err(DelegationError::InvalidStateTransition)?;
+            // Verify acceptor is the assignee
+            if item.assignee_id != acceptor_id {
+                return Err(self.deny(
+                    acceptor_id,
+                    "accept_work",
+                    DelegationError::NotAuthorized("Only the assignee can accept work".to_string()),
+                ));
+            }
+
+            item.accept()
+                .map_err(DelegationError::InvalidStateTransition)?;
 
-        let _ = self.event_tx.send(DelegationEvent::WorkAccepted {
+            item.clone()
+        };
+        self.persist_item(&item).await;
+
+        self.emit(DelegationEvent::WorkAccepted {
             work_item_id,
             assignee_id: acceptor_id,
-        });
+        })
+        .await;
 
-        Ok(item.clone())
+        Ok(item)
     }
 
     /// Decline a delegated work item
@@ -415,8 +1075,12 @@ impl DelegationManager {
                 .ok_or(DelegationError::WorkItemNotFound(work_item_id))?;
 
             if item.assignee_id != decliner_id {
-                return Err(DelegationError::NotAuthorized(
-                    "Only the assignee can decline work".to_string(),
+                return Err(self.deny(
+                    decliner_id,
+                    "decline_work",
+                    DelegationError::NotAuthorized(
+                        "Only the assignee can decline work".to_string(),
+                    ),
                 ));
             }
 
@@ -425,6 +1089,7 @@ impl DelegationManager {
 
             item.clone()
         };
+        self.persist_item(&item).await;
 
         // Remove from queue
         {
@@ -434,10 +1099,83 @@ impl DelegationManager {
             }
         }
 
-        let _ = self.event_tx.send(DelegationEvent::WorkDeclined {
+        self.emit(DelegationEvent::WorkDeclined {
             work_item_id,
             assignee_id: decliner_id,
-        });
+        })
+        .await;
+
+        Ok(item)
+    }
+
+    /// Pause accepted work, keeping it assigned
+    pub async fn pause_work(
+        &self,
+        work_item_id: Uuid,
+        assignee_id: Uuid,
+    ) -> DelegationResult<WorkItem> {
+        let item = {
+            let mut items = self.work_items.write().await;
+            let item = items
+                .get_mut(&work_item_id)
+                .ok_or(DelegationError::WorkItemNotFound(work_item_id))?;
+
+            if item.assignee_id != assignee_id {
+                return Err(self.deny(
+                    assignee_id,
+                    "pause_work",
+                    DelegationError::NotAuthorized("Only the assignee can pause work".to_string()),
+                ));
+            }
+
+            item.pause()
+                .map_err(DelegationError::InvalidStateTransition)?;
+
+            item.clone()
+        };
+        self.persist_item(&item).await;
+
+        self.emit(DelegationEvent::WorkPaused {
+            work_item_id,
+            assignee_id,
+        })
+        .await;
+
+        Ok(item)
+    }
+
+    /// Resume paused work
+    pub async fn resume_work(
+        &self,
+        work_item_id: Uuid,
+        assignee_id: Uuid,
+    ) -> DelegationResult<WorkItem> {
+        let item = {
+            let mut items = self.work_items.write().await;
+            let item = items
+                .get_mut(&work_item_id)
+                .ok_or(DelegationError::WorkItemNotFound(work_item_id))?;
+
+            if item.assignee_id != assignee_id {
+                return Err(self.deny(
+                    assignee_id,
+                    "resume_work",
+                    DelegationError::NotAuthorized("Only the assignee can resume work".to_string()),
+                ));
+            }
+
+            item.resume()
+                .map_err(DelegationError::InvalidStateTransition)?;
+
+            item.clone()
+        };
+        self.persist_item(&item).await;
+
+        self.emit(DelegationEvent::WorkResumed {
+            work_item_id,
+            assignee_id,
+        })
+        .await;
 
         Ok(item)
     }
@@ -457,8 +1195,10 @@ impl DelegationManager {
                 .ok_or(DelegationError::WorkItemNotFound(work_item_id))?;
 
             if item.assignee_id != submitter_id {
-                return Err(DelegationError::NotAuthorized(
-                    "Only the assignee can submit work".to_string(),
+                return Err(self.deny(
+                    submitter_id,
+                    "submit_work",
+                    DelegationError::NotAuthorized("Only the assignee can submit work".to_string()),
                 ));
             }
 
@@ -468,6 +1208,7 @@ impl DelegationManager {
 
             (item.clone(), needs_approval)
         };
+        self.persist_item(&item).await;
 
         // Remove from work queue
         {
@@ -477,40 +1218,126 @@ impl DelegationManager {
             }
         }
 
-        // Create approval request if needed
+        // Create an approval request per approver if needed
         if needs_approval {
-            let approval = ApprovalRequest::new(&item);
-            let approval_id = approval.id;
-            let approver_id = approval.approver_id;
+            let submitted_at = chrono::Utc::now();
+            for approver_id in item.get_approver_ids() {
+                let approval = ApprovalRequest::for_approver(&item, approver_id, submitted_at);
+                let approval_id = approval.id;
+                self.persist_approval(&approval).await;
+
+                {
+                    let mut approvals = self.approvals.write().await;
+                    approvals.insert(approval_id, approval);
+                }
 
-            {
-                let mut approvals = self.approvals.write().await;
-                approvals.insert(approval_id, approval);
-            }
+                {
+                    let mut queues = self.approval_queues.write().await;
+                    queues.entry(approver_id).or_default().push(approval_id);
+                }
 
-            {
-                let mut queues = self.approval_queues.write().await;
-                queues.entry(approver_id).or_default().push(approval_id);
+                self.emit(DelegationEvent::ApprovalRequested {
+                    approval_id,
+                    work_item_id,
+                    requester_id: submitter_id,
+                    approver_id,
+                })
+                .await;
             }
-
-            let _ = self.event_tx.send(DelegationEvent::ApprovalRequested {
-                approval_id,
-                work_item_id,
-                requester_id: submitter_id,
-                approver_id,
-            });
         } else {
-            let _ = self.event_tx.send(DelegationEvent::WorkApproved {
+            self.emit(DelegationEvent::WorkApproved {
                 work_item_id,
                 approver_id: item.delegator_id,
                 feedback: None,
-            });
+            })
+            .await;
+        }
+
+        Ok(item)
+    }
+
+    /// Withdraw a submission awaiting approval so the assignee can keep working on it
+    pub async fn withdraw_submission(
+        &self,
+        work_item_id: Uuid,
+        assignee_id: Uuid,
+    ) -> DelegationResult<WorkItem> {
+        let item = {
+            let mut items = self.work_items.write().await;
+            let item = items
+                .get_mut(&work_item_id)
+                .ok_or(DelegationError::WorkItemNotFound(work_item_id))?;
+
+            if item.assignee_id != assignee_id {
+                return Err(self.deny(
+                    assignee_id,
+                    "withdraw_submission",
+                    DelegationError::NotAuthorized(
+                        "Only the assignee can withdraw a submission".to_string(),
+                    ),
+                ));
+            }
+
+            item.withdraw()
+                .map_err(DelegationError::InvalidStateTransition)?;
+
+            item.clone()
+        };
+        self.persist_item(&item).await;
+        self.drop_pending_approvals(work_item_id).await;
+
+        // Back on the assignee's queue
+        {
+            let mut queues = self.work_queues.write().await;
+            let queue = queues.entry(assignee_id).or_default();
+            if !queue.contains(&work_item_id) {
+                queue.push(work_item_id);
+            }
         }
 
+        self.emit(DelegationEvent::SubmissionWithdrawn {
+            work_item_id,
+            assignee_id,
+        })
+        .await;
+
         Ok(item)
     }
 
+    /// Remove a work item's still-pending approval requests and their queue entries
+    async fn drop_pending_approvals(&self, work_item_id: Uuid) {
+        let dropped: Vec<(Uuid, Uuid)> = {
+            let mut approvals = self.approvals.write().await;
+            let ids: Vec<Uuid> = approvals
+                .values()
+                .filter(|a| a.work_item_id == work_item_id && a.status == ApprovalStatus::Pending)
+                .map(|a| a.id)
+                .collect();
+            ids.into_iter()
+                .filter_map(|id| approvals.remove(&id).map(|a| (a.id, a.approver_id)))
+                .collect()
+        };
+
+        if let Some(store) = &self.persistence {
+            for (approval_id, _) in &dropped {
+                if let Err(e) = store.delete_approval(*approval_id).await {
+                    tracing::error!("Failed to delete approval {}: {}", approval_id, e);
+                }
+            }
+        }
+
+        let mut queues = self.approval_queues.write().await;
+        for (approval_id, approver_id) in dropped {
+            if let Some(queue) = queues.get_mut(&approver_id) {
+                queue.retain(|&id| id != approval_id);
+            }
+        }
+    }
+
     /// Approve a work item
+    ///
+    /// With several approvers the item stays awaiting approval until its
+    /// quorum of approvals from the same submission is reached.
     pub async fn approve(
         &self,
         approval_id: Uuid,
@@ -525,64 +1352,189 @@ impl DelegationManager {
                 .ok_or(DelegationError::ParticipantNotFound(approver_id))?;
 
             if !approver.can_approve() {
-                return Err(DelegationError::InsufficientCapability {
-                    participant_id: approver_id,
-                    required: Capability::Approve,
-                });
+                return Err(self.deny(
+                    approver_id,
+                    "approve",
+                    DelegationError::InsufficientCapability {
+                        participant_id: approver_id,
+                        required: Capability::Approve,
+                    },
+                ));
             }
         }
 
-        let work_item_id = {
+        let (approval, approved) = {
             let mut approvals = self.approvals.write().await;
             let approval = approvals
                 .get_mut(&approval_id)
                 .ok_or(DelegationError::ApprovalNotFound(approval_id))?;
 
             if approval.approver_id != approver_id {
-                return Err(DelegationError::NotAuthorized(
-                    "Not the designated approver".to_string(),
+                return Err(self.deny(
+                    approver_id,
+                    "approve",
+                    DelegationError::NotAuthorized("Not the designated approver".to_string()),
                 ));
             }
 
             approval
                 .approve(feedback.clone())
                 .map_err(DelegationError::InvalidStateTransition)?;
-
-            approval.work_item_id
+            let approval = approval.clone();
+
+            // Approvals so far in this submission round, this one included
+            let approved = approvals
+                .values()
+                .filter(|a| {
+                    a.work_item_id == approval.work_item_id
+                        && a.status == ApprovalStatus::Approved
+                        && a.created_at >= approval.created_at
+                })
+                .count();
+
+            (approval, approved)
         };
+        let work_item_id = approval.work_item_id;
+        self.persist_approval(&approval).await;
 
-        // Update work item status
+        // Remove from approval queue
+        {
+            let mut queues = self.approval_queues.write().await;
+            if let Some(queue) = queues.get_mut(&approver_id) {
+                queue.retain(|&id| id != approval_id);
+            }
+        }
+
+        // Update work item status once enough approvers have signed off
         let item = {
             let mut items = self.work_items.write().await;
             let item = items
                 .get_mut(&work_item_id)
                 .ok_or(DelegationError::WorkItemNotFound(work_item_id))?;
 
+            if approved < item.quorum() {
+                let event = DelegationEvent::ApprovalGranted {
+                    approval_id,
+                    work_item_id,
+                    approver_id,
+                    approvals: approved,
+                    required: item.quorum(),
+                };
+                let item = item.clone();
+                drop(items);
+                self.emit(event).await;
+                return Ok((approval, item));
+            }
+
             item.status = WorkItemStatus::Approved;
             item.updated_at = chrono::Utc::now();
             item.clone()
         };
+        self.persist_item(&item).await;
 
-        // Remove from approval queue
+        // Approvers who had not answered yet no longer need to
+        self.drop_pending_approvals(work_item_id).await;
+
+        self.emit(DelegationEvent::WorkApproved {
+            work_item_id,
+            approver_id,
+            feedback,
+        })
+        .await;
+
+        Ok((approval, item))
+    }
+
+    /// Hand a pending approval to someone else (by its current approver)
+    ///
+    /// The work item's designated approver changes too, so a resubmission
+    /// after rejection goes to the new approver.
+    pub async fn reassign_approver(
+        &self,
+        approval_id: Uuid,
+        current_approver_id: Uuid,
+        new_approver_id: Uuid,
+    ) -> DelegationResult<ApprovalRequest> {
         {
-            let mut queues = self.approval_queues.write().await;
-            if let Some(queue) = queues.get_mut(&approver_id) {
-                queue.retain(|&id| id != approval_id);
+            let participants = self.participants.read().await;
+            let new_approver = participants
+                .get(&new_approver_id)
+                .ok_or(DelegationError::ParticipantNotFound(new_approver_id))?;
+
+            if !new_approver.can_approve() {
+                return Err(self.deny(
+                    current_approver_id,
+                    "reassign_approver",
+                    DelegationError::InsufficientCapability {
+                        participant_id: new_approver_id,
+                        required: Capability::Approve,
+                    },
+                ));
             }
         }
 
         let approval = {
-            let approvals = self.approvals.read().await;
-            approvals.get(&approval_id).cloned().unwrap()
+            let mut approvals = self.approvals.write().await;
+            let approval = approvals
+                .get_mut(&approval_id)
+                .ok_or(DelegationError::ApprovalNotFound(approval_id))?;
+
+            if approval.approver_id != current_approver_id {
+                return Err(self.deny(
+                    current_approver_id,
+                    "reassign_approver",
+                    DelegationError::NotAuthorized("Not the designated approver".to_string()),
+                ));
+            }
+
+            let work_item_id = approval.work_item_id;
+            let created_at = approval.created_at;
+            if approvals.values().any(|a| {
+                a.work_item_id == work_item_id
+                    && a.approver_id == new_approver_id
+                    && a.created_at == created_at
+            }) {
+                return Err(DelegationError::InvalidStateTransition(
+                    "Already an approver of this submission".to_string(),
+                ));
+            }
+
+            let approval = approvals.get_mut(&approval_id).unwrap();
+            approval
+                .reassign(new_approver_id)
+                .map_err(DelegationError::InvalidStateTransition)?;
+            approval.clone()
         };
+        self.persist_approval(&approval).await;
 
-        let _ = self.event_tx.send(DelegationEvent::WorkApproved {
-            work_item_id,
-            approver_id,
-            feedback,
-        });
+        let item = {
+            let mut items = self.work_items.write().await;
+            items.get_mut(&approval.work_item_id).map(|item| {
+                item.replace_approver(current_approver_id, new_approver_id);
+                item.clone()
+            })
+        };
+        if let Some(item) = &item {
+            self.persist_item(item).await;
+        }
 
-        Ok((approval, item))
+        {
+            let mut queues = self.approval_queues.write().await;
+            if let Some(queue) = queues.get_mut(&current_approver_id) {
+                queue.retain(|&id| id != approval_id);
+            }
+            queues.entry(new_approver_id).or_default().push(approval_id);
+        }
+
+        self.emit(DelegationEvent::ApproverReassigned {
+            approval_id,
+            work_item_id: approval.work_item_id,
+            previous_approver_id: current_approver_id,
+            new_approver_id,
+        })
+        .await;
+
+        Ok(approval)
     }
 
     /// Reject a work item
@@ -602,10 +1554,14 @@ impl DelegationManager {
                 .ok_or(DelegationError::ParticipantNotFound(rejecter_id))?;
 
             if !rejecter.can_approve() {
-                return Err(DelegationError::InsufficientCapability {
-                    participant_id: rejecter_id,
-                    required: Capability::Approve,
-                });
+                return Err(self.deny(
+                    rejecter_id,
+                    "reject",
+                    DelegationError::InsufficientCapability {
+                        participant_id: rejecter_id,
+                        required: Capability::Approve,
+                    },
+                ));
             }
         }
 
@@ -616,8 +1572,10 @@ impl DelegationManager {
                 .ok_or(DelegationError::ApprovalNotFound(approval_id))?;
 
             if approval.approver_id != rejecter_id {
-                return Err(DelegationError::NotAuthorized(
-                    "Not the designated approver".to_string(),
+                return Err(self.deny(
+                    rejecter_id,
+                    "reject",
+                    DelegationError::NotAuthorized("Not the designated approver".to_string()),
                 ));
             }
 
@@ -639,6 +1597,7 @@ impl DelegationManager {
             item.updated_at = chrono::Utc::now();
             item.clone()
         };
+        self.persist_item(&item).await;
 
         // Re-add to work queue for rework
         {
@@ -654,16 +1613,21 @@ impl DelegationManager {
             }
         }
 
+        // One rejection decides it; the other approvers no longer need to answer
+        self.drop_pending_approvals(work_item_id).await;
+
         let approval = {
             let approvals = self.approvals.read().await;
             approvals.get(&approval_id).cloned().unwrap()
         };
+        self.persist_approval(&approval).await;
 
-        let _ = self.event_tx.send(DelegationEvent::WorkRejected {
+        self.emit(DelegationEvent::WorkRejected {
             work_item_id,
             approver_id: rejecter_id,
             feedback,
-        });
+        })
+        .await;
 
         Ok((approval, item))
     }
@@ -682,8 +1646,12 @@ impl DelegationManager {
 
             // Only delegator can cancel
             if item.delegator_id != canceller_id {
-                return Err(DelegationError::NotAuthorized(
-                    "Only the delegator can cancel work".to_string(),
+                return Err(self.deny(
+                    canceller_id,
+                    "cancel_work",
+                    DelegationError::NotAuthorized(
+                        "Only the delegator can cancel work".to_string(),
+                    ),
                 ));
             }
 
@@ -692,6 +1660,7 @@ impl DelegationManager {
 
             item.clone()
         };
+        self.persist_item(&item).await;
 
         // Remove from assignee's queue
         {
@@ -701,14 +1670,83 @@ impl DelegationManager {
             }
         }
 
-        let _ = self.event_tx.send(DelegationEvent::WorkCancelled {
+        self.emit(DelegationEvent::WorkCancelled {
             work_item_id,
             cancelled_by: canceller_id,
-        });
+        })
+        .await;
 
         Ok(item)
     }
 
+    /// Move work to a different assignee (by delegator)
+    ///
+    /// The item goes back to pending in the new assignee's queue. Returns the
+    /// previous assignee along with the updated item.
+    pub async fn reassign_work(
+        &self,
+        work_item_id: Uuid,
+        new_assignee_id: Uuid,
+        requester_id: Uuid,
+    ) -> DelegationResult<(Uuid, WorkItem)> {
+        {
+            let participants = self.participants.read().await;
+            let assignee = participants
+                .get(&new_assignee_id)
+                .ok_or(DelegationError::ParticipantNotFound(new_assignee_id))?;
+
+            if !assignee.can_receive_work() {
+                return Err(DelegationError::NotAcceptingWork(new_assignee_id));
+            }
+        }
+
+        let (previous_assignee_id, item) = {
+            let mut items = self.work_items.write().await;
+            let item = items
+                .get_mut(&work_item_id)
+                .ok_or(DelegationError::WorkItemNotFound(work_item_id))?;
+
+            if item.delegator_id != requester_id {
+                return Err(self.deny(
+                    requester_id,
+                    "reassign_work",
+                    DelegationError::NotAuthorized(
+                        "Only the delegator can reassign work".to_string(),
+                    ),
+                ));
+            }
+
+            let previous_assignee_id = item.assignee_id;
+            item.reassign(new_assignee_id)
+                .map_err(DelegationError::InvalidStateTransition)?;
+
+            (previous_assignee_id, item.clone())
+        };
+        self.persist_item(&item).await;
+
+        {
+            let mut queues = self.work_queues.write().await;
+            if let Some(queue) = queues.get_mut(&previous_assignee_id) {
+                queue.retain(|&id| id != work_item_id);
+            }
+            queues
+                .entry(new_assignee_id)
+                .or_default()
+                .push(work_item_id);
+        }
+
+        let event = DelegationEvent::WorkReassigned {
+            work_item_id,
+            previous_assignee_id,
+            new_assignee_id,
+            reassigned_by: requester_id,
+        };
+        self.notify_assignee(new_assignee_id, &event, &item).await;
+        self.emit(event).await;
+
+        Ok((previous_assignee_id, item))
+    }
+
     /// Claim an unassigned work item from the general queue
     pub async fn claim_work(
         &self,
@@ -756,6 +1794,7 @@ impl DelegationManager {
 
             item.clone()
         };
+        self.persist_item(&item).await;
 
         // Add to claimer's queue
         {
@@ -763,14 +1802,102 @@ impl DelegationManager {
             queues.entry(claimer_id).or_default().push(work_item_id);
         }
 
-        let _ = self.event_tx.send(DelegationEvent::WorkClaimed {
+        self.emit(DelegationEvent::WorkClaimed {
             work_item_id,
             claimed_by: claimer_id,
-        });
+        })
+        .await;
 
         Ok(item)
     }
 
+    /// Drop links to deleted blocks from the work items that pointed at them
+    ///
+    /// Storage clears them when the blocks are deleted; this keeps memory in
+    /// step so later saves don't reference rows that are gone.
+    pub async fn unlink_blocks(&self, block_ids: &[Uuid]) {
+        let mut items = self.work_items.write().await;
+        for item in items.values_mut() {
+            if item.block_id.is_some_and(|id| block_ids.contains(&id)) {
+                item.block_id = None;
+            }
+        }
+    }
+
+    /// Leave a comment on a work item
+    ///
+    /// Only the item's delegator, assignee and approvers may comment.
+    pub async fn add_comment(
+        &self,
+        work_item_id: Uuid,
+        author_id: Uuid,
+        text: impl Into<String>,
+    ) -> DelegationResult<WorkItemComment> {
+        self.check_involved(work_item_id, author_id, "add_comment")
+            .await?;
+
+        let comment = WorkItemComment::new(work_item_id, author_id, text);
+        if let Some(store) = &self.persistence {
+            if let Err(e) = store.save_comment(&comment).await {
+                tracing::error!("Failed to persist comment {}: {}", comment.id, e);
+            }
+        }
+        {
+            let mut comments = self.comments.write().await;
+            comments
+                .entry(work_item_id)
+                .or_default()
+                .push(comment.clone());
+        }
+
+        self.emit(DelegationEvent::WorkCommentAdded {
+            comment_id: comment.id,
+            work_item_id,
+            author_id,
+            text: comment.text.clone(),
+        })
+        .await;
+
+        Ok(comment)
+    }
+
+    /// Comments on a work item, oldest first, as seen by `viewer_id`
+    pub async fn get_comments(
+        &self,
+        work_item_id: Uuid,
+        viewer_id: Uuid,
+    ) -> DelegationResult<Vec<WorkItemComment>> {
+        self.check_involved(work_item_id, viewer_id, "get_comments")
+            .await?;
+
+        let comments = self.comments.read().await;
+        Ok(comments.get(&work_item_id).cloned().unwrap_or_default())
+    }
+
+    /// Refuse `action` unless `participant_id` is a party to the work item
+    async fn check_involved(
+        &self,
+        work_item_id: Uuid,
+        participant_id: Uuid,
+        action: &str,
+    ) -> DelegationResult<()> {
+        let items = self.work_items.read().await;
+        let item = items
+            .get(&work_item_id)
+            .ok_or(DelegationError::WorkItemNotFound(work_item_id))?;
+
+        if !item.involves(participant_id) {
+            return Err(self.deny(
+                participant_id,
+                action,
+                DelegationError::NotAuthorized(
+                    "Only the delegator, assignee and approvers can see comments".to_string(),
+                ),
+            ));
+        }
+        Ok(())
+    }
+
     /// Get a participant's work queue
     pub async fn get_work_queue(&self, participant_id: Uuid) -> Vec<WorkItem> {
         let queues = self.work_queues.read().await;
@@ -787,6 +1914,36 @@ impl DelegationManager {
             .unwrap_or_default()
     }
 
+    /// A participant's work queue, highest priority first and oldest first within a priority
+    pub async fn get_work_queue_sorted(&self, participant_id: Uuid) -> Vec<WorkItem> {
+        let mut queue = self.get_work_queue(participant_id).await;
+        queue.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then_with(|| a.created_at.cmp(&b.created_at))
+        });
+        queue
+    }
+
+    /// Items in a participant's queue carrying `tag`, in queue order
+    pub async fn get_work_by_tag(&self, participant_id: Uuid, tag: &str) -> Vec<WorkItem> {
+        self.get_work_queue(participant_id)
+            .await
+            .into_iter()
+            .filter(|item| item.has_tag(tag))
+            .collect()
+    }
+
+    /// Items in a participant's queue that are past their due date
+    pub async fn get_overdue_items(&self, participant_id: Uuid) -> Vec<WorkItem> {
+        let now = chrono::Utc::now();
+        self.get_work_queue(participant_id)
+            .await
+            .into_iter()
+            .filter(|item| item.is_overdue(now))
+            .collect()
+    }
+
     /// Get a participant's pending approval requests
     pub async fn get_approval_queue(&self, participant_id: Uuid) -> Vec<ApprovalRequest> {
         let queues = self.approval_queues.read().await;
@@ -803,12 +1960,128 @@ impl DelegationManager {
             .unwrap_or_default()
     }
 
+    /// Number of work items in each status, including statuses with none
+    pub async fn count_work_items_by_status(&self) -> Vec<(WorkItemStatus, usize)> {
+        let items = self.work_items.read().await;
+        WorkItemStatus::ALL
+            .iter()
+            .map(|&status| {
+                let count = items.values().filter(|i| i.status == status).count();
+                (status, count)
+            })
+            .collect()
+    }
+
     /// Get a work item by ID
     pub async fn get_work_item(&self, id: Uuid) -> Option<WorkItem> {
         let items = self.work_items.read().await;
         items.get(&id).cloned()
     }
 
+    /// All work in a journal, oldest first, whoever it's assigned to
+    ///
+    /// Finished items (approved, declined, cancelled) are left out unless
+    /// `include_completed` is set.
+    pub async fn get_journal_work_items(
+        &self,
+        journal_id: Uuid,
+        include_completed: bool,
+    ) -> Vec<WorkItem> {
+        let items = self.work_items.read().await;
+        let mut matches: Vec<WorkItem> = items
+            .values()
+            .filter(|item| item.journal_id == journal_id)
+            .filter(|item| include_completed || !item.status.is_terminal())
+            .cloned()
+            .collect();
+
+        matches.sort_by_key(|w| w.created_at);
+        matches
+    }
+
+    /// Work in a journal left behind by participants who unregistered, oldest first
+    pub async fn get_unassigned_work(&self, journal_id: Uuid) -> Vec<WorkItem> {
+        let items = self.work_items.read().await;
+        let mut unassigned: Vec<WorkItem> = items
+            .values()
+            .filter(|item| item.journal_id == journal_id && item.is_unassigned())
+            .filter(|item| item.status == WorkItemStatus::Pending)
+            .cloned()
+            .collect();
+
+        unassigned.sort_by_key(|w| w.created_at);
+        unassigned
+    }
+
+    /// Work counts for one participant, as delegator, assignee and approver
+    pub async fn get_participant_stats(&self, participant_id: Uuid) -> ParticipantStats {
+        let mut stats = ParticipantStats::default();
+
+        for item in self.work_items.read().await.values() {
+            if item.delegator_id == participant_id {
+                stats.delegated_out += 1;
+            }
+            if item.assignee_id == participant_id {
+                stats.assigned += 1;
+                match item.status {
+                    WorkItemStatus::Approved => stats.completed += 1,
+                    WorkItemStatus::Declined => stats.declined += 1,
+                    WorkItemStatus::Cancelled => stats.cancelled += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        stats.pending_approvals = self
+            .approvals
+            .read()
+            .await
+            .values()
+            .filter(|a| a.approver_id == participant_id && a.status == ApprovalStatus::Pending)
+            .count();
+
+        stats
+    }
+
+    /// Search work items the requester is party to (delegator, assignee or approver)
+    ///
+    /// Every whitespace-separated token in `query` must appear, case-insensitively,
+    /// in the item's description or result. Results are newest first.
+    pub async fn search_work_items(
+        &self,
+        requester_id: Uuid,
+        query: &str,
+        journal_id: Option<Uuid>,
+    ) -> Vec<WorkItem> {
+        let tokens: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if tokens.is_empty() {
+            return vec![];
+        }
+
+        let items = self.work_items.read().await;
+        let mut matches: Vec<WorkItem> = items
+            .values()
+            .filter(|item| journal_id.is_none_or(|jid| item.journal_id == jid))
+            .filter(|item| {
+                item.delegator_id == requester_id
+                    || item.assignee_id == requester_id
+                    || (item.requires_approval && item.get_approver_id() == requester_id)
+            })
+            .filter(|item| {
+                let haystack = format!(
+                    "{}\n{}",
+                    item.description.to_lowercase(),
+                    item.result.as_deref().unwrap_or_default().to_lowercase()
+                );
+                tokens.iter().all(|t| haystack.contains(t.as_str()))
+            })
+            .cloned()
+            .collect();
+
+        matches.sort_by_key(|w| std::cmp::Reverse(w.created_at));
+        matches
+    }
+
     /// Get an approval request by ID
     pub async fn get_approval(&self, id: Uuid) -> Option<ApprovalRequest> {
         let approvals = self.approvals.read().await;
@@ -821,12 +2094,19 @@ impl DelegationManager {
         participants.values().cloned().collect()
     }
 
-    /// Get participants accepting work
-    pub async fn list_available_participants(&self) -> Vec<RegisteredParticipant> {
+    /// Get participants accepting work, optionally only those holding `required_capability`
+    pub async fn list_available_participants(
+        &self,
+        required_capability: Option<Capability>,
+    ) -> Vec<RegisteredParticipant> {
         let participants = self.participants.read().await;
         participants
             .values()
             .filter(|p| p.can_receive_work())
+            .filter(|p| match required_capability {
+                Some(cap) => p.has_capability(cap),
+                None => true,
+            })
             .cloned()
             .collect()
     }
@@ -964,6 +2244,98 @@ mod tests {
         assert!(queue.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_pause_and_resume_work() {
+        let manager = DelegationManager::new();
+        let mut rx = manager.subscribe();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+
+        let work = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Pending work cannot be paused
+        assert!(matches!(
+            manager.pause_work(work.id, agent.id()).await,
+            Err(DelegationError::InvalidStateTransition(_))
+        ));
+
+        manager.accept_work(work.id, agent.id()).await.unwrap();
+
+        // Only the assignee may pause
+        assert!(matches!(
+            manager.pause_work(work.id, user.id()).await,
+            Err(DelegationError::NotAuthorized(_))
+        ));
+
+        let paused = manager.pause_work(work.id, agent.id()).await.unwrap();
+        assert_eq!(paused.status, WorkItemStatus::Paused);
+
+        // Still assigned and queued while paused
+        let queue = manager.get_work_queue(agent.id()).await;
+        assert_eq!(queue.len(), 1);
+        assert!(manager
+            .submit_work(work.id, agent.id(), "Early")
+            .await
+            .is_err());
+
+        let resumed = manager.resume_work(work.id, agent.id()).await.unwrap();
+        assert_eq!(resumed.status, WorkItemStatus::InProgress);
+
+        let mut saw_paused = false;
+        let mut saw_resumed = false;
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                DelegationEvent::WorkPaused { work_item_id, .. } => {
+                    saw_paused = work_item_id == work.id;
+                }
+                DelegationEvent::WorkResumed { work_item_id, .. } => {
+                    saw_resumed = work_item_id == work.id;
+                }
+                _ => {}
+            }
+        }
+        assert!(saw_paused && saw_resumed);
+    }
+
+    #[tokio::test]
+    async fn test_paused_work_is_not_overdue() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let past_due = chrono::Utc::now() - chrono::Duration::minutes(5);
+
+        let work = manager
+            .delegate_item(
+                WorkItem::new(Uuid::new_v4(), "Task", user.id(), agent.id()).with_due_at(past_due),
+            )
+            .await
+            .unwrap();
+        manager.accept_work(work.id, agent.id()).await.unwrap();
+        assert_eq!(manager.get_overdue_items(agent.id()).await.len(), 1);
+
+        manager.pause_work(work.id, agent.id()).await.unwrap();
+        assert!(manager.get_overdue_items(agent.id()).await.is_empty());
+
+        // Resuming puts the missed deadline back in view
+        manager.resume_work(work.id, agent.id()).await.unwrap();
+        let overdue = manager.get_overdue_items(agent.id()).await;
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].id, work.id);
+    }
+
     #[tokio::test]
     async fn test_submit_work_no_approval() {
         let manager = DelegationManager::new();
@@ -1047,11 +2419,46 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_approve_work() {
+    async fn test_delegate_item_auto_execute_requires_agent() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let other_user = manager
+            .register_participant(Participant::new("Bob", ParticipantKind::User))
+            .await;
+        let journal_id = Uuid::new_v4();
+        let block_id = Uuid::new_v4();
+
+        let work = manager
+            .delegate_item(
+                WorkItem::for_block(journal_id, block_id, "Summarize", user.id(), agent.id())
+                    .with_auto_execute(true),
+            )
+            .await
+            .unwrap();
+        assert!(work.auto_execute);
+        assert_eq!(work.block_id, Some(block_id));
+        assert_eq!(manager.get_work_queue(agent.id()).await.len(), 1);
+
+        let result = manager
+            .delegate_item(
+                WorkItem::new(journal_id, "Summarize", user.id(), other_user.id())
+                    .with_auto_execute(true),
+            )
+            .await;
+        assert!(matches!(result, Err(DelegationError::NotAuthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delegate_with_explicit_approver() {
         let manager = DelegationManager::new();
 
         let user = manager.register_participant(make_user()).await;
         let agent = manager.register_participant(make_agent()).await;
+        let approver = manager
+            .register_participant(Participant::new("Carol", ParticipantKind::User))
+            .await;
 
         let work = manager
             .delegate(
@@ -1061,31 +2468,175 @@ mod tests {
                 agent.id(),
                 None,
                 true,
-                None,
+                Some(approver.id()),
             )
             .await
             .unwrap();
+        assert_eq!(work.get_approver_id(), approver.id());
+    }
+
+    #[tokio::test]
+    async fn test_delegate_with_unregistered_approver() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let ghost = Uuid::new_v4();
 
+        let result = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent.id(),
+                None,
+                true,
+                Some(ghost),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(DelegationError::ParticipantNotFound(id)) if id == ghost
+        ));
+        assert!(manager.get_work_queue(agent.id()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delegate_with_approver_lacking_capability() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let other_agent = manager
+            .register_participant(Participant::new("Reviewer Bot", ParticipantKind::Agent))
+            .await;
+
+        let result = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent.id(),
+                None,
+                true,
+                Some(other_agent.id()),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(DelegationError::InsufficientCapability {
+                participant_id,
+                required: Capability::Approve,
+            }) if participant_id == other_agent.id()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_submission() {
+        let manager = DelegationManager::new();
+        let mut rx = manager.subscribe();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+
+        let work = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent.id(),
+                None,
+                true,
+                None,
+            )
+            .await
+            .unwrap();
         manager.accept_work(work.id, agent.id()).await.unwrap();
         manager
             .submit_work(work.id, agent.id(), "Done!")
             .await
             .unwrap();
+        let approval_id = manager.get_approval_queue(user.id()).await[0].id;
+        while rx.try_recv().is_ok() {}
 
-        let approvals = manager.get_approval_queue(user.id()).await;
-        let approval_id = approvals[0].id;
+        // Only the assignee may withdraw
+        assert!(matches!(
+            manager.withdraw_submission(work.id, user.id()).await,
+            Err(DelegationError::NotAuthorized(_))
+        ));
 
-        let (approval, item) = manager
-            .approve(approval_id, user.id(), Some("Good job!".to_string()))
+        let item = manager
+            .withdraw_submission(work.id, agent.id())
             .await
             .unwrap();
+        assert_eq!(item.status, WorkItemStatus::InProgress);
 
-        assert_eq!(approval.status, ApprovalStatus::Approved);
+        // The approval is gone from the approver's queue and can't be acted on
+        assert!(manager.get_approval_queue(user.id()).await.is_empty());
+        assert!(manager.get_approval(approval_id).await.is_none());
+        assert!(manager.approve(approval_id, user.id(), None).await.is_err());
+
+        // Back in the assignee's queue
+        let queue = manager.get_work_queue(agent.id()).await;
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].id, work.id);
+
+        match rx.try_recv().unwrap() {
+            DelegationEvent::SubmissionWithdrawn {
+                work_item_id,
+                assignee_id,
+            } => {
+                assert_eq!(work_item_id, work.id);
+                assert_eq!(assignee_id, agent.id());
+            }
+            _ => panic!("Expected SubmissionWithdrawn event"),
+        }
+
+        // Resubmitting raises a fresh approval request
+        manager
+            .submit_work(work.id, agent.id(), "Done, really")
+            .await
+            .unwrap();
+        assert_eq!(manager.get_approval_queue(user.id()).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_submission_after_approval() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+
+        let work = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent.id(),
+                None,
+                true,
+                None,
+            )
+            .await
+            .unwrap();
+        manager.accept_work(work.id, agent.id()).await.unwrap();
+        manager
+            .submit_work(work.id, agent.id(), "Done!")
+            .await
+            .unwrap();
+        let approval_id = manager.get_approval_queue(user.id()).await[0].id;
+        manager.approve(approval_id, user.id(), None).await.unwrap();
+
+        assert!(matches!(
+            manager.withdraw_submission(work.id, agent.id()).await,
+            Err(DelegationError::InvalidStateTransition(_))
+        ));
+        let item = manager.get_work_item(work.id).await.unwrap();
         assert_eq!(item.status, WorkItemStatus::Approved);
     }
 
     #[tokio::test]
-    async fn test_reject_work() {
+    async fn test_approve_work() {
         let manager = DelegationManager::new();
 
         let user = manager.register_participant(make_user()).await;
@@ -1114,20 +2665,16 @@ mod tests {
         let approval_id = approvals[0].id;
 
         let (approval, item) = manager
-            .reject(approval_id, user.id(), "Needs more work")
+            .approve(approval_id, user.id(), Some("Good job!".to_string()))
             .await
             .unwrap();
 
-        assert_eq!(approval.status, ApprovalStatus::Rejected);
-        assert_eq!(item.status, WorkItemStatus::Rejected);
-
-        // Work should be back in agent's queue for rework
-        let queue = manager.get_work_queue(agent.id()).await;
-        assert_eq!(queue.len(), 1);
+        assert_eq!(approval.status, ApprovalStatus::Approved);
+        assert_eq!(item.status, WorkItemStatus::Approved);
     }
 
     #[tokio::test]
-    async fn test_cancel_work() {
+    async fn test_participant_stats_after_full_cycle() {
         let manager = DelegationManager::new();
 
         let user = manager.register_participant(make_user()).await;
@@ -1140,43 +2687,552 @@ mod tests {
                 user.id(),
                 agent.id(),
                 None,
-                false,
+                true,
                 None,
             )
             .await
             .unwrap();
+        manager.accept_work(work.id, agent.id()).await.unwrap();
+        manager
+            .submit_work(work.id, agent.id(), "Done!")
+            .await
+            .unwrap();
+
+        // Waiting on the delegator's review
+        let stats = manager.get_participant_stats(user.id()).await;
+        assert_eq!(stats.delegated_out, 1);
+        assert_eq!(stats.pending_approvals, 1);
+
+        let approval_id = manager.get_approval_queue(user.id()).await[0].id;
+        manager.approve(approval_id, user.id(), None).await.unwrap();
+
+        let stats = manager.get_participant_stats(agent.id()).await;
+        assert_eq!(
+            stats,
+            ParticipantStats {
+                assigned: 1,
+                completed: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            manager
+                .get_participant_stats(user.id())
+                .await
+                .pending_approvals,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reject_work() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+
+        let work = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent.id(),
+                None,
+                true,
+                None,
+            )
+            .await
+            .unwrap();
+
+        manager.accept_work(work.id, agent.id()).await.unwrap();
+        manager
+            .submit_work(work.id, agent.id(), "Done!")
+            .await
+            .unwrap();
+
+        let approvals = manager.get_approval_queue(user.id()).await;
+        let approval_id = approvals[0].id;
+
+        let (approval, item) = manager
+            .reject(approval_id, user.id(), "Needs more work")
+            .await
+            .unwrap();
+
+        assert_eq!(approval.status, ApprovalStatus::Rejected);
+        assert_eq!(item.status, WorkItemStatus::Rejected);
+
+        // Work should be back in agent's queue for rework
+        let queue = manager.get_work_queue(agent.id()).await;
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_work() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+
+        let work = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let cancelled = manager.cancel_work(work.id, user.id()).await.unwrap();
+        assert_eq!(cancelled.status, WorkItemStatus::Cancelled);
+
+        // Should be removed from queue
+        let queue = manager.get_work_queue(agent.id()).await;
+        assert!(queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_comments_are_returned_in_order() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let outsider = manager
+            .register_participant(Participant::new("Eve", ParticipantKind::User))
+            .await;
+
+        let work = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let mut rx = manager.subscribe();
+        let first = manager
+            .add_comment(work.id, user.id(), "Please cite sources")
+            .await
+            .unwrap();
+        let second = manager
+            .add_comment(work.id, agent.id(), "Will do")
+            .await
+            .unwrap();
+
+        match rx.try_recv().unwrap() {
+            DelegationEvent::WorkCommentAdded {
+                comment_id,
+                author_id,
+                ..
+            } => {
+                assert_eq!(comment_id, first.id);
+                assert_eq!(author_id, user.id());
+            }
+            other => panic!("Expected WorkCommentAdded event, got {:?}", other),
+        }
+
+        let comments = manager.get_comments(work.id, agent.id()).await.unwrap();
+        let ids: Vec<_> = comments.iter().map(|c| c.id).collect();
+        assert_eq!(ids, [first.id, second.id]);
+        assert_eq!(comments[1].text, "Will do");
+
+        // Someone with no part in the work can neither read nor write
+        assert!(matches!(
+            manager.get_comments(work.id, outsider.id()).await,
+            Err(DelegationError::NotAuthorized(_))
+        ));
+        assert!(matches!(
+            manager.add_comment(work.id, outsider.id(), "Hi").await,
+            Err(DelegationError::NotAuthorized(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_work_unauthorized() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+
+        let work = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Agent cannot cancel
+        let result = manager.cancel_work(work.id, agent.id()).await;
+        assert!(matches!(result, Err(DelegationError::NotAuthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_overdue_items() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let journal_id = Uuid::new_v4();
+        let now = chrono::Utc::now();
+
+        let late = manager
+            .delegate_item(
+                WorkItem::new(journal_id, "Late", user.id(), agent.id())
+                    .with_due_at(now - chrono::Duration::minutes(5)),
+            )
+            .await
+            .unwrap();
+        manager
+            .delegate_item(
+                WorkItem::new(journal_id, "Upcoming", user.id(), agent.id())
+                    .with_due_at(now + chrono::Duration::hours(1)),
+            )
+            .await
+            .unwrap();
+        manager
+            .delegate(
+                journal_id,
+                "Whenever",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        let on_hold = manager
+            .delegate_item(
+                WorkItem::new(journal_id, "On hold", user.id(), agent.id())
+                    .with_due_at(now - chrono::Duration::minutes(5)),
+            )
+            .await
+            .unwrap();
+        manager.accept_work(on_hold.id, agent.id()).await.unwrap();
+        manager.pause_work(on_hold.id, agent.id()).await.unwrap();
+
+        let overdue = manager.get_overdue_items(agent.id()).await;
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].id, late.id);
+
+        manager.cancel_work(late.id, user.id()).await.unwrap();
+        assert!(manager.get_overdue_items(agent.id()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reassign_work() {
+        let manager = DelegationManager::new();
+        let mut rx = manager.subscribe();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent1 = manager.register_participant(make_agent()).await;
+        let agent2 = manager
+            .register_participant(Participant::new("Bot 2", ParticipantKind::Agent))
+            .await;
+
+        let work = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent1.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        manager.accept_work(work.id, agent1.id()).await.unwrap();
+
+        // Only the delegator may move it
+        let result = manager
+            .reassign_work(work.id, agent2.id(), agent1.id())
+            .await;
+        assert!(matches!(result, Err(DelegationError::NotAuthorized(_))));
+
+        let (previous, item) = manager
+            .reassign_work(work.id, agent2.id(), user.id())
+            .await
+            .unwrap();
+        assert_eq!(previous, agent1.id());
+        assert_eq!(item.assignee_id, agent2.id());
+        assert_eq!(item.status, WorkItemStatus::Pending);
+        assert!(manager.get_work_queue(agent1.id()).await.is_empty());
+        assert_eq!(manager.get_work_queue(agent2.id()).await[0].id, work.id);
+
+        let mut reassigned = false;
+        while let Ok(event) = rx.try_recv() {
+            if let DelegationEvent::WorkReassigned {
+                work_item_id,
+                previous_assignee_id,
+                new_assignee_id,
+                ..
+            } = event
+            {
+                assert_eq!(work_item_id, work.id);
+                assert_eq!(previous_assignee_id, agent1.id());
+                assert_eq!(new_assignee_id, agent2.id());
+                reassigned = true;
+            }
+        }
+        assert!(reassigned);
+    }
+
+    #[tokio::test]
+    async fn test_reassign_work_awaiting_approval() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent1 = manager.register_participant(make_agent()).await;
+        let agent2 = manager
+            .register_participant(Participant::new("Bot 2", ParticipantKind::Agent))
+            .await;
+
+        let work = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent1.id(),
+                None,
+                true,
+                None,
+            )
+            .await
+            .unwrap();
+        manager.accept_work(work.id, agent1.id()).await.unwrap();
+        manager
+            .submit_work(work.id, agent1.id(), "Done")
+            .await
+            .unwrap();
+
+        let result = manager.reassign_work(work.id, agent2.id(), user.id()).await;
+        assert!(matches!(
+            result,
+            Err(DelegationError::InvalidStateTransition(_))
+        ));
+
+        // Reassigning to someone who isn't taking work is refused up front
+        manager
+            .set_accepting_work(agent2.id(), false)
+            .await
+            .unwrap();
+        let result = manager.reassign_work(work.id, agent2.id(), user.id()).await;
+        assert!(matches!(result, Err(DelegationError::NotAcceptingWork(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delegate_many_is_all_or_nothing() {
+        let manager = DelegationManager::new();
+        let journal_id = Uuid::new_v4();
+
+        let user = manager
+            .register_participant(Participant::new("Alice", ParticipantKind::User))
+            .await;
+        let mut agents = Vec::new();
+        for name in ["Bot 1", "Bot 2", "Bot 3"] {
+            let agent = manager
+                .register_participant(Participant::new(name, ParticipantKind::Agent))
+                .await;
+            agents.push(agent.id());
+        }
+        manager.set_accepting_work(agents[1], false).await.unwrap();
+
+        let result = manager
+            .delegate_many(
+                journal_id,
+                "Review the release notes",
+                user.id(),
+                agents.clone(),
+                None,
+                false,
+                None,
+            )
+            .await;
+        assert!(matches!(result, Err(DelegationError::NotAcceptingWork(id)) if id == agents[1]));
+
+        // Nobody got anything, including the assignee checked before the refusal
+        for &agent_id in &agents {
+            assert!(manager.get_work_queue(agent_id).await.is_empty());
+        }
+        assert!(manager
+            .get_journal_work_items(journal_id, true)
+            .await
+            .is_empty());
+
+        manager.set_accepting_work(agents[1], true).await.unwrap();
+        let items = manager
+            .delegate_many(
+                journal_id,
+                "Review the release notes",
+                user.id(),
+                agents.clone(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 3);
+        for (item, &agent_id) in items.iter().zip(&agents) {
+            assert_eq!(item.assignee_id, agent_id);
+            assert_eq!(manager.get_work_queue(agent_id).await.len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reassign_approver_moves_pending_approval() {
+        let manager = DelegationManager::new();
+
+        let lead = manager
+            .register_participant(Participant::new("Lead", ParticipantKind::User))
+            .await;
+        let deputy = manager
+            .register_participant(Participant::new("Deputy", ParticipantKind::User))
+            .await;
+        let agent = manager
+            .register_participant(Participant::new("Bot", ParticipantKind::Agent))
+            .await;
+
+        let work = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Draft the changelog",
+                lead.id(),
+                agent.id(),
+                None,
+                true,
+                None,
+            )
+            .await
+            .unwrap();
+        manager.accept_work(work.id, agent.id()).await.unwrap();
+        manager
+            .submit_work(work.id, agent.id(), "Draft")
+            .await
+            .unwrap();
+        let approval_id = manager.get_approval_queue(lead.id()).await[0].id;
+
+        // Only the current approver can hand it on, and only to someone who can approve
+        let result = manager
+            .reassign_approver(approval_id, deputy.id(), deputy.id())
+            .await;
+        assert!(matches!(result, Err(DelegationError::NotAuthorized(_))));
+        let result = manager
+            .reassign_approver(approval_id, lead.id(), agent.id())
+            .await;
+        assert!(matches!(
+            result,
+            Err(DelegationError::InsufficientCapability { .. })
+        ));
+
+        let approval = manager
+            .reassign_approver(approval_id, lead.id(), deputy.id())
+            .await
+            .unwrap();
+        assert_eq!(approval.approver_id, deputy.id());
+        assert!(manager.get_approval_queue(lead.id()).await.is_empty());
+        let queue = manager.get_approval_queue(deputy.id()).await;
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].id, approval_id);
+        assert_eq!(
+            manager.get_work_item(work.id).await.unwrap().approver_id,
+            Some(deputy.id())
+        );
+
+        // The new approver can now decide it
+        let (_, approved) = manager
+            .approve(approval_id, deputy.id(), None)
+            .await
+            .unwrap();
+        assert_eq!(approved.status, WorkItemStatus::Approved);
+    }
+
+    #[tokio::test]
+    async fn test_quorum_needs_every_required_approval() {
+        let manager = DelegationManager::new();
+
+        let lead = manager.register_participant(make_user()).await;
+        let reviewer = manager
+            .register_participant(Participant::new("Reviewer", ParticipantKind::User))
+            .await;
+        let agent = manager.register_participant(make_agent()).await;
+
+        let item = WorkItem::new(Uuid::new_v4(), "Ship the release", lead.id(), agent.id())
+            .require_approval_from([lead.id(), reviewer.id()])
+            .with_required_approvals(2);
+        let work = manager.delegate_item(item).await.unwrap();
+        manager.accept_work(work.id, agent.id()).await.unwrap();
+        manager
+            .submit_work(work.id, agent.id(), "Tagged v1.0")
+            .await
+            .unwrap();
 
-        let cancelled = manager.cancel_work(work.id, user.id()).await.unwrap();
-        assert_eq!(cancelled.status, WorkItemStatus::Cancelled);
+        // Each approver gets their own request
+        let first = manager.get_approval_queue(lead.id()).await[0].id;
+        let second = manager.get_approval_queue(reviewer.id()).await[0].id;
+        assert_ne!(first, second);
 
-        // Should be removed from queue
-        let queue = manager.get_work_queue(agent.id()).await;
-        assert!(queue.is_empty());
+        let (_, item) = manager.approve(first, lead.id(), None).await.unwrap();
+        assert_eq!(item.status, WorkItemStatus::AwaitingApproval);
+        assert!(manager.get_approval_queue(lead.id()).await.is_empty());
+
+        let (_, item) = manager.approve(second, reviewer.id(), None).await.unwrap();
+        assert_eq!(item.status, WorkItemStatus::Approved);
+        assert!(manager.get_approval_queue(reviewer.id()).await.is_empty());
     }
 
     #[tokio::test]
-    async fn test_cancel_work_unauthorized() {
+    async fn test_quorum_rejected_by_any_approver() {
         let manager = DelegationManager::new();
 
-        let user = manager.register_participant(make_user()).await;
+        let lead = manager.register_participant(make_user()).await;
+        let reviewer = manager
+            .register_participant(Participant::new("Reviewer", ParticipantKind::User))
+            .await;
         let agent = manager.register_participant(make_agent()).await;
 
-        let work = manager
-            .delegate(
-                Uuid::new_v4(),
-                "Task",
-                user.id(),
-                agent.id(),
-                None,
-                false,
-                None,
-            )
+        let item = WorkItem::new(Uuid::new_v4(), "Ship the release", lead.id(), agent.id())
+            .require_approval_from([lead.id(), reviewer.id()])
+            .with_required_approvals(2);
+        let work = manager.delegate_item(item).await.unwrap();
+        manager.accept_work(work.id, agent.id()).await.unwrap();
+        manager
+            .submit_work(work.id, agent.id(), "v1")
             .await
             .unwrap();
 
-        // Agent cannot cancel
-        let result = manager.cancel_work(work.id, agent.id()).await;
-        assert!(matches!(result, Err(DelegationError::NotAuthorized(_))));
+        let first = manager.get_approval_queue(lead.id()).await[0].id;
+        manager.approve(first, lead.id(), None).await.unwrap();
+        let second = manager.get_approval_queue(reviewer.id()).await[0].id;
+        let (_, item) = manager
+            .reject(second, reviewer.id(), "Missing changelog")
+            .await
+            .unwrap();
+        assert_eq!(item.status, WorkItemStatus::Rejected);
+
+        // A resubmission starts a new round; the earlier approval does not carry over
+        manager
+            .submit_work(work.id, agent.id(), "v2")
+            .await
+            .unwrap();
+        let first = manager.get_approval_queue(lead.id()).await[0].id;
+        let (_, item) = manager.approve(first, lead.id(), None).await.unwrap();
+        assert_eq!(item.status, WorkItemStatus::AwaitingApproval);
     }
 
     #[tokio::test]
@@ -1325,6 +3381,199 @@ mod tests {
         assert_eq!(queue2.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_departed_assignee_work_becomes_claimable() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let helper = manager
+            .register_participant(Participant::new("Helper", ParticipantKind::Agent))
+            .await;
+        let journal_id = Uuid::new_v4();
+
+        let work = manager
+            .delegate(journal_id, "Task", user.id(), agent.id(), None, false, None)
+            .await
+            .unwrap();
+        manager.accept_work(work.id, agent.id()).await.unwrap();
+        assert!(manager.get_unassigned_work(journal_id).await.is_empty());
+
+        let mut rx = manager.subscribe();
+        manager.unregister_participant(agent.id()).await.unwrap();
+
+        match rx.try_recv().unwrap() {
+            DelegationEvent::WorkUnassigned {
+                work_item_id,
+                previous_assignee_id,
+            } => {
+                assert_eq!(work_item_id, work.id);
+                assert_eq!(previous_assignee_id, agent.id());
+            }
+            other => panic!("Expected WorkUnassigned event, got {:?}", other),
+        }
+
+        let unassigned = manager.get_unassigned_work(journal_id).await;
+        assert_eq!(unassigned.len(), 1);
+        assert_eq!(unassigned[0].status, WorkItemStatus::Pending);
+        assert!(unassigned[0].is_unassigned());
+        assert!(manager.get_work_queue(agent.id()).await.is_empty());
+
+        let claimed = manager.claim_work(work.id, helper.id()).await.unwrap();
+        assert_eq!(claimed.assignee_id, helper.id());
+        assert!(manager.get_unassigned_work(journal_id).await.is_empty());
+        assert_eq!(manager.get_work_queue(helper.id()).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_returning_participant_keeps_work() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = make_agent();
+        manager.register_participant(agent.clone()).await;
+        let journal_id = Uuid::new_v4();
+
+        let work = manager
+            .delegate(journal_id, "Task", user.id(), agent.id, None, false, None)
+            .await
+            .unwrap();
+
+        let departed_at = chrono::Utc::now();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        manager.register_participant(agent.clone()).await;
+
+        assert!(manager
+            .unregister_if_departed(agent.id, departed_at)
+            .await
+            .is_none());
+        assert!(manager.get_participant(agent.id).await.is_some());
+        assert_eq!(manager.get_work_queue(agent.id).await[0].id, work.id);
+
+        // Gone for good this time
+        let departed_at = chrono::Utc::now();
+        assert!(manager
+            .unregister_if_departed(agent.id, departed_at)
+            .await
+            .is_some());
+        assert_eq!(manager.get_unassigned_work(journal_id).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_set_accepting_work() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let paused_a = manager.register_participant(make_agent()).await;
+        let paused_b = manager.register_participant(make_agent()).await;
+        let on_duty = manager.register_participant(make_agent()).await;
+        let missing = Uuid::new_v4();
+
+        let mut events = manager.subscribe();
+        let unknown = manager
+            .bulk_set_accepting_work(&[paused_a.id(), missing, paused_b.id()], false)
+            .await;
+        assert_eq!(unknown, vec![missing]);
+
+        for expected in [paused_a.id(), paused_b.id()] {
+            match events.try_recv().unwrap() {
+                DelegationEvent::ParticipantStatusChanged {
+                    participant_id,
+                    accepting_work,
+                } => {
+                    assert_eq!(participant_id, expected);
+                    assert!(!accepting_work);
+                }
+                other => panic!("Unexpected event: {:?}", other),
+            }
+        }
+
+        for agent in [&paused_a, &paused_b] {
+            let result = manager
+                .delegate(
+                    Uuid::new_v4(),
+                    "Task",
+                    user.id(),
+                    agent.id(),
+                    None,
+                    false,
+                    None,
+                )
+                .await;
+            assert!(matches!(result, Err(DelegationError::NotAcceptingWork(_))));
+        }
+
+        manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                on_duty.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_cancel_is_audited() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let intruder = manager.register_participant(make_user()).await;
+
+        let item = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Task",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(manager.stats().authorization_failures, 0);
+
+        let result = manager.cancel_work(item.id, intruder.id()).await;
+        // The caller sees the usual error
+        assert!(matches!(result, Err(DelegationError::NotAuthorized(_))));
+
+        let audit = manager.audit_log();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].actor_id, intruder.id());
+        assert_eq!(audit[0].action, "cancel_work");
+        assert!(audit[0]
+            .reason
+            .contains("Only the delegator can cancel work"));
+        assert_eq!(audit[0].capability, None);
+        assert_eq!(manager.stats().authorization_failures, 1);
+
+        // Missing capabilities are also counted per capability
+        let result = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Sneaky",
+                agent.id(),
+                user.id(),
+                None,
+                true,
+                Some(agent.id()),
+            )
+            .await;
+        assert!(result.is_err());
+        let stats = manager.stats();
+        assert_eq!(stats.authorization_failures, 2);
+        assert_eq!(
+            stats.capability_failures.get(&Capability::Approve),
+            Some(&1)
+        );
+    }
+
     #[tokio::test]
     async fn test_set_accepting_work() {
         let manager = DelegationManager::new();
@@ -1364,13 +3613,30 @@ mod tests {
         // Disable agent
         manager.set_accepting_work(agent.id(), false).await.unwrap();
 
-        let available = manager.list_available_participants().await;
+        let available = manager.list_available_participants(None).await;
 
         // Only user should be available (observer has 0 capacity)
         assert_eq!(available.len(), 1);
         assert_eq!(available[0].id(), user.id());
     }
 
+    #[tokio::test]
+    async fn test_list_available_participants_with_capability() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        manager.register_participant(make_agent()).await;
+
+        assert_eq!(manager.list_available_participants(None).await.len(), 2);
+
+        // Agents can't approve by default
+        let approvers = manager
+            .list_available_participants(Some(Capability::Approve))
+            .await;
+        assert_eq!(approvers.len(), 1);
+        assert_eq!(approvers[0].id(), user.id());
+    }
+
     #[tokio::test]
     async fn test_update_capabilities() {
         let manager = DelegationManager::new();
@@ -1458,4 +3724,246 @@ mod tests {
         assert_eq!(queue[1].id, high.id);
         assert_eq!(queue[2].id, normal.id);
     }
+
+    #[tokio::test]
+    async fn test_sorted_work_queue_puts_urgent_first() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+
+        let mut ids = Vec::new();
+        for (task, priority) in [
+            ("Low", WorkPriority::Low),
+            ("Normal", WorkPriority::Normal),
+            ("Urgent", WorkPriority::Urgent),
+            ("Also normal", WorkPriority::Normal),
+        ] {
+            let item = manager
+                .delegate(
+                    Uuid::new_v4(),
+                    task,
+                    user.id(),
+                    agent.id(),
+                    Some(priority),
+                    false,
+                    None,
+                )
+                .await
+                .unwrap();
+            ids.push(item.id);
+        }
+
+        let queue: Vec<Uuid> = manager
+            .get_work_queue_sorted(agent.id())
+            .await
+            .iter()
+            .map(|item| item.id)
+            .collect();
+        // Urgent jumps the earlier Low item; equal priorities keep their order
+        assert_eq!(queue, vec![ids[2], ids[1], ids[3], ids[0]]);
+    }
+
+    #[tokio::test]
+    async fn test_get_work_by_tag() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let journal_id = Uuid::new_v4();
+
+        let mut ids = Vec::new();
+        for (task, tags) in [
+            ("Fix login", vec!["Backend", "bug", "backend"]),
+            ("Restyle header", vec!["frontend"]),
+            ("Fix layout", vec![" BUG ", "frontend"]),
+            ("Untagged", vec![]),
+        ] {
+            let item = WorkItem::new(journal_id, task, user.id(), agent.id()).with_tags(tags);
+            ids.push(manager.delegate_item(item).await.unwrap().id);
+        }
+
+        let item = manager.get_work_item(ids[0]).await.unwrap();
+        assert_eq!(item.tags, vec!["backend", "bug"]);
+
+        let tagged = |items: Vec<WorkItem>| items.iter().map(|i| i.id).collect::<Vec<_>>();
+        assert_eq!(
+            tagged(manager.get_work_by_tag(agent.id(), "bug").await),
+            vec![ids[0], ids[2]]
+        );
+        assert_eq!(
+            tagged(manager.get_work_by_tag(agent.id(), "Frontend").await),
+            vec![ids[1], ids[2]]
+        );
+        assert!(manager.get_work_by_tag(agent.id(), "docs").await.is_empty());
+        assert!(manager.get_work_by_tag(user.id(), "bug").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_journal_work_items() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let other_agent = manager
+            .register_participant(Participant::new("Helper", ParticipantKind::Agent))
+            .await;
+        let journal_id = Uuid::new_v4();
+
+        let first = manager
+            .delegate(
+                journal_id,
+                "Draft the outline",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        let second = manager
+            .delegate(
+                journal_id,
+                "Collect references",
+                user.id(),
+                other_agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        manager
+            .delegate(
+                Uuid::new_v4(),
+                "Unrelated",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Every assignee's work in the journal, and nothing from elsewhere
+        let items = manager.get_journal_work_items(journal_id, false).await;
+        let ids: Vec<_> = items.iter().map(|item| item.id).collect();
+        assert_eq!(ids, vec![first.id, second.id]);
+
+        // Finished work is only listed on request
+        manager.cancel_work(second.id, user.id()).await.unwrap();
+        assert_eq!(
+            manager
+                .get_journal_work_items(journal_id, false)
+                .await
+                .len(),
+            1
+        );
+        assert_eq!(
+            manager.get_journal_work_items(journal_id, true).await.len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_work_items_by_description_and_result() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let journal_id = Uuid::new_v4();
+
+        let parser = manager
+            .delegate(
+                journal_id,
+                "Fix the JSON parser",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        manager
+            .delegate(
+                journal_id,
+                "Write release notes",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let found = manager
+            .search_work_items(user.id(), "json PARSER", None)
+            .await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, parser.id);
+
+        // Results are searched once the work is submitted
+        manager.accept_work(parser.id, agent.id()).await.unwrap();
+        manager
+            .submit_work(parser.id, agent.id(), "Handled trailing commas")
+            .await
+            .unwrap();
+        let found = manager
+            .search_work_items(agent.id(), "trailing commas", Some(journal_id))
+            .await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, parser.id);
+
+        // Journal scoping and empty queries
+        assert!(manager
+            .search_work_items(user.id(), "parser", Some(Uuid::new_v4()))
+            .await
+            .is_empty());
+        assert!(manager
+            .search_work_items(user.id(), "   ", None)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_work_items_scoped_to_parties() {
+        let manager = DelegationManager::new();
+
+        let user = manager.register_participant(make_user()).await;
+        let agent = manager.register_participant(make_agent()).await;
+        let approver = manager
+            .register_participant(Participant::new("Carol", ParticipantKind::User))
+            .await;
+        let outsider = manager
+            .register_participant(Participant::new("Mallory", ParticipantKind::User))
+            .await;
+
+        let work = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Rotate the API keys",
+                user.id(),
+                agent.id(),
+                None,
+                true,
+                Some(approver.id()),
+            )
+            .await
+            .unwrap();
+
+        for party in [user.id(), agent.id(), approver.id()] {
+            let found = manager.search_work_items(party, "api keys", None).await;
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].id, work.id);
+        }
+
+        assert!(manager
+            .search_work_items(outsider.id(), "api keys", None)
+            .await
+            .is_empty());
+    }
 }
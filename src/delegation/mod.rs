@@ -3,12 +3,18 @@
 //! This module implements a capability-based delegation system where any participant
 //! (human or agent) can delegate work to any other participant with appropriate permissions.
 
+pub mod audit;
 pub mod capability;
 pub mod manager;
+pub mod notify;
 pub mod participant;
 pub mod work_item;
 
+pub use audit::{AuthorizationFailure, DelegationAuditEntry, DelegationStats};
 pub use capability::Capability;
-pub use manager::{DelegationEvent, DelegationManager};
+pub use manager::{DelegationEvent, DelegationManager, ParticipantStats};
+pub use notify::{NotificationSink, WebhookSink};
 pub use participant::RegisteredParticipant;
-pub use work_item::{ApprovalRequest, ApprovalStatus, WorkItem, WorkItemStatus};
+pub use work_item::{
+    ApprovalRequest, ApprovalStatus, WorkItem, WorkItemComment, WorkItemStatus, UNASSIGNED,
+};
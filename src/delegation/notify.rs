@@ -0,0 +1,299 @@
+//! Outbound notifications for delegation events
+//!
+//! Sinks are invoked synchronously by the manager for every event, so
+//! implementations must hand off any slow work (network I/O) to a spawned
+//! task and return immediately.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::manager::DelegationEvent;
+use super::work_item::WorkItem;
+
+/// Receiver of delegation events for external delivery
+pub trait NotificationSink: Send + Sync {
+    /// Deliver an event. Must not block.
+    fn notify(&self, event: &DelegationEvent);
+}
+
+/// Posts delegation events as JSON to an HTTP endpoint
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+    events: HashSet<String>,
+}
+
+impl WebhookSink {
+    /// Events delivered when none are configured explicitly
+    pub const DEFAULT_EVENTS: [&'static str; 3] =
+        ["work_delegated", "approval_requested", "work_rejected"];
+
+    /// Create a webhook sink for the default set of events
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_events(url, Self::DEFAULT_EVENTS)
+    }
+
+    /// Create a webhook sink for specific event names (e.g. `work_delegated`)
+    pub fn with_events<I, S>(url: impl Into<String>, events: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            events: events.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Whether this sink delivers the given event
+    pub fn wants(&self, event: &DelegationEvent) -> bool {
+        self.events.contains(event.name())
+    }
+}
+
+impl NotificationSink for WebhookSink {
+    fn notify(&self, event: &DelegationEvent) {
+        if !self.wants(event) {
+            return;
+        }
+
+        let request = self.client.post(&self.url).json(event);
+        let name = event.name();
+
+        tokio::spawn(async move {
+            match request.send().await {
+                Ok(response) if !response.status().is_success() => {
+                    tracing::warn!("Webhook for {} returned status {}", name, response.status());
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("Webhook for {} failed: {}", name, e);
+                }
+            }
+        });
+    }
+}
+
+/// Body posted to a participant's own webhook when work lands in its queue
+#[derive(Serialize)]
+struct AssignmentPayload<'a> {
+    #[serde(flatten)]
+    event: &'a DelegationEvent,
+    work_item: &'a WorkItem,
+}
+
+/// Delivers work assignments to per-participant webhooks
+///
+/// Unlike [`WebhookSink`], which fans every event out to one endpoint, this
+/// targets the assignee's registered URL and retries failed deliveries.
+#[derive(Clone)]
+pub(crate) struct AssignmentWebhooks {
+    client: reqwest::Client,
+}
+
+impl AssignmentWebhooks {
+    /// Delivery attempts before giving up
+    const MAX_ATTEMPTS: u32 = 3;
+    /// Delay before the first retry; doubled for each later one
+    const INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+
+    pub(crate) fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Post the assignment in the background, retrying with backoff
+    pub(crate) fn deliver(&self, url: &str, event: &DelegationEvent, work_item: &WorkItem) {
+        let body = match serde_json::to_value(AssignmentPayload { event, work_item }) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!("Failed to encode assignment webhook: {}", e);
+                return;
+            }
+        };
+        let client = self.client.clone();
+        let url = url.to_string();
+        let work_item_id = work_item.id;
+
+        tokio::spawn(async move {
+            let mut backoff = Self::INITIAL_BACKOFF;
+            for attempt in 1..=Self::MAX_ATTEMPTS {
+                match client.post(&url).json(&body).send().await {
+                    Ok(response) if response.status().is_success() => return,
+                    Ok(response) => {
+                        tracing::warn!(
+                            "Assignment webhook for {} returned status {} (attempt {})",
+                            work_item_id,
+                            response.status(),
+                            attempt
+                        );
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "Assignment webhook for {} failed (attempt {}): {}",
+                            work_item_id,
+                            attempt,
+                            e
+                        );
+                    }
+                }
+                if attempt < Self::MAX_ATTEMPTS {
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+            }
+            tracing::error!(
+                "Giving up on assignment webhook for {} after {} attempts",
+                work_item_id,
+                Self::MAX_ATTEMPTS
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crdt::{Participant, ParticipantKind};
+    use crate::delegation::DelegationManager;
+    use std::sync::Arc;
+    use uuid::Uuid;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_webhook_default_events() {
+        let sink = WebhookSink::new("http://localhost/hook");
+        let delegated = DelegationEvent::WorkDelegated {
+            work_item_id: Uuid::new_v4(),
+            delegator_id: Uuid::new_v4(),
+            assignee_id: Uuid::new_v4(),
+            description: "Task".to_string(),
+        };
+        let accepted = DelegationEvent::WorkAccepted {
+            work_item_id: Uuid::new_v4(),
+            assignee_id: Uuid::new_v4(),
+        };
+        assert!(sink.wants(&delegated));
+        assert!(!sink.wants(&accepted));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_fires_on_delegation() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(body_partial_json(serde_json::json!({
+                "type": "work_delegated",
+                "description": "Review the migration"
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let manager = DelegationManager::new();
+        manager.add_sink(Arc::new(WebhookSink::new(format!(
+            "{}/hook",
+            mock_server.uri()
+        ))));
+
+        let user = manager
+            .register_participant(Participant::new("Alice", ParticipantKind::User))
+            .await;
+        let agent = manager
+            .register_participant(Participant::new("Bot", ParticipantKind::Agent))
+            .await;
+        manager
+            .delegate(
+                Uuid::new_v4(),
+                "Review the migration",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Delivery is spawned; wait for it to land
+        for _ in 0..50 {
+            let received = mock_server.received_requests().await.unwrap_or_default();
+            if !received.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_assignment_webhook_receives_work_item() {
+        let mock_server = MockServer::start().await;
+        let manager = DelegationManager::new();
+
+        let user = manager
+            .register_participant(Participant::new("Alice", ParticipantKind::User))
+            .await;
+        let agent = manager
+            .register_participant(Participant::new("Bot", ParticipantKind::Agent))
+            .await;
+        manager
+            .set_webhook_url(agent.id(), Some(format!("{}/agent", mock_server.uri())))
+            .await
+            .unwrap();
+
+        // The first attempt fails; the retry should still get through
+        Mock::given(method("POST"))
+            .and(path("/agent"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/agent"))
+            .and(body_partial_json(
+                serde_json::json!({ "type": "work_delegated" }),
+            ))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let item = manager
+            .delegate(
+                Uuid::new_v4(),
+                "Index the docs",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let mut received = Vec::new();
+        for _ in 0..50 {
+            received = mock_server.received_requests().await.unwrap_or_default();
+            if received.len() >= 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        assert_eq!(received.len(), 2);
+        let body: serde_json::Value = received[1].body_json().unwrap();
+        assert_eq!(body["work_item_id"], item.id.to_string());
+        assert_eq!(body["work_item"]["id"], item.id.to_string());
+        assert_eq!(body["work_item"]["description"], "Index the docs");
+        mock_server.verify().await;
+    }
+}
@@ -22,6 +22,9 @@ pub struct RegisteredParticipant {
     pub work_capacity: u32,
     /// When this participant was registered
     pub registered_at: DateTime<Utc>,
+    /// Endpoint that receives work delegated or reassigned to this participant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
 }
 
 impl RegisteredParticipant {
@@ -45,6 +48,7 @@ impl RegisteredParticipant {
             accepting_work: true,
             work_capacity,
             registered_at: Utc::now(),
+            webhook_url: None,
         }
     }
 
@@ -62,6 +66,7 @@ impl RegisteredParticipant {
             accepting_work: true,
             work_capacity,
             registered_at: Utc::now(),
+            webhook_url: None,
         }
     }
 
@@ -105,6 +110,11 @@ impl RegisteredParticipant {
         self.accepting_work = accepting;
     }
 
+    /// Set or clear the work assignment webhook
+    pub fn set_webhook_url(&mut self, url: Option<String>) {
+        self.webhook_url = url;
+    }
+
     /// Grant a capability
     pub fn grant_capability(&mut self, cap: Capability) {
         self.capabilities.add(cap);
@@ -6,6 +6,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Assignee of work whose participant left before finishing it; anyone may claim it
+pub const UNASSIGNED: Uuid = Uuid::nil();
+
 /// Status of a work item
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -14,6 +17,8 @@ pub enum WorkItemStatus {
     Pending,
     /// Work has been accepted and is being worked on
     InProgress,
+    /// Work is still assigned but the assignee has paused progress
+    Paused,
     /// Work is complete and awaiting approval
     AwaitingApproval,
     /// Work has been approved and is complete
@@ -27,10 +32,23 @@ pub enum WorkItemStatus {
 }
 
 impl WorkItemStatus {
+    /// Every status, in workflow order
+    pub const ALL: [WorkItemStatus; 8] = [
+        WorkItemStatus::Pending,
+        WorkItemStatus::InProgress,
+        WorkItemStatus::Paused,
+        WorkItemStatus::AwaitingApproval,
+        WorkItemStatus::Approved,
+        WorkItemStatus::Rejected,
+        WorkItemStatus::Declined,
+        WorkItemStatus::Cancelled,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             WorkItemStatus::Pending => "pending",
             WorkItemStatus::InProgress => "in_progress",
+            WorkItemStatus::Paused => "paused",
             WorkItemStatus::AwaitingApproval => "awaiting_approval",
             WorkItemStatus::Approved => "approved",
             WorkItemStatus::Rejected => "rejected",
@@ -60,6 +78,7 @@ impl std::str::FromStr for WorkItemStatus {
         match s {
             "pending" => Ok(WorkItemStatus::Pending),
             "in_progress" => Ok(WorkItemStatus::InProgress),
+            "paused" => Ok(WorkItemStatus::Paused),
             "awaiting_approval" => Ok(WorkItemStatus::AwaitingApproval),
             "approved" => Ok(WorkItemStatus::Approved),
             "rejected" => Ok(WorkItemStatus::Rejected),
@@ -131,15 +150,41 @@ pub struct WorkItem {
     /// Who should approve (defaults to delegator)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub approver_id: Option<Uuid>,
+    /// Everyone asked to approve when there is more than one approver
+    ///
+    /// `approver_id` holds the first of them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub approver_ids: Vec<Uuid>,
+    /// Distinct approvals needed before the work counts as approved
+    #[serde(default = "default_required_approvals")]
+    pub required_approvals: u32,
+    /// Whether the server carries out the work via OpenCode for an agent assignee
+    #[serde(default)]
+    pub auto_execute: bool,
     /// Result/output when work is complete
     #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<String>,
+    /// When the work should be finished by
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<DateTime<Utc>>,
+    /// Free-form labels for grouping work, lowercase and without repeats
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     /// Created timestamp
     pub created_at: DateTime<Utc>,
     /// Last updated timestamp
     pub updated_at: DateTime<Utc>,
 }
 
+fn default_required_approvals() -> u32 {
+    1
+}
+
+fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim();
+    (!tag.is_empty()).then(|| tag.to_lowercase())
+}
+
 impl WorkItem {
     /// Create a new work item
     pub fn new(
@@ -160,7 +205,12 @@ impl WorkItem {
             priority: WorkPriority::Normal,
             requires_approval: false,
             approver_id: None,
+            approver_ids: Vec::new(),
+            required_approvals: 1,
+            auto_execute: false,
             result: None,
+            due_at: None,
+            tags: Vec::new(),
             created_at: now,
             updated_at: now,
         }
@@ -186,12 +236,83 @@ impl WorkItem {
     }
 
     /// Require approval for this work item
-    pub fn require_approval(mut self, approver_id: Option<Uuid>) -> Self {
+    pub fn require_approval(self, approver_id: Option<Uuid>) -> Self {
+        self.require_approval_from(approver_id)
+    }
+
+    /// Require approval from each of `approver_ids` (the delegator if none)
+    ///
+    /// Repeated approvers are only asked once.
+    pub fn require_approval_from(mut self, approver_ids: impl IntoIterator<Item = Uuid>) -> Self {
+        let mut approver_ids: Vec<Uuid> = approver_ids.into_iter().collect();
+        let mut seen = std::collections::HashSet::new();
+        approver_ids.retain(|id| seen.insert(*id));
+
         self.requires_approval = true;
-        self.approver_id = approver_id;
+        self.approver_id = approver_ids.first().copied();
+        self.approver_ids = if approver_ids.len() > 1 {
+            approver_ids
+        } else {
+            Vec::new()
+        };
+        self
+    }
+
+    /// Set how many approvers must sign off (at least 1)
+    pub fn with_required_approvals(mut self, required: u32) -> Self {
+        self.required_approvals = required.max(1);
+        self
+    }
+
+    /// Have the server execute this work through OpenCode
+    pub fn with_auto_execute(mut self, auto_execute: bool) -> Self {
+        self.auto_execute = auto_execute;
         self
     }
 
+    /// Set a deadline
+    pub fn with_due_at(mut self, due_at: DateTime<Utc>) -> Self {
+        self.due_at = Some(due_at);
+        self
+    }
+
+    /// Label the work, replacing any existing tags
+    ///
+    /// Tags are trimmed and lowercased; blank and repeated tags are dropped.
+    pub fn with_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.tags.clear();
+        for tag in tags {
+            if let Some(tag) = normalize_tag(tag.as_ref()) {
+                if !self.tags.contains(&tag) {
+                    self.tags.push(tag);
+                }
+            }
+        }
+        self
+    }
+
+    /// Whether the work carries `tag`, compared the way tags are stored
+    pub fn has_tag(&self, tag: &str) -> bool {
+        normalize_tag(tag).is_some_and(|tag| self.tags.contains(&tag))
+    }
+
+    /// Whether the deadline has passed as of `now`
+    ///
+    /// Finished work is never overdue, and neither is paused work: the
+    /// assignee has said it is on hold.
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        match self.due_at {
+            Some(due_at) => {
+                due_at < now && !self.status.is_terminal() && self.status != WorkItemStatus::Paused
+            }
+            None => false,
+        }
+    }
+
     /// Accept the work item (move to in_progress)
     pub fn accept(&mut self) -> Result<(), String> {
         if self.status != WorkItemStatus::Pending {
@@ -218,6 +339,32 @@ impl WorkItem {
         Ok(())
     }
 
+    /// Pause active work without giving it back
+    pub fn pause(&mut self) -> Result<(), String> {
+        if !self.status.is_active() {
+            return Err(format!(
+                "Cannot pause work item with status: {}",
+                self.status.as_str()
+            ));
+        }
+        self.status = WorkItemStatus::Paused;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Resume paused work (move back to in_progress)
+    pub fn resume(&mut self) -> Result<(), String> {
+        if self.status != WorkItemStatus::Paused {
+            return Err(format!(
+                "Cannot resume work item with status: {}",
+                self.status.as_str()
+            ));
+        }
+        self.status = WorkItemStatus::InProgress;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     /// Submit the work for approval
     pub fn submit_for_approval(&mut self, result: impl Into<String>) -> Result<(), String> {
         if !self.status.is_active() {
@@ -236,6 +383,19 @@ impl WorkItem {
         Ok(())
     }
 
+    /// Take back a submission that has not been reviewed yet (move back to in_progress)
+    pub fn withdraw(&mut self) -> Result<(), String> {
+        if self.status != WorkItemStatus::AwaitingApproval {
+            return Err(format!(
+                "Cannot withdraw work item with status: {}",
+                self.status.as_str()
+            ));
+        }
+        self.status = WorkItemStatus::InProgress;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     /// Complete work without approval requirement
     pub fn complete(&mut self, result: impl Into<String>) -> Result<(), String> {
         if !self.status.is_active() {
@@ -250,6 +410,20 @@ impl WorkItem {
         Ok(())
     }
 
+    /// Hand the work item to a different assignee, starting over as pending
+    pub fn reassign(&mut self, assignee_id: Uuid) -> Result<(), String> {
+        if self.status.is_terminal() || self.status == WorkItemStatus::AwaitingApproval {
+            return Err(format!(
+                "Cannot reassign work item with status: {}",
+                self.status.as_str()
+            ));
+        }
+        self.assignee_id = assignee_id;
+        self.status = WorkItemStatus::Pending;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     /// Cancel the work item (by delegator)
     pub fn cancel(&mut self) -> Result<(), String> {
         if self.status.is_terminal() {
@@ -267,6 +441,47 @@ impl WorkItem {
     pub fn get_approver_id(&self) -> Uuid {
         self.approver_id.unwrap_or(self.delegator_id)
     }
+
+    /// Everyone who is asked to approve a submission
+    pub fn get_approver_ids(&self) -> Vec<Uuid> {
+        if self.approver_ids.is_empty() {
+            vec![self.get_approver_id()]
+        } else {
+            self.approver_ids.clone()
+        }
+    }
+
+    /// Whether the work is waiting in the unassigned pool for someone to claim it
+    pub fn is_unassigned(&self) -> bool {
+        self.assignee_id == UNASSIGNED
+    }
+
+    /// Whether `participant_id` delegated, is assigned or approves this item
+    pub fn involves(&self, participant_id: Uuid) -> bool {
+        self.delegator_id == participant_id
+            || self.assignee_id == participant_id
+            || self.get_approver_ids().contains(&participant_id)
+    }
+
+    /// Approvals needed to finish, capped at the number of approvers
+    pub fn quorum(&self) -> usize {
+        (self.required_approvals as usize).min(self.get_approver_ids().len())
+    }
+
+    /// Swap one approver for another, keeping their place in the list
+    pub fn replace_approver(&mut self, previous_id: Uuid, new_id: Uuid) {
+        if self.get_approver_id() == previous_id {
+            self.approver_id = Some(new_id);
+        }
+        for id in self
+            .approver_ids
+            .iter_mut()
+            .filter(|id| **id == previous_id)
+        {
+            *id = new_id;
+        }
+        self.updated_at = Utc::now();
+    }
 }
 
 /// Status of an approval request
@@ -330,14 +545,26 @@ pub struct ApprovalRequest {
 impl ApprovalRequest {
     /// Create a new approval request
     pub fn new(work_item: &WorkItem) -> Self {
+        Self::for_approver(work_item, work_item.get_approver_id(), Utc::now())
+    }
+
+    /// Create a request asking `approver_id` to review `work_item`
+    ///
+    /// Requests from one submission share `created_at`, which is how their
+    /// approvals are told apart from those of earlier submissions.
+    pub fn for_approver(
+        work_item: &WorkItem,
+        approver_id: Uuid,
+        created_at: DateTime<Utc>,
+    ) -> Self {
         Self {
             id: Uuid::new_v4(),
             work_item_id: work_item.id,
             requester_id: work_item.assignee_id,
-            approver_id: work_item.get_approver_id(),
+            approver_id,
             status: ApprovalStatus::Pending,
             feedback: None,
-            created_at: Utc::now(),
+            created_at,
             resolved_at: None,
         }
     }
@@ -369,6 +596,44 @@ impl ApprovalRequest {
         self.resolved_at = Some(Utc::now());
         Ok(())
     }
+
+    /// Hand the decision to a different approver
+    pub fn reassign(&mut self, new_approver_id: Uuid) -> Result<(), String> {
+        if self.status != ApprovalStatus::Pending {
+            return Err(format!(
+                "Cannot reassign request with status: {}",
+                self.status.as_str()
+            ));
+        }
+        self.approver_id = new_approver_id;
+        Ok(())
+    }
+}
+
+/// A note left on a work item by one of its parties
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkItemComment {
+    /// Unique identifier
+    pub id: Uuid,
+    /// The work item commented on
+    pub work_item_id: Uuid,
+    /// Who wrote the comment
+    pub author_id: Uuid,
+    pub text: String,
+    /// Created timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl WorkItemComment {
+    pub fn new(work_item_id: Uuid, author_id: Uuid, text: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            work_item_id,
+            author_id,
+            text: text.into(),
+            created_at: Utc::now(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -383,6 +648,7 @@ mod tests {
     fn test_work_item_status_as_str() {
         assert_eq!(WorkItemStatus::Pending.as_str(), "pending");
         assert_eq!(WorkItemStatus::InProgress.as_str(), "in_progress");
+        assert_eq!(WorkItemStatus::Paused.as_str(), "paused");
         assert_eq!(
             WorkItemStatus::AwaitingApproval.as_str(),
             "awaiting_approval"
@@ -409,6 +675,7 @@ mod tests {
     fn test_work_item_status_is_terminal() {
         assert!(!WorkItemStatus::Pending.is_terminal());
         assert!(!WorkItemStatus::InProgress.is_terminal());
+        assert!(!WorkItemStatus::Paused.is_terminal());
         assert!(!WorkItemStatus::AwaitingApproval.is_terminal());
         assert!(WorkItemStatus::Approved.is_terminal());
         assert!(!WorkItemStatus::Rejected.is_terminal());
@@ -420,6 +687,7 @@ mod tests {
     fn test_work_item_status_is_active() {
         assert!(!WorkItemStatus::Pending.is_active());
         assert!(WorkItemStatus::InProgress.is_active());
+        assert!(!WorkItemStatus::Paused.is_active());
         assert!(!WorkItemStatus::AwaitingApproval.is_active());
         assert!(!WorkItemStatus::Approved.is_active());
         assert!(WorkItemStatus::Rejected.is_active());
@@ -503,6 +771,21 @@ mod tests {
         assert_eq!(item.status, WorkItemStatus::Declined);
     }
 
+    #[test]
+    fn test_work_item_pause_and_resume() {
+        let mut item = make_work_item();
+        assert!(item.pause().is_err());
+
+        item.accept().unwrap();
+        assert!(item.pause().is_ok());
+        assert_eq!(item.status, WorkItemStatus::Paused);
+        assert!(item.complete("Too soon").is_err());
+
+        assert!(item.resume().is_ok());
+        assert_eq!(item.status, WorkItemStatus::InProgress);
+        assert!(item.resume().is_err());
+    }
+
     #[test]
     fn test_work_item_complete() {
         let mut item = make_work_item();
@@ -520,6 +803,18 @@ mod tests {
         assert_eq!(item.status, WorkItemStatus::AwaitingApproval);
     }
 
+    #[test]
+    fn test_work_item_withdraw() {
+        let mut item = make_work_item().require_approval(None);
+        item.accept().unwrap();
+        assert!(item.withdraw().is_err());
+
+        item.submit_for_approval("Please review").unwrap();
+        assert!(item.withdraw().is_ok());
+        assert_eq!(item.status, WorkItemStatus::InProgress);
+        assert_eq!(item.result, Some("Please review".to_string()));
+    }
+
     #[test]
     fn test_work_item_cancel() {
         let mut item = make_work_item();
@@ -599,6 +894,36 @@ mod tests {
         assert!(json.contains("pending"));
     }
 
+    #[test]
+    fn test_work_item_is_overdue() {
+        let now = Utc::now();
+        let mut item = make_work_item().with_due_at(now - chrono::Duration::hours(1));
+        assert!(item.is_overdue(now));
+        assert!(!make_work_item().is_overdue(now));
+        assert!(!make_work_item()
+            .with_due_at(now + chrono::Duration::hours(1))
+            .is_overdue(now));
+
+        item.accept().unwrap();
+        item.pause().unwrap();
+        assert!(!item.is_overdue(now));
+
+        item.resume().unwrap();
+        item.complete("Done").unwrap();
+        assert!(!item.is_overdue(now));
+    }
+
+    #[test]
+    fn test_work_item_due_at_serialized_only_when_set() {
+        let item = make_work_item();
+        let json = serde_json::to_string(&item).unwrap();
+        assert!(!json.contains("due_at"));
+
+        let item = item.with_due_at(Utc::now());
+        let json = serde_json::to_string(&item).unwrap();
+        assert!(json.contains("due_at"));
+    }
+
     #[test]
     fn test_approval_status_as_str() {
         assert_eq!(ApprovalStatus::Pending.as_str(), "pending");
@@ -0,0 +1,862 @@
+//! Database store for delegation participants, work items and approval requests
+
+use chrono::Utc;
+use sqlx::sqlite::SqliteExecutor;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::crdt::Participant;
+use crate::delegation::capability::{Capability, CapabilitySet};
+use crate::delegation::participant::RegisteredParticipant;
+use crate::delegation::{
+    ApprovalRequest, DelegationAuditEntry, DelegationEvent, WorkItem, WorkItemComment,
+};
+use crate::error::{AppError, Result};
+
+/// Persists the delegation manager's participants, work items and approvals
+///
+/// The manager stays the source of truth while running; this store is written
+/// through on every change so the manager can be rebuilt after a restart.
+#[derive(Clone)]
+pub struct DelegationStore {
+    pool: SqlitePool,
+}
+
+impl DelegationStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert or update a registered participant
+    pub async fn save_participant(&self, registered: &RegisteredParticipant) -> Result<()> {
+        let capabilities = serde_json::to_string(&registered.capabilities.to_vec())
+            .map_err(|e| AppError::Internal(format!("Failed to serialize capabilities: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO registered_participants (
+                id, participant_id, name, kind, capabilities, accepting_work,
+                work_capacity, webhook_url, registered_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                kind = excluded.kind,
+                capabilities = excluded.capabilities,
+                accepting_work = excluded.accepting_work,
+                work_capacity = excluded.work_capacity,
+                webhook_url = excluded.webhook_url,
+                registered_at = excluded.registered_at
+            "#,
+        )
+        .bind(registered.id().to_string())
+        .bind(registered.id().to_string())
+        .bind(registered.name())
+        .bind(registered.kind().as_str())
+        .bind(capabilities)
+        .bind(registered.accepting_work)
+        .bind(registered.work_capacity)
+        .bind(&registered.webhook_url)
+        .bind(registered.registered_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Forget a participant that unregistered
+    pub async fn delete_participant(&self, id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM registered_participants WHERE id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Insert or update a work item
+    pub async fn save_work_item(&self, item: &WorkItem) -> Result<()> {
+        upsert_work_item(&self.pool, item).await
+    }
+
+    /// Insert or update several work items, all or none
+    pub async fn save_work_items(&self, items: &[WorkItem]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for item in items {
+            upsert_work_item(&mut *tx, item).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Insert or update an approval request
+    pub async fn save_approval(&self, approval: &ApprovalRequest) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO approval_requests (
+                id, work_item_id, requester_id, approver_id, status, feedback,
+                created_at, resolved_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                approver_id = excluded.approver_id,
+                status = excluded.status,
+                feedback = excluded.feedback,
+                resolved_at = excluded.resolved_at
+            "#,
+        )
+        .bind(approval.id.to_string())
+        .bind(approval.work_item_id.to_string())
+        .bind(approval.requester_id.to_string())
+        .bind(approval.approver_id.to_string())
+        .bind(approval.status.as_str())
+        .bind(&approval.feedback)
+        .bind(approval.created_at)
+        .bind(approval.resolved_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove an approval request (e.g. when the submission is withdrawn)
+    pub async fn delete_approval(&self, id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM approval_requests WHERE id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Append an event to the audit table
+    pub async fn append_audit(&self, event: &DelegationEvent) -> Result<()> {
+        let details = serde_json::to_string(event)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize event: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO delegation_audit (event_type, actor_id, work_item_id, details, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(event.name())
+        .bind(event.actor_id().map(|id| id.to_string()))
+        .bind(event.work_item_id().map(|id| id.to_string()))
+        .bind(details)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The most recent `limit` audit entries for work items in `journal_id`, oldest first
+    pub async fn load_audit_log(
+        &self,
+        journal_id: Uuid,
+        limit: u32,
+    ) -> Result<Vec<DelegationAuditEntry>> {
+        let rows = sqlx::query_as::<_, AuditRow>(
+            r#"
+            SELECT * FROM (
+                SELECT a.id, a.event_type, a.actor_id, a.work_item_id, a.details, a.created_at
+                FROM delegation_audit a
+                JOIN work_items w ON w.id = a.work_item_id
+                WHERE w.journal_id = ?
+                ORDER BY a.id DESC
+                LIMIT ?
+            )
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(journal_id.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(DelegationAuditEntry::try_from)
+            .collect()
+    }
+
+    /// All registered participants, in registration order
+    pub async fn load_participants(&self) -> Result<Vec<RegisteredParticipant>> {
+        let rows = sqlx::query_as::<_, ParticipantRow>(
+            r#"
+            SELECT id, name, kind, capabilities, accepting_work, work_capacity,
+                   webhook_url, registered_at
+            FROM registered_participants
+            ORDER BY registered_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(RegisteredParticipant::try_from)
+            .collect()
+    }
+
+    /// All work items, oldest first
+    pub async fn load_work_items(&self) -> Result<Vec<WorkItem>> {
+        let rows = sqlx::query_as::<_, WorkItemRow>(
+            r#"
+            SELECT id, journal_id, description, block_id, delegator_id, assignee_id,
+                   status, priority, requires_approval, approver_id, approver_ids,
+                   required_approvals, auto_execute, result, due_at, tags, created_at, updated_at
+            FROM work_items
+            ORDER BY created_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(WorkItem::try_from).collect()
+    }
+
+    /// All approval requests, oldest first
+    pub async fn load_approvals(&self) -> Result<Vec<ApprovalRequest>> {
+        let rows = sqlx::query_as::<_, ApprovalRow>(
+            r#"
+            SELECT id, work_item_id, requester_id, approver_id, status, feedback,
+                   created_at, resolved_at
+            FROM approval_requests
+            ORDER BY created_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(ApprovalRequest::try_from).collect()
+    }
+
+    /// Insert a comment on a work item
+    pub async fn save_comment(&self, comment: &WorkItemComment) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO work_item_comments (id, work_item_id, author_id, text, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(comment.id.to_string())
+        .bind(comment.work_item_id.to_string())
+        .bind(comment.author_id.to_string())
+        .bind(&comment.text)
+        .bind(comment.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// All work item comments, oldest first
+    pub async fn load_comments(&self) -> Result<Vec<WorkItemComment>> {
+        let rows = sqlx::query_as::<_, CommentRow>(
+            r#"
+            SELECT id, work_item_id, author_id, text, created_at
+            FROM work_item_comments
+            ORDER BY created_at ASC, rowid ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(WorkItemComment::try_from).collect()
+    }
+}
+
+/// Write a work item through `executor`
+///
+/// An older copy of the item never replaces a newer one, since writes can
+/// land out of order once the manager has released its locks.
+async fn upsert_work_item<'e>(executor: impl SqliteExecutor<'e>, item: &WorkItem) -> Result<()> {
+    let approver_ids = serde_json::to_string(&item.approver_ids)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize approvers: {}", e)))?;
+    let tags = serde_json::to_string(&item.tags)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize tags: {}", e)))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO work_items (
+            id, journal_id, description, block_id, delegator_id, assignee_id,
+            status, priority, requires_approval, approver_id, approver_ids,
+            required_approvals, auto_execute, result, due_at, tags, created_at, updated_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(id) DO UPDATE SET
+            description = excluded.description,
+            block_id = excluded.block_id,
+            assignee_id = excluded.assignee_id,
+            status = excluded.status,
+            priority = excluded.priority,
+            requires_approval = excluded.requires_approval,
+            approver_id = excluded.approver_id,
+            approver_ids = excluded.approver_ids,
+            required_approvals = excluded.required_approvals,
+            auto_execute = excluded.auto_execute,
+            result = excluded.result,
+            due_at = excluded.due_at,
+            tags = excluded.tags,
+            updated_at = excluded.updated_at
+        WHERE excluded.updated_at >= work_items.updated_at
+        "#,
+    )
+    .bind(item.id.to_string())
+    .bind(item.journal_id.to_string())
+    .bind(&item.description)
+    .bind(item.block_id.map(|id| id.to_string()))
+    .bind(item.delegator_id.to_string())
+    .bind(item.assignee_id.to_string())
+    .bind(item.status.as_str())
+    .bind(item.priority.as_str())
+    .bind(item.requires_approval)
+    .bind(item.approver_id.map(|id| id.to_string()))
+    .bind(approver_ids)
+    .bind(item.required_approvals)
+    .bind(item.auto_execute)
+    .bind(&item.result)
+    .bind(item.due_at)
+    .bind(tags)
+    .bind(item.created_at)
+    .bind(item.updated_at)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+fn parse_uuid(what: &str, id: &str, value: &str) -> Result<Uuid> {
+    Uuid::parse_str(value)
+        .map_err(|e| AppError::Internal(format!("Invalid {} for {}: {}", what, id, e)))
+}
+
+#[derive(sqlx::FromRow)]
+struct ParticipantRow {
+    id: String,
+    name: String,
+    kind: String,
+    capabilities: String,
+    accepting_work: bool,
+    work_capacity: u32,
+    webhook_url: Option<String>,
+    registered_at: chrono::DateTime<Utc>,
+}
+
+impl TryFrom<ParticipantRow> for RegisteredParticipant {
+    type Error = AppError;
+
+    fn try_from(row: ParticipantRow) -> Result<Self> {
+        let id = &row.id;
+        let capabilities: Vec<Capability> = serde_json::from_str(&row.capabilities)
+            .map_err(|e| AppError::Internal(format!("Invalid capabilities for {}: {}", id, e)))?;
+        let participant = Participant::with_id(
+            parse_uuid("participant UUID", id, id)?,
+            row.name,
+            row.kind.parse().map_err(AppError::Internal)?,
+        );
+        Ok(RegisteredParticipant {
+            participant,
+            capabilities: CapabilitySet::from(capabilities),
+            accepting_work: row.accepting_work,
+            work_capacity: row.work_capacity,
+            registered_at: row.registered_at,
+            webhook_url: row.webhook_url,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct WorkItemRow {
+    id: String,
+    journal_id: String,
+    description: String,
+    block_id: Option<String>,
+    delegator_id: String,
+    assignee_id: String,
+    status: String,
+    priority: String,
+    requires_approval: bool,
+    approver_id: Option<String>,
+    approver_ids: String,
+    required_approvals: u32,
+    auto_execute: bool,
+    result: Option<String>,
+    due_at: Option<chrono::DateTime<Utc>>,
+    tags: String,
+    created_at: chrono::DateTime<Utc>,
+    updated_at: chrono::DateTime<Utc>,
+}
+
+impl TryFrom<WorkItemRow> for WorkItem {
+    type Error = AppError;
+
+    fn try_from(row: WorkItemRow) -> Result<Self> {
+        let id = &row.id;
+        Ok(WorkItem {
+            id: parse_uuid("work item UUID", id, id)?,
+            journal_id: parse_uuid("journal_id", id, &row.journal_id)?,
+            description: row.description,
+            block_id: row
+                .block_id
+                .as_deref()
+                .map(|v| parse_uuid("block_id", id, v))
+                .transpose()?,
+            delegator_id: parse_uuid("delegator_id", id, &row.delegator_id)?,
+            assignee_id: parse_uuid("assignee_id", id, &row.assignee_id)?,
+            status: row.status.parse().map_err(AppError::Internal)?,
+            priority: row.priority.parse().map_err(AppError::Internal)?,
+            requires_approval: row.requires_approval,
+            approver_id: row
+                .approver_id
+                .as_deref()
+                .map(|v| parse_uuid("approver_id", id, v))
+                .transpose()?,
+            approver_ids: serde_json::from_str(&row.approver_ids).map_err(|e| {
+                AppError::Internal(format!("Invalid approver_ids for {}: {}", id, e))
+            })?,
+            required_approvals: row.required_approvals,
+            auto_execute: row.auto_execute,
+            result: row.result,
+            due_at: row.due_at,
+            tags: serde_json::from_str(&row.tags)
+                .map_err(|e| AppError::Internal(format!("Invalid tags for {}: {}", id, e)))?,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ApprovalRow {
+    id: String,
+    work_item_id: String,
+    requester_id: String,
+    approver_id: String,
+    status: String,
+    feedback: Option<String>,
+    created_at: chrono::DateTime<Utc>,
+    resolved_at: Option<chrono::DateTime<Utc>>,
+}
+
+impl TryFrom<ApprovalRow> for ApprovalRequest {
+    type Error = AppError;
+
+    fn try_from(row: ApprovalRow) -> Result<Self> {
+        let id = &row.id;
+        Ok(ApprovalRequest {
+            id: parse_uuid("approval UUID", id, id)?,
+            work_item_id: parse_uuid("work_item_id", id, &row.work_item_id)?,
+            requester_id: parse_uuid("requester_id", id, &row.requester_id)?,
+            approver_id: parse_uuid("approver_id", id, &row.approver_id)?,
+            status: row.status.parse().map_err(AppError::Internal)?,
+            feedback: row.feedback,
+            created_at: row.created_at,
+            resolved_at: row.resolved_at,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct CommentRow {
+    id: String,
+    work_item_id: String,
+    author_id: String,
+    text: String,
+    created_at: chrono::DateTime<Utc>,
+}
+
+impl TryFrom<CommentRow> for WorkItemComment {
+    type Error = AppError;
+
+    fn try_from(row: CommentRow) -> Result<Self> {
+        let id = &row.id;
+        Ok(WorkItemComment {
+            id: parse_uuid("comment UUID", id, id)?,
+            work_item_id: parse_uuid("work_item_id", id, &row.work_item_id)?,
+            author_id: parse_uuid("author_id", id, &row.author_id)?,
+            text: row.text,
+            created_at: row.created_at,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AuditRow {
+    id: i64,
+    event_type: String,
+    actor_id: Option<String>,
+    work_item_id: Option<String>,
+    details: String,
+    created_at: chrono::DateTime<Utc>,
+}
+
+impl TryFrom<AuditRow> for DelegationAuditEntry {
+    type Error = AppError;
+
+    fn try_from(row: AuditRow) -> Result<Self> {
+        let id = row.id.to_string();
+        Ok(DelegationAuditEntry {
+            id: row.id,
+            event_type: row.event_type,
+            actor_id: row
+                .actor_id
+                .as_deref()
+                .map(|v| parse_uuid("actor_id", &id, v))
+                .transpose()?,
+            work_item_id: row
+                .work_item_id
+                .as_deref()
+                .map(|v| parse_uuid("work_item_id", &id, v))
+                .transpose()?,
+            details: serde_json::from_str(&row.details).map_err(|e| {
+                AppError::Internal(format!("Invalid details for audit entry {}: {}", id, e))
+            })?,
+            at: row.created_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crdt::{Participant, ParticipantKind};
+    use crate::delegation::manager::DelegationError;
+    use crate::delegation::{DelegationManager, WorkItemStatus};
+    use crate::models::BlockType;
+    use crate::store::Store;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_work_item_round_trip() {
+        let pool = setup_pool().await;
+        let journal = Store::new(pool.clone()).create_journal(None).await.unwrap();
+        let store = DelegationStore::new(pool);
+
+        let mut item = WorkItem::new(journal.id, "Write tests", Uuid::new_v4(), Uuid::new_v4())
+            .require_approval(Some(Uuid::new_v4()))
+            .with_due_at(Utc::now())
+            .with_tags(["backend", "Tests"]);
+        store.save_work_item(&item).await.unwrap();
+
+        item.accept().unwrap();
+        item.submit_for_approval("Done").unwrap();
+        store.save_work_item(&item).await.unwrap();
+
+        let loaded = store.load_work_items().await.unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].id, item.id);
+        assert_eq!(loaded[0].status, WorkItemStatus::AwaitingApproval);
+        assert_eq!(loaded[0].approver_id, item.approver_id);
+        assert_eq!(loaded[0].result.as_deref(), Some("Done"));
+        assert_eq!(loaded[0].due_at, item.due_at);
+        assert_eq!(loaded[0].tags, vec!["backend", "tests"]);
+    }
+
+    #[tokio::test]
+    async fn test_comments_survive_restart() {
+        let pool = setup_pool().await;
+        let journal = Store::new(pool.clone()).create_journal(None).await.unwrap();
+
+        let manager = DelegationManager::with_pool(pool.clone()).await.unwrap();
+        let user = manager
+            .register_participant(Participant::new("Alice", ParticipantKind::User))
+            .await;
+        let agent = manager
+            .register_participant(Participant::new("Bot", ParticipantKind::Agent))
+            .await;
+        let item = manager
+            .delegate(
+                journal.id,
+                "Summarize",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        manager
+            .add_comment(item.id, user.id(), "Keep it short")
+            .await
+            .unwrap();
+        manager
+            .add_comment(item.id, agent.id(), "Okay")
+            .await
+            .unwrap();
+        drop(manager);
+
+        let manager = DelegationManager::with_pool(pool).await.unwrap();
+        let texts: Vec<_> = manager
+            .get_comments(item.id, user.id())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| c.text)
+            .collect();
+        assert_eq!(texts, ["Keep it short", "Okay"]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_approval_survives_restart() {
+        let pool = setup_pool().await;
+        let journal = Store::new(pool.clone()).create_journal(None).await.unwrap();
+
+        let manager = DelegationManager::with_pool(pool.clone()).await.unwrap();
+        let user = manager
+            .register_participant(Participant::new("Alice", ParticipantKind::User))
+            .await;
+        let agent = manager
+            .register_participant(Participant::new("Bot", ParticipantKind::Agent))
+            .await;
+        let item = manager
+            .delegate(
+                journal.id,
+                "Summarize",
+                user.id(),
+                agent.id(),
+                None,
+                true,
+                None,
+            )
+            .await
+            .unwrap();
+        manager.accept_work(item.id, agent.id()).await.unwrap();
+        manager
+            .submit_work(item.id, agent.id(), "Summary")
+            .await
+            .unwrap();
+        let approval = manager.get_approval_queue(user.id()).await[0].clone();
+        drop(manager);
+
+        // A fresh manager over the same database sees the pending approval
+        let manager = DelegationManager::with_pool(pool).await.unwrap();
+        let queue = manager.get_approval_queue(user.id()).await;
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].id, approval.id);
+        assert_eq!(
+            manager.get_work_item(item.id).await.unwrap().status,
+            WorkItemStatus::AwaitingApproval
+        );
+        assert!(manager.get_work_queue(agent.id()).await.is_empty());
+
+        // Once the approver is back it can act on the restored request
+        let user = manager
+            .register_participant(Participant::with_id(
+                user.id(),
+                "Alice",
+                ParticipantKind::User,
+            ))
+            .await;
+        let (_, approved) = manager.approve(approval.id, user.id(), None).await.unwrap();
+        assert_eq!(approved.status, WorkItemStatus::Approved);
+        assert!(manager.get_approval_queue(user.id()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_participants_survive_restart() {
+        let pool = setup_pool().await;
+        let journal = Store::new(pool.clone()).create_journal(None).await.unwrap();
+
+        let manager = DelegationManager::with_pool(pool.clone()).await.unwrap();
+        let user = manager
+            .register_participant(Participant::new("Alice", ParticipantKind::User))
+            .await;
+        let agent = manager
+            .register_participant(Participant::new("Bot", ParticipantKind::Agent))
+            .await;
+        let item = manager
+            .delegate(
+                journal.id,
+                "Summarize",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+        manager.set_accepting_work(agent.id(), false).await.unwrap();
+        drop(manager);
+
+        // Without registering again, the agent is known and its queue intact
+        let manager = DelegationManager::with_pool(pool.clone()).await.unwrap();
+        let restored = manager.get_participant(agent.id()).await.unwrap();
+        assert_eq!(restored.name(), "Bot");
+        assert_eq!(restored.kind(), ParticipantKind::Agent);
+        assert!(!restored.accepting_work);
+        assert_eq!(manager.get_work_queue(agent.id()).await[0].id, item.id);
+        manager.accept_work(item.id, agent.id()).await.unwrap();
+
+        manager.unregister_participant(user.id()).await.unwrap();
+        let ids: Vec<_> = DelegationStore::new(pool)
+            .load_participants()
+            .await
+            .unwrap()
+            .iter()
+            .map(|p| p.id())
+            .collect();
+        assert_eq!(ids, [agent.id()]);
+    }
+
+    #[tokio::test]
+    async fn test_work_survives_deleting_its_block() {
+        let pool = setup_pool().await;
+        let store = Store::new(pool.clone());
+        let journal = store.create_journal(None).await.unwrap();
+        let block = store
+            .create_block(journal.id, BlockType::User, "Question")
+            .await
+            .unwrap();
+
+        let manager = DelegationManager::with_pool(pool.clone()).await.unwrap();
+        let user = manager
+            .register_participant(Participant::new("Alice", ParticipantKind::User))
+            .await;
+        let agent = manager
+            .register_participant(Participant::new("Bot", ParticipantKind::Agent))
+            .await;
+        let item = manager
+            .delegate_item(WorkItem::for_block(
+                journal.id,
+                block.id,
+                "Answer it",
+                user.id(),
+                agent.id(),
+            ))
+            .await
+            .unwrap();
+
+        let deleted = store.delete_block(block.id, false).await.unwrap();
+        manager.unlink_blocks(&deleted).await;
+        manager.accept_work(item.id, agent.id()).await.unwrap();
+        drop(manager);
+
+        let manager = DelegationManager::with_pool(pool).await.unwrap();
+        let item = manager.get_work_item(item.id).await.unwrap();
+        assert_eq!(item.block_id, None);
+        assert_eq!(item.status, WorkItemStatus::InProgress);
+    }
+
+    #[tokio::test]
+    async fn test_delegating_into_missing_journal_fails() {
+        let pool = setup_pool().await;
+        let manager = DelegationManager::with_pool(pool.clone()).await.unwrap();
+        let user = manager
+            .register_participant(Participant::new("Alice", ParticipantKind::User))
+            .await;
+        let agent = manager
+            .register_participant(Participant::new("Bot", ParticipantKind::Agent))
+            .await;
+
+        let missing = Uuid::new_v4();
+        let err = manager
+            .delegate(
+                missing,
+                "Summarize",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, DelegationError::MissingReference(refs) if refs.contains(&missing.to_string()))
+        );
+        assert!(manager.get_work_queue(agent.id()).await.is_empty());
+        assert!(DelegationStore::new(pool)
+            .load_work_items()
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delegation_events_are_audited() {
+        let pool = setup_pool().await;
+        let store = Store::new(pool.clone());
+        let journal = store.create_journal(None).await.unwrap();
+        let other = store.create_journal(None).await.unwrap();
+
+        let manager = DelegationManager::with_pool(pool.clone()).await.unwrap();
+        let user = manager
+            .register_participant(Participant::new("Alice", ParticipantKind::User))
+            .await;
+        let agent = manager
+            .register_participant(Participant::new("Bot", ParticipantKind::Agent))
+            .await;
+        let item = manager
+            .delegate(
+                journal.id,
+                "Summarize",
+                user.id(),
+                agent.id(),
+                None,
+                true,
+                None,
+            )
+            .await
+            .unwrap();
+        manager.accept_work(item.id, agent.id()).await.unwrap();
+        manager
+            .submit_work(item.id, agent.id(), "Summary")
+            .await
+            .unwrap();
+        let approval = manager.get_approval_queue(user.id()).await[0].clone();
+        manager.approve(approval.id, user.id(), None).await.unwrap();
+
+        let log = manager.get_audit_log(journal.id, 100).await.unwrap();
+        let events: Vec<_> = log.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(
+            events,
+            [
+                "work_delegated",
+                "work_accepted",
+                "approval_requested",
+                "work_approved"
+            ]
+        );
+        assert!(log.iter().all(|e| e.work_item_id == Some(item.id)));
+
+        let decisions: Vec<_> = log
+            .iter()
+            .filter(|e| matches!(e.event_type.as_str(), "work_delegated" | "work_approved"))
+            .collect();
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0].actor_id, Some(user.id()));
+        assert_eq!(decisions[1].actor_id, Some(user.id()));
+        assert_eq!(decisions[1].details["approver_id"], user.id().to_string());
+
+        // Only the newest entries are kept when limited
+        let tail = manager.get_audit_log(journal.id, 1).await.unwrap();
+        assert_eq!(tail.len(), 1);
+        assert_eq!(tail[0].event_type, "work_approved");
+
+        assert!(manager
+            .get_audit_log(other.id, 100)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Rows cannot be rewritten after the fact
+        assert!(sqlx::query("DELETE FROM delegation_audit")
+            .execute(&pool)
+            .await
+            .is_err());
+    }
+}
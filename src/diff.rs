@@ -0,0 +1,121 @@
+//! Line diffs between block contents
+//!
+//! Uses a longest-common-subsequence table, which is quadratic in the number
+//! of lines but trivially correct; block contents are small enough for that.
+
+use serde::{Deserialize, Serialize};
+
+/// What a hunk does to get from the old text to the new one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// A run of consecutive lines sharing the same operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub op: DiffOp,
+    pub lines: Vec<String>,
+}
+
+/// Compute the line diff turning `old` into `new`
+///
+/// Within a changed region, deletions are listed before insertions.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffHunk> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] is the LCS length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        let (op, line) = if i < a.len() && j < b.len() && a[i] == b[j] {
+            i += 1;
+            j += 1;
+            (DiffOp::Equal, a[i - 1])
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            i += 1;
+            (DiffOp::Delete, a[i - 1])
+        } else {
+            j += 1;
+            (DiffOp::Insert, b[j - 1])
+        };
+
+        match hunks.last_mut() {
+            Some(hunk) if hunk.op == op => hunk.lines.push(line.to_string()),
+            _ => hunks.push(DiffHunk {
+                op,
+                lines: vec![line.to_string()],
+            }),
+        }
+    }
+
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk(op: DiffOp, lines: &[&str]) -> DiffHunk {
+        DiffHunk {
+            op,
+            lines: lines.iter().map(|l| l.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_identical() {
+        assert_eq!(
+            diff_lines("a\nb", "a\nb"),
+            vec![hunk(DiffOp::Equal, &["a", "b"])]
+        );
+        assert!(diff_lines("", "").is_empty());
+    }
+
+    #[test]
+    fn test_changed_line() {
+        assert_eq!(
+            diff_lines("one\ntwo\nthree", "one\n2\nthree\nfour"),
+            vec![
+                hunk(DiffOp::Equal, &["one"]),
+                hunk(DiffOp::Delete, &["two"]),
+                hunk(DiffOp::Insert, &["2"]),
+                hunk(DiffOp::Equal, &["three"]),
+                hunk(DiffOp::Insert, &["four"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_and_to_empty() {
+        assert_eq!(
+            diff_lines("", "x\ny"),
+            vec![hunk(DiffOp::Insert, &["x", "y"])]
+        );
+        assert_eq!(
+            diff_lines("x\ny", ""),
+            vec![hunk(DiffOp::Delete, &["x", "y"])]
+        );
+    }
+
+    #[test]
+    fn test_hunk_serialization() {
+        let json = serde_json::to_value(hunk(DiffOp::Insert, &["new"])).unwrap();
+        assert_eq!(json, serde_json::json!({"op": "insert", "lines": ["new"]}));
+    }
+}
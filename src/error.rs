@@ -2,6 +2,7 @@
 
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,16 +13,78 @@ pub enum AppError {
     #[error("OpenCode error: {0}")]
     OpenCode(String),
 
+    #[error("OpenCode timed out after {}ms", .0.as_millis())]
+    OpenCodeTimeout(std::time::Duration),
+
     #[error("Not found: {0}")]
     NotFound(String),
 
     #[error("Bad request: {0}")]
     BadRequest(String),
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Block not ready: {0}")]
+    BlockNotReady(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
 
+/// Machine-readable category of a failure reported to a client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The journal, block, work item or participant doesn't exist
+    NotFound,
+    /// The request was malformed or its arguments were rejected
+    InvalidMessage,
+    /// The participant kind given isn't one the server knows
+    InvalidKind,
+    /// Someone in the journal already goes by the requested name
+    NameTaken,
+    /// The connection hasn't registered with the delegation system
+    NotRegistered,
+    /// The participant lacks a capability the action requires
+    InsufficientCapability,
+    /// The participant isn't allowed to act on this item
+    NotAuthorized,
+    /// The action isn't valid in the item's current state
+    InvalidState,
+    /// Someone else changed the item first; re-read it and try again
+    Conflict,
+    /// The block is still being written; wait for it to complete or fail
+    BlockNotReady,
+    /// The connection is sending requests faster than it's allowed to
+    RateLimited,
+    /// OpenCode stopped responding; the request may be retried
+    Timeout,
+    /// The frame was larger than the server accepts; nothing was parsed
+    MessageTooLarge,
+    /// A server-side failure; retrying may help
+    Internal,
+}
+
+impl AppError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::BadRequest(_) => ErrorCode::InvalidMessage,
+            AppError::Conflict(_) => ErrorCode::Conflict,
+            AppError::Forbidden(_) => ErrorCode::NotAuthorized,
+            AppError::BlockNotReady(_) => ErrorCode::BlockNotReady,
+            AppError::OpenCodeTimeout(_) => ErrorCode::Timeout,
+            AppError::Database(_) | AppError::OpenCode(_) | AppError::Internal(_) => {
+                ErrorCode::Internal
+            }
+        }
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, message) = match &self {
@@ -36,8 +99,14 @@ impl IntoResponse for AppError {
                 tracing::error!("OpenCode error: {}", e);
                 (StatusCode::BAD_GATEWAY, format!("OpenCode error: {}", e))
             }
+            AppError::OpenCodeTimeout(_) => {
+                tracing::warn!("{}", self);
+                (StatusCode::GATEWAY_TIMEOUT, self.to_string())
+            }
             AppError::NotFound(e) => (StatusCode::NOT_FOUND, e.clone()),
             AppError::BadRequest(e) => (StatusCode::BAD_REQUEST, e.clone()),
+            AppError::Forbidden(e) => (StatusCode::FORBIDDEN, e.clone()),
+            AppError::Conflict(e) | AppError::BlockNotReady(e) => (StatusCode::CONFLICT, e.clone()),
             AppError::Internal(e) => {
                 tracing::error!("Internal error: {}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, e.clone())
@@ -77,6 +146,35 @@ mod tests {
         assert!(debug_str.contains("NotFound"));
     }
 
+    #[test]
+    fn test_app_error_code() {
+        assert_eq!(
+            AppError::NotFound("journal".to_string()).code(),
+            ErrorCode::NotFound
+        );
+        assert_eq!(
+            AppError::BadRequest("empty".to_string()).code(),
+            ErrorCode::InvalidMessage
+        );
+        assert_eq!(
+            AppError::OpenCode("down".to_string()).code(),
+            ErrorCode::Internal
+        );
+        assert_eq!(
+            AppError::OpenCodeTimeout(std::time::Duration::from_millis(500)).code(),
+            ErrorCode::Timeout
+        );
+        assert_eq!(
+            serde_json::to_string(&ErrorCode::InsufficientCapability).unwrap(),
+            r#""insufficient_capability""#
+        );
+        assert_eq!(
+            serde_json::to_string(&AppError::BlockNotReady("streaming".to_string()).code())
+                .unwrap(),
+            r#""block_not_ready""#
+        );
+    }
+
     #[test]
     fn test_not_found_into_response() {
         let err = AppError::NotFound("resource".to_string());
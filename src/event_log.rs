@@ -0,0 +1,284 @@
+//! Append-only log of significant server events
+//!
+//! Each record is one JSON object per line, so the file can be replayed (or
+//! simply grepped) to reconstruct what happened after a crash. Writes are
+//! handed to a dedicated thread over a channel; callers never touch the disk.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use crate::delegation::{DelegationEvent, NotificationSink};
+use crate::models::{Block, Journal};
+
+/// Default size at which the log is rotated
+pub const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Number of rotated files kept alongside the active log (`events.log.1`, ...)
+const KEEP_ROTATED: usize = 3;
+
+/// An event recorded in the log
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LoggedEvent {
+    JournalCreated { journal: Journal },
+    BlockCreated { block: Block },
+    Delegation(DelegationEvent),
+}
+
+#[derive(Serialize)]
+struct LogLine<'a> {
+    ts: DateTime<Utc>,
+    #[serde(flatten)]
+    event: &'a LoggedEvent,
+}
+
+enum Command {
+    Write(String),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Handle to a background event log writer
+///
+/// Cloning is cheap; all clones feed the same file.
+#[derive(Clone)]
+pub struct EventLog {
+    tx: mpsc::Sender<Command>,
+}
+
+impl EventLog {
+    /// Open (or append to) the log at `path`, rotating once it exceeds `max_bytes`
+    pub fn open(path: impl Into<PathBuf>, max_bytes: u64) -> io::Result<Self> {
+        let writer = Writer::open(path.into(), max_bytes)?;
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::Builder::new()
+            .name("outer-event-log".to_string())
+            .spawn(move || writer.run(rx))?;
+
+        Ok(Self { tx })
+    }
+
+    /// Queue an event for writing. Never blocks.
+    pub fn record(&self, event: LoggedEvent) {
+        let line = LogLine {
+            ts: Utc::now(),
+            event: &event,
+        };
+        match serde_json::to_string(&line) {
+            Ok(line) => {
+                // The writer only goes away if it hit an I/O error, which it logs
+                let _ = self.tx.send(Command::Write(line));
+            }
+            Err(e) => tracing::warn!("Failed to serialize event log record: {}", e),
+        }
+    }
+
+    /// Wait until every event recorded so far has been written to disk
+    pub async fn flush(&self) {
+        let (tx, rx) = oneshot::channel();
+        if self.tx.send(Command::Flush(tx)).is_ok() {
+            let _ = rx.await;
+        }
+    }
+}
+
+impl NotificationSink for EventLog {
+    fn notify(&self, event: &DelegationEvent) {
+        self.record(LoggedEvent::Delegation(event.clone()));
+    }
+}
+
+struct Writer {
+    path: PathBuf,
+    max_bytes: u64,
+    file: BufWriter<File>,
+    written: u64,
+}
+
+impl Writer {
+    fn open(path: PathBuf, max_bytes: u64) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            file: BufWriter::new(file),
+            written,
+        })
+    }
+
+    fn run(mut self, rx: mpsc::Receiver<Command>) {
+        while let Ok(command) = rx.recv() {
+            // Drain whatever else is queued so a burst shares a single flush
+            let result = std::iter::once(command)
+                .chain(rx.try_iter())
+                .try_for_each(|command| self.handle(command))
+                .and_then(|_| self.file.flush());
+
+            if let Err(e) = result {
+                tracing::error!("Event log {} failed: {}", self.path.display(), e);
+                return;
+            }
+        }
+    }
+
+    fn handle(&mut self, command: Command) -> io::Result<()> {
+        match command {
+            Command::Write(line) => {
+                let len = line.len() as u64 + 1;
+                if self.written > 0 && self.written + len > self.max_bytes {
+                    self.rotate()?;
+                }
+                self.file.write_all(line.as_bytes())?;
+                self.file.write_all(b"\n")?;
+                self.written += len;
+            }
+            Command::Flush(done) => {
+                self.file.flush()?;
+                let _ = done.send(());
+            }
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        for n in (1..KEEP_ROTATED).rev() {
+            let from = rotated_path(&self.path, n);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, n + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))?;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.file = BufWriter::new(file);
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crdt::{Participant, ParticipantKind};
+    use crate::delegation::DelegationManager;
+    use crate::models::BlockType;
+    use crate::store::Store;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    fn temp_log_path() -> PathBuf {
+        std::env::temp_dir().join(format!("outer-events-{}.log", Uuid::new_v4()))
+    }
+
+    fn read_events(path: &Path) -> Vec<serde_json::Value> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_block_and_delegation_logged_in_order() {
+        let path = temp_log_path();
+        let log = EventLog::open(&path, DEFAULT_MAX_BYTES).unwrap();
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let store = Store::new(pool).with_event_log(log.clone());
+
+        let manager = DelegationManager::new();
+        manager.add_sink(Arc::new(log.clone()));
+
+        let journal = store.create_journal(None).await.unwrap();
+        let block = store
+            .create_block(journal.id, BlockType::User, "Write the docs")
+            .await
+            .unwrap();
+
+        let user = manager
+            .register_participant(Participant::new("Alice", ParticipantKind::User))
+            .await;
+        let agent = manager
+            .register_participant(Participant::new("Bot", ParticipantKind::Agent))
+            .await;
+        let item = manager
+            .delegate(
+                journal.id,
+                "Write the docs",
+                user.id(),
+                agent.id(),
+                None,
+                false,
+                None,
+            )
+            .await
+            .unwrap();
+
+        log.flush().await;
+        let events = read_events(&path);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0]["event"], "journal_created");
+        assert_eq!(events[0]["journal"]["id"], journal.id.to_string());
+        assert_eq!(events[1]["event"], "block_created");
+        assert_eq!(events[1]["block"]["id"], block.id.to_string());
+        // Both registrations come through the delegation sink too
+        for event in &events[2..4] {
+            assert_eq!(event["event"], "delegation");
+            assert_eq!(event["type"], "participant_registered");
+        }
+        assert_eq!(events[4]["event"], "delegation");
+        assert_eq!(events[4]["type"], "work_delegated");
+        assert_eq!(events[4]["work_item_id"], item.id.to_string());
+        assert!(events.iter().all(|e| e["ts"].is_string()));
+    }
+
+    #[tokio::test]
+    async fn test_rotates_by_size() {
+        let path = temp_log_path();
+        let log = EventLog::open(&path, 300).unwrap();
+
+        for _ in 0..10 {
+            log.notify(&DelegationEvent::WorkAccepted {
+                work_item_id: Uuid::new_v4(),
+                assignee_id: Uuid::new_v4(),
+            });
+        }
+        log.flush().await;
+
+        let active = fs::metadata(&path).unwrap().len();
+        assert!(active <= 300);
+        assert!(rotated_path(&path, 1).exists());
+        assert!(!rotated_path(&path, KEEP_ROTATED + 1).exists());
+
+        fs::remove_file(&path).ok();
+        for n in 1..=KEEP_ROTATED {
+            fs::remove_file(rotated_path(&path, n)).ok();
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Rendering a branch of blocks as a standalone transcript
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Block, BlockType};
+
+/// Transcript formats a branch can be exported in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// A heading per block followed by its content
+    Markdown,
+    /// The blocks themselves, as a JSON array
+    Json,
+}
+
+/// Render `blocks`, oldest first, in `format`
+pub fn render(blocks: &[Block], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Markdown => blocks
+            .iter()
+            .map(|block| {
+                let heading = match block.block_type {
+                    BlockType::User => "User",
+                    BlockType::Assistant => "Assistant",
+                    BlockType::System => "System",
+                };
+                format!("### {}\n\n{}\n", heading, block.content.trim_end())
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ExportFormat::Json => {
+            serde_json::to_string_pretty(blocks).expect("Blocks always serialize")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::BlockStatus;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn block(block_type: BlockType, content: &str) -> Block {
+        Block {
+            id: Uuid::new_v4(),
+            journal_id: Uuid::nil(),
+            block_type,
+            content: content.to_string(),
+            status: BlockStatus::Complete,
+            parent_id: None,
+            forked_from_id: None,
+            position: None,
+            version: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            content_html: None,
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let blocks = [
+            block(BlockType::User, "What is a monad?"),
+            block(
+                BlockType::Assistant,
+                "A monoid in the category of endofunctors.\n",
+            ),
+        ];
+        assert_eq!(
+            render(&blocks, ExportFormat::Markdown),
+            "### User\n\nWhat is a monad?\n\n### Assistant\n\nA monoid in the category of endofunctors.\n"
+        );
+    }
+
+    #[test]
+    fn test_render_json_round_trips() {
+        let blocks = [block(BlockType::User, "Hi")];
+        let parsed: Vec<Block> =
+            serde_json::from_str(&render(&blocks, ExportFormat::Json)).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].id, blocks[0].id);
+    }
+}
@@ -0,0 +1,135 @@
+//! Binary websocket framing for CRDT traffic
+//!
+//! JSON text frames remain the default protocol. After a client opts in with
+//! `{"type": "hello", "binary_crdt": true}`, CRDT updates and sync exchanges
+//! may travel as binary frames carrying raw Yrs bytes instead of base64.
+//!
+//! Every binary frame has a fixed 21-byte header followed by the payload:
+//!
+//! ```text
+//! offset  size  field
+//! 0       1     opcode
+//! 1       16    journal id (UUID, big-endian bytes)
+//! 17      4     payload length (u32, big-endian)
+//! 21      n     payload (raw Yrs bytes)
+//! ```
+//!
+//! The payload length must match the remaining bytes exactly.
+
+use uuid::Uuid;
+
+/// Size of the fixed frame header in bytes
+pub const HEADER_LEN: usize = 1 + 16 + 4;
+
+/// Operation carried by a binary frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    /// Yrs update to apply (client -> server, server -> client)
+    CrdtUpdate = 0x01,
+    /// Request the document state; payload is a state vector, empty for a full sync
+    SyncRequest = 0x02,
+    /// Document state or diff (server -> client)
+    SyncState = 0x03,
+}
+
+impl TryFrom<u8> for Opcode {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x01 => Ok(Opcode::CrdtUpdate),
+            0x02 => Ok(Opcode::SyncRequest),
+            0x03 => Ok(Opcode::SyncState),
+            _ => Err(format!("Invalid frame opcode: {:#04x}", value)),
+        }
+    }
+}
+
+/// A decoded binary frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryFrame {
+    pub opcode: Opcode,
+    pub journal_id: Uuid,
+    pub payload: Vec<u8>,
+}
+
+impl BinaryFrame {
+    pub fn new(opcode: Opcode, journal_id: Uuid, payload: Vec<u8>) -> Self {
+        Self {
+            opcode,
+            journal_id,
+            payload,
+        }
+    }
+
+    /// Encode the frame to wire bytes
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.payload.len());
+        bytes.push(self.opcode as u8);
+        bytes.extend_from_slice(self.journal_id.as_bytes());
+        bytes.extend_from_slice(&(self.payload.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    /// Decode a frame from wire bytes
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < HEADER_LEN {
+            return Err(format!(
+                "Frame too short: {} bytes, header is {}",
+                bytes.len(),
+                HEADER_LEN
+            ));
+        }
+
+        let opcode = Opcode::try_from(bytes[0])?;
+        let journal_id = Uuid::from_slice(&bytes[1..17]).map_err(|e| e.to_string())?;
+        let len = u32::from_be_bytes([bytes[17], bytes[18], bytes[19], bytes[20]]) as usize;
+
+        let payload = &bytes[HEADER_LEN..];
+        if payload.len() != len {
+            return Err(format!(
+                "Frame length mismatch: header says {}, got {}",
+                len,
+                payload.len()
+            ));
+        }
+
+        Ok(Self::new(opcode, journal_id, payload.to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let frame = BinaryFrame::new(Opcode::CrdtUpdate, Uuid::new_v4(), vec![1, 2, 3, 4]);
+        let bytes = frame.encode();
+        assert_eq!(bytes.len(), HEADER_LEN + 4);
+        assert_eq!(bytes[0], 0x01);
+        assert_eq!(BinaryFrame::decode(&bytes).unwrap(), frame);
+    }
+
+    #[test]
+    fn test_frame_empty_payload() {
+        let frame = BinaryFrame::new(Opcode::SyncRequest, Uuid::new_v4(), vec![]);
+        let decoded = BinaryFrame::decode(&frame.encode()).unwrap();
+        assert_eq!(decoded.opcode, Opcode::SyncRequest);
+        assert!(decoded.payload.is_empty());
+    }
+
+    #[test]
+    fn test_frame_decode_errors() {
+        assert!(BinaryFrame::decode(&[0x01, 0x02]).is_err());
+
+        let mut bytes = BinaryFrame::new(Opcode::CrdtUpdate, Uuid::new_v4(), vec![9]).encode();
+        bytes[0] = 0x7f;
+        assert!(BinaryFrame::decode(&bytes).is_err());
+
+        let mut bytes = BinaryFrame::new(Opcode::CrdtUpdate, Uuid::new_v4(), vec![9]).encode();
+        bytes.push(0);
+        assert!(BinaryFrame::decode(&bytes).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+//! Health check reporting database and OpenCode connectivity
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::opencode::OpenCodeClient;
+use crate::AppState;
+
+/// How long to wait for OpenCode before reporting it as down
+const OPENCODE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Reachability of one dependency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentStatus {
+    Ok,
+    Error,
+}
+
+impl ComponentStatus {
+    fn of<T, E: std::fmt::Display>(component: &str, result: Result<T, E>) -> Self {
+        match result {
+            Ok(_) => ComponentStatus::Ok,
+            Err(e) => {
+                tracing::warn!("Health check: {} unavailable: {}", component, e);
+                ComponentStatus::Error
+            }
+        }
+    }
+}
+
+/// Body of the `/health` response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub db: ComponentStatus,
+    pub opencode: ComponentStatus,
+}
+
+impl HealthReport {
+    pub fn is_healthy(&self) -> bool {
+        self.db == ComponentStatus::Ok && self.opencode == ComponentStatus::Ok
+    }
+}
+
+/// Check the database and the OpenCode server at `opencode_url` concurrently
+pub async fn check(state: &AppState, opencode_url: &str) -> HealthReport {
+    let opencode = OpenCodeClient::new(opencode_url);
+    let (db, oc) = tokio::join!(state.store.ping(), opencode.ping(OPENCODE_TIMEOUT));
+
+    HealthReport {
+        db: ComponentStatus::of("database", db),
+        opencode: ComponentStatus::of("OpenCode", oc),
+    }
+}
+
+/// `GET /health`: 200 when every dependency is reachable, 503 otherwise
+pub async fn handler(State(state): State<Arc<AppState>>) -> (StatusCode, Json<HealthReport>) {
+    let opencode_url =
+        std::env::var("OPENCODE_URL").unwrap_or_else(|_| "http://localhost:4096".to_string());
+    let report = check(&state, &opencode_url).await;
+
+    let status = if report.is_healthy() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
@@ -0,0 +1,106 @@
+//! Idempotency keys for submits
+//!
+//! A client that retries a submit after losing its connection can't tell
+//! whether the first attempt landed. Sending the same key both times lets the
+//! server recognise the retry and replay the blocks it already created
+//! instead of making a second pair.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+/// How long a key is remembered after its submit was first seen
+pub const KEY_TTL: Duration = Duration::from_secs(5 * 60);
+
+struct Entry {
+    seen_at: Instant,
+    /// Blocks the submit created, in creation order
+    block_ids: Vec<Uuid>,
+}
+
+/// Short-lived map from (journal, key) to the blocks a submit created
+pub struct SubmitKeys {
+    ttl: Duration,
+    entries: Mutex<HashMap<(Uuid, String), Entry>>,
+}
+
+impl Default for SubmitKeys {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubmitKeys {
+    pub fn new() -> Self {
+        Self::with_ttl(KEY_TTL)
+    }
+
+    /// Remember keys for `ttl` instead of the default
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Claim `key` for a new submit to `journal_id`
+    ///
+    /// Returns `None` if the key is new, or the blocks created under it if
+    /// it was used recently. A key whose submit failed before creating any
+    /// blocks is handed out again.
+    pub fn claim(&self, journal_id: Uuid, key: &str) -> Option<Vec<Uuid>> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| entry.seen_at.elapsed() < self.ttl);
+
+        let entry = entries
+            .entry((journal_id, key.to_string()))
+            .or_insert_with(|| Entry {
+                seen_at: Instant::now(),
+                block_ids: Vec::new(),
+            });
+        if entry.block_ids.is_empty() {
+            None
+        } else {
+            Some(entry.block_ids.clone())
+        }
+    }
+
+    /// Note that the submit holding `key` created `block_id`
+    pub fn record(&self, journal_id: Uuid, key: &str, block_id: Uuid) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.get_mut(&(journal_id, key.to_string())) {
+            entry.block_ids.push(block_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeat_key_returns_recorded_blocks() {
+        let keys = SubmitKeys::new();
+        let journal_id = Uuid::new_v4();
+        let block_id = Uuid::new_v4();
+
+        assert_eq!(keys.claim(journal_id, "abc"), None);
+        keys.record(journal_id, "abc", block_id);
+        assert_eq!(keys.claim(journal_id, "abc"), Some(vec![block_id]));
+
+        // Keys are scoped to their journal
+        assert_eq!(keys.claim(Uuid::new_v4(), "abc"), None);
+    }
+
+    #[test]
+    fn test_keys_expire() {
+        let keys = SubmitKeys::with_ttl(Duration::ZERO);
+        let journal_id = Uuid::new_v4();
+
+        assert_eq!(keys.claim(journal_id, "abc"), None);
+        keys.record(journal_id, "abc", Uuid::new_v4());
+        assert_eq!(keys.claim(journal_id, "abc"), None);
+    }
+}
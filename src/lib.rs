@@ -1,29 +1,153 @@
 //! Outer.sh server - collaborative AI conversation interface
 
+pub mod auth;
+pub mod codec;
 pub mod crdt;
 pub mod delegation;
+pub mod delegation_store;
+pub mod diff;
 pub mod error;
+pub mod event_log;
+pub mod export;
+pub mod frame;
+pub mod health;
+pub mod idempotency;
+pub mod limiter;
+pub mod markdown;
+pub mod metrics;
 pub mod models;
 pub mod opencode;
+pub mod snapshot_store;
+pub mod sse;
 pub mod store;
+pub mod streams;
 pub mod websocket;
 
 use sqlx::SqlitePool;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Application state shared across handlers
 pub struct AppState {
     pub store: store::Store,
     pub room_manager: crdt::room::RoomManager,
     pub delegation_manager: delegation::DelegationManager,
+    pub submit_limiter: limiter::SubmitLimiter,
+    pub submit_keys: idempotency::SubmitKeys,
+    pub streams: streams::StreamRegistry,
+    pub metrics: metrics::Metrics,
+    pub auth: auth::Authenticator,
+    /// Largest text frame a client may send, in bytes
+    max_frame_bytes: AtomicUsize,
+    /// Show raw upstream error text in failed blocks instead of a friendly message
+    raw_errors: AtomicBool,
+    /// How long to wait on OpenCode, in milliseconds; 0 waits indefinitely
+    opencode_timeout_ms: AtomicU64,
+    /// Tries at creating an OpenCode session, including the first
+    opencode_session_attempts: AtomicU32,
+    /// Registered participants need the submit (or fork) capability to generate responses
+    require_submit_capability: AtomicBool,
+    /// Name untitled journals from their first exchange
+    auto_title: AtomicBool,
 }
 
 impl AppState {
     pub fn new(pool: SqlitePool) -> Arc<Self> {
+        Self::with_store(store::Store::new(pool))
+    }
+
+    /// Build state around a preconfigured store (e.g. with split read/write pools)
+    pub fn with_store(store: store::Store) -> Arc<Self> {
+        Self::from_parts(store, delegation::DelegationManager::new())
+    }
+
+    /// Build state around a store and a delegation manager (e.g. one restored from the database)
+    pub fn from_parts(
+        store: store::Store,
+        delegation_manager: delegation::DelegationManager,
+    ) -> Arc<Self> {
         Arc::new(Self {
-            store: store::Store::new(pool),
+            store,
             room_manager: crdt::room::RoomManager::new(),
-            delegation_manager: delegation::DelegationManager::new(),
+            delegation_manager,
+            submit_limiter: limiter::SubmitLimiter::new(),
+            submit_keys: idempotency::SubmitKeys::new(),
+            streams: streams::StreamRegistry::new(),
+            metrics: metrics::Metrics::new(),
+            auth: auth::Authenticator::new(),
+            max_frame_bytes: AtomicUsize::new(websocket::DEFAULT_MAX_FRAME_BYTES),
+            raw_errors: AtomicBool::new(false),
+            opencode_timeout_ms: AtomicU64::new(0),
+            opencode_session_attempts: AtomicU32::new(opencode::DEFAULT_SESSION_ATTEMPTS),
+            require_submit_capability: AtomicBool::new(false),
+            auto_title: AtomicBool::new(true),
         })
     }
+
+    /// Largest text frame accepted from clients before it is parsed
+    pub fn max_frame_bytes(&self) -> usize {
+        self.max_frame_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Change the frame size limit; connections pick it up on their next message
+    pub fn set_max_frame_bytes(&self, bytes: usize) {
+        self.max_frame_bytes.store(bytes, Ordering::Relaxed);
+    }
+
+    /// Whether failed blocks show the raw upstream error rather than a friendly message
+    pub fn raw_errors(&self) -> bool {
+        self.raw_errors.load(Ordering::Relaxed)
+    }
+
+    /// Show raw upstream errors to users; meant for debugging
+    pub fn set_raw_errors(&self, enabled: bool) {
+        self.raw_errors.store(enabled, Ordering::Relaxed);
+    }
+
+    /// How long connections wait on OpenCode before giving up, if at all
+    pub fn opencode_timeout(&self) -> Option<Duration> {
+        match self.opencode_timeout_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
+
+    /// Change the OpenCode timeout; `None` waits indefinitely. New connections pick it up.
+    pub fn set_opencode_timeout(&self, timeout: Option<Duration>) {
+        let ms = timeout.map_or(0, |t| u64::try_from(t.as_millis()).unwrap_or(u64::MAX));
+        self.opencode_timeout_ms.store(ms, Ordering::Relaxed);
+    }
+
+    /// How many times connections try to create an OpenCode session
+    pub fn opencode_session_attempts(&self) -> u32 {
+        self.opencode_session_attempts.load(Ordering::Relaxed)
+    }
+
+    /// Change the session attempts (at least one); `1` disables retrying
+    pub fn set_opencode_session_attempts(&self, attempts: u32) {
+        self.opencode_session_attempts
+            .store(attempts.max(1), Ordering::Relaxed);
+    }
+
+    /// Whether registered participants need the submit (or fork) capability to generate responses
+    pub fn require_submit_capability(&self) -> bool {
+        self.require_submit_capability.load(Ordering::Relaxed)
+    }
+
+    /// Check capabilities on submit, fork and rerun; anonymous connections are never checked
+    pub fn set_require_submit_capability(&self, required: bool) {
+        self.require_submit_capability
+            .store(required, Ordering::Relaxed);
+    }
+
+    /// Whether untitled journals are named automatically after their first exchange
+    pub fn auto_title(&self) -> bool {
+        self.auto_title.load(Ordering::Relaxed)
+    }
+
+    /// Turn automatic titles generated by OpenCode on or off
+    pub fn set_auto_title(&self, enabled: bool) {
+        self.auto_title.store(enabled, Ordering::Relaxed);
+    }
 }
@@ -0,0 +1,413 @@
+//! Per-journal limit on concurrent submits
+//!
+//! Submits beyond the limit wait in a FIFO queue. Each waiter holds a
+//! [`QueueTicket`] that reports its 1-based position in line and is updated
+//! whenever someone ahead of it starts or gives up, so clients can show how
+//! far back they are.
+//!
+//! Separately, each connection can be held to a rate of new submits with a
+//! [`SubmitRate`] token bucket, so one client can't open sessions without end.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+use uuid::Uuid;
+
+struct Waiter {
+    block_id: Uuid,
+    /// Position in line; 0 once the waiter has been given a slot
+    position: watch::Sender<usize>,
+}
+
+#[derive(Default)]
+struct JournalQueue {
+    running: usize,
+    waiters: VecDeque<Waiter>,
+}
+
+impl JournalQueue {
+    fn renumber(&self) {
+        for (i, waiter) in self.waiters.iter().enumerate() {
+            waiter.position.send_if_modified(|position| {
+                let changed = *position != i + 1;
+                *position = i + 1;
+                changed
+            });
+        }
+    }
+}
+
+/// Limits how many submits may stream at once within a single journal
+pub struct SubmitLimiter {
+    max_per_journal: AtomicUsize,
+    /// Per-connection submits per minute; 0 for no limit
+    per_connection_rate: AtomicU32,
+    queues: Mutex<HashMap<Uuid, JournalQueue>>,
+}
+
+impl Default for SubmitLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubmitLimiter {
+    /// Create a limiter with no limit
+    pub fn new() -> Self {
+        Self {
+            max_per_journal: AtomicUsize::new(usize::MAX),
+            per_connection_rate: AtomicU32::new(0),
+            queues: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cap concurrent submits per journal (minimum 1)
+    pub fn set_max_per_journal(&self, max: usize) {
+        self.max_per_journal.store(max.max(1), Ordering::Relaxed);
+    }
+
+    /// Allow each connection `per_minute` submits a minute (0 to lift the limit)
+    ///
+    /// Only affects connections opened afterwards.
+    pub fn set_submits_per_minute(&self, per_minute: u32) {
+        self.per_connection_rate
+            .store(per_minute, Ordering::Relaxed);
+    }
+
+    /// A fresh rate limit for a new connection, if one is configured
+    pub fn connection_rate(&self) -> Option<SubmitRate> {
+        match self.per_connection_rate.load(Ordering::Relaxed) {
+            0 => None,
+            per_minute => Some(SubmitRate::per_minute(per_minute)),
+        }
+    }
+
+    /// Take a slot for `block_id` in `journal_id`, or join the back of the queue
+    pub fn acquire(&self, journal_id: Uuid, block_id: Uuid) -> Acquire<'_> {
+        let max = self.max_per_journal.load(Ordering::Relaxed);
+        let mut queues = self.queues.lock().unwrap();
+        let queue = queues.entry(journal_id).or_default();
+
+        if queue.waiters.is_empty() && queue.running < max {
+            queue.running += 1;
+            return Acquire::Ready(SubmitPermit {
+                limiter: self,
+                journal_id,
+            });
+        }
+
+        let (tx, rx) = watch::channel(queue.waiters.len() + 1);
+        queue.waiters.push_back(Waiter {
+            block_id,
+            position: tx,
+        });
+        Acquire::Queued(QueueTicket {
+            limiter: self,
+            journal_id,
+            block_id,
+            position: rx,
+            converted: false,
+        })
+    }
+
+    /// Number of submits waiting in `journal_id`
+    pub fn queued(&self, journal_id: Uuid) -> usize {
+        self.queues
+            .lock()
+            .unwrap()
+            .get(&journal_id)
+            .map_or(0, |queue| queue.waiters.len())
+    }
+
+    fn release(&self, journal_id: Uuid) {
+        let max = self.max_per_journal.load(Ordering::Relaxed);
+        let mut queues = self.queues.lock().unwrap();
+        let Some(queue) = queues.get_mut(&journal_id) else {
+            return;
+        };
+
+        queue.running = queue.running.saturating_sub(1);
+        while queue.running < max {
+            let Some(waiter) = queue.waiters.pop_front() else {
+                break;
+            };
+            queue.running += 1;
+            waiter.position.send_replace(0);
+        }
+        queue.renumber();
+
+        if queue.running == 0 && queue.waiters.is_empty() {
+            queues.remove(&journal_id);
+        }
+    }
+
+    fn abandon(&self, journal_id: Uuid, block_id: Uuid) {
+        let mut queues = self.queues.lock().unwrap();
+        let Some(queue) = queues.get_mut(&journal_id) else {
+            return;
+        };
+
+        match queue.waiters.iter().position(|w| w.block_id == block_id) {
+            Some(index) => {
+                queue.waiters.remove(index);
+                queue.renumber();
+            }
+            None => {
+                // Granted a slot before the ticket was redeemed; give it back
+                drop(queues);
+                self.release(journal_id);
+            }
+        }
+    }
+}
+
+/// Outcome of [`SubmitLimiter::acquire`]
+pub enum Acquire<'a> {
+    /// A slot was free
+    Ready(SubmitPermit<'a>),
+    /// The journal is at its limit; wait on the ticket
+    Queued(QueueTicket<'a>),
+}
+
+/// A running submit's slot, released on drop
+pub struct SubmitPermit<'a> {
+    limiter: &'a SubmitLimiter,
+    journal_id: Uuid,
+}
+
+impl Drop for SubmitPermit<'_> {
+    fn drop(&mut self) {
+        self.limiter.release(self.journal_id);
+    }
+}
+
+/// A queued submit's place in line; dropping it leaves the queue
+pub struct QueueTicket<'a> {
+    limiter: &'a SubmitLimiter,
+    journal_id: Uuid,
+    block_id: Uuid,
+    position: watch::Receiver<usize>,
+    converted: bool,
+}
+
+impl<'a> QueueTicket<'a> {
+    /// Current 1-based position, or 0 once a slot has been granted
+    pub fn position(&self) -> usize {
+        *self.position.borrow()
+    }
+
+    /// Wait for the position to change and return the new one
+    pub async fn changed(&mut self) -> usize {
+        // The sender is only dropped after it has sent the final 0
+        let _ = self.position.changed().await;
+        *self.position.borrow_and_update()
+    }
+
+    /// Exchange a granted ticket for its permit
+    ///
+    /// Returns the ticket unchanged if it is still waiting.
+    pub fn into_permit(mut self) -> Result<SubmitPermit<'a>, Self> {
+        if self.position() != 0 {
+            return Err(self);
+        }
+        self.converted = true;
+        Ok(SubmitPermit {
+            limiter: self.limiter,
+            journal_id: self.journal_id,
+        })
+    }
+}
+
+impl Drop for QueueTicket<'_> {
+    fn drop(&mut self) {
+        if !self.converted {
+            self.limiter.abandon(self.journal_id, self.block_id);
+        }
+    }
+}
+
+/// Token bucket limiting how often one connection may submit
+///
+/// Holds up to a minute's allowance and refills continuously, so a client
+/// that has been quiet can burst but a steady flood is held to the rate.
+#[derive(Debug)]
+pub struct SubmitRate {
+    per_minute: u32,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl SubmitRate {
+    /// A full bucket allowing `per_minute` submits a minute
+    pub fn per_minute(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            tokens: per_minute as f64,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    pub fn per_minute_limit(&self) -> u32 {
+        self.per_minute
+    }
+
+    /// Spend a token if one is available
+    pub fn try_acquire(&mut self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        let refill = elapsed.as_secs_f64() * self.per_minute as f64 / 60.0;
+        self.tokens = (self.tokens + refill).min(self.per_minute as f64);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// How long until the next token is available
+    pub fn retry_after(&self) -> Duration {
+        let missing = (1.0 - self.tokens).max(0.0);
+        Duration::from_secs_f64(missing * 60.0 / self.per_minute as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued<'a>(limiter: &'a SubmitLimiter, journal_id: Uuid) -> QueueTicket<'a> {
+        match limiter.acquire(journal_id, Uuid::new_v4()) {
+            Acquire::Queued(ticket) => ticket,
+            Acquire::Ready(_) => panic!("Expected to be queued"),
+        }
+    }
+
+    fn ready<'a>(limiter: &'a SubmitLimiter, journal_id: Uuid) -> SubmitPermit<'a> {
+        match limiter.acquire(journal_id, Uuid::new_v4()) {
+            Acquire::Ready(permit) => permit,
+            Acquire::Queued(_) => panic!("Expected a free slot"),
+        }
+    }
+
+    #[test]
+    fn test_unlimited_by_default() {
+        let limiter = SubmitLimiter::new();
+        let journal_id = Uuid::new_v4();
+        let _permits: Vec<_> = (0..10).map(|_| ready(&limiter, journal_id)).collect();
+        assert_eq!(limiter.queued(journal_id), 0);
+    }
+
+    #[tokio::test]
+    async fn test_waiters_advance_until_granted() {
+        let limiter = SubmitLimiter::new();
+        limiter.set_max_per_journal(1);
+        let journal_id = Uuid::new_v4();
+
+        let mut permit = ready(&limiter, journal_id);
+        let mut tickets: VecDeque<_> = (0..3).map(|_| queued(&limiter, journal_id)).collect();
+        let mut seen: Vec<Vec<usize>> = tickets.iter().map(|t| vec![t.position()]).collect();
+
+        // Other journals are unaffected
+        let _other = ready(&limiter, Uuid::new_v4());
+
+        for granted in 0..seen.len() {
+            drop(permit);
+            for (ticket, seen) in tickets.iter_mut().zip(&mut seen[granted..]) {
+                seen.push(ticket.changed().await);
+            }
+            let Ok(next) = tickets.pop_front().unwrap().into_permit() else {
+                panic!("Front of the queue should have been granted");
+            };
+            permit = next;
+        }
+        drop(permit);
+
+        assert_eq!(seen[0], vec![1, 0]);
+        assert_eq!(seen[1], vec![2, 1, 0]);
+        assert_eq!(seen[2], vec![3, 2, 1, 0]);
+        assert_eq!(limiter.queued(journal_id), 0);
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_ticket_leaves_queue() {
+        let limiter = SubmitLimiter::new();
+        limiter.set_max_per_journal(1);
+        let journal_id = Uuid::new_v4();
+
+        let permit = ready(&limiter, journal_id);
+        let first = queued(&limiter, journal_id);
+        let mut second = queued(&limiter, journal_id);
+        assert_eq!(second.position(), 2);
+
+        drop(first);
+        assert_eq!(second.changed().await, 1);
+        assert_eq!(limiter.queued(journal_id), 1);
+
+        drop(permit);
+        assert_eq!(second.changed().await, 0);
+        assert!(second.into_permit().is_ok());
+    }
+
+    #[test]
+    fn test_unredeemed_grant_is_released() {
+        let limiter = SubmitLimiter::new();
+        limiter.set_max_per_journal(1);
+        let journal_id = Uuid::new_v4();
+
+        let permit = ready(&limiter, journal_id);
+        let ticket = queued(&limiter, journal_id);
+        drop(permit);
+        assert_eq!(ticket.position(), 0);
+
+        // Dropping a granted ticket must free the slot it was given
+        drop(ticket);
+        let _permit = ready(&limiter, journal_id);
+    }
+
+    #[test]
+    fn test_submit_rate_refills_over_time() {
+        let start = Instant::now();
+        let mut rate = SubmitRate {
+            per_minute: 6,
+            tokens: 6.0,
+            refilled_at: start,
+        };
+
+        // A full bucket allows a burst of the whole allowance
+        for _ in 0..6 {
+            assert!(rate.try_acquire_at(start));
+        }
+        assert!(!rate.try_acquire_at(start));
+        assert_eq!(rate.retry_after(), Duration::from_secs(10));
+
+        // One token comes back every ten seconds, not all at once
+        assert!(!rate.try_acquire_at(start + Duration::from_secs(5)));
+        assert!(rate.try_acquire_at(start + Duration::from_secs(10)));
+        assert!(!rate.try_acquire_at(start + Duration::from_secs(10)));
+
+        // Refills never exceed the allowance
+        let later = start + Duration::from_secs(3600);
+        for _ in 0..6 {
+            assert!(rate.try_acquire_at(later));
+        }
+        assert!(!rate.try_acquire_at(later));
+    }
+
+    #[test]
+    fn test_connection_rate_unlimited_by_default() {
+        let limiter = SubmitLimiter::new();
+        assert!(limiter.connection_rate().is_none());
+
+        limiter.set_submits_per_minute(30);
+        assert_eq!(limiter.connection_rate().unwrap().per_minute_limit(), 30);
+    }
+}
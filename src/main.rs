@@ -1,13 +1,27 @@
 //! Outer.sh server - collaborative AI conversation interface
 
 use std::borrow::Cow;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
+use axum::body::Body;
+use axum::http::Request;
 use axum::{routing::get, Router};
-use clap::Parser;
+use clap::builder::BoolishValueParser;
+use clap::{ArgAction, Parser};
+use outer::crdt::room::{DuplicateNamePolicy, DEFAULT_SYNC_CHUNK_BYTES};
+use outer::delegation::{DelegationManager, WebhookSink};
+use outer::event_log::{self, EventLog};
+use outer::opencode::DEFAULT_SESSION_ATTEMPTS;
+use outer::snapshot_store::SnapshotStore;
+use outer::store::Store;
+use outer::websocket::DEFAULT_MAX_FRAME_BYTES;
 use outer::AppState;
 use reedline::{DefaultPrompt, DefaultPromptSegment, Reedline, Signal};
-use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use tokio::sync::Notify;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -33,8 +47,125 @@ struct Args {
     /// Skip interactive prompts (for automation)
     #[arg(long, env = "OUTER_NON_INTERACTIVE")]
     non_interactive: bool,
+
+    /// Open a separate read-only pool with this many connections (file databases only).
+    /// Switches the database to WAL mode so reads don't wait on writes.
+    #[arg(long, env = "OUTER_READ_CONNECTIONS")]
+    read_connections: Option<u32>,
+
+    /// Maximum number of journals with live collaboration rooms
+    #[arg(long, env = "OUTER_MAX_ROOMS")]
+    max_rooms: Option<usize>,
+
+    /// Refuse subscribers whose name someone in the journal already has,
+    /// rather than numbering it ("Alice (2)")
+    #[arg(long, env = "OUTER_REJECT_DUPLICATE_NAMES")]
+    reject_duplicate_names: bool,
+
+    /// Maximum number of responses streaming at once in a single journal;
+    /// further submits are queued
+    #[arg(long, env = "OUTER_MAX_CONCURRENT_SUBMITS")]
+    max_concurrent_submits: Option<usize>,
+
+    /// Maximum submits (including forks and reruns) per minute from a single
+    /// connection; short bursts up to this many are allowed
+    #[arg(long, env = "OUTER_SUBMIT_RATE")]
+    submit_rate: Option<u32>,
+
+    /// CRDT sync states larger than this many bytes are sent to text clients
+    /// as several chunked messages
+    #[arg(long, env = "OUTER_SYNC_CHUNK_BYTES", default_value_t = DEFAULT_SYNC_CHUNK_BYTES)]
+    sync_chunk_bytes: usize,
+
+    /// Largest text message accepted from a client, in bytes; bigger ones are
+    /// refused with an error before parsing
+    #[arg(long, env = "OUTER_MAX_FRAME_BYTES", default_value_t = DEFAULT_MAX_FRAME_BYTES)]
+    max_frame_bytes: usize,
+
+    /// Remove participants from presence after this many seconds without a
+    /// heartbeat or cursor move (disabled unless set)
+    #[arg(long, env = "OUTER_PRESENCE_TIMEOUT")]
+    presence_timeout: Option<u64>,
+
+    /// Hand a disconnected participant's unfinished work back to the unassigned
+    /// pool if they haven't re-registered after this many seconds (kept until
+    /// they unregister unless set)
+    #[arg(long, env = "OUTER_RELEASE_WORK_AFTER")]
+    release_work_after: Option<u64>,
+
+    /// Append significant events (journal/block creation, delegation lifecycle)
+    /// to this file as newline-delimited JSON
+    #[arg(long, env = "OUTER_EVENT_LOG")]
+    event_log: Option<PathBuf>,
+
+    /// Rotate the event log once it grows past this many bytes
+    #[arg(long, env = "OUTER_EVENT_LOG_MAX_BYTES", default_value_t = event_log::DEFAULT_MAX_BYTES)]
+    event_log_max_bytes: u64,
+
+    /// Webhook URL to POST delegation events to
+    #[arg(long, env = "OUTER_WEBHOOK_URL")]
+    webhook_url: Option<String>,
+
+    /// Delegation events to send to the webhook (comma-separated)
+    #[arg(
+        long,
+        env = "OUTER_WEBHOOK_EVENTS",
+        value_delimiter = ',',
+        default_value = "work_delegated,approval_requested,work_rejected"
+    )]
+    webhook_events: Vec<String>,
+
+    /// Shared secret accepted as a bearer token, in addition to the `tokens` table
+    #[arg(long, env = "OUTER_AUTH_TOKEN", hide_env_values = true)]
+    auth_token: Option<String>,
+
+    /// Let websocket clients connect without a token (local development)
+    #[arg(long, env = "OUTER_AUTH_DISABLED", value_parser = BoolishValueParser::new())]
+    auth_disabled: bool,
+
+    /// Issue a bearer token for this name, print it and exit
+    #[arg(long, value_name = "NAME")]
+    create_token: Option<String>,
+
+    /// Show raw OpenCode error text in failed blocks instead of a friendly
+    /// message (for debugging)
+    #[arg(long, env = "OUTER_RAW_ERRORS", value_parser = BoolishValueParser::new())]
+    raw_errors: bool,
+
+    /// Fail a response if OpenCode is silent for this many milliseconds, per
+    /// request and between streamed events (disabled unless set)
+    #[arg(long, env = "OPENCODE_TIMEOUT_MS")]
+    opencode_timeout_ms: Option<u64>,
+
+    /// Tries at creating an OpenCode session before giving up; connection
+    /// errors and 5xx responses are retried with backoff (1 disables retrying)
+    #[arg(
+        long,
+        env = "OPENCODE_SESSION_ATTEMPTS",
+        default_value_t = DEFAULT_SESSION_ATTEMPTS,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    opencode_session_attempts: u32,
+
+    /// Require registered participants to hold the submit (or fork) capability
+    /// before generating responses
+    #[arg(long, env = "OUTER_REQUIRE_SUBMIT_CAP", value_parser = BoolishValueParser::new())]
+    require_submit_cap: bool,
+
+    /// Name untitled journals from their first exchange (`false` to disable)
+    #[arg(
+        long,
+        env = "OUTER_AUTO_TITLE",
+        default_value_t = true,
+        action = ArgAction::Set,
+        value_parser = BoolishValueParser::new()
+    )]
+    auto_title: bool,
 }
 
+/// How long shutdown waits for open connections, then again for streams to finish
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
 /// Extract the file path from a SQLite connection URL.
 /// Handles formats like:
 /// - `sqlite:path.db`
@@ -137,34 +268,248 @@ async fn main() -> anyhow::Result<()> {
         args.database_url
     };
 
-    let pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(&database_url)
-        .await?;
+    let split_reads = args
+        .read_connections
+        .filter(|_| extract_sqlite_path(&database_url).is_some());
+
+    let (store, write_pool) = match split_reads {
+        Some(read_connections) => {
+            let options = SqliteConnectOptions::from_str(&database_url)?;
+
+            // SQLite allows a single writer; one connection avoids busy errors
+            let write_pool = SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect_with(options.clone().journal_mode(SqliteJournalMode::Wal))
+                .await?;
+
+            // Run migrations
+            sqlx::migrate!("./migrations").run(&write_pool).await?;
+
+            let read_pool = SqlitePoolOptions::new()
+                .max_connections(read_connections.max(1))
+                .connect_with(options.read_only(true).create_if_missing(false))
+                .await?;
+
+            tracing::info!(
+                "Using separate read pool with {} connections",
+                read_connections.max(1)
+            );
+            (Store::with_pools(read_pool, write_pool.clone()), write_pool)
+        }
+        None => {
+            let pool = SqlitePoolOptions::new()
+                .max_connections(5)
+                .connect(&database_url)
+                .await?;
+
+            // Run migrations
+            sqlx::migrate!("./migrations").run(&pool).await?;
+
+            (Store::new(pool.clone()), pool)
+        }
+    };
+
+    if let Some(name) = &args.create_token {
+        // Printed once: only its hash is kept
+        println!("{}", store.create_token(name).await?);
+        return Ok(());
+    }
+
+    let event_log = match &args.event_log {
+        Some(path) => {
+            tracing::info!("Writing event log to {}", path.display());
+            Some(EventLog::open(path, args.event_log_max_bytes)?)
+        }
+        None => None,
+    };
+
+    let store = match &event_log {
+        Some(log) => store.with_event_log(log.clone()),
+        None => store,
+    };
+
+    // A crash leaves blocks streaming that nothing will ever finish
+    match store.fail_streaming_blocks().await {
+        Ok(0) => {}
+        Ok(n) => tracing::warn!(
+            "Marked {} blocks left streaming by the last run as failed",
+            n
+        ),
+        Err(e) => tracing::error!("Failed to clean up interrupted blocks: {}", e),
+    }
+
+    let delegation_manager = DelegationManager::with_pool(write_pool.clone()).await?;
+    let state = AppState::from_parts(store, delegation_manager);
+    state
+        .room_manager
+        .set_snapshot_store(SnapshotStore::new(write_pool));
+
+    if let Some(log) = event_log {
+        state.delegation_manager.add_sink(Arc::new(log));
+    }
+
+    if let Some(max_rooms) = args.max_rooms {
+        tracing::info!("Limiting live rooms to {}", max_rooms);
+        state.room_manager.set_max_rooms(max_rooms);
+    }
 
-    // Run migrations
-    sqlx::migrate!("./migrations").run(&pool).await?;
+    if args.reject_duplicate_names {
+        state
+            .room_manager
+            .set_duplicate_name_policy(DuplicateNamePolicy::Reject);
+    }
 
-    let state = AppState::new(pool);
+    state
+        .room_manager
+        .set_sync_chunk_bytes(args.sync_chunk_bytes);
+
+    state.set_max_frame_bytes(args.max_frame_bytes);
+    state.set_raw_errors(args.raw_errors);
+    state.set_require_submit_capability(args.require_submit_cap);
+    state.set_auto_title(args.auto_title);
+
+    if let Some(ms) = args.opencode_timeout_ms.filter(|&ms| ms > 0) {
+        tracing::info!("Timing out OpenCode after {}ms of silence", ms);
+        state.set_opencode_timeout(Some(Duration::from_millis(ms)));
+    }
+    state.set_opencode_session_attempts(args.opencode_session_attempts);
+
+    if args.auth_disabled {
+        tracing::warn!("Authentication is disabled; anyone who can reach /ws has full access");
+    } else {
+        if args.auth_token.is_none() {
+            tracing::info!(
+                "No OUTER_AUTH_TOKEN set; only tokens issued with --create-token are accepted"
+            );
+        }
+        state.auth.enable(args.auth_token.clone());
+    }
+
+    if let Some(secs) = args.presence_timeout {
+        tracing::info!("Reaping participants silent for {}s", secs);
+        state
+            .room_manager
+            .spawn_presence_reaper(Duration::from_secs(secs));
+    }
+
+    if let Some(secs) = args.release_work_after {
+        tracing::info!("Releasing work of participants gone for {}s", secs);
+        state
+            .delegation_manager
+            .set_release_grace(Duration::from_secs(secs));
+    }
+
+    if let Some(max) = args.max_concurrent_submits {
+        tracing::info!("Limiting concurrent submits to {} per journal", max);
+        state.submit_limiter.set_max_per_journal(max);
+    }
+
+    if let Some(rate) = args.submit_rate {
+        tracing::info!("Limiting each connection to {} submits per minute", rate);
+        state.submit_limiter.set_submits_per_minute(rate);
+    }
+
+    if let Some(url) = args.webhook_url {
+        tracing::info!("Sending delegation events to webhook {}", url);
+        state
+            .delegation_manager
+            .add_sink(Arc::new(WebhookSink::with_events(url, args.webhook_events)));
+    }
 
     // Build router
     let app = Router::new()
-        .route("/health", get(health))
+        .route("/health", get(outer::health::handler))
+        .route("/metrics", get(outer::metrics::handler))
         .route("/ws", get(outer::websocket::handler))
-        .layer(TraceLayer::new_for_http())
+        .route("/journals/:id/stream", get(outer::sse::handler))
+        .layer(
+            TraceLayer::new_for_http().make_span_with(|request: &Request<Body>| {
+                tracing::debug_span!(
+                    "request",
+                    method = %request.method(),
+                    uri = %outer::auth::redacted_uri(request.uri()),
+                    version = ?request.version(),
+                )
+            }),
+        )
         .layer(CorsLayer::permissive())
-        .with_state(state);
+        .with_state(Arc::clone(&state));
 
     // Start server
     let bind_addr = format!("{}:{}", args.host, args.port);
     let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
     tracing::info!("Server listening on {}", listener.local_addr()?);
 
-    axum::serve(listener, app).await?;
+    let signalled = Arc::new(Notify::new());
+    let serve = axum::serve(listener, app).with_graceful_shutdown({
+        let signalled = Arc::clone(&signalled);
+        async move {
+            shutdown_signal().await;
+            tracing::info!("Shutting down; no longer accepting connections");
+            signalled.notify_one();
+        }
+    });
+    // Websockets and event streams stay open indefinitely, so don't wait on them forever
+    tokio::select! {
+        result = serve => result?,
+        _ = async {
+            signalled.notified().await;
+            tokio::time::sleep(SHUTDOWN_GRACE).await;
+        } => tracing::warn!("Closing connections still open after {:?}", SHUTDOWN_GRACE),
+    }
+
+    drain_streams(&state).await;
+    match state.store.fail_streaming_blocks().await {
+        Ok(0) => {}
+        Ok(n) => tracing::warn!("Marked {} blocks as failed: server shutdown", n),
+        Err(e) => tracing::error!("Failed to mark interrupted blocks as failed: {}", e),
+    }
 
     Ok(())
 }
 
-async fn health() -> &'static str {
-    "ok"
+/// Resolve on Ctrl-C, or SIGTERM where there is one
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Give in-flight OpenCode streams up to [`SHUTDOWN_GRACE`] to finish
+async fn drain_streams(state: &AppState) {
+    let deadline = tokio::time::Instant::now() + SHUTDOWN_GRACE;
+    while !state.streams.is_empty() {
+        if tokio::time::Instant::now() >= deadline {
+            tracing::warn!(
+                "{} streams still running after {:?}",
+                state.streams.len(),
+                SHUTDOWN_GRACE
+            );
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
 }
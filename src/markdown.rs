@@ -0,0 +1,153 @@
+//! Server-side rendering of assistant blocks for clients that would rather
+//! not ship a markdown renderer
+//!
+//! Raw HTML in the source is escaped rather than passed through, and links
+//! or images pointing at `javascript:` URLs lose their target.
+
+use std::str::FromStr;
+
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+
+use crate::error::AppError;
+use crate::models::{Block, BlockType};
+
+/// Formats a client can ask for block content to be rendered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderFormat {
+    Html,
+}
+
+impl FromStr for RenderFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "html" => Ok(RenderFormat::Html),
+            other => Err(AppError::BadRequest(format!(
+                "Unsupported render format '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Render markdown to HTML
+pub fn to_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        other => other,
+    });
+
+    let mut out = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut out, events);
+    out
+}
+
+/// Fill in `content_html` on an assistant block; other blocks are left alone
+pub fn render_block(block: &mut Block, format: RenderFormat) {
+    if block.block_type != BlockType::Assistant {
+        return;
+    }
+    block.content_html = Some(match format {
+        RenderFormat::Html => to_html(&block.content),
+    });
+}
+
+/// Drop URLs that would run script when followed
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    let scheme = url.trim_start().get(..11).unwrap_or_default();
+    if scheme.eq_ignore_ascii_case("javascript:") {
+        CowStr::Borrowed("#")
+    } else {
+        url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::BlockStatus;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn block(block_type: BlockType, content: &str) -> Block {
+        Block {
+            id: Uuid::new_v4(),
+            journal_id: Uuid::nil(),
+            block_type,
+            content: content.to_string(),
+            status: BlockStatus::Complete,
+            parent_id: None,
+            forked_from_id: None,
+            position: None,
+            version: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            content_html: None,
+        }
+    }
+
+    #[test]
+    fn test_render_block_adds_html() {
+        let mut answer = block(BlockType::Assistant, "Some **bold** text");
+        render_block(&mut answer, RenderFormat::Html);
+
+        let json = serde_json::to_value(&answer).unwrap();
+        assert!(json["content_html"]
+            .as_str()
+            .unwrap()
+            .contains("<strong>bold</strong>"));
+        assert_eq!(json["content"], "Some **bold** text");
+
+        let mut prompt = block(BlockType::User, "**bold**");
+        render_block(&mut prompt, RenderFormat::Html);
+        assert!(serde_json::to_value(&prompt).unwrap()["content_html"].is_null());
+    }
+
+    #[test]
+    fn test_raw_html_and_script_links_are_neutralized() {
+        let html = to_html("<script>alert(1)</script>\n\nHi <b>there</b>");
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("&lt;b&gt;"));
+
+        let html = to_html("[click](JavaScript:alert(1)) [ok](https://example.com)");
+        assert!(!html.to_lowercase().contains("javascript:"));
+        assert!(html.contains("href=\"https://example.com\""));
+    }
+
+    #[test]
+    fn test_unknown_render_format_is_rejected() {
+        assert_eq!("html".parse::<RenderFormat>().unwrap(), RenderFormat::Html);
+        assert!(matches!(
+            "pdf".parse::<RenderFormat>(),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,185 @@
+//! Prometheus metrics served at `/metrics`
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use axum::{extract::State, http::header, response::IntoResponse};
+
+use crate::error::AppError;
+use crate::AppState;
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Counters and gauges updated as the server handles traffic
+///
+/// Room and work item counts are read from their managers when rendered
+/// rather than tracked here.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    submits: AtomicU64,
+    active_connections: AtomicU64,
+    opencode_errors: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a submit that got past the rate limiter
+    pub fn record_submit(&self) {
+        self.submits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a failure talking to OpenCode
+    pub fn record_opencode_error(&self) {
+        self.opencode_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count `err` if OpenCode caused it; other failures are ignored
+    pub fn record_error(&self, err: &AppError) {
+        if matches!(err, AppError::OpenCode(_) | AppError::OpenCodeTimeout(_)) {
+            self.record_opencode_error();
+        }
+    }
+
+    /// Mark a websocket connection as open until the returned guard is dropped
+    pub fn connection_opened(&self) -> ConnectionGuard<'_> {
+        self.active_connections.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard { metrics: self }
+    }
+
+    pub fn submits(&self) -> u64 {
+        self.submits.load(Ordering::Relaxed)
+    }
+
+    pub fn active_connections(&self) -> u64 {
+        self.active_connections.load(Ordering::Relaxed)
+    }
+
+    pub fn opencode_errors(&self) -> u64 {
+        self.opencode_errors.load(Ordering::Relaxed)
+    }
+}
+
+/// Keeps a connection counted as active; dropping it, on any exit path, releases it
+pub struct ConnectionGuard<'a> {
+    metrics: &'a Metrics,
+}
+
+impl Drop for ConnectionGuard<'_> {
+    fn drop(&mut self) {
+        self.metrics
+            .active_connections
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Write one metric's HELP and TYPE header
+fn describe(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Render every metric in the Prometheus text format
+pub async fn render(state: &AppState) -> String {
+    let metrics = &state.metrics;
+    let mut out = String::new();
+
+    describe(
+        &mut out,
+        "outer_submits_total",
+        "counter",
+        "Prompts submitted by clients",
+    );
+    let _ = writeln!(out, "outer_submits_total {}", metrics.submits());
+
+    describe(
+        &mut out,
+        "outer_active_connections",
+        "gauge",
+        "Open websocket connections",
+    );
+    let _ = writeln!(
+        out,
+        "outer_active_connections {}",
+        metrics.active_connections()
+    );
+
+    describe(
+        &mut out,
+        "outer_active_rooms",
+        "gauge",
+        "Journals with a live collaboration room",
+    );
+    let _ = writeln!(
+        out,
+        "outer_active_rooms {}",
+        state.room_manager.room_count().await
+    );
+
+    describe(
+        &mut out,
+        "outer_work_items",
+        "gauge",
+        "Delegated work items by status",
+    );
+    for (status, count) in state.delegation_manager.count_work_items_by_status().await {
+        let _ = writeln!(
+            out,
+            "outer_work_items{{status=\"{}\"}} {}",
+            status.as_str(),
+            count
+        );
+    }
+
+    describe(
+        &mut out,
+        "outer_opencode_errors_total",
+        "counter",
+        "Requests to OpenCode that failed or timed out",
+    );
+    let _ = writeln!(
+        out,
+        "outer_opencode_errors_total {}",
+        metrics.opencode_errors()
+    );
+
+    out
+}
+
+/// `GET /metrics`: current metrics for Prometheus to scrape
+pub async fn handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], render(&state).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_guard_releases_on_drop() {
+        let metrics = Metrics::new();
+        let first = metrics.connection_opened();
+        let second = metrics.connection_opened();
+        assert_eq!(metrics.active_connections(), 2);
+
+        drop(first);
+        assert_eq!(metrics.active_connections(), 1);
+        drop(second);
+        assert_eq!(metrics.active_connections(), 0);
+    }
+
+    #[test]
+    fn test_only_opencode_errors_are_counted() {
+        let metrics = Metrics::new();
+        metrics.record_error(&AppError::NotFound("block".into()));
+        metrics.record_error(&AppError::OpenCode("bad gateway".into()));
+        metrics.record_error(&AppError::OpenCodeTimeout(std::time::Duration::from_secs(
+            1,
+        )));
+        assert_eq!(metrics.opencode_errors(), 2);
+    }
+}
@@ -11,6 +11,12 @@ pub struct Journal {
     pub title: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Set once the journal has been soft-deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Set while the journal is archived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 /// A block represents a single message/turn in a journal
@@ -27,16 +33,76 @@ pub struct Block {
     /// Original block ID that was forked/re-run to create this block
     #[serde(skip_serializing_if = "Option::is_none")]
     pub forked_from_id: Option<Uuid>,
+    /// Manual ordering key (fractional index); `None` keeps chronological order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<f64>,
+    /// Bumped on every content write; pass it back to update the content
+    #[serde(default)]
+    pub version: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// `content` rendered as HTML, only present when the client asked for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_html: Option<String>,
+}
+
+impl Block {
+    /// Whether the block is done changing, so it's safe to fork or re-run
+    pub fn is_terminal_status(&self) -> bool {
+        self.status.is_final()
+    }
+}
+
+/// A block to insert with [`crate::store::Store::batch_create_blocks`]
+///
+/// The ID is chosen up front so later blocks in the same batch can name
+/// earlier ones as their parent or fork origin.
+#[derive(Debug, Clone)]
+pub struct NewBlock {
+    pub id: Uuid,
+    pub block_type: BlockType,
+    pub content: String,
+    pub status: BlockStatus,
+    pub parent_id: Option<Uuid>,
+    pub forked_from_id: Option<Uuid>,
 }
 
-/// Type of block (user message or assistant response)
+impl NewBlock {
+    /// A finished block of the given type
+    pub fn new(block_type: BlockType, content: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            block_type,
+            content: content.into(),
+            status: BlockStatus::Complete,
+            parent_id: None,
+            forked_from_id: None,
+        }
+    }
+
+    pub fn with_status(mut self, status: BlockStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_parent(mut self, parent_id: Uuid) -> Self {
+        self.parent_id = Some(parent_id);
+        self
+    }
+
+    pub fn forked_from(mut self, forked_from_id: Uuid) -> Self {
+        self.forked_from_id = Some(forked_from_id);
+        self
+    }
+}
+
+/// Type of block (user message, assistant response or system prompt)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum BlockType {
     User,
     Assistant,
+    System,
 }
 
 impl BlockType {
@@ -44,6 +110,7 @@ impl BlockType {
         match self {
             BlockType::User => "user",
             BlockType::Assistant => "assistant",
+            BlockType::System => "system",
         }
     }
 }
@@ -55,6 +122,7 @@ impl std::str::FromStr for BlockType {
         match s {
             "user" => Ok(BlockType::User),
             "assistant" => Ok(BlockType::Assistant),
+            "system" => Ok(BlockType::System),
             _ => Err(format!("Invalid block type: {}", s)),
         }
     }
@@ -65,6 +133,9 @@ impl std::str::FromStr for BlockType {
 #[serde(rename_all = "snake_case")]
 pub enum BlockStatus {
     Pending,
+    /// Waiting for a free submit slot in its journal. Only reported to
+    /// clients; the stored status stays `pending` until streaming starts.
+    Queued,
     Streaming,
     Complete,
     Error,
@@ -74,11 +145,17 @@ impl BlockStatus {
     pub fn as_str(&self) -> &'static str {
         match self {
             BlockStatus::Pending => "pending",
+            BlockStatus::Queued => "queued",
             BlockStatus::Streaming => "streaming",
             BlockStatus::Complete => "complete",
             BlockStatus::Error => "error",
         }
     }
+
+    /// Complete or failed; nothing more will be streamed into the block
+    pub fn is_final(&self) -> bool {
+        matches!(self, BlockStatus::Complete | BlockStatus::Error)
+    }
 }
 
 impl std::str::FromStr for BlockStatus {
@@ -87,6 +164,7 @@ impl std::str::FromStr for BlockStatus {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "pending" => Ok(BlockStatus::Pending),
+            "queued" => Ok(BlockStatus::Queued),
             "streaming" => Ok(BlockStatus::Streaming),
             "complete" => Ok(BlockStatus::Complete),
             "error" => Ok(BlockStatus::Error),
@@ -95,6 +173,183 @@ impl std::str::FromStr for BlockStatus {
     }
 }
 
+/// Order in which a journal's blocks are listed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockOrder {
+    /// By creation time
+    #[default]
+    Chronological,
+    /// By manual `position`, with unpositioned blocks after in creation order
+    ByPosition,
+}
+
+impl BlockOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlockOrder::Chronological => "chronological",
+            BlockOrder::ByPosition => "by_position",
+        }
+    }
+}
+
+impl std::str::FromStr for BlockOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "chronological" => Ok(BlockOrder::Chronological),
+            "by_position" => Ok(BlockOrder::ByPosition),
+            _ => Err(format!("Invalid block order: {}", s)),
+        }
+    }
+}
+
+/// Order in which journals are listed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalSort {
+    /// Most recently updated first
+    #[default]
+    Updated,
+    /// Newest first
+    Created,
+    /// Alphabetically by title, ignoring case
+    Title,
+}
+
+/// Line diff between two blocks' content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockDiff {
+    pub a: Uuid,
+    pub b: Uuid,
+    pub hunks: Vec<crate::diff::DiffHunk>,
+    /// Set when the blocks don't share fork/rerun lineage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+/// Content a user block held before it was edited
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockEdit {
+    pub block_id: Uuid,
+    pub content: String,
+    /// The block's version while it held this content
+    pub version: i64,
+    /// When this content was replaced
+    pub edited_at: DateTime<Utc>,
+}
+
+/// Tokens OpenCode reported spending on an assistant block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+/// Everyone who left one emoji on a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockReaction {
+    pub emoji: String,
+    pub count: usize,
+    /// Participants who reacted, earliest first
+    pub reactors: Vec<Uuid>,
+}
+
+/// A change to a block while it is created, streamed or cancelled
+///
+/// Relayed to everyone subscribed to the block's journal.
+#[derive(Debug, Clone)]
+pub enum BlockEvent {
+    Created(Block),
+    ContentDelta {
+        block_id: Uuid,
+        delta: String,
+        offset: usize,
+    },
+    StatusChanged {
+        block_id: Uuid,
+        status: BlockStatus,
+    },
+    Forked {
+        original_block_id: Uuid,
+        new_block: Block,
+    },
+    Cancelled {
+        block_id: Uuid,
+    },
+    Deleted {
+        block_id: Uuid,
+    },
+    /// A user rewrote their prompt
+    Edited(Block),
+    /// OpenCode reported token counts for an assistant block
+    Usage {
+        block_id: Uuid,
+        usage: BlockUsage,
+    },
+    /// Someone added or took back a reaction
+    ReactionChanged {
+        block_id: Uuid,
+        reaction: BlockReaction,
+    },
+}
+
+/// A journal paired with its newest block, for "recent activity" listings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    pub journal: Journal,
+    /// `None` for a journal with no blocks yet
+    pub latest_block: Option<Block>,
+}
+
+/// Blocks loaded from storage, with a count of rows that couldn't be decoded
+#[derive(Debug, Clone, Default)]
+pub struct LoadedBlocks {
+    pub blocks: Vec<Block>,
+    pub skipped: usize,
+}
+
+/// One page of a journal's blocks, newest first
+#[derive(Debug, Clone, Default)]
+pub struct BlocksPage {
+    pub blocks: Vec<Block>,
+    /// Whether older blocks remain past the end of this page
+    pub has_more: bool,
+}
+
+/// Aggregate storage figures for operators
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageStats {
+    pub journal_count: i64,
+    pub block_count: i64,
+    /// Mean blocks per journal (0 when there are no journals)
+    pub avg_blocks_per_journal: f64,
+    /// Journal with the most blocks, if any blocks exist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub largest_journal: Option<LargestJournal>,
+    /// Total size of all block content in bytes
+    pub total_content_bytes: i64,
+}
+
+/// The journal holding the most blocks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LargestJournal {
+    pub journal_id: Uuid,
+    pub block_count: i64,
+}
+
+/// How big a journal is, without its blocks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalSummary {
+    pub journal_id: Uuid,
+    pub block_count: i64,
+    pub user_block_count: i64,
+    pub assistant_block_count: i64,
+    /// Latest block update, or when the journal was last touched if it has no blocks
+    pub last_activity: DateTime<Utc>,
+}
+
 /// Request to create a new journal
 #[derive(Debug, Deserialize)]
 pub struct CreateJournalRequest {
@@ -116,6 +371,7 @@ mod tests {
     fn test_block_type_as_str() {
         assert_eq!(BlockType::User.as_str(), "user");
         assert_eq!(BlockType::Assistant.as_str(), "assistant");
+        assert_eq!(BlockType::System.as_str(), "system");
     }
 
     #[test]
@@ -125,6 +381,7 @@ mod tests {
             "assistant".parse::<BlockType>().unwrap(),
             BlockType::Assistant
         );
+        assert_eq!("system".parse::<BlockType>().unwrap(), BlockType::System);
     }
 
     #[test]
@@ -159,6 +416,15 @@ mod tests {
         assert_eq!("error".parse::<BlockStatus>().unwrap(), BlockStatus::Error);
     }
 
+    #[test]
+    fn test_block_status_is_final() {
+        assert!(BlockStatus::Complete.is_final());
+        assert!(BlockStatus::Error.is_final());
+        assert!(!BlockStatus::Pending.is_final());
+        assert!(!BlockStatus::Queued.is_final());
+        assert!(!BlockStatus::Streaming.is_final());
+    }
+
     #[test]
     fn test_block_status_from_str_invalid() {
         let result = "invalid".parse::<BlockStatus>();
@@ -166,6 +432,23 @@ mod tests {
         assert_eq!(result.unwrap_err(), "Invalid block status: invalid");
     }
 
+    #[test]
+    fn test_block_order_from_str() {
+        assert_eq!(
+            "by_position".parse::<BlockOrder>().unwrap(),
+            BlockOrder::ByPosition
+        );
+        assert_eq!(
+            BlockOrder::Chronological
+                .as_str()
+                .parse::<BlockOrder>()
+                .unwrap(),
+            BlockOrder::Chronological
+        );
+        assert_eq!(BlockOrder::default(), BlockOrder::Chronological);
+        assert!("sideways".parse::<BlockOrder>().is_err());
+    }
+
     #[test]
     fn test_journal_serialization() {
         let journal = Journal {
@@ -173,6 +456,8 @@ mod tests {
             title: "Test".to_string(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            deleted_at: None,
+            archived_at: None,
         };
         let json = serde_json::to_string(&journal).unwrap();
         assert!(json.contains("Test"));
@@ -188,8 +473,11 @@ mod tests {
             status: BlockStatus::Complete,
             parent_id: None,
             forked_from_id: None,
+            position: None,
+            version: 0,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            content_html: None,
         };
         let json = serde_json::to_string(&block).unwrap();
         assert!(json.contains("Hello"));
@@ -212,8 +500,11 @@ mod tests {
             status: BlockStatus::Complete,
             parent_id: Some(parent_id),
             forked_from_id: Some(forked_from_id),
+            position: None,
+            version: 0,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            content_html: None,
         };
         let json = serde_json::to_string(&block).unwrap();
         assert!(json.contains("parent_id"));
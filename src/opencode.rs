@@ -3,14 +3,57 @@
 use futures::stream::Stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
 use std::pin::Pin;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::error::{AppError, Result};
 
+/// How long a fetched model list is reused before asking the server again
+pub const MODELS_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Model lists by server URL, with when they were fetched
+type ModelsCache = HashMap<String, (Instant, Vec<ModelInfo>)>;
+
+/// Shared by every client because one is built per connection; caching per
+/// client would refetch on each connect.
+static MODELS_CACHE: LazyLock<Mutex<ModelsCache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Attempts at creating a session unless configured otherwise
+pub const DEFAULT_SESSION_ATTEMPTS: u32 = 3;
+
+/// How session creation is retried when OpenCode is briefly unavailable
+///
+/// Only connection failures and 5xx responses are retried. The wait starts
+/// at `initial_backoff` and doubles after each attempt, up to `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Attempts in total, including the first; `1` disables retrying
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_SESSION_ATTEMPTS,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
 /// OpenCode client for interacting with the OpenCode server
+#[derive(Clone)]
 pub struct OpenCodeClient {
     client: Client,
     base_url: String,
+    /// Longest wait for a response, or between events on a stream
+    timeout: Option<Duration>,
+    retry: RetryConfig,
 }
 
 impl OpenCodeClient {
@@ -18,18 +61,101 @@ impl OpenCodeClient {
         Self {
             client: Client::new(),
             base_url: base_url.into(),
+            timeout: None,
+            retry: RetryConfig::default(),
         }
     }
 
-    /// Create a new session
-    pub async fn create_session(&self, _request: CreateSessionRequest) -> Result<Session> {
+    /// Retry session creation according to `retry` instead of the default
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Give up on requests that take longer than `timeout`
+    ///
+    /// For streamed responses this bounds the gap between events rather than
+    /// the whole response, so a slow but steady stream keeps going.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Run a request, failing with [`AppError::OpenCodeTimeout`] if it outlasts the timeout
+    async fn bounded<T>(&self, request: impl Future<Output = Result<T>>) -> Result<T> {
+        match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, request)
+                .await
+                .map_err(|_| AppError::OpenCodeTimeout(timeout))?,
+            None => request.await,
+        }
+    }
+
+    /// Check that the server answers at all, giving up after `timeout`
+    pub async fn ping(&self, timeout: std::time::Duration) -> Result<()> {
         let response = self
+            .client
+            .get(&self.base_url)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| AppError::OpenCode(e.to_string()))?;
+
+        if response.status().is_server_error() {
+            return Err(AppError::OpenCode(format!(
+                "Health check failed: {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Create a new session
+    ///
+    /// Transient failures are retried with backoff (see [`RetryConfig`]). The
+    /// timeout applies to each attempt, and an attempt that times out is not
+    /// retried.
+    pub async fn create_session(&self, request: CreateSessionRequest) -> Result<Session> {
+        let mut backoff = self.retry.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match self.bounded(self.try_create_session(&request)).await? {
+                Ok(session) => return Ok(session),
+                Err(e) if attempt >= self.retry.max_attempts => return Err(e),
+                Err(e) => {
+                    tracing::warn!(
+                        "Creating OpenCode session failed (attempt {} of {}), retrying in {:?}: {}",
+                        attempt,
+                        self.retry.max_attempts,
+                        backoff,
+                        e
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.retry.max_backoff);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// One attempt at creating a session
+    ///
+    /// Failures worth retrying come back as `Ok(Err(_))`; anything else is
+    /// final.
+    async fn try_create_session(
+        &self,
+        request: &CreateSessionRequest,
+    ) -> Result<std::result::Result<Session, AppError>> {
+        let response = match self
             .client
             .post(format!("{}/session", self.base_url))
-            .json(&serde_json::json!({}))
+            .json(request)
             .send()
             .await
-            .map_err(|e| {
+        {
+            Ok(response) => response,
+            Err(e) => {
                 if e.is_connect() {
                     tracing::warn!(
                         "Failed to connect to OpenCode server at {}: {}",
@@ -37,16 +163,26 @@ impl OpenCodeClient {
                         e
                     );
                 }
-                AppError::OpenCode(e.to_string())
-            })?;
+                let transient = e.is_connect() || e.is_request();
+                let error = AppError::OpenCode(e.to_string());
+                return if transient {
+                    Ok(Err(error))
+                } else {
+                    Err(error)
+                };
+            }
+        };
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
-            return Err(AppError::OpenCode(format!(
-                "Failed to create session: {} - {}",
-                status, text
-            )));
+            let error =
+                AppError::OpenCode(format!("Failed to create session: {} - {}", status, text));
+            return if status.is_server_error() {
+                Ok(Err(error))
+            } else {
+                Err(error)
+            };
         }
 
         let text = response
@@ -54,7 +190,7 @@ impl OpenCodeClient {
             .await
             .map_err(|e| AppError::OpenCode(format!("Failed to read response: {}", e)))?;
 
-        serde_json::from_str(&text).map_err(|e| {
+        let session = serde_json::from_str(&text).map_err(|e| {
             // Log the full response for debugging
             tracing::error!(
                 "Failed to parse OpenCode response as JSON: {}. Full response:\n{}",
@@ -71,7 +207,44 @@ impl OpenCodeClient {
                 "Invalid JSON from OpenCode: {}. Response: {}",
                 e, preview
             ))
-        })
+        })?;
+        Ok(Ok(session))
+    }
+
+    /// Models the server can run, cached for [`MODELS_CACHE_TTL`]
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        if let Some((fetched_at, models)) = MODELS_CACHE.lock().unwrap().get(&self.base_url) {
+            if fetched_at.elapsed() < MODELS_CACHE_TTL {
+                return Ok(models.clone());
+            }
+        }
+
+        let response = self
+            .client
+            .get(format!("{}/models", self.base_url))
+            .send()
+            .await
+            .map_err(|e| AppError::OpenCode(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(AppError::OpenCode(format!(
+                "Failed to list models: {} - {}",
+                status, text
+            )));
+        }
+
+        let models: Vec<ModelInfo> = response
+            .json()
+            .await
+            .map_err(|e| AppError::OpenCode(format!("Invalid model list: {}", e)))?;
+
+        MODELS_CACHE
+            .lock()
+            .unwrap()
+            .insert(self.base_url.clone(), (Instant::now(), models.clone()));
+        Ok(models)
     }
 
     /// Send a message and stream the response
@@ -82,20 +255,23 @@ impl OpenCodeClient {
     ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
         // First, subscribe to the event stream
         let event_response = self
-            .client
-            .get(format!("{}/event", self.base_url))
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_connect() {
-                    tracing::warn!(
-                        "Failed to connect to OpenCode server at {}: {}",
-                        self.base_url,
-                        e
-                    );
-                }
-                AppError::OpenCode(e.to_string())
-            })?;
+            .bounded(async {
+                self.client
+                    .get(format!("{}/event", self.base_url))
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        if e.is_connect() {
+                            tracing::warn!(
+                                "Failed to connect to OpenCode server at {}: {}",
+                                self.base_url,
+                                e
+                            );
+                        }
+                        AppError::OpenCode(e.to_string())
+                    })
+            })
+            .await?;
 
         if !event_response.status().is_success() {
             let status = event_response.status();
@@ -115,15 +291,18 @@ impl OpenCodeClient {
         };
 
         let prompt_response = self
-            .client
-            .post(format!(
-                "{}/session/{}/prompt_async",
-                self.base_url, session_id
-            ))
-            .json(&prompt_body)
-            .send()
-            .await
-            .map_err(|e| AppError::OpenCode(format!("Failed to send prompt: {}", e)))?;
+            .bounded(async {
+                self.client
+                    .post(format!(
+                        "{}/session/{}/prompt_async",
+                        self.base_url, session_id
+                    ))
+                    .json(&prompt_body)
+                    .send()
+                    .await
+                    .map_err(|e| AppError::OpenCode(format!("Failed to send prompt: {}", e)))
+            })
+            .await?;
 
         // prompt_async returns 204 on success
         if !prompt_response.status().is_success() {
@@ -137,30 +316,75 @@ impl OpenCodeClient {
 
         // Parse SSE stream, filtering for our session
         let session_id_owned = session_id.to_string();
-        let stream = parse_sse_stream(event_response, Some(session_id_owned));
+        let stream = parse_sse_stream(event_response, Some(session_id_owned), self.timeout);
         Ok(Box::pin(stream))
     }
 
+    /// Ask for a short title summarizing an exchange
+    ///
+    /// Runs in its own session so the summarization prompt never shows up in
+    /// the journal's conversation.
+    pub async fn generate_title(&self, prompt: &str, response: &str) -> Result<String> {
+        use futures::StreamExt;
+
+        let session = self
+            .create_session(CreateSessionRequest {
+                model: None,
+                system_prompt: None,
+            })
+            .await?;
+
+        let content = format!(
+            "Write a concise title (at most six words) for a conversation that starts with \
+             the exchange below. Reply with the title only, no quotes or punctuation at the end.\n\n\
+             User: {}\n\nAssistant: {}",
+            prompt, response
+        );
+
+        let mut stream = self
+            .send_message(&session.id, SendMessageRequest { content })
+            .await?;
+
+        let mut title = String::new();
+        while let Some(event) = stream.next().await {
+            match event? {
+                StreamEvent::Content(content) => title.push_str(&content.text),
+                StreamEvent::Done => break,
+                StreamEvent::Error(error) => return Err(AppError::OpenCode(error.message)),
+                StreamEvent::Usage(_) | StreamEvent::Unknown { .. } => {}
+            }
+        }
+
+        let title = clean_title(&title);
+        if title.is_empty() {
+            return Err(AppError::OpenCode("Empty title from OpenCode".to_string()));
+        }
+        Ok(title)
+    }
+
     /// Subscribe to events for a session
     pub async fn subscribe_events(
         &self,
         session_id: &str,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>> {
         let response = self
-            .client
-            .get(format!("{}/event", self.base_url))
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_connect() {
-                    tracing::warn!(
-                        "Failed to connect to OpenCode server at {}: {}",
-                        self.base_url,
-                        e
-                    );
-                }
-                AppError::OpenCode(e.to_string())
-            })?;
+            .bounded(async {
+                self.client
+                    .get(format!("{}/event", self.base_url))
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        if e.is_connect() {
+                            tracing::warn!(
+                                "Failed to connect to OpenCode server at {}: {}",
+                                self.base_url,
+                                e
+                            );
+                        }
+                        AppError::OpenCode(e.to_string())
+                    })
+            })
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -172,15 +396,19 @@ impl OpenCodeClient {
         }
 
         let session_id_owned = session_id.to_string();
-        let stream = parse_sse_stream(response, Some(session_id_owned));
+        let stream = parse_sse_stream(response, Some(session_id_owned), self.timeout);
         Ok(Box::pin(stream))
     }
 }
 
 /// Parse SSE stream from response
+///
+/// With an `idle_timeout`, the stream yields [`AppError::OpenCodeTimeout`] and
+/// ends if nothing arrives for that long.
 fn parse_sse_stream(
     response: reqwest::Response,
     session_filter: Option<String>,
+    idle_timeout: Option<Duration>,
 ) -> impl Stream<Item = Result<StreamEvent>> + Send {
     use futures::StreamExt;
 
@@ -190,7 +418,18 @@ fn parse_sse_stream(
         let mut event_type = String::new();
         let mut data = String::new();
 
-        while let Some(chunk) = bytes_stream.next().await {
+        loop {
+            let next = match idle_timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, bytes_stream.next()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        yield Err(AppError::OpenCodeTimeout(timeout));
+                        break;
+                    }
+                },
+                None => bytes_stream.next().await,
+            };
+            let Some(chunk) = next else { break };
             let chunk = match chunk {
                 Ok(c) => c,
                 Err(e) => {
@@ -257,9 +496,13 @@ pub(crate) fn parse_event(
                     return Ok(None); // Skip events for other sessions
                 }
             }
-            // Also check for part.sessionID
-            if let Some(part) = props.get("part") {
-                if let Some(session_id) = part.get("sessionID").and_then(|s| s.as_str()) {
+            // Also check for part.sessionID and info.sessionID
+            for nested in ["part", "info"] {
+                if let Some(session_id) = props
+                    .get(nested)
+                    .and_then(|n| n.get("sessionID"))
+                    .and_then(|s| s.as_str())
+                {
                     if session_id != filter_session {
                         return Ok(None);
                     }
@@ -291,6 +534,32 @@ pub(crate) fn parse_event(
             }
             Ok(None)
         }
+        // Message metadata; assistant messages carry token counts once the model reports them
+        "message.updated" => {
+            let tokens = properties
+                .and_then(|p| p.get("info"))
+                .and_then(|info| info.get("tokens"));
+            let count = |key: &str| {
+                tokens
+                    .and_then(|t| t.get(key))
+                    .and_then(|n| n.as_u64())
+                    .map(|n| u32::try_from(n).unwrap_or(u32::MAX))
+            };
+            match (count("input"), count("output")) {
+                (Some(prompt_tokens), Some(completion_tokens))
+                    if prompt_tokens > 0 || completion_tokens > 0 =>
+                {
+                    Ok(Some(StreamEvent::Usage(UsageEvent {
+                        prompt_tokens,
+                        completion_tokens,
+                    })))
+                }
+                _ => Ok(Some(StreamEvent::Unknown {
+                    event_type: payload_type.to_string(),
+                    data: data.to_string(),
+                })),
+            }
+        }
         "session.idle" => Ok(Some(StreamEvent::Done)),
         "session.error" => {
             if let Some(props) = properties {
@@ -303,9 +572,15 @@ pub(crate) fn parse_event(
                 } else {
                     "Unknown error".to_string()
                 };
+                // OpenCode names its error types (e.g. `ProviderAuthError`)
+                let code = props
+                    .get("error")
+                    .and_then(|e| e.get("name"))
+                    .and_then(|n| n.as_str())
+                    .map(String::from);
                 Ok(Some(StreamEvent::Error(ErrorEvent {
                     message: error_msg,
-                    code: None,
+                    code,
                 })))
             } else {
                 Ok(Some(StreamEvent::Error(ErrorEvent {
@@ -324,14 +599,43 @@ pub(crate) fn parse_event(
     }
 }
 
+/// Maximum length of a generated title, in characters
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Normalize a model-generated title to a single trimmed line
+fn clean_title(raw: &str) -> String {
+    let line = raw.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let line = line
+        .trim()
+        .trim_start_matches("Title:")
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'' || c == '*')
+        .trim_end_matches('.')
+        .trim();
+    line.chars().take(MAX_TITLE_CHARS).collect()
+}
+
 // Request/Response types
 
 #[derive(Debug, Serialize)]
 pub struct CreateSessionRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub system_prompt: Option<String>,
 }
 
+/// A model offered by the OpenCode server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// Identifier to pass as a session's `model` (e.g. `anthropic/claude-sonnet-4`)
+    pub id: String,
+    pub name: String,
+    /// Maximum tokens of context, if the server reports it
+    #[serde(default)]
+    pub context_window: Option<u32>,
+}
+
 #[derive(Debug, Serialize)]
 struct PromptRequest {
     parts: Vec<TextPart>,
@@ -361,9 +665,14 @@ pub struct SendMessageRequest {
 #[derive(Debug, Clone)]
 pub enum StreamEvent {
     Content(ContentEvent),
+    /// Token counts so far for the message being streamed
+    Usage(UsageEvent),
     Done,
     Error(ErrorEvent),
-    Unknown { event_type: String, data: String },
+    Unknown {
+        event_type: String,
+        data: String,
+    },
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -371,16 +680,100 @@ pub struct ContentEvent {
     pub text: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct UsageEvent {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ErrorEvent {
     pub message: String,
     pub code: Option<String>,
 }
 
+/// Broad classes of upstream failure worth explaining to users
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpstreamErrorKind {
+    RateLimited,
+    ContextTooLong,
+    AuthFailed,
+    Other,
+}
+
+impl ErrorEvent {
+    /// Classify the error from its code and message text
+    pub fn kind(&self) -> UpstreamErrorKind {
+        let code = self
+            .code
+            .as_deref()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let message = self.message.to_ascii_lowercase();
+        let mentions = |needles: &[&str]| {
+            needles
+                .iter()
+                .any(|n| code.contains(n) || message.contains(n))
+        };
+
+        if code == "429"
+            || mentions(&["rate limit", "rate_limit", "ratelimit", "too many requests"])
+        {
+            UpstreamErrorKind::RateLimited
+        } else if mentions(&[
+            "context length",
+            "context_length",
+            "context window",
+            "maximum context",
+            "prompt is too long",
+            "too many tokens",
+        ]) {
+            UpstreamErrorKind::ContextTooLong
+        } else if code == "401"
+            || code == "403"
+            || mentions(&["auth", "api key", "api_key", "unauthorized", "forbidden"])
+        {
+            UpstreamErrorKind::AuthFailed
+        } else {
+            UpstreamErrorKind::Other
+        }
+    }
+
+    /// A message suitable for end users, without provider internals
+    pub fn user_message(&self) -> &'static str {
+        match self.kind() {
+            UpstreamErrorKind::RateLimited => {
+                "The model provider is rate limiting requests. Please wait a moment and try again."
+            }
+            UpstreamErrorKind::ContextTooLong => {
+                "This conversation is too long for the model. Fork from an earlier block or start a new journal."
+            }
+            UpstreamErrorKind::AuthFailed => {
+                "The server could not authenticate with the model provider. Please contact the operator."
+            }
+            UpstreamErrorKind::Other => "The model failed to respond. Please try again.",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_clean_title() {
+        assert_eq!(
+            clean_title("  \"Rust lifetimes explained.\"\n"),
+            "Rust lifetimes explained"
+        );
+        assert_eq!(
+            clean_title("\nTitle: Fixing a flaky test\nextra"),
+            "Fixing a flaky test"
+        );
+        assert_eq!(clean_title("   "), "");
+        assert_eq!(clean_title(&"x".repeat(200)).len(), MAX_TITLE_CHARS);
+    }
+
     #[test]
     fn test_opencode_client_new() {
         let client = OpenCodeClient::new("http://localhost:8080");
@@ -425,6 +818,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_event_message_usage() {
+        let data = r#"{"type": "message.updated", "properties": {"info": {"sessionID": "ses_123", "role": "assistant", "tokens": {"input": 120, "output": 45, "reasoning": 0}}}}"#;
+        let event = parse_event("", data, Some("ses_123")).unwrap();
+        match event {
+            Some(StreamEvent::Usage(usage)) => {
+                assert_eq!(usage.prompt_tokens, 120);
+                assert_eq!(usage.completion_tokens, 45);
+            }
+            _ => panic!("Expected Usage event"),
+        }
+
+        // No counts yet, or another session's message
+        let data = r#"{"type": "message.updated", "properties": {"info": {"sessionID": "ses_123", "tokens": {"input": 0, "output": 0}}}}"#;
+        assert!(matches!(
+            parse_event("", data, Some("ses_123")).unwrap(),
+            Some(StreamEvent::Unknown { .. })
+        ));
+        let data = r#"{"type": "message.updated", "properties": {"info": {"sessionID": "ses_other", "tokens": {"input": 1, "output": 1}}}}"#;
+        assert!(parse_event("", data, Some("ses_123")).unwrap().is_none());
+    }
+
     #[test]
     fn test_parse_event_filters_other_sessions() {
         let data = r#"{"type": "session.idle", "properties": {"sessionID": "ses_other"}}"#;
@@ -557,6 +972,53 @@ mod tests {
         assert_eq!(cloned.text, "hello");
     }
 
+    #[test]
+    fn test_error_event_user_message() {
+        let event = |message: &str, code: Option<&str>| ErrorEvent {
+            message: message.to_string(),
+            code: code.map(String::from),
+        };
+
+        let rate_limited = event("Rate limit reached for requests", None);
+        assert_eq!(rate_limited.kind(), UpstreamErrorKind::RateLimited);
+        assert!(rate_limited.user_message().contains("rate limiting"));
+        assert_eq!(
+            event("slow down", Some("429")).kind(),
+            UpstreamErrorKind::RateLimited
+        );
+
+        let too_long = event(
+            "prompt is too long: 212000 tokens > 200000 maximum",
+            Some("APIError"),
+        );
+        assert_eq!(too_long.kind(), UpstreamErrorKind::ContextTooLong);
+        assert!(too_long.user_message().contains("too long"));
+        assert_eq!(
+            event("This model's maximum context length is 8192 tokens", None).kind(),
+            UpstreamErrorKind::ContextTooLong
+        );
+
+        let auth = event("invalid x-api-key", Some("ProviderAuthError"));
+        assert_eq!(auth.kind(), UpstreamErrorKind::AuthFailed);
+        assert!(auth.user_message().contains("authenticate"));
+
+        let other = event("upstream connect error sk-live-1234", None);
+        assert_eq!(other.kind(), UpstreamErrorKind::Other);
+        assert!(!other.user_message().contains("sk-live"));
+    }
+
+    #[test]
+    fn test_parse_session_error_keeps_error_name() {
+        let data = r#"{"type":"session.error","properties":{"error":{"name":"ProviderAuthError","message":"bad key"}}}"#;
+        match parse_event("message", data, None).unwrap() {
+            Some(StreamEvent::Error(e)) => {
+                assert_eq!(e.message, "bad key");
+                assert_eq!(e.code.as_deref(), Some("ProviderAuthError"));
+            }
+            other => panic!("Expected error event, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_error_event_clone() {
         let event = ErrorEvent {
@@ -567,4 +1029,118 @@ mod tests {
         assert_eq!(cloned.message, "error");
         assert_eq!(cloned.code, Some("500".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_create_session_times_out() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/session"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(30)))
+            .mount(&mock_server)
+            .await;
+
+        let client =
+            OpenCodeClient::new(mock_server.uri()).with_timeout(Duration::from_millis(200));
+        let started = Instant::now();
+        let result = client
+            .create_session(CreateSessionRequest {
+                model: None,
+                system_prompt: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(AppError::OpenCodeTimeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_create_session_retries_only_server_errors() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let retry = RetryConfig {
+            max_attempts: DEFAULT_SESSION_ATTEMPTS,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(20),
+        };
+        let request = || CreateSessionRequest {
+            model: None,
+            system_prompt: None,
+        };
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/session"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("Bad model"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let client = OpenCodeClient::new(mock_server.uri()).with_retry_config(retry);
+        assert!(matches!(
+            client.create_session(request()).await,
+            Err(AppError::OpenCode(_))
+        ));
+        mock_server.verify().await;
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/session"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+        let client = OpenCodeClient::new(mock_server.uri()).with_retry_config(retry);
+        assert!(client.create_session(request()).await.is_err());
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_stream_times_out_only_when_idle() {
+        use futures::StreamExt;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // A server that sends events a little apart, then goes quiet without closing
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\n\r\n")
+                .await
+                .unwrap();
+            for _ in 0..3 {
+                socket
+                    .write_all(b"data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"x\"}}\n\n")
+                    .await
+                    .unwrap();
+                tokio::time::sleep(Duration::from_millis(150)).await;
+            }
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        });
+
+        let client = OpenCodeClient::new(format!("http://{}", addr))
+            .with_timeout(Duration::from_millis(250));
+        let mut stream = client.subscribe_events("sess").await.unwrap();
+
+        // The whole stream outlasts the timeout, but no single gap does
+        for _ in 0..3 {
+            assert!(matches!(
+                stream.next().await,
+                Some(Ok(StreamEvent::Content(_)))
+            ));
+        }
+
+        let started = Instant::now();
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(AppError::OpenCodeTimeout(_)))
+        ));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(stream.next().await.is_none());
+    }
 }
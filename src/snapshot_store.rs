@@ -0,0 +1,79 @@
+//! Database store for journal CRDT snapshots
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::error::Result;
+
+/// Persists each journal's encoded Yrs document
+///
+/// Only the latest state is kept; a snapshot is a full update that can be
+/// applied to an empty document to rebuild it.
+#[derive(Clone)]
+pub struct SnapshotStore {
+    pool: SqlitePool,
+}
+
+impl SnapshotStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Replace the stored snapshot for a journal
+    pub async fn save(&self, journal_id: Uuid, state: &[u8]) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO crdt_snapshots (journal_id, state, updated_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(journal_id) DO UPDATE SET
+                state = excluded.state,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(journal_id.to_string())
+        .bind(state)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The stored snapshot for a journal, if one has been written
+    pub async fn load(&self, journal_id: Uuid) -> Result<Option<Vec<u8>>> {
+        let state = sqlx::query_scalar::<_, Vec<u8>>(
+            "SELECT state FROM crdt_snapshots WHERE journal_id = ?",
+        )
+        .bind(journal_id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_snapshot_overwrite() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create in-memory database");
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let journal = Store::new(pool.clone()).create_journal(None).await.unwrap();
+        let store = SnapshotStore::new(pool);
+
+        assert_eq!(store.load(journal.id).await.unwrap(), None);
+
+        store.save(journal.id, &[1, 2, 3]).await.unwrap();
+        store.save(journal.id, &[4, 5]).await.unwrap();
+        assert_eq!(store.load(journal.id).await.unwrap(), Some(vec![4, 5]));
+    }
+}
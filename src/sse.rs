@@ -0,0 +1,177 @@
+//! Read-only journal feed over server-sent events
+//!
+//! `GET /journals/:id/stream` follows a journal's room the way a websocket
+//! subscriber would, for dashboards and scripts that only want to watch.
+//! Each event is named after the websocket message's `type` and carries the
+//! same JSON. CRDT traffic isn't forwarded, and the stream neither joins
+//! the room nor accepts submits.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures::Stream;
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+use crate::auth;
+use crate::crdt::room::RoomEvent;
+use crate::websocket::ServerMessage;
+use crate::AppState;
+
+/// `GET /journals/:id/stream`
+pub async fn handler(
+    Path(journal_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<auth::TokenQuery>,
+) -> Response {
+    if let Err(status) = state
+        .auth
+        .authenticate(&state.store, &headers, &query)
+        .await
+    {
+        return status.into_response();
+    }
+    if let Err(e) = state.store.get_journal(journal_id).await {
+        return e.into_response();
+    }
+    let room = match state.room_manager.get_or_create(journal_id).await {
+        Ok(room) => room,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response(),
+    };
+
+    Sse::new(events(journal_id, room.subscribe()))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// Turn a room subscription into SSE events, ending when the room goes away
+fn events(
+    journal_id: Uuid,
+    rx: broadcast::Receiver<RoomEvent>,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    futures::stream::unfold(rx, move |mut rx| async move {
+        loop {
+            let event = match rx.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::warn!(
+                        "Event stream for journal {} missed {} events",
+                        journal_id,
+                        missed
+                    );
+                    continue;
+                }
+                Err(RecvError::Closed) => return None,
+            };
+            if let Some(event) = to_server_message(journal_id, event).and_then(|msg| encode(&msg)) {
+                return Some((Ok(event), rx));
+            }
+        }
+    })
+}
+
+/// The websocket message a subscriber would get for `event`, if any
+fn to_server_message(journal_id: Uuid, event: RoomEvent) -> Option<ServerMessage> {
+    match event {
+        RoomEvent::ParticipantJoined(participant) => Some(ServerMessage::ParticipantJoined {
+            journal_id,
+            participant,
+        }),
+        RoomEvent::ParticipantLeft { participant_id } => Some(ServerMessage::ParticipantLeft {
+            journal_id,
+            participant_id,
+        }),
+        RoomEvent::CursorMoved {
+            participant_id,
+            block_id,
+            offset,
+            anchor,
+            head,
+        } => Some(ServerMessage::CursorMoved {
+            journal_id,
+            participant_id,
+            block_id,
+            offset,
+            anchor,
+            head,
+        }),
+        RoomEvent::StatusChanged {
+            participant_id,
+            status,
+        } => Some(ServerMessage::ParticipantStatusChanged {
+            journal_id,
+            participant_id,
+            status,
+        }),
+        RoomEvent::TypingChanged {
+            participant_id,
+            typing,
+        } => Some(ServerMessage::TypingChanged {
+            journal_id,
+            participant_id,
+            typing,
+        }),
+        RoomEvent::BlockEvent { event, .. } => Some(ServerMessage::from(event)),
+        RoomEvent::RoleChanged {
+            participant_id,
+            role,
+            ..
+        } => Some(ServerMessage::RoleChanged {
+            journal_id,
+            participant_id,
+            role,
+        }),
+        RoomEvent::JournalRenamed { title, .. } => {
+            Some(ServerMessage::JournalRenamed { journal_id, title })
+        }
+        RoomEvent::CrdtUpdate { .. } | RoomEvent::SyncState { .. } => None,
+    }
+}
+
+/// An SSE event named after the message's `type`
+fn encode(msg: &ServerMessage) -> Option<Event> {
+    let value = match serde_json::to_value(msg) {
+        Ok(value) => value,
+        Err(e) => {
+            tracing::error!("Failed to serialize event: {}", e);
+            return None;
+        }
+    };
+    let name = value["type"].as_str().unwrap_or("message").to_string();
+    Some(Event::default().event(name).data(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_crdt_traffic_is_not_streamed() {
+        let journal_id = Uuid::new_v4();
+        let (tx, rx) = broadcast::channel(8);
+        let mut stream = Box::pin(events(journal_id, rx));
+
+        tx.send(RoomEvent::CrdtUpdate {
+            source: None,
+            seq: 1,
+            update: vec![1, 2, 3],
+        })
+        .unwrap();
+        tx.send(RoomEvent::JournalRenamed {
+            origin: None,
+            title: "Renamed".to_string(),
+        })
+        .unwrap();
+        drop(tx);
+
+        // Only the rename comes through before the stream ends with the room
+        assert!(stream.next().await.is_some());
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -1,27 +1,81 @@
 //! Database store for journals and blocks
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
 use sqlx::SqlitePool;
 use uuid::Uuid;
 
+use crate::crdt::JournalRole;
 use crate::error::{AppError, Result};
-use crate::models::{Block, BlockStatus, BlockType, Journal};
+use crate::event_log::{EventLog, LoggedEvent};
+use crate::models::{
+    Block, BlockDiff, BlockEdit, BlockOrder, BlockReaction, BlockStatus, BlockType, BlockUsage,
+    BlocksPage, Journal, JournalSort, JournalSummary, LargestJournal, LoadedBlocks, NewBlock,
+    StorageStats,
+};
+
+/// Titles journals get when nobody names them (the server's and the CLI's)
+pub const DEFAULT_JOURNAL_TITLES: [&str; 2] = ["Untitled", "CLI Session"];
+
+/// Longest title taken from the start of a message, in characters
+const AUTOTITLE_MAX_CHARS: usize = 60;
+
+/// Longest reaction accepted, in characters (room for multi-codepoint emoji)
+const MAX_REACTION_CHARS: usize = 16;
 
 /// Database store
+///
+/// Reads and writes may use separate pools so that list/get traffic does not
+/// queue behind long-running writes. With SQLite in WAL mode, readers see the
+/// last committed state while a write is in progress.
 #[derive(Clone)]
 pub struct Store {
-    pool: SqlitePool,
+    read_pool: SqlitePool,
+    write_pool: SqlitePool,
+    event_log: Option<EventLog>,
 }
 
 impl Store {
+    /// Create a store that uses one pool for both reads and writes
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            read_pool: pool.clone(),
+            write_pool: pool,
+            event_log: None,
+        }
+    }
+
+    /// Create a store that routes reads and writes to separate pools
+    ///
+    /// The read pool may be opened read-only; every write goes through `write_pool`.
+    pub fn with_pools(read_pool: SqlitePool, write_pool: SqlitePool) -> Self {
+        Self {
+            read_pool,
+            write_pool,
+            event_log: None,
+        }
+    }
+
+    /// Record journal and block creation to an event log
+    pub fn with_event_log(mut self, event_log: EventLog) -> Self {
+        self.event_log = Some(event_log);
+        self
+    }
+
+    fn log_event(&self, event: impl FnOnce() -> LoggedEvent) {
+        if let Some(log) = &self.event_log {
+            log.record(event());
+        }
     }
 
     // Journal operations
 
     pub async fn create_journal(&self, title: Option<String>) -> Result<Journal> {
-        let id = Uuid::new_v4();
+        self.create_journal_with_id(Uuid::new_v4(), title).await
+    }
+
+    /// Create a journal with a caller-chosen ID (e.g. one a client subscribed to first)
+    pub async fn create_journal_with_id(&self, id: Uuid, title: Option<String>) -> Result<Journal> {
         let title = title.unwrap_or_else(|| "Untitled".to_string());
         let now = Utc::now();
 
@@ -35,47 +89,409 @@ impl Store {
         .bind(&title)
         .bind(now)
         .bind(now)
-        .execute(&self.pool)
+        .execute(&self.write_pool)
         .await?;
 
-        Ok(Journal {
+        let journal = Journal {
             id,
             title,
             created_at: now,
             updated_at: now,
-        })
+            deleted_at: None,
+            archived_at: None,
+        };
+        self.log_event(|| LoggedEvent::JournalCreated {
+            journal: journal.clone(),
+        });
+
+        Ok(journal)
     }
 
     pub async fn get_journal(&self, id: Uuid) -> Result<Journal> {
         let row = sqlx::query_as::<_, JournalRow>(
             r#"
-            SELECT id, title, created_at, updated_at
+            SELECT id, title, created_at, updated_at, deleted_at, archived_at
             FROM journals
-            WHERE id = ?
+            WHERE id = ? AND deleted_at IS NULL
             "#,
         )
         .bind(id.to_string())
-        .fetch_optional(&self.pool)
+        .fetch_optional(&self.read_pool)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Journal {} not found", id)))?;
 
         row.try_into()
     }
 
-    pub async fn list_journals(&self) -> Result<Vec<Journal>> {
-        let rows = sqlx::query_as::<_, JournalRow>(
+    /// Fetch a journal and its blocks from a single consistent snapshot
+    pub async fn get_journal_with_blocks(&self, id: Uuid) -> Result<(Journal, Vec<Block>)> {
+        let (journal, loaded) = self.load_journal_with_blocks(id, false).await?;
+        Ok((journal, loaded.blocks))
+    }
+
+    /// Like [`Store::get_journal_with_blocks`], but skips blocks whose rows
+    /// can't be decoded instead of failing the whole journal
+    pub async fn get_journal_with_blocks_lenient(
+        &self,
+        id: Uuid,
+    ) -> Result<(Journal, LoadedBlocks)> {
+        self.load_journal_with_blocks(id, true).await
+    }
+
+    async fn load_journal_with_blocks(
+        &self,
+        id: Uuid,
+        lenient: bool,
+    ) -> Result<(Journal, LoadedBlocks)> {
+        let mut tx = self.read_pool.begin().await?;
+
+        let journal_row = sqlx::query_as::<_, JournalRow>(
             r#"
-            SELECT id, title, created_at, updated_at
+            SELECT id, title, created_at, updated_at, deleted_at, archived_at
             FROM journals
-            ORDER BY updated_at DESC
+            WHERE id = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Journal {} not found", id)))?;
+
+        let block_rows = sqlx::query_as::<_, BlockRow>(
+            r#"
+            SELECT id, journal_id, block_type, content, status, parent_id, forked_from_id, position, version, created_at, updated_at
+            FROM blocks
+            WHERE journal_id = ?
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let journal = journal_row.try_into()?;
+        let loaded = convert_block_rows(block_rows, lenient)?;
+
+        Ok((journal, loaded))
+    }
+
+    pub async fn update_journal_title(&self, id: Uuid, title: &str) -> Result<Journal> {
+        let now = Utc::now();
+
+        let result = sqlx::query(
+            r#"
+            UPDATE journals SET title = ?, updated_at = ? WHERE id = ?
+            "#,
+        )
+        .bind(title)
+        .bind(now)
+        .bind(id.to_string())
+        .execute(&self.write_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Journal {} not found", id)));
+        }
+
+        self.get_journal(id).await
+    }
+
+    /// Title a journal after `content` if it still has a default title
+    ///
+    /// Returns the retitled journal, or `None` if it had already been named
+    /// or `content` has no words to take a title from.
+    pub async fn maybe_autotitle_journal(
+        &self,
+        id: Uuid,
+        content: &str,
+    ) -> Result<Option<Journal>> {
+        let Some(title) = title_from_content(content) else {
+            return Ok(None);
+        };
+        self.retitle_unnamed_journal(id, &title, None).await
+    }
+
+    /// Replace a journal's title with a generated one, unless someone has named it
+    ///
+    /// Only a default title, or the one [`Self::maybe_autotitle_journal`] took
+    /// from `prompt`, is replaced. Returns the retitled journal, or `None` if
+    /// it was left alone.
+    pub async fn replace_placeholder_title(
+        &self,
+        id: Uuid,
+        prompt: &str,
+        title: &str,
+    ) -> Result<Option<Journal>> {
+        let derived = title_from_content(prompt);
+        self.retitle_unnamed_journal(id, title, derived.as_deref())
+            .await
+    }
+
+    /// Set `title` if the journal still has a default title, or `derived` when given
+    async fn retitle_unnamed_journal(
+        &self,
+        id: Uuid,
+        title: &str,
+        derived: Option<&str>,
+    ) -> Result<Option<Journal>> {
+        // Checking the title in the update keeps a concurrent rename from being overwritten
+        let result = sqlx::query(
+            r#"
+            UPDATE journals SET title = ?, updated_at = ?
+            WHERE id = ? AND (title IN (?, ?) OR title = ?) AND deleted_at IS NULL
             "#,
         )
-        .fetch_all(&self.pool)
+        .bind(title)
+        .bind(Utc::now())
+        .bind(id.to_string())
+        .bind(DEFAULT_JOURNAL_TITLES[0])
+        .bind(DEFAULT_JOURNAL_TITLES[1])
+        .bind(derived)
+        .execute(&self.write_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get_journal(id).await.map(Some)
+    }
+
+    /// Give a journal a new title, rejecting blank ones
+    pub async fn rename_journal(&self, id: Uuid, title: &str) -> Result<Journal> {
+        let title = title.trim();
+        if title.is_empty() {
+            return Err(AppError::BadRequest(
+                "Journal title cannot be empty".to_string(),
+            ));
+        }
+
+        self.update_journal_title(id, title).await
+    }
+
+    pub async fn list_journals(&self) -> Result<Vec<Journal>> {
+        self.list_journals_with_deleted(false).await
+    }
+
+    /// List journals, optionally including soft-deleted ones
+    ///
+    /// Archived journals are left out.
+    pub async fn list_journals_with_deleted(&self, include_deleted: bool) -> Result<Vec<Journal>> {
+        self.list_journals_filtered(include_deleted, false, None, JournalSort::Updated)
+            .await
+    }
+
+    /// List journals, optionally including soft-deleted and archived ones
+    ///
+    /// `query` keeps only journals whose title contains it, ignoring case; a
+    /// blank query matches everything.
+    pub async fn list_journals_filtered(
+        &self,
+        include_deleted: bool,
+        include_archived: bool,
+        query: Option<&str>,
+        sort: JournalSort,
+    ) -> Result<Vec<Journal>> {
+        let order_by = match sort {
+            JournalSort::Updated => "updated_at DESC",
+            JournalSort::Created => "created_at DESC",
+            JournalSort::Title => "title COLLATE NOCASE ASC, created_at DESC",
+        };
+        let pattern = query
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(|q| format!("%{}%", escape_like(q)));
+
+        let rows = sqlx::query_as::<_, JournalRow>(&format!(
+            r#"
+            SELECT id, title, created_at, updated_at, deleted_at, archived_at
+            FROM journals
+            WHERE (? OR deleted_at IS NULL)
+              AND (? OR archived_at IS NULL)
+              AND (? IS NULL OR title LIKE ? ESCAPE '\')
+            ORDER BY {}
+            "#,
+            order_by
+        ))
+        .bind(include_deleted)
+        .bind(include_archived)
+        .bind(&pattern)
+        .bind(&pattern)
+        .fetch_all(&self.read_pool)
         .await?;
 
         rows.into_iter().map(|r| r.try_into()).collect()
     }
 
+    /// Archive or unarchive a journal
+    ///
+    /// Archived journals are hidden from listings but can still be read and
+    /// subscribed to. Archiving an archived journal keeps its original time.
+    pub async fn set_journal_archived(&self, id: Uuid, archived: bool) -> Result<Journal> {
+        let result = sqlx::query(
+            r#"
+            UPDATE journals
+            SET archived_at = CASE WHEN ? THEN COALESCE(archived_at, ?) ELSE NULL END
+            WHERE id = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(archived)
+        .bind(Utc::now())
+        .bind(id.to_string())
+        .execute(&self.write_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Journal {} not found", id)));
+        }
+
+        self.get_journal(id).await
+    }
+
+    /// The most recently updated journals, each with its newest block (if any)
+    ///
+    /// Soft-deleted journals are left out.
+    pub async fn get_recent_activity(&self, limit: u32) -> Result<Vec<(Journal, Option<Block>)>> {
+        let rows = sqlx::query_as::<_, ActivityRow>(
+            r#"
+            SELECT j.id, j.title, j.created_at, j.updated_at, j.deleted_at, j.archived_at,
+                   b.id AS block_id, b.block_type, b.content, b.status, b.parent_id,
+                   b.forked_from_id, b.position, b.version,
+                   b.created_at AS block_created_at, b.updated_at AS block_updated_at
+            FROM journals j
+            LEFT JOIN blocks b ON b.id = (
+                SELECT id FROM blocks
+                WHERE journal_id = j.id
+                ORDER BY created_at DESC, rowid DESC
+                LIMIT 1
+            )
+            WHERE j.deleted_at IS NULL
+            ORDER BY j.updated_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        rows.into_iter().map(ActivityRow::into_entry).collect()
+    }
+
+    /// Mark a journal as deleted. Its blocks are kept, but it no longer shows
+    /// up in listings or lookups.
+    pub async fn soft_delete_journal(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE journals SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(id.to_string())
+        .execute(&self.write_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Journal {} not found", id)));
+        }
+
+        Ok(())
+    }
+
+    /// Run a trivial query to confirm the database is reachable
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.write_pool).await?;
+        Ok(())
+    }
+
+    /// Aggregate counts and sizes across all journals and blocks
+    pub async fn stats(&self) -> Result<StorageStats> {
+        let mut tx = self.read_pool.begin().await?;
+
+        let (journal_count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM journals")
+            .fetch_one(&mut *tx)
+            .await?;
+
+        // CAST to BLOB so LENGTH counts bytes rather than characters
+        let (block_count, total_content_bytes): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), COALESCE(SUM(LENGTH(CAST(content AS BLOB))), 0)
+            FROM blocks
+            "#,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let largest: Option<(String, i64)> = sqlx::query_as(
+            r#"
+            SELECT journal_id, COUNT(*) AS block_count
+            FROM blocks
+            GROUP BY journal_id
+            ORDER BY block_count DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let largest_journal = largest
+            .map(|(journal_id, block_count)| {
+                Uuid::parse_str(&journal_id)
+                    .map(|journal_id| LargestJournal {
+                        journal_id,
+                        block_count,
+                    })
+                    .map_err(|e| AppError::Internal(format!("Invalid UUID: {}", e)))
+            })
+            .transpose()?;
+
+        let avg_blocks_per_journal = if journal_count > 0 {
+            block_count as f64 / journal_count as f64
+        } else {
+            0.0
+        };
+
+        Ok(StorageStats {
+            journal_count,
+            block_count,
+            avg_blocks_per_journal,
+            largest_journal,
+            total_content_bytes,
+        })
+    }
+
+    /// Block counts and latest activity for one journal
+    pub async fn journal_summary(&self, journal_id: Uuid) -> Result<JournalSummary> {
+        let row: Option<(i64, i64, i64, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(b.id),
+                COALESCE(SUM(b.block_type = 'user'), 0),
+                COALESCE(SUM(b.block_type = 'assistant'), 0),
+                COALESCE(MAX(b.updated_at), j.updated_at)
+            FROM journals j
+            LEFT JOIN blocks b ON b.journal_id = j.id
+            WHERE j.id = ? AND j.deleted_at IS NULL
+            GROUP BY j.id
+            "#,
+        )
+        .bind(journal_id.to_string())
+        .fetch_optional(&self.read_pool)
+        .await?;
+
+        let (block_count, user_block_count, assistant_block_count, last_activity) =
+            row.ok_or_else(|| AppError::NotFound(format!("Journal {} not found", journal_id)))?;
+        Ok(JournalSummary {
+            journal_id,
+            block_count,
+            user_block_count,
+            assistant_block_count,
+            last_activity,
+        })
+    }
+
     // Block operations
 
     pub async fn create_block(
@@ -99,9 +515,9 @@ impl Store {
     ) -> Result<Block> {
         let id = Uuid::new_v4();
         let now = Utc::now();
-        // User blocks are complete immediately; assistant blocks start pending
+        // User and system blocks are complete immediately; assistant blocks start pending
         let status = match block_type {
-            BlockType::User => BlockStatus::Complete,
+            BlockType::User | BlockType::System => BlockStatus::Complete,
             BlockType::Assistant => BlockStatus::Pending,
         };
 
@@ -120,7 +536,7 @@ impl Store {
         .bind(forked_from_id.map(|u| u.to_string()))
         .bind(now)
         .bind(now)
-        .execute(&self.pool)
+        .execute(&self.write_pool)
         .await?;
 
         // Update journal's updated_at
@@ -131,10 +547,10 @@ impl Store {
         )
         .bind(now)
         .bind(journal_id.to_string())
-        .execute(&self.pool)
+        .execute(&self.write_pool)
         .await?;
 
-        Ok(Block {
+        let block = Block {
             id,
             journal_id,
             block_type,
@@ -142,21 +558,238 @@ impl Store {
             status,
             parent_id,
             forked_from_id,
+            position: None,
+            version: 0,
             created_at: now,
             updated_at: now,
-        })
+            content_html: None,
+        };
+        self.log_event(|| LoggedEvent::BlockCreated {
+            block: block.clone(),
+        });
+
+        Ok(block)
+    }
+
+    /// Insert many blocks in one transaction, e.g. for imports or seeding
+    ///
+    /// Blocks keep their order (each gets a slightly later `created_at` than
+    /// the one before) and the journal's `updated_at` is bumped once.
+    pub async fn batch_create_blocks(
+        &self,
+        journal_id: Uuid,
+        blocks: Vec<NewBlock>,
+    ) -> Result<Vec<Block>> {
+        if blocks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let start = Utc::now();
+        let mut tx = self.write_pool.begin().await?;
+
+        // Check first, so a missing journal isn't reported as a foreign key failure
+        let exists: Option<i64> = sqlx::query_scalar("SELECT 1 FROM journals WHERE id = ?")
+            .bind(journal_id.to_string())
+            .fetch_optional(&mut *tx)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::NotFound(format!(
+                "Journal {} not found",
+                journal_id
+            )));
+        }
+
+        let mut created = Vec::with_capacity(blocks.len());
+
+        for (i, new_block) in blocks.into_iter().enumerate() {
+            let created_at = start + chrono::Duration::microseconds(i as i64);
+
+            sqlx::query(
+                r#"
+                INSERT INTO blocks (id, journal_id, block_type, content, status, parent_id, forked_from_id, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(new_block.id.to_string())
+            .bind(journal_id.to_string())
+            .bind(new_block.block_type.as_str())
+            .bind(&new_block.content)
+            .bind(new_block.status.as_str())
+            .bind(new_block.parent_id.map(|u| u.to_string()))
+            .bind(new_block.forked_from_id.map(|u| u.to_string()))
+            .bind(created_at)
+            .bind(created_at)
+            .execute(&mut *tx)
+            .await?;
+
+            created.push(Block {
+                id: new_block.id,
+                journal_id,
+                block_type: new_block.block_type,
+                content: new_block.content,
+                status: new_block.status,
+                parent_id: new_block.parent_id,
+                forked_from_id: new_block.forked_from_id,
+                position: None,
+                version: 0,
+                created_at,
+                updated_at: created_at,
+                content_html: None,
+            });
+        }
+
+        let last = created.last().map(|b| b.created_at).unwrap_or(start);
+        sqlx::query(
+            r#"
+            UPDATE journals SET updated_at = ? WHERE id = ?
+            "#,
+        )
+        .bind(last)
+        .bind(journal_id.to_string())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        for block in &created {
+            self.log_event(|| LoggedEvent::BlockCreated {
+                block: block.clone(),
+            });
+        }
+
+        Ok(created)
+    }
+
+    /// Create a submit's user block and its pending assistant block together
+    ///
+    /// Both are written in one transaction, so a failure part way never leaves
+    /// a prompt without a block for its response. Returns `(user, assistant)`.
+    pub async fn begin_submit(&self, journal_id: Uuid, content: &str) -> Result<(Block, Block)> {
+        let user = NewBlock::new(BlockType::User, content);
+        let assistant = NewBlock::new(BlockType::Assistant, "").with_status(BlockStatus::Pending);
+        let mut created = self
+            .batch_create_blocks(journal_id, vec![user, assistant])
+            .await?
+            .into_iter();
+        match (created.next(), created.next()) {
+            (Some(user), Some(assistant)) => Ok((user, assistant)),
+            _ => Err(AppError::Internal(
+                "Submit blocks were not all created".to_string(),
+            )),
+        }
+    }
+
+    /// Copy a journal and all its blocks under fresh IDs
+    ///
+    /// `parent_id` and `forked_from_id` are rewritten to point at the copies,
+    /// so the clone has the same branches as the original. Blocks that were
+    /// still pending or streaming are copied as errors, since nothing will
+    /// finish them. The title defaults to the original's with " (copy)".
+    pub async fn clone_journal(&self, journal_id: Uuid, title: Option<String>) -> Result<Journal> {
+        let source = self.get_journal(journal_id).await?;
+        let blocks = self.get_blocks_for_journal(journal_id).await?;
+
+        let ids: std::collections::HashMap<Uuid, Uuid> = blocks
+            .iter()
+            .map(|block| (block.id, Uuid::new_v4()))
+            .collect();
+        let remap = |id: Option<Uuid>| id.and_then(|id| ids.get(&id).copied());
+
+        let title = title
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| format!("{} (copy)", source.title));
+        let now = Utc::now();
+        let journal = Journal {
+            id: Uuid::new_v4(),
+            title,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+            archived_at: None,
+        };
+
+        let mut tx = self.write_pool.begin().await?;
+        // A block may name one copied later in the loop; check lineage at commit
+        sqlx::query("PRAGMA defer_foreign_keys = ON")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO journals (id, title, created_at, updated_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(journal.id.to_string())
+        .bind(&journal.title)
+        .bind(now)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        let mut copies = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let status = match block.status {
+                BlockStatus::Complete | BlockStatus::Error => block.status,
+                _ => BlockStatus::Error,
+            };
+            let copy = Block {
+                id: ids[&block.id],
+                journal_id: journal.id,
+                status,
+                parent_id: remap(block.parent_id),
+                forked_from_id: remap(block.forked_from_id),
+                ..block
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO blocks (id, journal_id, block_type, content, status, parent_id, forked_from_id, position, version, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(copy.id.to_string())
+            .bind(journal.id.to_string())
+            .bind(copy.block_type.as_str())
+            .bind(&copy.content)
+            .bind(copy.status.as_str())
+            .bind(copy.parent_id.map(|u| u.to_string()))
+            .bind(copy.forked_from_id.map(|u| u.to_string()))
+            .bind(copy.position)
+            .bind(copy.version)
+            .bind(copy.created_at)
+            .bind(copy.updated_at)
+            .execute(&mut *tx)
+            .await?;
+
+            copies.push(copy);
+        }
+
+        tx.commit().await?;
+
+        self.log_event(|| LoggedEvent::JournalCreated {
+            journal: journal.clone(),
+        });
+        for block in &copies {
+            self.log_event(|| LoggedEvent::BlockCreated {
+                block: block.clone(),
+            });
+        }
+
+        Ok(journal)
     }
 
     pub async fn get_block(&self, id: Uuid) -> Result<Block> {
         let row = sqlx::query_as::<_, BlockRow>(
             r#"
-            SELECT id, journal_id, block_type, content, status, parent_id, forked_from_id, created_at, updated_at
+            SELECT id, journal_id, block_type, content, status, parent_id, forked_from_id, position, version, created_at, updated_at
             FROM blocks
             WHERE id = ?
             "#,
         )
         .bind(id.to_string())
-        .fetch_optional(&self.pool)
+        .fetch_optional(&self.read_pool)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Block {} not found", id)))?;
 
@@ -164,36 +797,194 @@ impl Store {
     }
 
     pub async fn get_blocks_for_journal(&self, journal_id: Uuid) -> Result<Vec<Block>> {
-        let rows = sqlx::query_as::<_, BlockRow>(
+        self.get_blocks_for_journal_ordered(journal_id, BlockOrder::Chronological)
+            .await
+    }
+
+    /// Fetch a journal's blocks in the given order
+    pub async fn get_blocks_for_journal_ordered(
+        &self,
+        journal_id: Uuid,
+        order: BlockOrder,
+    ) -> Result<Vec<Block>> {
+        let rows = self.fetch_block_rows(journal_id, order).await?;
+        Ok(convert_block_rows(rows, false)?.blocks)
+    }
+
+    /// Fetch a journal's blocks, skipping (and logging) rows that can't be decoded
+    pub async fn get_blocks_for_journal_lenient(
+        &self,
+        journal_id: Uuid,
+        order: BlockOrder,
+    ) -> Result<LoadedBlocks> {
+        let rows = self.fetch_block_rows(journal_id, order).await?;
+        convert_block_rows(rows, true)
+    }
+
+    async fn fetch_block_rows(&self, journal_id: Uuid, order: BlockOrder) -> Result<Vec<BlockRow>> {
+        let order_by = match order {
+            BlockOrder::Chronological => "created_at ASC",
+            // Positioned blocks first, then the unpositioned tail chronologically
+            BlockOrder::ByPosition => "position IS NULL, position ASC, created_at ASC",
+        };
+
+        let rows = sqlx::query_as::<_, BlockRow>(&format!(
             r#"
-            SELECT id, journal_id, block_type, content, status, parent_id, forked_from_id, created_at, updated_at
+            SELECT id, journal_id, block_type, content, status, parent_id, forked_from_id, position, version, created_at, updated_at
             FROM blocks
             WHERE journal_id = ?
-            ORDER BY created_at ASC
+            ORDER BY {}
+            "#,
+            order_by
+        ))
+        .bind(journal_id.to_string())
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Fetch up to `limit` blocks created before `before`, newest first
+    ///
+    /// Pass the `created_at` of the last block of a page as `before` to get
+    /// the next one. Blocks added while a client is paging are newer than its
+    /// cursor, so they never shift later pages.
+    pub async fn get_blocks_page(
+        &self,
+        journal_id: Uuid,
+        before: Option<DateTime<Utc>>,
+        limit: u32,
+    ) -> Result<BlocksPage> {
+        let rows = sqlx::query_as::<_, BlockRow>(
+            r#"
+            SELECT id, journal_id, block_type, content, status, parent_id, forked_from_id, position, version, created_at, updated_at
+            FROM blocks
+            WHERE journal_id = ? AND (? IS NULL OR created_at < ?)
+            ORDER BY created_at DESC, id DESC
+            LIMIT ?
             "#,
         )
         .bind(journal_id.to_string())
-        .fetch_all(&self.pool)
+        .bind(before)
+        .bind(before)
+        .bind(i64::from(limit) + 1)
+        .fetch_all(&self.read_pool)
         .await?;
 
-        rows.into_iter().map(|r| r.try_into()).collect()
+        let mut blocks = convert_block_rows(rows, false)?.blocks;
+        let has_more = blocks.len() > limit as usize;
+        blocks.truncate(limit as usize);
+
+        Ok(BlocksPage { blocks, has_more })
+    }
+
+    /// Full-text search over block content, best matches first
+    ///
+    /// Each whitespace-separated word of `query` must appear in a block for it
+    /// to match; FTS operators are treated as plain text. Blocks in deleted
+    /// journals are never returned.
+    pub async fn search_blocks(
+        &self,
+        query: &str,
+        journal_id: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<Block>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Err(AppError::BadRequest(
+                "Search query must not be empty".to_string(),
+            ));
+        };
+        let journal_id = journal_id.map(|id| id.to_string());
+
+        let rows = sqlx::query_as::<_, BlockRow>(
+            r#"
+            SELECT b.id, b.journal_id, b.block_type, b.content, b.status, b.parent_id, b.forked_from_id, b.position, b.version, b.created_at, b.updated_at
+            FROM blocks_fts
+            JOIN blocks b ON b.rowid = blocks_fts.rowid
+            JOIN journals j ON j.id = b.journal_id
+            WHERE blocks_fts MATCH ?
+              AND (? IS NULL OR b.journal_id = ?)
+              AND j.deleted_at IS NULL
+            ORDER BY blocks_fts.rank
+            LIMIT ?
+            "#,
+        )
+        .bind(fts_query)
+        .bind(&journal_id)
+        .bind(&journal_id)
+        .bind(i64::from(limit))
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(convert_block_rows(rows, false)?.blocks)
     }
 
-    pub async fn update_block_content(&self, id: Uuid, content: &str) -> Result<()> {
+    /// Move a block to a fractional position within its journal.
+    ///
+    /// To place a block between two neighbours, pass a value between their
+    /// positions (see [`position_between`]); no other block is renumbered.
+    pub async fn reorder_block(&self, block_id: Uuid, new_position: f64) -> Result<Block> {
+        if !new_position.is_finite() {
+            return Err(AppError::BadRequest(format!(
+                "Invalid block position: {}",
+                new_position
+            )));
+        }
+
         let now = Utc::now();
 
-        sqlx::query(
+        let result = sqlx::query(
+            r#"
+            UPDATE blocks SET position = ?, updated_at = ? WHERE id = ?
+            "#,
+        )
+        .bind(new_position)
+        .bind(now)
+        .bind(block_id.to_string())
+        .execute(&self.write_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Block {} not found", block_id)));
+        }
+
+        self.get_block(block_id).await
+    }
+
+    /// Replace a block's content, provided nobody has written it since `expected_version`
+    ///
+    /// Returns the new version. If the stored version has moved on, the write
+    /// is refused with [`AppError::Conflict`] and the caller should re-read.
+    pub async fn update_block_content(
+        &self,
+        id: Uuid,
+        content: &str,
+        expected_version: i64,
+    ) -> Result<i64> {
+        let now = Utc::now();
+
+        let result = sqlx::query(
             r#"
-            UPDATE blocks SET content = ?, updated_at = ? WHERE id = ?
+            UPDATE blocks SET content = ?, version = version + 1, updated_at = ?
+            WHERE id = ? AND version = ?
             "#,
         )
         .bind(content)
         .bind(now)
         .bind(id.to_string())
-        .execute(&self.pool)
+        .bind(expected_version)
+        .execute(&self.write_pool)
         .await?;
 
-        Ok(())
+        if result.rows_affected() == 0 {
+            let current = self.get_block(id).await?;
+            return Err(AppError::Conflict(format!(
+                "Block {} is at version {}, not {}",
+                id, current.version, expected_version
+            )));
+        }
+
+        Ok(expected_version + 1)
     }
 
     pub async fn update_block_status(&self, id: Uuid, status: BlockStatus) -> Result<()> {
@@ -207,24 +998,335 @@ impl Store {
         .bind(status.as_str())
         .bind(now)
         .bind(id.to_string())
-        .execute(&self.pool)
+        .execute(&self.write_pool)
         .await?;
 
         Ok(())
     }
 
-    /// Fork a block: create a new user block with the same content, branching from the parent
-    /// Returns the new user block (caller should then create assistant block and send to OpenCode)
-    pub async fn fork_block(&self, block_id: Uuid) -> Result<Block> {
-        let original = self.get_block(block_id).await?;
+    /// Mark every block still streaming as failed, returning how many there were
+    ///
+    /// Only safe when nothing can be writing to them: at startup, or once
+    /// shutdown has given in-flight streams their chance to finish.
+    pub async fn fail_streaming_blocks(&self) -> Result<u64> {
+        let result = sqlx::query("UPDATE blocks SET status = ?, updated_at = ? WHERE status = ?")
+            .bind(BlockStatus::Error.as_str())
+            .bind(Utc::now())
+            .bind(BlockStatus::Streaming.as_str())
+            .execute(&self.write_pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
 
-        // For forking, we create a new user block with the original's content
-        // The parent_id points to the block we're forking after (creating a branch point)
-        // The forked_from_id points to the original block being forked
-        self.create_block_with_lineage(
-            original.journal_id,
-            BlockType::User,
-            &original.content,
+    /// Rewrite the content of a user's prompt, keeping what it said before
+    ///
+    /// Assistant and system blocks can't be edited. The replaced content is
+    /// saved with the version it had, and the block's version is bumped.
+    pub async fn edit_user_block(&self, block_id: Uuid, content: &str) -> Result<Block> {
+        if content.trim().is_empty() {
+            return Err(AppError::BadRequest(
+                "Block content cannot be empty".to_string(),
+            ));
+        }
+
+        let mut tx = self.write_pool.begin().await?;
+
+        let current: Option<(String, String, i64)> =
+            sqlx::query_as("SELECT block_type, content, version FROM blocks WHERE id = ?")
+                .bind(block_id.to_string())
+                .fetch_optional(&mut *tx)
+                .await?;
+        let Some((block_type, previous, version)) = current else {
+            return Err(AppError::NotFound(format!("Block {} not found", block_id)));
+        };
+        if block_type != BlockType::User.as_str() {
+            return Err(AppError::BadRequest(format!(
+                "Only user blocks can be edited; block {} is a {} block",
+                block_id, block_type
+            )));
+        }
+
+        if previous != content {
+            let now = Utc::now();
+            sqlx::query(
+                r#"
+                INSERT INTO block_edits (block_id, content, version, edited_at)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(block_id.to_string())
+            .bind(&previous)
+            .bind(version)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                r#"
+                UPDATE blocks SET content = ?, version = version + 1, updated_at = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(content)
+            .bind(now)
+            .bind(block_id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        self.get_block(block_id).await
+    }
+
+    /// Record the token counts for a block, replacing any reported earlier
+    pub async fn set_block_usage(&self, block_id: Uuid, usage: BlockUsage) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE blocks SET prompt_tokens = ?, completion_tokens = ? WHERE id = ?
+            "#,
+        )
+        .bind(usage.prompt_tokens)
+        .bind(usage.completion_tokens)
+        .bind(block_id.to_string())
+        .execute(&self.write_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Block {} not found", block_id)));
+        }
+        Ok(())
+    }
+
+    /// Token counts recorded for a block, or `None` if OpenCode never sent any
+    pub async fn get_block_usage(&self, block_id: Uuid) -> Result<Option<BlockUsage>> {
+        let row: Option<(Option<u32>, Option<u32>)> =
+            sqlx::query_as("SELECT prompt_tokens, completion_tokens FROM blocks WHERE id = ?")
+                .bind(block_id.to_string())
+                .fetch_optional(&self.read_pool)
+                .await?;
+
+        match row {
+            None => Err(AppError::NotFound(format!("Block {} not found", block_id))),
+            Some((Some(prompt_tokens), Some(completion_tokens))) => Ok(Some(BlockUsage {
+                prompt_tokens,
+                completion_tokens,
+            })),
+            Some(_) => Ok(None),
+        }
+    }
+
+    /// Add `participant_id`'s `emoji` reaction to a block, or remove it if
+    /// they already left one
+    ///
+    /// Returns who has that reaction on the block afterwards.
+    pub async fn toggle_reaction(
+        &self,
+        block_id: Uuid,
+        participant_id: Uuid,
+        emoji: &str,
+    ) -> Result<BlockReaction> {
+        let emoji = emoji.trim();
+        if emoji.is_empty() || emoji.chars().count() > MAX_REACTION_CHARS {
+            return Err(AppError::BadRequest(format!(
+                "A reaction must be 1 to {} characters",
+                MAX_REACTION_CHARS
+            )));
+        }
+        self.get_block(block_id).await?;
+
+        let mut tx = self.write_pool.begin().await?;
+        let removed = sqlx::query(
+            "DELETE FROM block_reactions WHERE block_id = ? AND participant_id = ? AND emoji = ?",
+        )
+        .bind(block_id.to_string())
+        .bind(participant_id.to_string())
+        .bind(emoji)
+        .execute(&mut *tx)
+        .await?;
+        if removed.rows_affected() == 0 {
+            sqlx::query(
+                r#"
+                INSERT INTO block_reactions (block_id, participant_id, emoji, created_at)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(block_id.to_string())
+            .bind(participant_id.to_string())
+            .bind(emoji)
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT participant_id FROM block_reactions
+            WHERE block_id = ? AND emoji = ?
+            ORDER BY created_at ASC, rowid ASC
+            "#,
+        )
+        .bind(block_id.to_string())
+        .bind(emoji)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        let reactors = rows
+            .into_iter()
+            .filter_map(|(id,)| Uuid::parse_str(&id).ok())
+            .collect::<Vec<_>>();
+        Ok(BlockReaction {
+            emoji: emoji.to_string(),
+            count: reactors.len(),
+            reactors,
+        })
+    }
+
+    /// Reactions on a block, in the order each emoji was first used
+    pub async fn get_reactions(&self, block_id: Uuid) -> Result<Vec<BlockReaction>> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT emoji, participant_id FROM block_reactions
+            WHERE block_id = ?
+            ORDER BY created_at ASC, rowid ASC
+            "#,
+        )
+        .bind(block_id.to_string())
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let mut reactions: Vec<BlockReaction> = Vec::new();
+        for (emoji, participant_id) in rows {
+            let Ok(participant_id) = Uuid::parse_str(&participant_id) else {
+                continue;
+            };
+            match reactions.iter_mut().find(|r| r.emoji == emoji) {
+                Some(reaction) => {
+                    reaction.reactors.push(participant_id);
+                    reaction.count += 1;
+                }
+                None => reactions.push(BlockReaction {
+                    emoji,
+                    count: 1,
+                    reactors: vec![participant_id],
+                }),
+            }
+        }
+        Ok(reactions)
+    }
+
+    /// Earlier contents of a block, oldest first; empty if it was never edited
+    pub async fn get_block_history(&self, block_id: Uuid) -> Result<Vec<BlockEdit>> {
+        // Distinguish an unknown block from one with no edits
+        self.get_block(block_id).await?;
+
+        let rows: Vec<(String, i64, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT content, version, edited_at
+            FROM block_edits
+            WHERE block_id = ?
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(block_id.to_string())
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(content, version, edited_at)| BlockEdit {
+                block_id,
+                content,
+                version,
+                edited_at,
+            })
+            .collect())
+    }
+
+    /// Delete a block, along with its descendants if `cascade` is set
+    ///
+    /// Descendants are blocks reachable through `parent_id` or `forked_from_id`
+    /// links. Without `cascade`, a block that has any is left alone and
+    /// [`AppError::Conflict`] is returned. Returns the deleted IDs, starting
+    /// with `block_id`.
+    pub async fn delete_block(&self, block_id: Uuid, cascade: bool) -> Result<Vec<Uuid>> {
+        // Walks both lineage links; UNION rather than UNION ALL so a cycle ends
+        const DOOMED: &str = r#"
+            WITH RECURSIVE doomed(id) AS (
+                SELECT ?
+                UNION
+                SELECT b.id FROM blocks b
+                JOIN doomed d ON b.parent_id = d.id OR b.forked_from_id = d.id
+            )
+        "#;
+
+        let mut tx = self.write_pool.begin().await?;
+
+        let exists: Option<String> = sqlx::query_scalar("SELECT id FROM blocks WHERE id = ?")
+            .bind(block_id.to_string())
+            .fetch_optional(&mut *tx)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::NotFound(format!("Block {} not found", block_id)));
+        }
+
+        let ids: Vec<String> = sqlx::query_scalar(&format!("{} SELECT id FROM doomed", DOOMED))
+            .bind(block_id.to_string())
+            .fetch_all(&mut *tx)
+            .await?;
+        let mut deleted = vec![block_id];
+        for id in &ids {
+            let id = Uuid::parse_str(id)
+                .map_err(|e| AppError::Internal(format!("Invalid block UUID {}: {}", id, e)))?;
+            if id != block_id {
+                deleted.push(id);
+            }
+        }
+
+        if deleted.len() > 1 && !cascade {
+            return Err(AppError::Conflict(format!(
+                "Block {} has {} descendant block(s); delete with cascade to remove them too",
+                block_id,
+                deleted.len() - 1
+            )));
+        }
+
+        // Work items about these blocks outlive them, just without the link
+        sqlx::query(&format!(
+            "{} UPDATE work_items SET block_id = NULL WHERE block_id IN (SELECT id FROM doomed)",
+            DOOMED
+        ))
+        .bind(block_id.to_string())
+        .execute(&mut *tx)
+        .await?;
+
+        // One statement, so links between the deleted rows are never left dangling
+        sqlx::query(&format!(
+            "{} DELETE FROM blocks WHERE id IN (SELECT id FROM doomed)",
+            DOOMED
+        ))
+        .bind(block_id.to_string())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(deleted)
+    }
+
+    /// Fork a block: create a new user block with the same content, branching from the parent
+    /// Returns the new user block (caller should then create assistant block and send to OpenCode)
+    pub async fn fork_block(&self, block_id: Uuid) -> Result<Block> {
+        let original = self.get_block(block_id).await?;
+
+        // For forking, we create a new user block with the original's content
+        // The parent_id points to the block we're forking after (creating a branch point)
+        // The forked_from_id points to the original block being forked
+        self.create_block_with_lineage(
+            original.journal_id,
+            BlockType::User,
+            &original.content,
             Some(block_id), // parent_id: the block we're branching from
             Some(block_id), // forked_from_id: the original block
         )
@@ -254,6 +1356,11 @@ impl Store {
                     })?;
                 (user_block.content.clone(), block_id)
             }
+            BlockType::System => {
+                return Err(AppError::BadRequest(
+                    "System blocks can't be re-run".to_string(),
+                ));
+            }
         };
 
         self.create_block_with_lineage(
@@ -266,18 +1373,95 @@ impl Store {
         .await
     }
 
+    /// Line diff from block `a`'s content to block `b`'s
+    ///
+    /// Comparing blocks from unrelated branches still works, but the result
+    /// carries a warning since the diff is unlikely to mean much.
+    pub async fn diff_blocks(&self, a: Uuid, b: Uuid) -> Result<BlockDiff> {
+        let old = self.get_block(a).await?;
+        let new = self.get_block(b).await?;
+
+        let related = !self
+            .lineage(&old)
+            .await?
+            .is_disjoint(&self.lineage(&new).await?);
+
+        Ok(BlockDiff {
+            a,
+            b,
+            hunks: crate::diff::diff_lines(&old.content, &new.content),
+            warning: (!related).then(|| format!("Blocks {} and {} do not share lineage", a, b)),
+        })
+    }
+
+    /// The block plus every ancestor reachable through parent and fork links
+    async fn lineage(&self, block: &Block) -> Result<std::collections::HashSet<Uuid>> {
+        let mut seen = std::collections::HashSet::from([block.id]);
+        let mut pending: Vec<Uuid> = block
+            .parent_id
+            .into_iter()
+            .chain(block.forked_from_id)
+            .collect();
+
+        while let Some(id) = pending.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            match self.get_block(id).await {
+                Ok(ancestor) => pending.extend(
+                    ancestor
+                        .parent_id
+                        .into_iter()
+                        .chain(ancestor.forked_from_id),
+                ),
+                // Links can dangle if an ancestor was removed
+                Err(AppError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(seen)
+    }
+
+    /// The path from the root of `leaf_id`'s branch down to it, oldest first
+    ///
+    /// Follows `parent_id` links only, so sibling forks and reruns along the
+    /// way are left out.
+    pub async fn get_branch(&self, leaf_id: Uuid) -> Result<Vec<Block>> {
+        let mut branch = vec![self.get_block(leaf_id).await?];
+        let mut seen = std::collections::HashSet::from([leaf_id]);
+
+        while let Some(parent_id) = branch.last().and_then(|block| block.parent_id) {
+            if !seen.insert(parent_id) {
+                return Err(AppError::Internal(format!(
+                    "Cycle in parent links at block {}",
+                    parent_id
+                )));
+            }
+            match self.get_block(parent_id).await {
+                Ok(parent) => branch.push(parent),
+                // Links can dangle if an ancestor was removed
+                Err(AppError::NotFound(_)) => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        branch.reverse();
+        Ok(branch)
+    }
+
     /// Get blocks that were forked from a specific block
     pub async fn get_forks(&self, block_id: Uuid) -> Result<Vec<Block>> {
         let rows = sqlx::query_as::<_, BlockRow>(
             r#"
-            SELECT id, journal_id, block_type, content, status, parent_id, forked_from_id, created_at, updated_at
+            SELECT id, journal_id, block_type, content, status, parent_id, forked_from_id, position, version, created_at, updated_at
             FROM blocks
             WHERE forked_from_id = ?
             ORDER BY created_at ASC
             "#,
         )
         .bind(block_id.to_string())
-        .fetch_all(&self.pool)
+        .fetch_all(&self.read_pool)
         .await?;
 
         rows.into_iter().map(|r| r.try_into()).collect()
@@ -287,18 +1471,153 @@ impl Store {
     pub async fn get_children(&self, block_id: Uuid) -> Result<Vec<Block>> {
         let rows = sqlx::query_as::<_, BlockRow>(
             r#"
-            SELECT id, journal_id, block_type, content, status, parent_id, forked_from_id, created_at, updated_at
+            SELECT id, journal_id, block_type, content, status, parent_id, forked_from_id, position, version, created_at, updated_at
             FROM blocks
             WHERE parent_id = ?
             ORDER BY created_at ASC
             "#,
         )
         .bind(block_id.to_string())
-        .fetch_all(&self.pool)
+        .fetch_all(&self.read_pool)
         .await?;
 
         rows.into_iter().map(|r| r.try_into()).collect()
     }
+
+    /// OpenCode session a journal's submits continue, if one was recorded
+    pub async fn get_journal_session(&self, journal_id: Uuid) -> Result<Option<String>> {
+        let session_id: Option<String> =
+            sqlx::query_scalar("SELECT session_id FROM journal_sessions WHERE journal_id = ?")
+                .bind(journal_id.to_string())
+                .fetch_optional(&self.read_pool)
+                .await?;
+        Ok(session_id)
+    }
+
+    /// Record `session_id` as the journal's session unless it already has one
+    ///
+    /// Returns whichever session the journal ends up with, so two submits
+    /// that each created a session agree on the one that was kept.
+    pub async fn get_or_set_journal_session(
+        &self,
+        journal_id: Uuid,
+        session_id: &str,
+    ) -> Result<String> {
+        sqlx::query(
+            r#"
+            INSERT INTO journal_sessions (journal_id, session_id, created_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(journal_id) DO NOTHING
+            "#,
+        )
+        .bind(journal_id.to_string())
+        .bind(session_id)
+        .bind(Utc::now())
+        .execute(&self.write_pool)
+        .await?;
+
+        let stored: String =
+            sqlx::query_scalar("SELECT session_id FROM journal_sessions WHERE journal_id = ?")
+                .bind(journal_id.to_string())
+                .fetch_one(&self.write_pool)
+                .await?;
+        Ok(stored)
+    }
+
+    /// Issue a new bearer token for `name`
+    ///
+    /// Only a hash is stored, so the returned token can't be recovered later.
+    pub async fn create_token(&self, name: &str) -> Result<String> {
+        let token = Uuid::new_v4().simple().to_string();
+        sqlx::query("INSERT INTO tokens (token_hash, name) VALUES (?, ?)")
+            .bind(hash_token(&token))
+            .bind(name)
+            .execute(&self.write_pool)
+            .await?;
+        Ok(token)
+    }
+
+    /// Name a bearer token was issued to, or `None` if it isn't known
+    pub async fn token_identity(&self, token: &str) -> Result<Option<String>> {
+        let name: Option<String> =
+            sqlx::query_scalar("SELECT name FROM tokens WHERE token_hash = ?")
+                .bind(hash_token(token))
+                .fetch_optional(&self.read_pool)
+                .await?;
+        Ok(name)
+    }
+
+    /// Record `role` for `member` of a journal unless they already hold one,
+    /// returning whichever role is stored
+    ///
+    /// Ownership is only granted while nobody else owns the journal; a later
+    /// claim to it is recorded as `Member`.
+    pub async fn claim_journal_role(
+        &self,
+        journal_id: Uuid,
+        member: &str,
+        role: JournalRole,
+    ) -> Result<JournalRole> {
+        let mut tx = self.write_pool.begin().await?;
+
+        let held: Option<String> = sqlx::query_scalar(
+            "SELECT role FROM journal_roles WHERE journal_id = ? AND member = ?",
+        )
+        .bind(journal_id.to_string())
+        .bind(member)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(held) = held {
+            return held.parse().map_err(AppError::Internal);
+        }
+
+        let owned: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM journal_roles WHERE journal_id = ? AND role = 'owner')",
+        )
+        .bind(journal_id.to_string())
+        .fetch_one(&mut *tx)
+        .await?;
+        let role = match role {
+            JournalRole::Owner if owned => JournalRole::Member,
+            role => role,
+        };
+
+        sqlx::query("INSERT INTO journal_roles (journal_id, member, role) VALUES (?, ?, ?)")
+            .bind(journal_id.to_string())
+            .bind(member)
+            .bind(role.as_str())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(role)
+    }
+
+    /// Give `member` of a journal a new role, replacing any they held
+    pub async fn set_journal_role(
+        &self,
+        journal_id: Uuid,
+        member: &str,
+        role: JournalRole,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO journal_roles (journal_id, member, role) VALUES (?, ?, ?)
+            ON CONFLICT (journal_id, member) DO UPDATE SET role = excluded.role
+            "#,
+        )
+        .bind(journal_id.to_string())
+        .bind(member)
+        .bind(role.as_str())
+        .execute(&self.write_pool)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Hex SHA-256 digest a bearer token is stored under
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
 }
 
 // Internal row types for sqlx
@@ -309,6 +1628,8 @@ struct JournalRow {
     title: String,
     created_at: chrono::DateTime<Utc>,
     updated_at: chrono::DateTime<Utc>,
+    deleted_at: Option<chrono::DateTime<Utc>>,
+    archived_at: Option<chrono::DateTime<Utc>>,
 }
 
 impl TryFrom<JournalRow> for Journal {
@@ -321,10 +1642,24 @@ impl TryFrom<JournalRow> for Journal {
             title: row.title,
             created_at: row.created_at,
             updated_at: row.updated_at,
+            deleted_at: row.deleted_at,
+            archived_at: row.archived_at,
         })
     }
 }
 
+/// Pick a fractional position between two neighbours.
+///
+/// `None` on either side means the start or end of the list.
+pub fn position_between(before: Option<f64>, after: Option<f64>) -> f64 {
+    match (before, after) {
+        (Some(a), Some(b)) => a + (b - a) / 2.0,
+        (Some(a), None) => a + 1.0,
+        (None, Some(b)) => b - 1.0,
+        (None, None) => 0.0,
+    }
+}
+
 #[derive(sqlx::FromRow)]
 struct BlockRow {
     id: String,
@@ -334,50 +1669,161 @@ struct BlockRow {
     status: String,
     parent_id: Option<String>,
     forked_from_id: Option<String>,
+    position: Option<f64>,
+    version: i64,
     created_at: chrono::DateTime<Utc>,
     updated_at: chrono::DateTime<Utc>,
 }
 
+/// A journal joined with its newest block; the block columns are null when it has none
+#[derive(sqlx::FromRow)]
+struct ActivityRow {
+    #[sqlx(flatten)]
+    journal: JournalRow,
+    block_id: Option<String>,
+    block_type: Option<String>,
+    content: Option<String>,
+    status: Option<String>,
+    parent_id: Option<String>,
+    forked_from_id: Option<String>,
+    position: Option<f64>,
+    version: Option<i64>,
+    block_created_at: Option<chrono::DateTime<Utc>>,
+    block_updated_at: Option<chrono::DateTime<Utc>>,
+}
+
+impl ActivityRow {
+    fn into_entry(self) -> Result<(Journal, Option<Block>)> {
+        let block = match (self.block_id, self.block_created_at, self.block_updated_at) {
+            (Some(id), Some(created_at), Some(updated_at)) => Some(
+                BlockRow {
+                    id,
+                    journal_id: self.journal.id.clone(),
+                    block_type: self.block_type.unwrap_or_default(),
+                    content: self.content.unwrap_or_default(),
+                    status: self.status.unwrap_or_default(),
+                    parent_id: self.parent_id,
+                    forked_from_id: self.forked_from_id,
+                    position: self.position,
+                    version: self.version.unwrap_or_default(),
+                    created_at,
+                    updated_at,
+                }
+                .try_into()?,
+            ),
+            _ => None,
+        };
+        Ok((self.journal.try_into()?, block))
+    }
+}
+
 impl TryFrom<BlockRow> for Block {
     type Error = AppError;
 
     fn try_from(row: BlockRow) -> Result<Self> {
+        let invalid = |what: &str, e: &dyn std::fmt::Display| {
+            AppError::Internal(format!("Invalid {} for block {}: {}", what, row.id, e))
+        };
+
         let parent_id = row
             .parent_id
-            .map(|s| Uuid::parse_str(&s))
+            .as_deref()
+            .map(Uuid::parse_str)
             .transpose()
-            .map_err(|e| AppError::Internal(format!("Invalid parent_id UUID: {}", e)))?;
+            .map_err(|e| invalid("parent_id UUID", &e))?;
         let forked_from_id = row
             .forked_from_id
-            .map(|s| Uuid::parse_str(&s))
+            .as_deref()
+            .map(Uuid::parse_str)
             .transpose()
-            .map_err(|e| AppError::Internal(format!("Invalid forked_from_id UUID: {}", e)))?;
+            .map_err(|e| invalid("forked_from_id UUID", &e))?;
 
         Ok(Block {
-            id: Uuid::parse_str(&row.id)
-                .map_err(|e| AppError::Internal(format!("Invalid UUID: {}", e)))?,
+            id: Uuid::parse_str(&row.id).map_err(|e| invalid("UUID", &e))?,
             journal_id: Uuid::parse_str(&row.journal_id)
-                .map_err(|e| AppError::Internal(format!("Invalid UUID: {}", e)))?,
+                .map_err(|e| invalid("journal_id UUID", &e))?,
             block_type: row
                 .block_type
                 .parse()
-                .map_err(|e| AppError::Internal(format!("Invalid block type: {}", e)))?,
+                .map_err(|e| invalid("block type", &e))?,
             content: row.content,
-            status: row
-                .status
-                .parse()
-                .map_err(|e| AppError::Internal(format!("Invalid status: {}", e)))?,
+            status: row.status.parse().map_err(|e| invalid("status", &e))?,
             parent_id,
             forked_from_id,
+            position: row.position,
+            version: row.version,
             created_at: row.created_at,
             updated_at: row.updated_at,
+            content_html: None,
         })
     }
 }
 
+/// A journal title made from the start of `content`
+///
+/// Whitespace is collapsed, and anything past [`AUTOTITLE_MAX_CHARS`] is cut
+/// at the last word boundary and marked with an ellipsis.
+pub fn title_from_content(content: &str) -> Option<String> {
+    let text = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    let Some((cut, _)) = text.char_indices().nth(AUTOTITLE_MAX_CHARS) else {
+        return Some(text);
+    };
+
+    // Keep whole words unless the first one alone is too long
+    let head = &text[..cut];
+    let head = if text[cut..].starts_with(' ') {
+        head
+    } else {
+        head.rsplit_once(' ').map_or(head, |(words, _)| words)
+    };
+    Some(format!("{}…", head.trim_end()))
+}
+
+/// Quote each word of a user query so FTS5 treats it literally
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Escape `%`, `_` and `\` so LIKE matches them literally (with `ESCAPE '\'`)
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Decode block rows. In strict mode the first bad row fails the call;
+/// in lenient mode bad rows are logged and counted instead.
+fn convert_block_rows(rows: Vec<BlockRow>, lenient: bool) -> Result<LoadedBlocks> {
+    let mut loaded = LoadedBlocks::default();
+    for row in rows {
+        match Block::try_from(row) {
+            Ok(block) => loaded.blocks.push(block),
+            Err(e) if lenient => {
+                tracing::warn!("Skipping unreadable block row: {}", e);
+                loaded.skipped += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(loaded)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::diff::DiffOp;
     use sqlx::sqlite::SqlitePoolOptions;
 
     async fn setup_test_db() -> Store {
@@ -387,6 +1833,12 @@ mod tests {
             .await
             .expect("Failed to create in-memory database");
 
+        create_tables(&pool).await;
+
+        Store::new(pool)
+    }
+
+    async fn create_tables(pool: &SqlitePool) {
         // Run migrations manually
         sqlx::query(
             r#"
@@ -394,11 +1846,13 @@ mod tests {
                 id TEXT PRIMARY KEY NOT NULL,
                 title TEXT NOT NULL,
                 created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                deleted_at DATETIME,
+                archived_at DATETIME
             )
             "#,
         )
-        .execute(&pool)
+        .execute(pool)
         .await
         .expect("Failed to create journals table");
 
@@ -407,81 +1861,1031 @@ mod tests {
             CREATE TABLE IF NOT EXISTS blocks (
                 id TEXT PRIMARY KEY NOT NULL,
                 journal_id TEXT NOT NULL REFERENCES journals(id),
-                block_type TEXT NOT NULL CHECK (block_type IN ('user', 'assistant')),
+                block_type TEXT NOT NULL CHECK (block_type IN ('user', 'assistant', 'system')),
                 content TEXT NOT NULL DEFAULT '',
                 status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'streaming', 'complete', 'error')),
                 parent_id TEXT REFERENCES blocks(id),
                 forked_from_id TEXT REFERENCES blocks(id),
+                position REAL,
+                version INTEGER NOT NULL DEFAULT 0,
                 created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
                 updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
             )
             "#,
         )
-        .execute(&pool)
+        .execute(pool)
         .await
         .expect("Failed to create blocks table");
 
-        Store::new(pool)
+        sqlx::query(include_str!(
+            "../migrations/20260110000009_block_search.sql"
+        ))
+        .execute(pool)
+        .await
+        .expect("Failed to create block search index");
+
+        sqlx::query(include_str!(
+            "../migrations/20260110000005_work_item_persistence.sql"
+        ))
+        .execute(pool)
+        .await
+        .expect("Failed to create work items table");
+
+        sqlx::query(include_str!("../migrations/20260110000016_block_edits.sql"))
+            .execute(pool)
+            .await
+            .expect("Failed to create block edits table");
+
+        sqlx::query(include_str!("../migrations/20260110000017_tokens.sql"))
+            .execute(pool)
+            .await
+            .expect("Failed to create tokens table");
+
+        sqlx::query(include_str!("../migrations/20260110000018_block_usage.sql"))
+            .execute(pool)
+            .await
+            .expect("Failed to add block usage columns");
+
+        sqlx::query(include_str!(
+            "../migrations/20260110000020_block_reactions.sql"
+        ))
+        .execute(pool)
+        .await
+        .expect("Failed to create block reactions table");
+
+        sqlx::query(include_str!(
+            "../migrations/20260110000021_journal_sessions.sql"
+        ))
+        .execute(pool)
+        .await
+        .expect("Failed to create journal sessions table");
+
+        sqlx::query(include_str!(
+            "../migrations/20260110000023_journal_roles.sql"
+        ))
+        .execute(pool)
+        .await
+        .expect("Failed to create journal roles table");
+    }
+
+    #[tokio::test]
+    async fn test_split_pool_store() {
+        use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+
+        let path = std::env::temp_dir().join(format!("outer-split-{}.db", Uuid::new_v4()));
+
+        let write_pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(
+                SqliteConnectOptions::new()
+                    .filename(&path)
+                    .create_if_missing(true)
+                    .journal_mode(SqliteJournalMode::Wal),
+            )
+            .await
+            .expect("Failed to open write pool");
+        create_tables(&write_pool).await;
+
+        let read_pool = SqlitePoolOptions::new()
+            .max_connections(4)
+            .connect_with(SqliteConnectOptions::new().filename(&path).read_only(true))
+            .await
+            .expect("Failed to open read pool");
+
+        let store = Store::with_pools(read_pool, write_pool);
+
+        // Writes land and are visible to reads
+        let journal = store
+            .create_journal(Some("Split".to_string()))
+            .await
+            .unwrap();
+        let block = store
+            .create_block(journal.id, BlockType::User, "Hello")
+            .await
+            .unwrap();
+        store
+            .update_block_content(block.id, "Hello, world", block.version)
+            .await
+            .unwrap();
+        assert_eq!(store.get_journal(journal.id).await.unwrap().title, "Split");
+        assert_eq!(
+            store.get_block(block.id).await.unwrap().content,
+            "Hello, world"
+        );
+
+        // Hold the only write connection in an open transaction
+        let mut tx = store.write_pool.begin().await.unwrap();
+        sqlx::query("UPDATE blocks SET content = ? WHERE id = ?")
+            .bind("uncommitted")
+            .bind(block.id.to_string())
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+
+        // Reads proceed against the last committed state
+        let (fetched, blocks) = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            store.get_journal_with_blocks(journal.id),
+        )
+        .await
+        .expect("Read blocked behind write")
+        .unwrap();
+        assert_eq!(fetched.id, journal.id);
+        assert_eq!(blocks[0].content, "Hello, world");
+        let journals =
+            tokio::time::timeout(std::time::Duration::from_secs(2), store.list_journals())
+                .await
+                .expect("Read blocked behind write")
+                .unwrap();
+        assert_eq!(journals.len(), 1);
+
+        tx.commit().await.unwrap();
+        assert_eq!(
+            store.get_block(block.id).await.unwrap().content,
+            "uncommitted"
+        );
+
+        // Read-only pool rejects writes
+        assert!(sqlx::query("DELETE FROM blocks")
+            .execute(&store.read_pool)
+            .await
+            .is_err());
+
+        drop(store);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_journal_with_title() {
+        let store = setup_test_db().await;
+        let journal = store
+            .create_journal(Some("My Journal".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(journal.title, "My Journal");
+    }
+
+    #[tokio::test]
+    async fn test_create_journal_without_title() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        assert_eq!(journal.title, "Untitled");
+    }
+
+    #[tokio::test]
+    async fn test_get_journal() {
+        let store = setup_test_db().await;
+        let created = store
+            .create_journal(Some("Test".to_string()))
+            .await
+            .unwrap();
+        let fetched = store.get_journal(created.id).await.unwrap();
+        assert_eq!(fetched.id, created.id);
+        assert_eq!(fetched.title, "Test");
+    }
+
+    #[tokio::test]
+    async fn test_get_journal_not_found() {
+        let store = setup_test_db().await;
+        let result = store.get_journal(Uuid::new_v4()).await;
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), AppError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_get_journal_with_blocks() {
+        let store = setup_test_db().await;
+        let journal = store
+            .create_journal(Some("Snapshot".to_string()))
+            .await
+            .unwrap();
+        let first = store
+            .create_block(journal.id, BlockType::User, "Question")
+            .await
+            .unwrap();
+        let second = store
+            .create_block(journal.id, BlockType::Assistant, "Answer")
+            .await
+            .unwrap();
+
+        // Blocks from other journals must not leak in
+        let other = store.create_journal(None).await.unwrap();
+        store
+            .create_block(other.id, BlockType::User, "Elsewhere")
+            .await
+            .unwrap();
+
+        let (fetched, blocks) = store.get_journal_with_blocks(journal.id).await.unwrap();
+        assert_eq!(fetched.id, journal.id);
+        assert_eq!(fetched.title, "Snapshot");
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].id, first.id);
+        assert_eq!(blocks[1].id, second.id);
+        assert!(blocks.iter().all(|b| b.journal_id == journal.id));
+        // The journal timestamp reflects the last block written
+        assert!(fetched.updated_at >= blocks[1].created_at);
+    }
+
+    #[tokio::test]
+    async fn test_get_journal_with_blocks_not_found() {
+        let store = setup_test_db().await;
+        let result = store.get_journal_with_blocks(Uuid::new_v4()).await;
+        assert!(matches!(result.unwrap_err(), AppError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_block_row_skipped_in_lenient_mode() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let first = store
+            .create_block(journal.id, BlockType::User, "Question")
+            .await
+            .unwrap();
+        let second = store
+            .create_block(journal.id, BlockType::Assistant, "Answer")
+            .await
+            .unwrap();
+
+        // Valid as far as the schema goes, but the ID isn't a UUID
+        let bad_id = "block-from-an-old-import";
+        sqlx::query(
+            r#"
+            INSERT INTO blocks (id, journal_id, block_type, content, status, created_at, updated_at)
+            VALUES (?, ?, 'user', 'Corrupt', 'complete', ?, ?)
+            "#,
+        )
+        .bind(bad_id)
+        .bind(journal.id.to_string())
+        .bind(Utc::now())
+        .bind(Utc::now())
+        .execute(&store.write_pool)
+        .await
+        .unwrap();
+
+        let loaded = store
+            .get_blocks_for_journal_lenient(journal.id, BlockOrder::Chronological)
+            .await
+            .unwrap();
+        assert_eq!(loaded.skipped, 1);
+        let ids: Vec<Uuid> = loaded.blocks.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![first.id, second.id]);
+
+        let (_, loaded) = store
+            .get_journal_with_blocks_lenient(journal.id)
+            .await
+            .unwrap();
+        assert_eq!(loaded.blocks.len(), 2);
+        assert_eq!(loaded.skipped, 1);
+
+        // Strict loads still fail, but name the offending block
+        let err = store.get_blocks_for_journal(journal.id).await.unwrap_err();
+        assert!(matches!(err, AppError::Internal(_)));
+        assert!(err.to_string().contains(bad_id));
+    }
+
+    #[tokio::test]
+    async fn test_diff_blocks() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let prompt = store
+            .create_block(journal.id, BlockType::User, "Explain")
+            .await
+            .unwrap();
+        let original = store
+            .create_block_with_lineage(
+                journal.id,
+                BlockType::Assistant,
+                "Intro\nOld detail\nOutro",
+                Some(prompt.id),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let fork = store.fork_block(prompt.id).await.unwrap();
+        let forked_answer = store
+            .create_block_with_lineage(
+                journal.id,
+                BlockType::Assistant,
+                "Intro\nNew detail\nOutro",
+                Some(fork.id),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let diff = store
+            .diff_blocks(original.id, forked_answer.id)
+            .await
+            .unwrap();
+        assert!(diff.warning.is_none());
+        let ops: Vec<(DiffOp, Vec<String>)> =
+            diff.hunks.into_iter().map(|h| (h.op, h.lines)).collect();
+        assert_eq!(
+            ops,
+            vec![
+                (DiffOp::Equal, vec!["Intro".to_string()]),
+                (DiffOp::Delete, vec!["Old detail".to_string()]),
+                (DiffOp::Insert, vec!["New detail".to_string()]),
+                (DiffOp::Equal, vec!["Outro".to_string()]),
+            ]
+        );
+
+        // Unrelated blocks still diff, with a warning
+        let stray = store
+            .create_block(journal.id, BlockType::User, "Intro")
+            .await
+            .unwrap();
+        let diff = store.diff_blocks(original.id, stray.id).await.unwrap();
+        assert!(diff.warning.unwrap().contains("do not share lineage"));
+        assert_eq!(diff.hunks[0].op, DiffOp::Equal);
+
+        let missing = store.diff_blocks(original.id, Uuid::new_v4()).await;
+        assert!(matches!(missing.unwrap_err(), AppError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_begin_submit_creates_both_blocks() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+
+        let (user, assistant) = store.begin_submit(journal.id, "Hello").await.unwrap();
+        assert_eq!(user.block_type, BlockType::User);
+        assert_eq!(user.status, BlockStatus::Complete);
+        assert_eq!(assistant.block_type, BlockType::Assistant);
+        assert_eq!(assistant.status, BlockStatus::Pending);
+
+        let blocks = store.get_blocks_for_journal(journal.id).await.unwrap();
+        let ids: Vec<Uuid> = blocks.iter().map(|b| b.id).collect();
+        assert_eq!(ids, [user.id, assistant.id]);
+        assert_eq!(blocks[0].content, "Hello");
+        assert_eq!(blocks[1].content, "");
+    }
+
+    #[tokio::test]
+    async fn test_begin_submit_rolls_back_on_failure() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+
+        // Make the second insert fail after the user block is already written
+        sqlx::query(
+            r#"
+            CREATE TRIGGER refuse_assistant BEFORE INSERT ON blocks
+            WHEN NEW.block_type = 'assistant'
+            BEGIN SELECT RAISE(ABORT, 'no assistants'); END
+            "#,
+        )
+        .execute(&store.write_pool)
+        .await
+        .unwrap();
+
+        assert!(store.begin_submit(journal.id, "Hello").await.is_err());
+        assert!(store
+            .get_blocks_for_journal(journal.id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_batch_create_blocks() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+
+        let question = NewBlock::new(BlockType::User, "Question");
+        let answer = NewBlock::new(BlockType::Assistant, "Answer").with_parent(question.id);
+        let retry = NewBlock::new(BlockType::User, "Question")
+            .with_parent(answer.id)
+            .forked_from(question.id);
+        let failed = NewBlock::new(BlockType::Assistant, "Timed out")
+            .with_parent(retry.id)
+            .with_status(BlockStatus::Error);
+        let ids = [question.id, answer.id, retry.id, failed.id];
+
+        let created = store
+            .batch_create_blocks(journal.id, vec![question, answer, retry, failed])
+            .await
+            .unwrap();
+        assert_eq!(created.len(), 4);
+
+        let blocks = store.get_blocks_for_journal(journal.id).await.unwrap();
+        let stored_ids: Vec<Uuid> = blocks.iter().map(|b| b.id).collect();
+        assert_eq!(stored_ids, ids);
+        assert_eq!(blocks[1].parent_id, Some(ids[0]));
+        assert_eq!(blocks[2].parent_id, Some(ids[1]));
+        assert_eq!(blocks[2].forked_from_id, Some(ids[0]));
+        assert_eq!(blocks[0].status, BlockStatus::Complete);
+        assert_eq!(blocks[3].status, BlockStatus::Error);
+        assert_eq!(blocks[3].content, "Timed out");
+
+        // The journal timestamp is bumped once, to the last block's time
+        let journal = store.get_journal(journal.id).await.unwrap();
+        assert_eq!(journal.updated_at, blocks[3].created_at);
+
+        // Nothing is written for a missing journal
+        let missing = Uuid::new_v4();
+        let result = store
+            .batch_create_blocks(missing, vec![NewBlock::new(BlockType::User, "Orphan")])
+            .await;
+        assert!(matches!(result.unwrap_err(), AppError::NotFound(_)));
+        assert!(store
+            .get_blocks_for_journal(missing)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_journal_title() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+
+        let updated = store
+            .update_journal_title(journal.id, "Greeting the world")
+            .await
+            .unwrap();
+        assert_eq!(updated.title, "Greeting the world");
+        assert!(updated.updated_at >= journal.updated_at);
+
+        let fetched = store.get_journal(journal.id).await.unwrap();
+        assert_eq!(fetched.title, "Greeting the world");
+    }
+
+    #[tokio::test]
+    async fn test_update_journal_title_not_found() {
+        let store = setup_test_db().await;
+        let result = store.update_journal_title(Uuid::new_v4(), "Nope").await;
+        assert!(matches!(result.unwrap_err(), AppError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_autotitle_only_replaces_default_titles() {
+        let store = setup_test_db().await;
+        let untitled = store.create_journal(None).await.unwrap();
+        let named = store
+            .create_journal(Some("Release planning".to_string()))
+            .await
+            .unwrap();
+
+        let content =
+            "How do I migrate the billing service from the legacy queue to the new event bus?";
+        let retitled = store
+            .maybe_autotitle_journal(untitled.id, content)
+            .await
+            .unwrap()
+            .expect("untitled journal should be retitled");
+        assert_eq!(
+            retitled.title,
+            "How do I migrate the billing service from the legacy queue…"
+        );
+
+        // Once named, later messages leave the title alone
+        let again = store
+            .maybe_autotitle_journal(untitled.id, "Something else")
+            .await
+            .unwrap();
+        assert!(again.is_none());
+
+        let unchanged = store
+            .maybe_autotitle_journal(named.id, content)
+            .await
+            .unwrap();
+        assert!(unchanged.is_none());
+        let fetched = store.get_journal(named.id).await.unwrap();
+        assert_eq!(fetched.title, "Release planning");
+    }
+
+    #[tokio::test]
+    async fn test_generated_title_replaces_only_placeholders() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let prompt = "Explain borrowing in Rust";
+        store
+            .maybe_autotitle_journal(journal.id, prompt)
+            .await
+            .unwrap();
+
+        // The title taken from the prompt is still a placeholder
+        let retitled = store
+            .replace_placeholder_title(journal.id, prompt, "Rust Borrowing Basics")
+            .await
+            .unwrap()
+            .expect("prompt title should be replaced");
+        assert_eq!(retitled.title, "Rust Borrowing Basics");
+
+        // A title someone chose is kept
+        store
+            .rename_journal(journal.id, "Ownership notes")
+            .await
+            .unwrap();
+        let kept = store
+            .replace_placeholder_title(journal.id, prompt, "Something generated")
+            .await
+            .unwrap();
+        assert!(kept.is_none());
+        let fetched = store.get_journal(journal.id).await.unwrap();
+        assert_eq!(fetched.title, "Ownership notes");
+    }
+
+    #[test]
+    fn test_title_from_content() {
+        assert_eq!(
+            title_from_content("  Hello\n  world "),
+            Some("Hello world".to_string())
+        );
+        assert_eq!(title_from_content(" \n\t"), None);
+
+        let long_word = "x".repeat(AUTOTITLE_MAX_CHARS + 5);
+        let title = title_from_content(&long_word).unwrap();
+        assert_eq!(title.chars().count(), AUTOTITLE_MAX_CHARS + 1);
+    }
+
+    #[tokio::test]
+    async fn test_edit_user_block_keeps_history() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let block = store
+            .create_block(journal.id, BlockType::User, "Helo world")
+            .await
+            .unwrap();
+
+        let edited = store
+            .edit_user_block(block.id, "Hello world")
+            .await
+            .unwrap();
+        assert_eq!(edited.content, "Hello world");
+        assert_eq!(edited.version, block.version + 1);
+
+        let history = store.get_block_history(block.id).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "Helo world");
+        assert_eq!(history[0].version, block.version);
+
+        // Saving the same text again isn't an edit
+        let unchanged = store
+            .edit_user_block(block.id, "Hello world")
+            .await
+            .unwrap();
+        assert_eq!(unchanged.version, edited.version);
+        assert_eq!(store.get_block_history(block.id).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_edit_rejects_assistant_blocks() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let block = store
+            .create_block(journal.id, BlockType::Assistant, "Answer")
+            .await
+            .unwrap();
+
+        let result = store.edit_user_block(block.id, "Better answer").await;
+        assert!(matches!(result.unwrap_err(), AppError::BadRequest(_)));
+
+        let fetched = store.get_block(block.id).await.unwrap();
+        assert_eq!(fetched.content, "Answer");
+        assert!(store.get_block_history(block.id).await.unwrap().is_empty());
+
+        assert!(matches!(
+            store
+                .edit_user_block(Uuid::new_v4(), "Hi")
+                .await
+                .unwrap_err(),
+            AppError::NotFound(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_journal_summary_counts_blocks_by_type() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+
+        let empty = store.journal_summary(journal.id).await.unwrap();
+        assert_eq!(empty.block_count, 0);
+        assert_eq!(empty.last_activity, journal.updated_at);
+
+        for (block_type, content) in [
+            (BlockType::System, "Be brief"),
+            (BlockType::User, "One"),
+            (BlockType::Assistant, "1"),
+            (BlockType::User, "Two"),
+            (BlockType::Assistant, "2"),
+            (BlockType::User, "Three"),
+        ] {
+            store
+                .create_block(journal.id, block_type, content)
+                .await
+                .unwrap();
+        }
+        let last = store
+            .get_blocks_for_journal(journal.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|b| b.updated_at)
+            .max()
+            .unwrap();
+
+        let summary = store.journal_summary(journal.id).await.unwrap();
+        assert_eq!(summary.journal_id, journal.id);
+        assert_eq!(summary.block_count, 6);
+        assert_eq!(summary.user_block_count, 3);
+        assert_eq!(summary.assistant_block_count, 2);
+        assert_eq!(summary.last_activity, last);
+
+        assert!(matches!(
+            store.journal_summary(Uuid::new_v4()).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_block_usage_is_null_until_reported() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let block = store
+            .create_block(journal.id, BlockType::Assistant, "Answer")
+            .await
+            .unwrap();
+        assert_eq!(store.get_block_usage(block.id).await.unwrap(), None);
+
+        let usage = BlockUsage {
+            prompt_tokens: 100,
+            completion_tokens: 20,
+        };
+        store.set_block_usage(block.id, usage).await.unwrap();
+        assert_eq!(store.get_block_usage(block.id).await.unwrap(), Some(usage));
+
+        assert!(matches!(
+            store.set_block_usage(Uuid::new_v4(), usage).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_toggle_reaction() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let block = store
+            .create_block(journal.id, BlockType::Assistant, "Answer")
+            .await
+            .unwrap();
+        let alice = Uuid::new_v4();
+        let bob = Uuid::new_v4();
+
+        let reaction = store.toggle_reaction(block.id, alice, "👍").await.unwrap();
+        assert_eq!(reaction.count, 1);
+        let reaction = store.toggle_reaction(block.id, bob, "👍").await.unwrap();
+        assert_eq!(reaction.count, 2);
+        assert_eq!(reaction.reactors, vec![alice, bob]);
+        store.toggle_reaction(block.id, bob, "⭐").await.unwrap();
+
+        // Reacting again takes it back
+        let reaction = store.toggle_reaction(block.id, alice, "👍").await.unwrap();
+        assert_eq!(reaction.count, 1);
+        assert_eq!(reaction.reactors, vec![bob]);
+
+        let reactions = store.get_reactions(block.id).await.unwrap();
+        let counts: Vec<(&str, usize)> = reactions
+            .iter()
+            .map(|r| (r.emoji.as_str(), r.count))
+            .collect();
+        assert_eq!(counts, vec![("👍", 1), ("⭐", 1)]);
+
+        assert!(matches!(
+            store.toggle_reaction(block.id, alice, " ").await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            store.toggle_reaction(Uuid::new_v4(), alice, "👍").await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_first_journal_session_is_kept() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        assert_eq!(store.get_journal_session(journal.id).await.unwrap(), None);
+
+        assert_eq!(
+            store
+                .get_or_set_journal_session(journal.id, "sess_a")
+                .await
+                .unwrap(),
+            "sess_a"
+        );
+        assert_eq!(
+            store
+                .get_or_set_journal_session(journal.id, "sess_b")
+                .await
+                .unwrap(),
+            "sess_a"
+        );
+        assert_eq!(
+            store
+                .get_journal_session(journal.id)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("sess_a")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fail_streaming_blocks() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let streaming = store
+            .create_block(journal.id, BlockType::Assistant, "Half an ans")
+            .await
+            .unwrap();
+        store
+            .update_block_status(streaming.id, BlockStatus::Streaming)
+            .await
+            .unwrap();
+        let done = store
+            .create_block(journal.id, BlockType::Assistant, "Whole answer")
+            .await
+            .unwrap();
+        store
+            .update_block_status(done.id, BlockStatus::Complete)
+            .await
+            .unwrap();
+
+        assert_eq!(store.fail_streaming_blocks().await.unwrap(), 1);
+        assert_eq!(
+            store.get_block(streaming.id).await.unwrap().status,
+            BlockStatus::Error
+        );
+        assert_eq!(
+            store.get_block(done.id).await.unwrap().status,
+            BlockStatus::Complete
+        );
+        assert_eq!(store.fail_streaming_blocks().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_token_identity() {
+        let store = setup_test_db().await;
+        let token = store.create_token("alice").await.unwrap();
+
+        assert_eq!(
+            store.token_identity(&token).await.unwrap().as_deref(),
+            Some("alice")
+        );
+        assert_eq!(store.token_identity("not-a-token").await.unwrap(), None);
+
+        // The token itself is never written down
+        let stored: String = sqlx::query_scalar("SELECT token_hash FROM tokens")
+            .fetch_one(&store.read_pool)
+            .await
+            .unwrap();
+        assert_ne!(stored, token);
+        assert_eq!(store.token_identity(&stored).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_journal_roles_are_claimed_once() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+
+        let claim = |member: &'static str, role| {
+            let store = &store;
+            async move {
+                store
+                    .claim_journal_role(journal.id, member, role)
+                    .await
+                    .unwrap()
+            }
+        };
+        assert_eq!(claim("alice", JournalRole::Owner).await, JournalRole::Owner);
+        // Someone arriving later can't take ownership, and nobody changes
+        // their own role by claiming again
+        assert_eq!(claim("bob", JournalRole::Owner).await, JournalRole::Member);
+        assert_eq!(claim("alice", JournalRole::Guest).await, JournalRole::Owner);
+
+        store
+            .set_journal_role(journal.id, "bob", JournalRole::Guest)
+            .await
+            .unwrap();
+        assert_eq!(claim("bob", JournalRole::Member).await, JournalRole::Guest);
+    }
+
+    #[tokio::test]
+    async fn test_rename_journal() {
+        let store = setup_test_db().await;
+        let journal = store
+            .create_journal(Some("CLI Session".to_string()))
+            .await
+            .unwrap();
+
+        let renamed = store
+            .rename_journal(journal.id, "  Release planning ")
+            .await
+            .unwrap();
+        assert_eq!(renamed.title, "Release planning");
+        assert!(renamed.updated_at >= journal.updated_at);
+
+        let result = store.rename_journal(journal.id, " \t ").await;
+        assert!(matches!(result.unwrap_err(), AppError::BadRequest(_)));
+        let fetched = store.get_journal(journal.id).await.unwrap();
+        assert_eq!(fetched.title, "Release planning");
+    }
+
+    #[tokio::test]
+    async fn test_list_journals() {
+        let store = setup_test_db().await;
+        store
+            .create_journal(Some("First".to_string()))
+            .await
+            .unwrap();
+        store
+            .create_journal(Some("Second".to_string()))
+            .await
+            .unwrap();
+
+        let journals = store.list_journals().await.unwrap();
+        assert_eq!(journals.len(), 2);
     }
 
     #[tokio::test]
-    async fn test_create_journal_with_title() {
+    async fn test_list_journals_empty() {
         let store = setup_test_db().await;
-        let journal = store
-            .create_journal(Some("My Journal".to_string()))
-            .await
-            .unwrap();
-        assert_eq!(journal.title, "My Journal");
+        let journals = store.list_journals().await.unwrap();
+        assert!(journals.is_empty());
     }
 
     #[tokio::test]
-    async fn test_create_journal_without_title() {
+    async fn test_list_journals_matching_title() {
         let store = setup_test_db().await;
-        let journal = store.create_journal(None).await.unwrap();
-        assert_eq!(journal.title, "Untitled");
+        for title in [
+            "Release Planning",
+            "Weekly notes",
+            "Planning poker",
+            "50% done",
+        ] {
+            store.create_journal(Some(title.to_string())).await.unwrap();
+        }
+
+        let titles = |journals: Vec<Journal>| -> Vec<String> {
+            journals.into_iter().map(|j| j.title).collect()
+        };
+
+        let found = store
+            .list_journals_filtered(false, false, Some("PLANNING"), JournalSort::Title)
+            .await
+            .unwrap();
+        assert_eq!(titles(found), ["Planning poker", "Release Planning"]);
+
+        // Wildcards in the query are taken literally
+        let found = store
+            .list_journals_filtered(false, false, Some("%"), JournalSort::Title)
+            .await
+            .unwrap();
+        assert_eq!(titles(found), ["50% done"]);
+
+        let found = store
+            .list_journals_filtered(false, false, Some("  "), JournalSort::Title)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 4);
     }
 
     #[tokio::test]
-    async fn test_get_journal() {
+    async fn test_list_journals_sorted_by_title() {
         let store = setup_test_db().await;
-        let created = store
-            .create_journal(Some("Test".to_string()))
+        for title in ["beta", "Gamma", "alpha"] {
+            store.create_journal(Some(title.to_string())).await.unwrap();
+        }
+
+        let titles: Vec<_> = store
+            .list_journals_filtered(false, false, None, JournalSort::Title)
             .await
-            .unwrap();
-        let fetched = store.get_journal(created.id).await.unwrap();
-        assert_eq!(fetched.id, created.id);
-        assert_eq!(fetched.title, "Test");
+            .unwrap()
+            .into_iter()
+            .map(|j| j.title)
+            .collect();
+        assert_eq!(titles, ["alpha", "beta", "Gamma"]);
     }
 
     #[tokio::test]
-    async fn test_get_journal_not_found() {
+    async fn test_soft_delete_journal() {
         let store = setup_test_db().await;
-        let result = store.get_journal(Uuid::new_v4()).await;
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), AppError::NotFound(_)));
+        let kept = store
+            .create_journal(Some("Kept".to_string()))
+            .await
+            .unwrap();
+        let deleted = store
+            .create_journal(Some("Deleted".to_string()))
+            .await
+            .unwrap();
+
+        store.soft_delete_journal(deleted.id).await.unwrap();
+
+        let journals = store.list_journals().await.unwrap();
+        assert_eq!(journals.len(), 1);
+        assert_eq!(journals[0].id, kept.id);
+        assert!(matches!(
+            store.get_journal(deleted.id).await.unwrap_err(),
+            AppError::NotFound(_)
+        ));
+
+        let journals = store.list_journals_with_deleted(true).await.unwrap();
+        assert_eq!(journals.len(), 2);
+        let restored = journals.iter().find(|j| j.id == deleted.id).unwrap();
+        assert!(restored.deleted_at.is_some());
+
+        // Deleting twice is reported as missing
+        assert!(matches!(
+            store.soft_delete_journal(deleted.id).await.unwrap_err(),
+            AppError::NotFound(_)
+        ));
     }
 
     #[tokio::test]
-    async fn test_list_journals() {
+    async fn test_archived_journal_hidden_from_list() {
         let store = setup_test_db().await;
-        store
-            .create_journal(Some("First".to_string()))
+        let active = store
+            .create_journal(Some("Active".to_string()))
+            .await
+            .unwrap();
+        let done = store
+            .create_journal(Some("Done".to_string()))
             .await
             .unwrap();
         store
-            .create_journal(Some("Second".to_string()))
+            .create_block(done.id, BlockType::User, "Wrap up")
             .await
             .unwrap();
 
+        let archived = store.set_journal_archived(done.id, true).await.unwrap();
+        assert!(archived.archived_at.is_some());
+
         let journals = store.list_journals().await.unwrap();
+        assert_eq!(journals.len(), 1);
+        assert_eq!(journals[0].id, active.id);
+        let journals = store
+            .list_journals_filtered(false, true, None, JournalSort::Updated)
+            .await
+            .unwrap();
         assert_eq!(journals.len(), 2);
+
+        // Still readable while archived
+        let (journal, blocks) = store.get_journal_with_blocks(done.id).await.unwrap();
+        assert!(journal.archived_at.is_some());
+        assert_eq!(blocks.len(), 1);
+
+        let unarchived = store.set_journal_archived(done.id, false).await.unwrap();
+        assert!(unarchived.archived_at.is_none());
+        assert_eq!(store.list_journals().await.unwrap().len(), 2);
+
+        assert!(matches!(
+            store
+                .set_journal_archived(Uuid::new_v4(), true)
+                .await
+                .unwrap_err(),
+            AppError::NotFound(_)
+        ));
     }
 
     #[tokio::test]
-    async fn test_list_journals_empty() {
+    async fn test_stats() {
         let store = setup_test_db().await;
-        let journals = store.list_journals().await.unwrap();
-        assert!(journals.is_empty());
+
+        let empty = store.stats().await.unwrap();
+        assert_eq!(empty.journal_count, 0);
+        assert_eq!(empty.block_count, 0);
+        assert_eq!(empty.avg_blocks_per_journal, 0.0);
+        assert!(empty.largest_journal.is_none());
+        assert_eq!(empty.total_content_bytes, 0);
+
+        let small = store.create_journal(None).await.unwrap();
+        let big = store.create_journal(None).await.unwrap();
+        store.create_journal(None).await.unwrap();
+
+        store
+            .create_block(small.id, BlockType::User, "hello")
+            .await
+            .unwrap();
+        for content in ["one", "two", "héllo"] {
+            store
+                .create_block(big.id, BlockType::User, content)
+                .await
+                .unwrap();
+        }
+
+        let stats = store.stats().await.unwrap();
+        assert_eq!(stats.journal_count, 3);
+        assert_eq!(stats.block_count, 4);
+        assert!((stats.avg_blocks_per_journal - 4.0 / 3.0).abs() < f64::EPSILON);
+        let largest = stats.largest_journal.unwrap();
+        assert_eq!(largest.journal_id, big.id);
+        assert_eq!(largest.block_count, 3);
+        // "é" is two bytes
+        assert_eq!(stats.total_content_bytes, 5 + 3 + 3 + 6);
     }
 
     #[tokio::test]
@@ -510,6 +2914,23 @@ mod tests {
         assert_eq!(block.block_type, BlockType::Assistant);
     }
 
+    #[tokio::test]
+    async fn test_create_system_block() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let block = store
+            .create_block(journal.id, BlockType::System, "Answer in French")
+            .await
+            .unwrap();
+        assert_eq!(block.block_type, BlockType::System);
+        // Like user blocks, system blocks need no response to be complete
+        assert_eq!(block.status, BlockStatus::Complete);
+
+        let fetched = store.get_block(block.id).await.unwrap();
+        assert_eq!(fetched.block_type, BlockType::System);
+        assert!(store.rerun_block(block.id).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_get_block() {
         let store = setup_test_db().await;
@@ -558,6 +2979,176 @@ mod tests {
         assert!(blocks.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_get_blocks_page() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        for i in 0..5 {
+            store
+                .create_block(journal.id, BlockType::User, &format!("Message {}", i))
+                .await
+                .unwrap();
+        }
+
+        let first = store.get_blocks_page(journal.id, None, 2).await.unwrap();
+        assert!(first.has_more);
+        let contents: Vec<_> = first.blocks.iter().map(|b| b.content.as_str()).collect();
+        assert_eq!(contents, vec!["Message 4", "Message 3"]);
+
+        // A block arriving mid-scroll doesn't disturb the next page
+        store
+            .create_block(journal.id, BlockType::User, "Late")
+            .await
+            .unwrap();
+
+        let cursor = first.blocks.last().map(|b| b.created_at);
+        let second = store.get_blocks_page(journal.id, cursor, 2).await.unwrap();
+        assert!(second.has_more);
+        let contents: Vec<_> = second.blocks.iter().map(|b| b.content.as_str()).collect();
+        assert_eq!(contents, vec!["Message 2", "Message 1"]);
+
+        let cursor = second.blocks.last().map(|b| b.created_at);
+        let last = store.get_blocks_page(journal.id, cursor, 2).await.unwrap();
+        assert!(!last.has_more);
+        assert_eq!(last.blocks.len(), 1);
+        assert_eq!(last.blocks[0].content, "Message 0");
+    }
+
+    #[tokio::test]
+    async fn test_search_blocks() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let other = store.create_journal(None).await.unwrap();
+        store
+            .create_block(journal.id, BlockType::User, "How do I parse JSON?")
+            .await
+            .unwrap();
+        let target = store
+            .create_block(journal.id, BlockType::Assistant, "Use serde")
+            .await
+            .unwrap();
+        store
+            .create_block(other.id, BlockType::User, "Unrelated question")
+            .await
+            .unwrap();
+
+        // Edits are indexed as well as inserts
+        store
+            .update_block_content(
+                target.id,
+                "Use serde with the zanzibar feature",
+                target.version,
+            )
+            .await
+            .unwrap();
+
+        let found = store.search_blocks("zanzibar", None, 10).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, target.id);
+
+        assert!(store
+            .search_blocks("zanzibar", Some(other.id), 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            store.search_blocks("serde", None, 10).await.unwrap().len(),
+            1
+        );
+        assert!(store.search_blocks("  ", None, 10).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reorder_block_between_neighbours() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let a = store
+            .create_block(journal.id, BlockType::User, "A")
+            .await
+            .unwrap();
+        let b = store
+            .create_block(journal.id, BlockType::User, "B")
+            .await
+            .unwrap();
+        let c = store
+            .create_block(journal.id, BlockType::User, "C")
+            .await
+            .unwrap();
+
+        store.reorder_block(a.id, 1.0).await.unwrap();
+        store.reorder_block(b.id, 2.0).await.unwrap();
+
+        // Move C between A and B without touching either
+        let moved = store
+            .reorder_block(c.id, position_between(Some(1.0), Some(2.0)))
+            .await
+            .unwrap();
+        assert_eq!(moved.position, Some(1.5));
+
+        let blocks = store
+            .get_blocks_for_journal_ordered(journal.id, BlockOrder::ByPosition)
+            .await
+            .unwrap();
+        let contents: Vec<&str> = blocks.iter().map(|b| b.content.as_str()).collect();
+        assert_eq!(contents, vec!["A", "C", "B"]);
+        assert_eq!(blocks[0].position, Some(1.0));
+        assert_eq!(blocks[2].position, Some(2.0));
+
+        // Chronological order is unaffected
+        let blocks = store.get_blocks_for_journal(journal.id).await.unwrap();
+        let contents: Vec<&str> = blocks.iter().map(|b| b.content.as_str()).collect();
+        assert_eq!(contents, vec!["A", "B", "C"]);
+    }
+
+    #[tokio::test]
+    async fn test_blocks_by_position_with_unpositioned() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let mut ids = Vec::new();
+        for content in ["first", "second", "third", "fourth"] {
+            let block = store
+                .create_block(journal.id, BlockType::User, content)
+                .await
+                .unwrap();
+            ids.push(block.id);
+        }
+
+        // Pin the last block to the top; the rest keep their creation order
+        store.reorder_block(ids[3], -1.0).await.unwrap();
+        store.reorder_block(ids[1], 5.0).await.unwrap();
+
+        let blocks = store
+            .get_blocks_for_journal_ordered(journal.id, BlockOrder::ByPosition)
+            .await
+            .unwrap();
+        let contents: Vec<&str> = blocks.iter().map(|b| b.content.as_str()).collect();
+        assert_eq!(contents, vec!["fourth", "second", "first", "third"]);
+        assert_eq!(blocks[2].position, None);
+    }
+
+    #[tokio::test]
+    async fn test_reorder_block_errors() {
+        let store = setup_test_db().await;
+        let result = store.reorder_block(Uuid::new_v4(), 1.0).await;
+        assert!(matches!(result.unwrap_err(), AppError::NotFound(_)));
+
+        let journal = store.create_journal(None).await.unwrap();
+        let block = store
+            .create_block(journal.id, BlockType::User, "A")
+            .await
+            .unwrap();
+        let result = store.reorder_block(block.id, f64::NAN).await;
+        assert!(matches!(result.unwrap_err(), AppError::BadRequest(_)));
+    }
+
+    #[test]
+    fn test_position_between() {
+        assert_eq!(position_between(Some(1.0), Some(2.0)), 1.5);
+        assert_eq!(position_between(Some(1.0), None), 2.0);
+        assert_eq!(position_between(None, Some(1.0)), 0.0);
+        assert_eq!(position_between(None, None), 0.0);
+    }
+
     #[tokio::test]
     async fn test_update_block_content() {
         let store = setup_test_db().await;
@@ -567,13 +3158,44 @@ mod tests {
             .await
             .unwrap();
 
-        store
-            .update_block_content(block.id, "Updated")
+        let version = store
+            .update_block_content(block.id, "Updated", block.version)
             .await
             .unwrap();
+        assert_eq!(version, block.version + 1);
 
         let fetched = store.get_block(block.id).await.unwrap();
         assert_eq!(fetched.content, "Updated");
+        assert_eq!(fetched.version, version);
+    }
+
+    #[tokio::test]
+    async fn test_update_block_content_stale_version_conflicts() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let block = store
+            .create_block(journal.id, BlockType::Assistant, "")
+            .await
+            .unwrap();
+
+        // Two writers read the same version; the first one wins
+        store
+            .update_block_content(block.id, "First writer", block.version)
+            .await
+            .unwrap();
+        let result = store
+            .update_block_content(block.id, "Second writer", block.version)
+            .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        let fetched = store.get_block(block.id).await.unwrap();
+        assert_eq!(fetched.content, "First writer");
+
+        // Unknown blocks are still reported as missing
+        let result = store
+            .update_block_content(Uuid::new_v4(), "Nobody", 0)
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
     }
 
     #[tokio::test]
@@ -645,6 +3267,8 @@ mod tests {
             title: "Test".to_string(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            deleted_at: None,
+            archived_at: None,
         };
         let result: Result<Journal> = row.try_into();
         assert!(result.is_err());
@@ -661,6 +3285,8 @@ mod tests {
             status: "pending".to_string(),
             parent_id: None,
             forked_from_id: None,
+            position: None,
+            version: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -678,6 +3304,8 @@ mod tests {
             status: "pending".to_string(),
             parent_id: None,
             forked_from_id: None,
+            position: None,
+            version: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -695,6 +3323,8 @@ mod tests {
             status: "pending".to_string(),
             parent_id: None,
             forked_from_id: None,
+            position: None,
+            version: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -712,6 +3342,8 @@ mod tests {
             status: "invalid".to_string(),
             parent_id: None,
             forked_from_id: None,
+            position: None,
+            version: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -729,6 +3361,8 @@ mod tests {
             status: "pending".to_string(),
             parent_id: Some("not-a-uuid".to_string()),
             forked_from_id: None,
+            position: None,
+            version: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -746,6 +3380,8 @@ mod tests {
             status: "pending".to_string(),
             parent_id: None,
             forked_from_id: Some("not-a-uuid".to_string()),
+            position: None,
+            version: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -824,6 +3460,70 @@ mod tests {
         assert_ne!(forked.id, original.id);
     }
 
+    #[tokio::test]
+    async fn test_clone_journal_remaps_lineage() {
+        let store = setup_test_db().await;
+        let journal = store
+            .create_journal(Some("Explore".to_string()))
+            .await
+            .unwrap();
+
+        let prompt = store
+            .create_block(journal.id, BlockType::User, "Question")
+            .await
+            .unwrap();
+        let answer = store
+            .create_block_with_lineage(
+                journal.id,
+                BlockType::Assistant,
+                "Answer",
+                Some(prompt.id),
+                None,
+            )
+            .await
+            .unwrap();
+        let fork = store.fork_block(prompt.id).await.unwrap();
+        let refork = store.fork_block(fork.id).await.unwrap();
+
+        let clone = store.clone_journal(journal.id, None).await.unwrap();
+        assert_ne!(clone.id, journal.id);
+        assert_eq!(clone.title, "Explore (copy)");
+
+        let originals = store.get_blocks_for_journal(journal.id).await.unwrap();
+        let copies = store.get_blocks_for_journal(clone.id).await.unwrap();
+        assert_eq!(copies.len(), 4);
+
+        // Copies line up with the originals, under new IDs
+        let copy_of: std::collections::HashMap<Uuid, Uuid> = originals
+            .iter()
+            .zip(&copies)
+            .map(|(original, copy)| {
+                assert_eq!(original.content, copy.content);
+                assert_ne!(original.id, copy.id);
+                (original.id, copy.id)
+            })
+            .collect();
+        for (original, copy) in originals.iter().zip(&copies) {
+            assert_eq!(copy.parent_id, original.parent_id.map(|id| copy_of[&id]));
+            assert_eq!(
+                copy.forked_from_id,
+                original.forked_from_id.map(|id| copy_of[&id])
+            );
+        }
+        let copied_refork = &copies[3];
+        assert_eq!(copied_refork.forked_from_id, Some(copy_of[&fork.id]));
+        assert_eq!(copy_of[&refork.id], copied_refork.id);
+        assert_eq!(
+            copies[1].parent_id,
+            Some(copy_of[&answer.parent_id.unwrap()])
+        );
+
+        // The original is untouched
+        let unchanged = store.get_blocks_for_journal(journal.id).await.unwrap();
+        assert_eq!(unchanged.len(), 4);
+        assert_eq!(unchanged[3].forked_from_id, Some(fork.id));
+    }
+
     #[tokio::test]
     async fn test_rerun_user_block() {
         let store = setup_test_db().await;
@@ -944,6 +3644,58 @@ mod tests {
         assert_eq!(children.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_get_branch_excludes_siblings() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+
+        let question = store
+            .create_block(journal.id, BlockType::User, "Question")
+            .await
+            .unwrap();
+        let answer = store
+            .create_block_with_lineage(
+                journal.id,
+                BlockType::Assistant,
+                "Answer",
+                Some(question.id),
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Two follow-ups branch off the same answer
+        let mut leaves = Vec::new();
+        for follow_up in ["Tell me more", "Say it shorter"] {
+            let fork = store.fork_block(answer.id).await.unwrap();
+            store
+                .update_block_content(fork.id, follow_up, fork.version)
+                .await
+                .unwrap();
+            let reply = store
+                .create_block_with_lineage(
+                    journal.id,
+                    BlockType::Assistant,
+                    "Reply",
+                    Some(fork.id),
+                    None,
+                )
+                .await
+                .unwrap();
+            leaves.push((fork.id, reply.id));
+        }
+
+        let (fork_id, leaf_id) = leaves[1];
+        let branch = store.get_branch(leaf_id).await.unwrap();
+        let ids: Vec<_> = branch.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![question.id, answer.id, fork_id, leaf_id]);
+        assert_eq!(branch[2].content, "Say it shorter");
+
+        // A root is a branch of one
+        let root = store.get_branch(question.id).await.unwrap();
+        assert_eq!(root.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_fork_nonexistent_block() {
         let store = setup_test_db().await;
@@ -959,4 +3711,144 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), AppError::NotFound(_)));
     }
+
+    #[tokio::test]
+    async fn test_delete_block_with_children_conflicts() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+
+        let question = store
+            .create_block(journal.id, BlockType::User, "Question")
+            .await
+            .unwrap();
+        store.fork_block(question.id).await.unwrap();
+
+        let result = store.delete_block(question.id, false).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert!(store.get_block(question.id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_block_unlinks_work_items() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+        let block = store
+            .create_block(journal.id, BlockType::User, "Question")
+            .await
+            .unwrap();
+
+        let work_item_id = Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO work_items (id, journal_id, description, block_id, delegator_id, assignee_id) \
+             VALUES (?, ?, 'Answer it', ?, ?, ?)",
+        )
+        .bind(&work_item_id)
+        .bind(journal.id.to_string())
+        .bind(block.id.to_string())
+        .bind(Uuid::new_v4().to_string())
+        .bind(Uuid::new_v4().to_string())
+        .execute(&store.write_pool)
+        .await
+        .unwrap();
+
+        let deleted = store.delete_block(block.id, false).await.unwrap();
+        assert_eq!(deleted, [block.id]);
+
+        let block_id: Option<String> =
+            sqlx::query_scalar("SELECT block_id FROM work_items WHERE id = ?")
+                .bind(&work_item_id)
+                .fetch_one(&store.read_pool)
+                .await
+                .unwrap();
+        assert_eq!(block_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_delete_block_cascades_to_descendants() {
+        let store = setup_test_db().await;
+        let journal = store.create_journal(None).await.unwrap();
+
+        let question = store
+            .create_block(journal.id, BlockType::User, "Question")
+            .await
+            .unwrap();
+        let answer = store
+            .create_block_with_lineage(
+                journal.id,
+                BlockType::Assistant,
+                "Answer",
+                Some(question.id),
+                None,
+            )
+            .await
+            .unwrap();
+        let fork = store.fork_block(answer.id).await.unwrap();
+        let unrelated = store
+            .create_block(journal.id, BlockType::User, "Elsewhere")
+            .await
+            .unwrap();
+
+        let deleted = store.delete_block(answer.id, true).await.unwrap();
+        assert_eq!(deleted[0], answer.id);
+        assert_eq!(deleted.len(), 2);
+        assert!(deleted.contains(&fork.id));
+
+        for id in [answer.id, fork.id] {
+            assert!(matches!(
+                store.get_block(id).await,
+                Err(AppError::NotFound(_))
+            ));
+        }
+        assert!(store.get_block(question.id).await.is_ok());
+        assert!(store.get_block(unrelated.id).await.is_ok());
+
+        // A leaf needs no cascade
+        assert_eq!(
+            store.delete_block(question.id, false).await.unwrap(),
+            vec![question.id]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_recent_activity_attaches_latest_block() {
+        let store = setup_test_db().await;
+
+        let older = store
+            .create_journal(Some("Older".to_string()))
+            .await
+            .unwrap();
+        store
+            .create_block(older.id, BlockType::User, "First question")
+            .await
+            .unwrap();
+        let older_latest = store
+            .create_block(older.id, BlockType::Assistant, "First answer")
+            .await
+            .unwrap();
+
+        let newer = store
+            .create_journal(Some("Newer".to_string()))
+            .await
+            .unwrap();
+        let newer_latest = store
+            .create_block(newer.id, BlockType::User, "Second question")
+            .await
+            .unwrap();
+
+        let empty = store
+            .create_journal(Some("Empty".to_string()))
+            .await
+            .unwrap();
+
+        let activity = store.get_recent_activity(10).await.unwrap();
+        let order: Vec<_> = activity.iter().map(|(j, _)| j.id).collect();
+        assert_eq!(order, vec![empty.id, newer.id, older.id]);
+
+        assert!(activity[0].1.is_none());
+        assert_eq!(activity[1].1.as_ref().unwrap().id, newer_latest.id);
+        assert_eq!(activity[2].1.as_ref().unwrap().id, older_latest.id);
+        assert_eq!(activity[2].1.as_ref().unwrap().content, "First answer");
+
+        assert_eq!(store.get_recent_activity(1).await.unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,179 @@
+//! Registry of in-flight OpenCode streams, keyed by the block being written.
+//!
+//! A streaming loop registers its block and holds the returned
+//! [`ActiveStream`] for as long as it runs. Through the registry, `Cancel`
+//! can trip the stream's token, and a reconnecting client can pick up the
+//! content streamed so far plus every delta after it. Entries remove
+//! themselves when the stream ends.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// A piece of streamed content and where it starts in the block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentDelta {
+    pub delta: String,
+    pub offset: usize,
+}
+
+struct Entry {
+    token: CancellationToken,
+    /// Everything streamed so far
+    content: String,
+    deltas: broadcast::Sender<ContentDelta>,
+}
+
+/// Blocks that are currently streaming
+#[derive(Default)]
+pub struct StreamRegistry {
+    entries: Mutex<HashMap<Uuid, Entry>>,
+}
+
+impl StreamRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `block_id` is streaming; the entry lives as long as the guard
+    pub fn register(&self, block_id: Uuid) -> ActiveStream<'_> {
+        let token = CancellationToken::new();
+        let (deltas, _) = broadcast::channel(256);
+        self.entries.lock().unwrap().insert(
+            block_id,
+            Entry {
+                token: token.clone(),
+                content: String::new(),
+                deltas,
+            },
+        );
+        ActiveStream {
+            registry: self,
+            block_id,
+            token,
+        }
+    }
+
+    /// Signal the stream for `block_id` to stop; false if it isn't streaming
+    pub fn cancel(&self, block_id: Uuid) -> bool {
+        match self.entries.lock().unwrap().get(&block_id) {
+            Some(entry) => {
+                entry.token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Content streamed so far plus a receiver for every later delta
+    ///
+    /// Taken together so nothing falls between the snapshot and the first
+    /// received delta. The receiver closes when the stream ends. `None` if
+    /// `block_id` isn't streaming.
+    pub fn resume(&self, block_id: Uuid) -> Option<(String, broadcast::Receiver<ContentDelta>)> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(&block_id)?;
+        Some((entry.content.clone(), entry.deltas.subscribe()))
+    }
+
+    /// Whether `block_id` has a live stream
+    pub fn is_streaming(&self, block_id: Uuid) -> bool {
+        self.entries.lock().unwrap().contains_key(&block_id)
+    }
+
+    /// Number of blocks streaming right now
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A registered stream; dropping it removes the registry entry
+pub struct ActiveStream<'a> {
+    registry: &'a StreamRegistry,
+    block_id: Uuid,
+    token: CancellationToken,
+}
+
+impl ActiveStream<'_> {
+    /// Resolves once the stream has been cancelled
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+
+    /// Record newly streamed content and pass it on to resumed clients
+    pub fn push(&self, delta: &str) {
+        let mut entries = self.registry.entries.lock().unwrap();
+        if let Some(entry) = entries.get_mut(&self.block_id) {
+            let offset = entry.content.len();
+            entry.content.push_str(delta);
+            let _ = entry.deltas.send(ContentDelta {
+                delta: delta.to_string(),
+                offset,
+            });
+        }
+    }
+}
+
+impl Drop for ActiveStream<'_> {
+    fn drop(&mut self) {
+        self.registry.entries.lock().unwrap().remove(&self.block_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancel_reaches_registered_stream() {
+        let registry = StreamRegistry::new();
+        let block_id = Uuid::new_v4();
+        assert!(!registry.cancel(block_id));
+
+        let stream = registry.register(block_id);
+        assert!(registry.is_streaming(block_id));
+        assert!(registry.cancel(block_id));
+        tokio::time::timeout(std::time::Duration::from_secs(1), stream.cancelled())
+            .await
+            .expect("Token was not cancelled");
+
+        drop(stream);
+        assert!(!registry.is_streaming(block_id));
+    }
+
+    #[tokio::test]
+    async fn test_resume_picks_up_mid_stream() {
+        let registry = StreamRegistry::new();
+        let block_id = Uuid::new_v4();
+        assert!(registry.resume(block_id).is_none());
+
+        let stream = registry.register(block_id);
+        stream.push("Hello, ");
+
+        let (content, mut deltas) = registry.resume(block_id).unwrap();
+        assert_eq!(content, "Hello, ");
+
+        stream.push("world");
+        assert_eq!(
+            deltas.recv().await.unwrap(),
+            ContentDelta {
+                delta: "world".to_string(),
+                offset: 7,
+            }
+        );
+
+        // The receiver closes once the stream ends
+        drop(stream);
+        assert!(matches!(
+            deltas.recv().await,
+            Err(broadcast::error::RecvError::Closed)
+        ));
+    }
+}
@@ -3,25 +3,65 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        State,
+        Query, State,
     },
-    response::IntoResponse,
+    http::HeaderMap,
+    response::{IntoResponse, Response},
 };
 use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
-use crate::crdt::{Participant, ParticipantKind, ParticipantStatus, RoomEvent};
+use crate::auth;
+use crate::codec::{self, Encoding, EncodingSink};
+use crate::crdt::{
+    JournalRole, JournalRoom, Participant, ParticipantKind, ParticipantStatus, RoomEvent,
+};
 use crate::delegation::capability::CapabilitySet;
+use crate::delegation::manager::DelegationError;
 use crate::delegation::work_item::WorkPriority;
-use crate::delegation::{Capability, WorkItemStatus};
-use crate::error;
-use crate::models::{BlockStatus, BlockType};
-use crate::opencode::{OpenCodeClient, SendMessageRequest, StreamEvent};
+use crate::delegation::{Capability, DelegationEvent, WorkItem, WorkItemStatus};
+use crate::error::{self, ErrorCode};
+use crate::frame::{BinaryFrame, Opcode};
+use crate::limiter::{Acquire, QueueTicket, SubmitPermit, SubmitRate};
+use crate::markdown;
+use crate::models::{BlockEvent, BlockStatus, BlockType, BlockUsage};
+use crate::opencode::{
+    ErrorEvent, OpenCodeClient, RetryConfig, SendMessageRequest, StreamEvent, UsageEvent,
+};
+use crate::store;
 use crate::AppState;
 
+/// Largest page a client may request with `get_blocks_page`
+const MAX_BLOCKS_PAGE: u32 = 500;
+
+/// Results returned by `search_blocks` when the client gives no limit
+const DEFAULT_SEARCH_RESULTS: u32 = 20;
+
+/// Most results a client may request with `search_blocks`
+const MAX_SEARCH_RESULTS: u32 = 100;
+
+/// Journals returned by `get_recent_activity` when the client gives no limit
+const DEFAULT_RECENT_ACTIVITY: u32 = 20;
+
+/// Most journals a client may request with `get_recent_activity`
+const MAX_RECENT_ACTIVITY: u32 = 100;
+
+/// Most audit entries returned by `GetAuditLog`
+const AUDIT_LOG_LIMIT: u32 = 500;
+
+/// Largest text frame accepted by default (1 MiB)
+pub const DEFAULT_MAX_FRAME_BYTES: usize = 1024 * 1024;
+
+/// A chunked CRDT update may grow to this many times the sync chunk size
+const MAX_UPDATE_CHUNKS: usize = 64;
+
+/// Content given to a block whose response never arrived
+const OPENCODE_TIMEOUT_MESSAGE: &str = "OpenCode timed out";
+
 /// Create a user-friendly error message from an error, keeping full details separate
 fn make_error_message(err: &error::AppError) -> ServerMessage {
     let full_error = err.to_string();
@@ -32,6 +72,8 @@ fn make_error_message(err: &error::AppError) -> ServerMessage {
     } else if full_error.contains("Failed to connect") || full_error.contains("Connection refused")
     {
         "Cannot connect to OpenCode server".to_string()
+    } else if matches!(err, error::AppError::OpenCodeTimeout(_)) {
+        OPENCODE_TIMEOUT_MESSAGE.to_string()
     } else if full_error.contains("Failed to create session") {
         "Failed to create OpenCode session".to_string()
     } else {
@@ -51,52 +93,122 @@ fn make_error_message(err: &error::AppError) -> ServerMessage {
     };
 
     ServerMessage::Error {
+        code: err.code(),
         message: friendly_message,
         details,
     }
 }
 
 /// WebSocket handler
+///
+/// When auth is enabled the upgrade is refused with 401 unless the request
+/// carries a valid token (see [`crate::auth`]).
 pub async fn handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+    headers: HeaderMap,
+    Query(query): Query<auth::TokenQuery>,
+) -> Response {
+    let identity = match state
+        .auth
+        .authenticate(&state.store, &headers, &query)
+        .await
+    {
+        Ok(identity) => identity,
+        Err(status) => return status.into_response(),
+    };
+    ws.on_upgrade(move |socket| handle_socket(socket, state, identity))
+        .into_response()
 }
 
+/// Outgoing half of a client connection
+type ClientSink = EncodingSink<futures::stream::SplitSink<WebSocket, Message>>;
+
 /// Connection state for tracking subscriptions and delegation
 struct ConnectionState {
+    /// Tags block events this connection causes, so its own room forwarders skip them
+    id: Uuid,
     /// Map of journal_id -> participant_id for this connection (CRDT presence)
     subscriptions: std::collections::HashMap<Uuid, Uuid>,
     /// The registered participant ID for delegation (per journal)
     /// Map of journal_id -> registered_participant_id
     delegation_registrations: std::collections::HashMap<Uuid, Uuid>,
+    /// Whether the client opted in to binary CRDT frames (shared with forwarding tasks)
+    binary_crdt: Arc<AtomicBool>,
+    /// Whether replies go out as MessagePack (shared with the connection's sink)
+    msgpack: Arc<AtomicBool>,
+    /// Room event forwarding task per subscribed journal, aborted on unsubscribe/disconnect
+    forwarders: std::collections::HashMap<Uuid, tokio::task::AbortHandle>,
+    /// Highest CRDT sequence number acknowledged per subscribed journal
+    /// (shared with forwarding tasks, which replay from it after lagging)
+    crdt_acks: std::collections::HashMap<Uuid, Arc<AtomicU64>>,
+    /// Task pushing delegation events to this connection, aborted on disconnect
+    delegation_forwarder: Option<tokio::task::AbortHandle>,
+    /// Journals where this client asked for observers to be collapsed into a count
+    collapsed_presence: std::collections::HashSet<Uuid>,
+    /// Chunked CRDT updates being reassembled: journal_id -> (next sequence, bytes so far)
+    partial_updates: std::collections::HashMap<Uuid, (u32, Vec<u8>)>,
+    /// Budget shared by Submit, Fork and Rerun, which all start OpenCode sessions
+    submit_rate: Option<SubmitRate>,
+    /// Who the connection's token belongs to; `None` when auth is disabled
+    identity: Option<String>,
 }
 
 impl ConnectionState {
-    fn new() -> Self {
+    fn new(submit_rate: Option<SubmitRate>, identity: Option<String>) -> Self {
         Self {
+            id: Uuid::new_v4(),
             subscriptions: std::collections::HashMap::new(),
             delegation_registrations: std::collections::HashMap::new(),
+            binary_crdt: Arc::new(AtomicBool::new(false)),
+            msgpack: Arc::new(AtomicBool::new(false)),
+            forwarders: std::collections::HashMap::new(),
+            crdt_acks: std::collections::HashMap::new(),
+            delegation_forwarder: None,
+            collapsed_presence: std::collections::HashSet::new(),
+            partial_updates: std::collections::HashMap::new(),
+            submit_rate,
+            identity,
         }
     }
 }
 
-async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
+async fn handle_socket(socket: WebSocket, state: Arc<AppState>, identity: Option<String>) {
+    let _connection = state.metrics.connection_opened();
     let (sender, mut receiver) = socket.split();
-    let sender = Arc::new(Mutex::new(sender));
 
     // Get OpenCode URL from environment
     let opencode_url =
         std::env::var("OPENCODE_URL").unwrap_or_else(|_| "http://localhost:4096".to_string());
-    let opencode = OpenCodeClient::new(opencode_url);
+    let mut opencode = OpenCodeClient::new(opencode_url).with_retry_config(RetryConfig {
+        max_attempts: state.opencode_session_attempts(),
+        ..RetryConfig::default()
+    });
+    if let Some(timeout) = state.opencode_timeout() {
+        opencode = opencode.with_timeout(timeout);
+    }
 
     // Connection state
-    let conn_state = Arc::new(Mutex::new(ConnectionState::new()));
+    let conn_state = Arc::new(Mutex::new(ConnectionState::new(
+        state.submit_limiter.connection_rate(),
+        identity,
+    )));
+    let (connection_id, msgpack) = {
+        let conn = conn_state.lock().await;
+        (conn.id, Arc::clone(&conn.msgpack))
+    };
+    let sender = Arc::new(Mutex::new(EncodingSink::new(sender, msgpack)));
 
     while let Some(msg) = receiver.next().await {
-        let msg = match msg {
-            Ok(Message::Text(text)) => text,
+        let (encoding, msg) = match msg {
+            Ok(Message::Text(text)) => (Encoding::Json, text.into_bytes()),
+            Ok(Message::Binary(data)) if codec::is_message_pack(&data) => {
+                (Encoding::MessagePack, data)
+            }
+            Ok(Message::Binary(data)) => {
+                handle_binary_frame(&sender, &state, &conn_state, &data).await;
+                continue;
+            }
             Ok(Message::Close(_)) => break,
             Ok(_) => continue,
             Err(e) => {
@@ -105,11 +217,31 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
             }
         };
 
+        // Refuse oversized frames before spending memory on parsing them
+        let max_frame_bytes = state.max_frame_bytes();
+        if msg.len() > max_frame_bytes {
+            let error = ServerMessage::Error {
+                code: ErrorCode::MessageTooLarge,
+                message: format!(
+                    "Message of {} bytes exceeds the {} byte limit",
+                    msg.len(),
+                    max_frame_bytes
+                ),
+                details: None,
+            };
+            let mut sender = sender.lock().await;
+            let _ = sender
+                .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                .await;
+            continue;
+        }
+
         // Parse client message
-        let client_msg: ClientMessage = match serde_json::from_str(&msg) {
+        let client_msg: ClientMessage = match encoding.decode(&msg) {
             Ok(m) => m,
             Err(e) => {
                 let error = ServerMessage::Error {
+                    code: ErrorCode::InvalidMessage,
                     message: format!("Invalid message: {}", e),
                     details: None,
                 };
@@ -124,32 +256,123 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
             }
         };
 
+        let required = match &client_msg {
+            ClientMessage::Submit { .. } | ClientMessage::Rerun { .. } => Some(Capability::Submit),
+            ClientMessage::Fork { .. } => Some(Capability::Fork),
+            _ => None,
+        };
+        if let Some(required) = required.filter(|_| state.require_submit_capability()) {
+            // Only registered participants are checked; anonymous connections predate capabilities
+            let participant_id = {
+                let conn = conn_state.lock().await;
+                conn.delegation_registrations.values().next().copied()
+            };
+            if let Some(participant_id) = participant_id {
+                let allowed = state
+                    .delegation_manager
+                    .get_participant(participant_id)
+                    .await
+                    .is_some_and(|p| p.has_capability(required));
+                if !allowed {
+                    let error = ServerMessage::Error {
+                        code: ErrorCode::InsufficientCapability,
+                        message: format!(
+                            "Generating responses requires the {} capability",
+                            required.as_str()
+                        ),
+                        details: None,
+                    };
+                    let mut sender = sender.lock().await;
+                    let _ = sender
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await;
+                    continue;
+                }
+            }
+        }
+
+        if matches!(
+            client_msg,
+            ClientMessage::Submit { .. } | ClientMessage::Fork { .. } | ClientMessage::Rerun { .. }
+        ) {
+            let mut conn = conn_state.lock().await;
+            if let Some(rate) = conn.submit_rate.as_mut() {
+                if !rate.try_acquire() {
+                    let error = ServerMessage::Error {
+                        code: ErrorCode::RateLimited,
+                        message: format!(
+                            "Rate limit of {} submits per minute exceeded; retry in {}s",
+                            rate.per_minute_limit(),
+                            rate.retry_after().as_secs_f64().ceil()
+                        ),
+                        details: None,
+                    };
+                    drop(conn);
+                    let mut sender = sender.lock().await;
+                    let _ = sender
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await;
+                    continue;
+                }
+            }
+        }
+
         // Handle message
         match client_msg {
             ClientMessage::Submit {
                 journal_id,
                 content,
                 session_id,
+                model,
+                system_prompt,
+                idempotency_key,
             } => {
-                let mut sender_guard = sender.lock().await;
-                if let Err(e) = handle_submit(
-                    &mut sender_guard,
-                    &state,
-                    &opencode,
-                    journal_id,
-                    content,
-                    session_id,
-                )
-                .await
-                {
-                    let error = make_error_message(&e);
-                    if let Err(e) = sender_guard
-                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
-                        .await
+                state.metrics.record_submit();
+                // Stream in the background so this connection can still be read, e.g. to cancel
+                let sender = Arc::clone(&sender);
+                let state = Arc::clone(&state);
+                let opencode = opencode.clone();
+                tokio::spawn(async move {
+                    match handle_submit(
+                        &sender,
+                        &state,
+                        &opencode,
+                        connection_id,
+                        journal_id,
+                        content,
+                        session_id,
+                        model,
+                        system_prompt,
+                        idempotency_key,
+                    )
+                    .await
                     {
-                        tracing::error!("Failed to send error: {}", e);
+                        Ok(Some(exchange)) if state.auto_title() => {
+                            auto_title_journal(
+                                sender,
+                                state,
+                                opencode,
+                                connection_id,
+                                journal_id,
+                                exchange,
+                            )
+                            .await;
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            state.metrics.record_error(&e);
+                            let error = make_error_message(&e);
+                            if let Err(e) = sender
+                                .lock()
+                                .await
+                                .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                                .await
+                            {
+                                tracing::error!("Failed to send error: {}", e);
+                            }
+                        }
                     }
-                }
+                });
             }
             ClientMessage::CreateJournal { title } => {
                 match state.store.create_journal(title).await {
@@ -168,6 +391,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                     Err(e) => {
                         let error = ServerMessage::Error {
+                            code: e.code(),
                             message: e.to_string(),
                             details: None,
                         };
@@ -181,15 +405,55 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                 }
             }
-            ClientMessage::GetJournal { journal_id } => {
-                match state.store.get_journal(journal_id).await {
-                    Ok(journal) => {
-                        let blocks = state
-                            .store
-                            .get_blocks_for_journal(journal_id)
-                            .await
-                            .unwrap_or_default();
-                        let msg = ServerMessage::Journal { journal, blocks };
+            ClientMessage::CloneJournal { journal_id, title } => {
+                let msg = match state.store.clone_journal(journal_id, title).await {
+                    Ok(journal) => ServerMessage::JournalCloned { journal },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::GetJournal { journal_id, render } => {
+                let format = match render
+                    .as_deref()
+                    .map(str::parse::<markdown::RenderFormat>)
+                    .transpose()
+                {
+                    Ok(format) => format,
+                    Err(e) => {
+                        let error = ServerMessage::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
+                match state
+                    .store
+                    .get_journal_with_blocks_lenient(journal_id)
+                    .await
+                {
+                    Ok((journal, mut loaded)) => {
+                        if let Some(format) = format {
+                            for block in &mut loaded.blocks {
+                                markdown::render_block(block, format);
+                            }
+                        }
+                        let msg = ServerMessage::Journal {
+                            journal,
+                            blocks: loaded.blocks,
+                        };
                         let mut sender = sender.lock().await;
                         if let Err(e) = sender
                             .send(Message::Text(serde_json::to_string(&msg).unwrap()))
@@ -197,9 +461,21 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                         {
                             tracing::error!("Failed to send journal: {}", e);
                         }
+                        if loaded.skipped > 0 {
+                            let warning = ServerMessage::Warning {
+                                message: format!(
+                                    "{} block(s) in journal {} could not be read and were skipped",
+                                    loaded.skipped, journal_id
+                                ),
+                            };
+                            let _ = sender
+                                .send(Message::Text(serde_json::to_string(&warning).unwrap()))
+                                .await;
+                        }
                     }
                     Err(e) => {
                         let error = ServerMessage::Error {
+                            code: e.code(),
                             message: e.to_string(),
                             details: None,
                         };
@@ -213,134 +489,760 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                 }
             }
-            ClientMessage::ListJournals => match state.store.list_journals().await {
-                Ok(journals) => {
-                    let msg = ServerMessage::Journals { journals };
-                    let mut sender = sender.lock().await;
-                    if let Err(e) = sender
-                        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                        .await
-                    {
-                        tracing::error!("Failed to send journals: {}", e);
-                    }
-                }
-                Err(e) => {
-                    let error = ServerMessage::Error {
+            ClientMessage::GetJournalSummary { journal_id } => {
+                let msg = match state.store.journal_summary(journal_id).await {
+                    Ok(summary) => ServerMessage::JournalSummary { summary },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
                         message: e.to_string(),
                         details: None,
-                    };
-                    let mut sender = sender.lock().await;
-                    if let Err(e) = sender
-                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
-                        .await
-                    {
-                        tracing::error!("Failed to send error: {}", e);
-                    }
-                }
-            },
-            ClientMessage::Fork {
-                block_id,
-                session_id,
-            } => {
-                let mut sender_guard = sender.lock().await;
-                if let Err(e) =
-                    handle_fork(&mut sender_guard, &state, &opencode, block_id, session_id).await
-                {
-                    let error = make_error_message(&e);
-                    if let Err(e) = sender_guard
-                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
-                        .await
-                    {
-                        tracing::error!("Failed to send error: {}", e);
-                    }
-                }
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
             }
-            ClientMessage::Rerun {
-                block_id,
-                session_id,
-            } => {
-                let mut sender_guard = sender.lock().await;
-                if let Err(e) =
-                    handle_rerun(&mut sender_guard, &state, &opencode, block_id, session_id).await
+            ClientMessage::GetBlock { block_id, render } => {
+                let format = match render
+                    .as_deref()
+                    .map(str::parse::<markdown::RenderFormat>)
+                    .transpose()
                 {
-                    let error = make_error_message(&e);
-                    if let Err(e) = sender_guard
-                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
-                        .await
-                    {
-                        tracing::error!("Failed to send error: {}", e);
+                    Ok(format) => format,
+                    Err(e) => {
+                        let error = ServerMessage::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
                     }
+                };
+                let msg = match state.store.get_block(block_id).await {
+                    Ok(mut block) => {
+                        if let Some(format) = format {
+                            markdown::render_block(&mut block, format);
+                        }
+                        ServerMessage::Block { block }
+                    }
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                if let Err(e) = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await
+                {
+                    tracing::error!("Failed to send block: {}", e);
                 }
             }
-            ClientMessage::Cancel { block_id } => {
-                let mut sender_guard = sender.lock().await;
-                if let Err(e) = handle_cancel(&mut sender_guard, &state, block_id).await {
-                    let error = ServerMessage::Error {
+            ClientMessage::FetchBlock { block_id } => {
+                let msg = match state.store.get_block(block_id).await {
+                    Ok(block) => ServerMessage::BlockFetched { block },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
                         message: e.to_string(),
                         details: None,
-                    };
-                    if let Err(e) = sender_guard
-                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
-                        .await
-                    {
-                        tracing::error!("Failed to send error: {}", e);
-                    }
+                    },
+                };
+                let mut sender = sender.lock().await;
+                if let Err(e) = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await
+                {
+                    tracing::error!("Failed to send block: {}", e);
                 }
             }
-            ClientMessage::Subscribe {
+            ClientMessage::GetBlocksPage {
                 journal_id,
-                name,
-                kind,
+                before,
+                limit,
             } => {
-                handle_subscribe(
-                    Arc::clone(&sender),
-                    &state,
-                    Arc::clone(&conn_state),
-                    journal_id,
-                    name,
-                    kind,
-                )
-                .await;
-            }
-            ClientMessage::Unsubscribe { journal_id } => {
-                handle_unsubscribe(
-                    Arc::clone(&sender),
-                    &state,
-                    Arc::clone(&conn_state),
-                    journal_id,
-                )
-                .await;
+                let limit = limit.clamp(1, MAX_BLOCKS_PAGE);
+                let msg = match state.store.get_blocks_page(journal_id, before, limit).await {
+                    Ok(page) => ServerMessage::BlocksPage {
+                        blocks: page.blocks,
+                        has_more: page.has_more,
+                    },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
             }
-            ClientMessage::Cursor {
+            ClientMessage::SearchBlocks {
+                query,
                 journal_id,
-                block_id,
-                offset,
+                limit,
             } => {
-                let conn = conn_state.lock().await;
-                if let Some(&participant_id) = conn.subscriptions.get(&journal_id) {
-                    if let Some(room) = state.room_manager.get(journal_id).await {
-                        room.update_cursor(participant_id, block_id, offset).await;
-                    }
-                }
+                let limit = limit
+                    .unwrap_or(DEFAULT_SEARCH_RESULTS)
+                    .clamp(1, MAX_SEARCH_RESULTS);
+                let msg = match state.store.search_blocks(&query, journal_id, limit).await {
+                    Ok(blocks) => ServerMessage::SearchResults { blocks },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
             }
-            ClientMessage::GetPresence { journal_id } => {
-                if let Some(room) = state.room_manager.get(journal_id).await {
-                    let participants = room.participants().await;
-                    let msg = ServerMessage::Presence {
-                        journal_id,
-                        participants,
-                    };
-                    let mut sender = sender.lock().await;
-                    if let Err(e) = sender
-                        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                        .await
-                    {
-                        tracing::error!("Failed to send presence: {}", e);
-                    }
-                } else {
-                    let msg = ServerMessage::Presence {
-                        journal_id,
+            ClientMessage::GetRecentActivity { limit } => {
+                let limit = limit
+                    .unwrap_or(DEFAULT_RECENT_ACTIVITY)
+                    .clamp(1, MAX_RECENT_ACTIVITY);
+                let msg = match state.store.get_recent_activity(limit).await {
+                    Ok(entries) => ServerMessage::RecentActivity {
+                        entries: entries
+                            .into_iter()
+                            .map(|(journal, latest_block)| crate::models::ActivityEntry {
+                                journal,
+                                latest_block,
+                            })
+                            .collect(),
+                    },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::ExportBranch { block_id, format } => {
+                let msg = match state.store.get_branch(block_id).await {
+                    Ok(blocks) => ServerMessage::BranchExport {
+                        content: crate::export::render(&blocks, format),
+                        format,
+                    },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::DiffBlocks { a, b } => {
+                let msg = match state.store.diff_blocks(a, b).await {
+                    Ok(diff) => ServerMessage::BlockDiff {
+                        a: diff.a,
+                        b: diff.b,
+                        hunks: diff.hunks,
+                        warning: diff.warning,
+                    },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                if let Err(e) = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await
+                {
+                    tracing::error!("Failed to send block diff: {}", e);
+                }
+            }
+            ClientMessage::ListModels => {
+                let msg = match opencode.list_models().await {
+                    Ok(models) => ServerMessage::Models { models },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::GetStorageStats => {
+                let conn = conn_state.lock().await;
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let is_admin = match participant_id {
+                    Some(id) => state
+                        .delegation_manager
+                        .get_participant(id)
+                        .await
+                        .is_some_and(|p| p.has_capability(Capability::Admin)),
+                    None => false,
+                };
+
+                let msg = if !is_admin {
+                    ServerMessage::Error {
+                        code: ErrorCode::InsufficientCapability,
+                        message: "Storage stats require the admin capability".to_string(),
+                        details: None,
+                    }
+                } else {
+                    match state.store.stats().await {
+                        Ok(stats) => ServerMessage::StorageStats { stats },
+                        Err(e) => ServerMessage::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                            details: None,
+                        },
+                    }
+                };
+                let mut sender = sender.lock().await;
+                if let Err(e) = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await
+                {
+                    tracing::error!("Failed to send storage stats: {}", e);
+                }
+            }
+            ClientMessage::ReorderBlock { block_id, position } => {
+                let msg = match state.store.reorder_block(block_id, position).await {
+                    Ok(block) => ServerMessage::BlockReordered { block },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                if let Err(e) = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await
+                {
+                    tracing::error!("Failed to send reorder result: {}", e);
+                }
+            }
+            ClientMessage::EditBlock { block_id, content } => {
+                let mut sender_guard = sender.lock().await;
+                if let Err(e) =
+                    handle_edit_block(&mut sender_guard, &state, connection_id, block_id, &content)
+                        .await
+                {
+                    let error = ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    };
+                    if let Err(e) = sender_guard
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await
+                    {
+                        tracing::error!("Failed to send error: {}", e);
+                    }
+                }
+            }
+            ClientMessage::GetBlockHistory { block_id } => {
+                let msg = match state.store.get_block_history(block_id).await {
+                    Ok(edits) => ServerMessage::BlockHistory { block_id, edits },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                if let Err(e) = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await
+                {
+                    tracing::error!("Failed to send block history: {}", e);
+                }
+            }
+            ClientMessage::React { block_id, emoji } => {
+                let subscriptions = conn_state.lock().await.subscriptions.clone();
+                let mut sender_guard = sender.lock().await;
+                if let Err(e) = handle_react(
+                    &mut sender_guard,
+                    &state,
+                    connection_id,
+                    &subscriptions,
+                    block_id,
+                    &emoji,
+                )
+                .await
+                {
+                    let error = ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    };
+                    if let Err(e) = sender_guard
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await
+                    {
+                        tracing::error!("Failed to send error: {}", e);
+                    }
+                }
+            }
+            ClientMessage::GetReactions { block_id } => {
+                let msg = match state.store.get_reactions(block_id).await {
+                    Ok(reactions) => ServerMessage::Reactions {
+                        block_id,
+                        reactions,
+                    },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                if let Err(e) = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await
+                {
+                    tracing::error!("Failed to send reactions: {}", e);
+                }
+            }
+            ClientMessage::DeleteBlock { block_id, cascade } => {
+                let mut sender_guard = sender.lock().await;
+                if let Err(e) = handle_delete_block(
+                    &mut sender_guard,
+                    &state,
+                    &conn_state,
+                    connection_id,
+                    block_id,
+                    cascade,
+                )
+                .await
+                {
+                    let error = ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    };
+                    if let Err(e) = sender_guard
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await
+                    {
+                        tracing::error!("Failed to send error: {}", e);
+                    }
+                }
+            }
+            ClientMessage::ListJournals {
+                include_deleted,
+                include_archived,
+                query,
+                sort,
+            } => match state
+                .store
+                .list_journals_filtered(
+                    include_deleted,
+                    include_archived,
+                    query.as_deref(),
+                    sort.unwrap_or_default(),
+                )
+                .await
+            {
+                Ok(journals) => {
+                    let msg = ServerMessage::Journals { journals };
+                    let mut sender = sender.lock().await;
+                    if let Err(e) = sender
+                        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                        .await
+                    {
+                        tracing::error!("Failed to send journals: {}", e);
+                    }
+                }
+                Err(e) => {
+                    let error = ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    };
+                    let mut sender = sender.lock().await;
+                    if let Err(e) = sender
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await
+                    {
+                        tracing::error!("Failed to send error: {}", e);
+                    }
+                }
+            },
+            ClientMessage::DeleteJournal { journal_id } => {
+                if let Err(e) =
+                    check_journal_role(&state, &conn_state, journal_id, JournalRole::can_manage)
+                        .await
+                {
+                    let error = ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    };
+                    let mut sender = sender.lock().await;
+                    let _ = sender
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await;
+                    continue;
+                }
+                let msg = match state.store.soft_delete_journal(journal_id).await {
+                    Ok(()) => ServerMessage::JournalDeleted { journal_id },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::RenameJournal { journal_id, title } => {
+                if let Err(e) =
+                    check_journal_role(&state, &conn_state, journal_id, JournalRole::can_rename)
+                        .await
+                {
+                    let error = ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    };
+                    let mut sender = sender.lock().await;
+                    let _ = sender
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await;
+                    continue;
+                }
+                let msg = match state.store.rename_journal(journal_id, &title).await {
+                    Ok(journal) => {
+                        if let Some(room) = state.room_manager.get(journal_id).await {
+                            room.broadcast_rename(journal.title.clone(), Some(connection_id));
+                        }
+                        ServerMessage::JournalRenamed {
+                            journal_id,
+                            title: journal.title,
+                        }
+                    }
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::ArchiveJournal {
+                journal_id,
+                archived,
+            } => {
+                if let Err(e) =
+                    check_journal_role(&state, &conn_state, journal_id, JournalRole::can_manage)
+                        .await
+                {
+                    let error = ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    };
+                    let mut sender = sender.lock().await;
+                    let _ = sender
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await;
+                    continue;
+                }
+                let msg = match state.store.set_journal_archived(journal_id, archived).await {
+                    Ok(journal) => ServerMessage::JournalUpdated { journal },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::SetRole {
+                journal_id,
+                participant_id,
+                role,
+            } => {
+                let msg =
+                    match set_journal_role(&state, &conn_state, journal_id, participant_id, role)
+                        .await
+                    {
+                        Ok(()) => ServerMessage::RoleChanged {
+                            journal_id,
+                            participant_id,
+                            role,
+                        },
+                        Err(error) => error,
+                    };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::Fork {
+                block_id,
+                session_id,
+                model,
+            } => {
+                // Stream in the background like a submit, so a cancel can still be read
+                let sender = Arc::clone(&sender);
+                let state = Arc::clone(&state);
+                let opencode = opencode.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_fork(
+                        &sender,
+                        &state,
+                        &opencode,
+                        connection_id,
+                        block_id,
+                        session_id,
+                        model,
+                    )
+                    .await
+                    {
+                        state.metrics.record_error(&e);
+                        let error = make_error_message(&e);
+                        if let Err(e) = sender
+                            .lock()
+                            .await
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await
+                        {
+                            tracing::error!("Failed to send error: {}", e);
+                        }
+                    }
+                });
+            }
+            ClientMessage::Rerun {
+                block_id,
+                session_id,
+                model,
+            } => {
+                // Stream in the background like a submit, so a cancel can still be read
+                let sender = Arc::clone(&sender);
+                let state = Arc::clone(&state);
+                let opencode = opencode.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_rerun(
+                        &sender,
+                        &state,
+                        &opencode,
+                        connection_id,
+                        block_id,
+                        session_id,
+                        model,
+                    )
+                    .await
+                    {
+                        state.metrics.record_error(&e);
+                        let error = make_error_message(&e);
+                        if let Err(e) = sender
+                            .lock()
+                            .await
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await
+                        {
+                            tracing::error!("Failed to send error: {}", e);
+                        }
+                    }
+                });
+            }
+            ClientMessage::Cancel { block_id } => {
+                let mut sender_guard = sender.lock().await;
+                if let Err(e) =
+                    handle_cancel(&mut sender_guard, &state, connection_id, block_id).await
+                {
+                    let error = ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    };
+                    if let Err(e) = sender_guard
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await
+                    {
+                        tracing::error!("Failed to send error: {}", e);
+                    }
+                }
+            }
+            ClientMessage::ResumeBlock { block_id } => {
+                if let Err(e) = handle_resume(&sender, &state, &conn_state, block_id).await {
+                    let error = ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    };
+                    let mut sender = sender.lock().await;
+                    let _ = sender
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await;
+                }
+            }
+            ClientMessage::Subscribe {
+                journal_id,
+                name,
+                kind,
+                known_participants,
+                create,
+                collapse_observers,
+            } => {
+                let kind = match ParticipantKind::parse_or_user(kind.as_deref()) {
+                    Ok(kind) => kind,
+                    Err(message) => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::InvalidKind,
+                            message,
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
+                if let Err(e) = ensure_journal(&state, journal_id, create).await {
+                    let error = ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    };
+                    let mut sender = sender.lock().await;
+                    let _ = sender
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await;
+                    continue;
+                }
+                handle_subscribe(
+                    Arc::clone(&sender),
+                    &state,
+                    Arc::clone(&conn_state),
+                    journal_id,
+                    SubscribeOptions {
+                        name,
+                        participant_kind: kind,
+                        known_participants,
+                        collapse_observers,
+                    },
+                )
+                .await;
+            }
+            ClientMessage::Unsubscribe { journal_id } => {
+                handle_unsubscribe(
+                    Arc::clone(&sender),
+                    &state,
+                    Arc::clone(&conn_state),
+                    journal_id,
+                )
+                .await;
+            }
+            ClientMessage::Cursor {
+                journal_id,
+                block_id,
+                offset,
+                anchor,
+                head,
+            } => {
+                // Fill in whichever end of the range is missing, so older
+                // clients sending only `offset` get a zero-width selection
+                let head = head.or(offset).or(anchor);
+                let anchor = anchor.or(head);
+
+                let conn = conn_state.lock().await;
+                if let Some(&participant_id) = conn.subscriptions.get(&journal_id) {
+                    if let Some(room) = state.room_manager.get(journal_id).await {
+                        room.update_selection(participant_id, block_id, anchor, head)
+                            .await;
+                    }
+                }
+            }
+            ClientMessage::Typing { journal_id, typing } => {
+                let participant_id = conn_state
+                    .lock()
+                    .await
+                    .subscriptions
+                    .get(&journal_id)
+                    .copied();
+                if let Some(participant_id) = participant_id {
+                    if let Some(room) = state.room_manager.get(journal_id).await {
+                        room.set_typing(participant_id, typing).await;
+                    }
+                }
+            }
+            ClientMessage::Heartbeat { journal_id } => {
+                let participant_id = conn_state
+                    .lock()
+                    .await
+                    .subscriptions
+                    .get(&journal_id)
+                    .copied();
+                let alive = match (participant_id, state.room_manager.get(journal_id).await) {
+                    (Some(participant_id), Some(room)) => room.heartbeat(participant_id).await,
+                    _ => false,
+                };
+                // Tell a client whose presence was reaped so it can subscribe again
+                if !alive {
+                    let error = ServerMessage::Error {
+                        code: ErrorCode::InvalidState,
+                        message: format!("Not present in journal {}; subscribe again", journal_id),
+                        details: None,
+                    };
+                    let mut sender = sender.lock().await;
+                    let _ = sender
+                        .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                        .await;
+                }
+            }
+            ClientMessage::GetPresence { journal_id } => {
+                if let Some(room) = state.room_manager.get(journal_id).await {
+                    let collapse = conn_state
+                        .lock()
+                        .await
+                        .collapsed_presence
+                        .contains(&journal_id);
+                    let (participants, observer_count) =
+                        presence_snapshot(room.participants().await, collapse);
+                    let msg = ServerMessage::Presence {
+                        journal_id,
+                        participants,
+                        observer_count,
+                    };
+                    let mut sender = sender.lock().await;
+                    if let Err(e) = sender
+                        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                        .await
+                    {
+                        tracing::error!("Failed to send presence: {}", e);
+                    }
+                } else {
+                    let msg = ServerMessage::Presence {
+                        journal_id,
                         participants: vec![],
+                        observer_count: None,
                     };
                     let mut sender = sender.lock().await;
                     let _ = sender
@@ -348,28 +1250,89 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                         .await;
                 }
             }
-            ClientMessage::CrdtUpdate { journal_id, update } => {
-                let conn = conn_state.lock().await;
+            ClientMessage::Hello {
+                binary_crdt,
+                msgpack,
+            } => {
+                {
+                    let conn = conn_state.lock().await;
+                    conn.binary_crdt.store(binary_crdt, Ordering::Relaxed);
+                    conn.msgpack.store(msgpack, Ordering::Relaxed);
+                }
+
+                let msg = ServerMessage::Welcome {
+                    binary_crdt,
+                    msgpack,
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::CrdtUpdate {
+                journal_id,
+                update,
+                seq,
+                last,
+            } => {
+                let update_bytes = match base64_decode(&update) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::InvalidMessage,
+                            message: format!("Invalid base64 update: {}", e),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
+
+                let mut conn = conn_state.lock().await;
                 let participant_id = conn.subscriptions.get(&journal_id).copied();
+                let update_bytes = match seq {
+                    None => Ok(Some(update_bytes)),
+                    // Partial buffers only live as long as a subscription
+                    Some(_) if participant_id.is_none() => {
+                        Err("Subscribe to the journal before sending chunked updates".to_string())
+                    }
+                    Some(seq) => reassemble_update(
+                        &mut conn.partial_updates,
+                        journal_id,
+                        seq,
+                        last,
+                        update_bytes,
+                        state
+                            .room_manager
+                            .sync_chunk_bytes()
+                            .saturating_mul(MAX_UPDATE_CHUNKS),
+                    ),
+                };
                 drop(conn);
 
-                if let Some(room) = state.room_manager.get(journal_id).await {
-                    match base64_decode(&update) {
-                        Ok(update_bytes) => {
+                match update_bytes {
+                    Ok(Some(update_bytes)) => {
+                        if let Some(room) = state.room_manager.get(journal_id).await {
                             if let Err(e) = room.apply_update(participant_id, &update_bytes).await {
                                 tracing::error!("Failed to apply CRDT update: {:?}", e);
                             }
                         }
-                        Err(e) => {
-                            let error = ServerMessage::Error {
-                                message: format!("Invalid base64 update: {}", e),
-                                details: None,
-                            };
-                            let mut sender = sender.lock().await;
-                            let _ = sender
-                                .send(Message::Text(serde_json::to_string(&error).unwrap()))
-                                .await;
-                        }
+                    }
+                    // More chunks to come
+                    Ok(None) => {}
+                    Err(message) => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::InvalidMessage,
+                            message,
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
                     }
                 }
             }
@@ -387,33 +1350,72 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     };
 
                     if let Some(data) = state_data {
-                        let msg = ServerMessage::SyncState {
-                            journal_id,
-                            state: base64_encode(&data),
-                        };
+                        let chunk_bytes = state.room_manager.sync_chunk_bytes();
                         let mut sender = sender.lock().await;
-                        if let Err(e) = sender
-                            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                            .await
+                        if let Err(e) =
+                            send_sync_state(&mut sender, journal_id, &data, chunk_bytes).await
                         {
                             tracing::error!("Failed to send sync state: {}", e);
                         }
                     }
                 }
             }
+            ClientMessage::CrdtAck { journal_id, seq } => {
+                let conn = conn_state.lock().await;
+                if let Some(acked) = conn.crdt_acks.get(&journal_id) {
+                    acked.fetch_max(seq, Ordering::Relaxed);
+                }
+            }
+            ClientMessage::CrdtResync {
+                journal_id,
+                from_seq,
+            } => {
+                if let Some(room) = state.room_manager.get(journal_id).await {
+                    let chunk_bytes = state.room_manager.sync_chunk_bytes();
+                    let mut sender = sender.lock().await;
+                    if let Err(e) =
+                        send_crdt_replay(&mut sender, &room, journal_id, from_seq, chunk_bytes)
+                            .await
+                    {
+                        tracing::error!("Failed to resend CRDT updates: {}", e);
+                    }
+                }
+            }
             // --- Delegation handlers ---
             ClientMessage::RegisterParticipant {
                 journal_id,
                 name,
                 kind,
                 capabilities,
+                webhook_url,
+                participant_id,
             } => {
-                let participant_kind = kind
-                    .as_deref()
-                    .and_then(|k| k.parse().ok())
-                    .unwrap_or(ParticipantKind::User);
+                let participant_kind = match ParticipantKind::parse_or_user(kind.as_deref()) {
+                    Ok(kind) => kind,
+                    Err(message) => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::InvalidKind,
+                            message,
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
 
-                let participant = Participant::new(&name, participant_kind);
+                // Reuse a requested ID, else share the presence identity if
+                // already subscribed to this journal
+                let presence_id = {
+                    let conn = conn_state.lock().await;
+                    conn.subscriptions.get(&journal_id).copied()
+                };
+                let participant = match participant_id.or(presence_id) {
+                    Some(id) => Participant::with_id(id, &name, participant_kind),
+                    None => Participant::new(&name, participant_kind),
+                };
 
                 let registered = if let Some(caps) = capabilities {
                     let cap_set: CapabilitySet = caps
@@ -431,6 +1433,13 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                         .register_participant(participant)
                         .await
                 };
+                if webhook_url.is_some() {
+                    // Just registered, so the participant is always found
+                    let _ = state
+                        .delegation_manager
+                        .set_webhook_url(registered.id(), webhook_url)
+                        .await;
+                }
 
                 // Store registration
                 {
@@ -443,23 +1452,137 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     participant_id: registered.id(),
                     name: registered.name().to_string(),
                     kind: registered.kind().as_str().to_string(),
-                    capabilities: registered
-                        .capabilities
-                        .to_vec()
-                        .iter()
-                        .map(|c| c.as_str().to_string())
-                        .collect(),
+                    capabilities: capability_names(&registered.capabilities),
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::UnregisterParticipant { journal_id } => {
+                let participant_id = {
+                    let mut conn = conn_state.lock().await;
+                    conn.delegation_registrations.remove(&journal_id)
+                };
+                let msg = match participant_id {
+                    Some(participant_id) => {
+                        state
+                            .delegation_manager
+                            .unregister_participant(participant_id)
+                            .await;
+                        ServerMessage::ParticipantUnregistered { participant_id }
+                    }
+                    None => ServerMessage::Error {
+                        code: ErrorCode::NotRegistered,
+                        message: "Not registered with delegation system".to_string(),
+                        details: None,
+                    },
                 };
                 let mut sender = sender.lock().await;
                 let _ = sender
                     .send(Message::Text(serde_json::to_string(&msg).unwrap()))
                     .await;
             }
-            ClientMessage::Delegate {
+            ClientMessage::Delegate {
+                journal_id,
+                description,
+                assignee_id,
+                block_id,
+                priority,
+                requires_approval,
+                approver_id,
+                approver_ids,
+                required_approvals,
+                auto_execute,
+                due_at,
+                tags,
+            } => {
+                let conn = conn_state.lock().await;
+                let delegator_id = match conn.delegation_registrations.get(&journal_id) {
+                    Some(&id) => id,
+                    None => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
+                            message: "Not registered with delegation system".to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
+                drop(conn);
+
+                let priority = priority
+                    .as_deref()
+                    .and_then(|p| p.parse::<WorkPriority>().ok());
+
+                let mut work_item = match block_id {
+                    Some(block_id) => WorkItem::for_block(
+                        journal_id,
+                        block_id,
+                        description,
+                        delegator_id,
+                        assignee_id,
+                    ),
+                    None => WorkItem::new(journal_id, description, delegator_id, assignee_id),
+                };
+                if let Some(p) = priority {
+                    work_item = work_item.with_priority(p);
+                }
+                if requires_approval {
+                    work_item = work_item
+                        .require_approval_from(approver_id.into_iter().chain(approver_ids));
+                    if let Some(required) = required_approvals {
+                        work_item = work_item.with_required_approvals(required);
+                    }
+                }
+                if let Some(due_at) = due_at {
+                    work_item = work_item.with_due_at(due_at);
+                }
+                let work_item = work_item.with_tags(tags).with_auto_execute(auto_execute);
+
+                match state.delegation_manager.delegate_item(work_item).await {
+                    Ok(work_item) => {
+                        let msg = ServerMessage::WorkDelegated {
+                            work_item: work_item.clone(),
+                        };
+                        {
+                            let mut sender = sender.lock().await;
+                            let _ = sender
+                                .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                                .await;
+                        }
+
+                        if work_item.auto_execute {
+                            tokio::spawn(auto_execute_work(
+                                Arc::clone(&sender),
+                                Arc::clone(&state),
+                                opencode.clone(),
+                                connection_id,
+                                work_item,
+                            ));
+                        }
+                    }
+                    Err(e) => {
+                        let error = ServerMessage::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                    }
+                }
+            }
+            ClientMessage::DelegateMany {
                 journal_id,
                 description,
-                assignee_id,
-                block_id: _,
+                assignee_ids,
                 priority,
                 requires_approval,
                 approver_id,
@@ -469,6 +1592,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     Some(&id) => id,
                     None => {
                         let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
                             message: "Not registered with delegation system".to_string(),
                             details: None,
                         };
@@ -485,21 +1609,64 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     .as_deref()
                     .and_then(|p| p.parse::<WorkPriority>().ok());
 
-                match state
+                let msg = match state
                     .delegation_manager
-                    .delegate(
+                    .delegate_many(
                         journal_id,
                         description,
                         delegator_id,
-                        assignee_id,
+                        assignee_ids,
                         priority,
                         requires_approval,
                         approver_id,
                     )
                     .await
+                {
+                    Ok(work_items) => ServerMessage::WorkBatchDelegated { work_items },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::AcceptWork { work_item_id } => {
+                let conn = conn_state.lock().await;
+                // Find the participant ID (from any journal registration)
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let participant_id = match participant_id {
+                    Some(id) => id,
+                    None => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
+                            message: "Not registered with delegation system".to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
+
+                match state
+                    .delegation_manager
+                    .accept_work(work_item_id, participant_id)
+                    .await
                 {
                     Ok(work_item) => {
-                        let msg = ServerMessage::WorkDelegated { work_item };
+                        update_work_presence(&state, &work_item).await;
+                        let msg = ServerMessage::WorkAccepted {
+                            work_item_id,
+                            assignee_id: participant_id,
+                        };
                         let mut sender = sender.lock().await;
                         let _ = sender
                             .send(Message::Text(serde_json::to_string(&msg).unwrap()))
@@ -507,6 +1674,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                     Err(e) => {
                         let error = ServerMessage::Error {
+                            code: e.code(),
                             message: e.to_string(),
                             details: None,
                         };
@@ -517,9 +1685,8 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                 }
             }
-            ClientMessage::AcceptWork { work_item_id } => {
+            ClientMessage::DeclineWork { work_item_id } => {
                 let conn = conn_state.lock().await;
-                // Find the participant ID (from any journal registration)
                 let participant_id = conn.delegation_registrations.values().next().copied();
                 drop(conn);
 
@@ -527,6 +1694,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     Some(id) => id,
                     None => {
                         let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
                             message: "Not registered with delegation system".to_string(),
                             details: None,
                         };
@@ -540,11 +1708,12 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
 
                 match state
                     .delegation_manager
-                    .accept_work(work_item_id, participant_id)
+                    .decline_work(work_item_id, participant_id)
                     .await
                 {
-                    Ok(_) => {
-                        let msg = ServerMessage::WorkAccepted {
+                    Ok(work_item) => {
+                        update_work_presence(&state, &work_item).await;
+                        let msg = ServerMessage::WorkDeclined {
                             work_item_id,
                             assignee_id: participant_id,
                         };
@@ -555,6 +1724,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                     Err(e) => {
                         let error = ServerMessage::Error {
+                            code: e.code(),
                             message: e.to_string(),
                             details: None,
                         };
@@ -565,7 +1735,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                 }
             }
-            ClientMessage::DeclineWork { work_item_id } => {
+            ClientMessage::PauseWork { work_item_id } => {
                 let conn = conn_state.lock().await;
                 let participant_id = conn.delegation_registrations.values().next().copied();
                 drop(conn);
@@ -574,6 +1744,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     Some(id) => id,
                     None => {
                         let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
                             message: "Not registered with delegation system".to_string(),
                             details: None,
                         };
@@ -587,11 +1758,112 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
 
                 match state
                     .delegation_manager
-                    .decline_work(work_item_id, participant_id)
+                    .pause_work(work_item_id, participant_id)
                     .await
                 {
-                    Ok(_) => {
-                        let msg = ServerMessage::WorkDeclined {
+                    Ok(work_item) => {
+                        update_work_presence(&state, &work_item).await;
+                        let msg = ServerMessage::WorkPaused {
+                            work_item_id,
+                            assignee_id: participant_id,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                            .await;
+                    }
+                    Err(e) => {
+                        let error = ServerMessage::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                    }
+                }
+            }
+            ClientMessage::ResumeWork { work_item_id } => {
+                let conn = conn_state.lock().await;
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let participant_id = match participant_id {
+                    Some(id) => id,
+                    None => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
+                            message: "Not registered with delegation system".to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
+
+                match state
+                    .delegation_manager
+                    .resume_work(work_item_id, participant_id)
+                    .await
+                {
+                    Ok(work_item) => {
+                        update_work_presence(&state, &work_item).await;
+                        let msg = ServerMessage::WorkResumed {
+                            work_item_id,
+                            assignee_id: participant_id,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                            .await;
+                    }
+                    Err(e) => {
+                        let error = ServerMessage::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                    }
+                }
+            }
+            ClientMessage::WithdrawWork { work_item_id } => {
+                let conn = conn_state.lock().await;
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let participant_id = match participant_id {
+                    Some(id) => id,
+                    None => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
+                            message: "Not registered with delegation system".to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
+
+                match state
+                    .delegation_manager
+                    .withdraw_submission(work_item_id, participant_id)
+                    .await
+                {
+                    Ok(work_item) => {
+                        update_work_presence(&state, &work_item).await;
+                        let msg = ServerMessage::SubmissionWithdrawn {
                             work_item_id,
                             assignee_id: participant_id,
                         };
@@ -602,6 +1874,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                     Err(e) => {
                         let error = ServerMessage::Error {
+                            code: e.code(),
                             message: e.to_string(),
                             details: None,
                         };
@@ -624,6 +1897,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     Some(id) => id,
                     None => {
                         let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
                             message: "Not registered with delegation system".to_string(),
                             details: None,
                         };
@@ -641,30 +1915,8 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     .await
                 {
                     Ok(work_item) => {
-                        if work_item.status == WorkItemStatus::AwaitingApproval {
-                            // Get the approval request
-                            let approvals = state
-                                .delegation_manager
-                                .get_approval_queue(work_item.get_approver_id())
-                                .await;
-                            if let Some(approval) =
-                                approvals.iter().find(|a| a.work_item_id == work_item_id)
-                            {
-                                let msg = ServerMessage::ApprovalRequested {
-                                    approval: approval.clone(),
-                                    work_item: work_item.clone(),
-                                };
-                                let mut sender = sender.lock().await;
-                                let _ = sender
-                                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                                    .await;
-                            }
-                        } else {
-                            let msg = ServerMessage::WorkApproved {
-                                work_item_id,
-                                approver_id: work_item.delegator_id,
-                                feedback: None,
-                            };
+                        update_work_presence(&state, &work_item).await;
+                        if let Some(msg) = submitted_work_message(&state, &work_item).await {
                             let mut sender = sender.lock().await;
                             let _ = sender
                                 .send(Message::Text(serde_json::to_string(&msg).unwrap()))
@@ -673,6 +1925,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                     Err(e) => {
                         let error = ServerMessage::Error {
+                            code: e.code(),
                             message: e.to_string(),
                             details: None,
                         };
@@ -695,6 +1948,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     Some(id) => id,
                     None => {
                         let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
                             message: "Not registered with delegation system".to_string(),
                             details: None,
                         };
@@ -712,10 +1966,18 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     .await
                 {
                     Ok((_, work_item)) => {
-                        let msg = ServerMessage::WorkApproved {
-                            work_item_id: work_item.id,
-                            approver_id: participant_id,
-                            feedback,
+                        let msg = if work_item.status == WorkItemStatus::Approved {
+                            ServerMessage::WorkApproved {
+                                work_item_id: work_item.id,
+                                approver_id: participant_id,
+                                feedback,
+                            }
+                        } else {
+                            ServerMessage::ApprovalGranted {
+                                approval_id,
+                                work_item_id: work_item.id,
+                                approver_id: participant_id,
+                            }
                         };
                         let mut sender = sender.lock().await;
                         let _ = sender
@@ -724,6 +1986,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                     Err(e) => {
                         let error = ServerMessage::Error {
+                            code: e.code(),
                             message: e.to_string(),
                             details: None,
                         };
@@ -746,6 +2009,107 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     Some(id) => id,
                     None => {
                         let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
+                            message: "Not registered with delegation system".to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
+
+                match state
+                    .delegation_manager
+                    .reject(approval_id, participant_id, &feedback)
+                    .await
+                {
+                    Ok((_, work_item)) => {
+                        let msg = ServerMessage::WorkRejected {
+                            work_item_id: work_item.id,
+                            approver_id: participant_id,
+                            feedback,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                            .await;
+                    }
+                    Err(e) => {
+                        let error = ServerMessage::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                    }
+                }
+            }
+            ClientMessage::CancelWork { work_item_id } => {
+                let conn = conn_state.lock().await;
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let participant_id = match participant_id {
+                    Some(id) => id,
+                    None => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
+                            message: "Not registered with delegation system".to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
+
+                match state
+                    .delegation_manager
+                    .cancel_work(work_item_id, participant_id)
+                    .await
+                {
+                    Ok(work_item) => {
+                        update_work_presence(&state, &work_item).await;
+                        let msg = ServerMessage::WorkCancelled {
+                            work_item_id,
+                            cancelled_by: participant_id,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                            .await;
+                    }
+                    Err(e) => {
+                        let error = ServerMessage::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                    }
+                }
+            }
+            ClientMessage::ClaimWork { work_item_id } => {
+                let conn = conn_state.lock().await;
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let participant_id = match participant_id {
+                    Some(id) => id,
+                    None => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
                             message: "Not registered with delegation system".to_string(),
                             details: None,
                         };
@@ -759,14 +2123,13 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
 
                 match state
                     .delegation_manager
-                    .reject(approval_id, participant_id, &feedback)
+                    .claim_work(work_item_id, participant_id)
                     .await
                 {
-                    Ok((_, work_item)) => {
-                        let msg = ServerMessage::WorkRejected {
-                            work_item_id: work_item.id,
-                            approver_id: participant_id,
-                            feedback,
+                    Ok(_) => {
+                        let msg = ServerMessage::WorkClaimed {
+                            work_item_id,
+                            claimed_by: participant_id,
                         };
                         let mut sender = sender.lock().await;
                         let _ = sender
@@ -775,6 +2138,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                     Err(e) => {
                         let error = ServerMessage::Error {
+                            code: e.code(),
                             message: e.to_string(),
                             details: None,
                         };
@@ -785,7 +2149,10 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                 }
             }
-            ClientMessage::CancelWork { work_item_id } => {
+            ClientMessage::ReassignWork {
+                work_item_id,
+                new_assignee_id,
+            } => {
                 let conn = conn_state.lock().await;
                 let participant_id = conn.delegation_registrations.values().next().copied();
                 drop(conn);
@@ -794,6 +2161,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     Some(id) => id,
                     None => {
                         let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
                             message: "Not registered with delegation system".to_string(),
                             details: None,
                         };
@@ -805,34 +2173,125 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                 };
 
-                match state
+                let msg = match state
                     .delegation_manager
-                    .cancel_work(work_item_id, participant_id)
+                    .reassign_work(work_item_id, new_assignee_id, participant_id)
                     .await
                 {
-                    Ok(_) => {
-                        let msg = ServerMessage::WorkCancelled {
+                    Ok((previous_assignee_id, work_item)) => {
+                        update_presence_for(&state, work_item.journal_id, previous_assignee_id)
+                            .await;
+                        update_work_presence(&state, &work_item).await;
+                        ServerMessage::WorkReassigned {
                             work_item_id,
-                            cancelled_by: participant_id,
+                            previous_assignee_id,
+                            new_assignee_id,
+                        }
+                    }
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::ReassignApprover {
+                approval_id,
+                new_approver_id,
+            } => {
+                let conn = conn_state.lock().await;
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let participant_id = match participant_id {
+                    Some(id) => id,
+                    None => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
+                            message: "Not registered with delegation system".to_string(),
+                            details: None,
                         };
                         let mut sender = sender.lock().await;
                         let _ = sender
-                            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
                             .await;
+                        continue;
                     }
-                    Err(e) => {
+                };
+
+                let msg = match state
+                    .delegation_manager
+                    .reassign_approver(approval_id, participant_id, new_approver_id)
+                    .await
+                {
+                    Ok(approval) => ServerMessage::ApproverReassigned {
+                        approval_id,
+                        work_item_id: approval.work_item_id,
+                        previous_approver_id: participant_id,
+                        new_approver_id,
+                    },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::AddWorkComment { work_item_id, text } => {
+                let conn = conn_state.lock().await;
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let participant_id = match participant_id {
+                    Some(id) => id,
+                    None => {
                         let error = ServerMessage::Error {
-                            message: e.to_string(),
+                            code: ErrorCode::NotRegistered,
+                            message: "Not registered with delegation system".to_string(),
                             details: None,
                         };
                         let mut sender = sender.lock().await;
                         let _ = sender
                             .send(Message::Text(serde_json::to_string(&error).unwrap()))
                             .await;
+                        continue;
                     }
-                }
+                };
+
+                let msg = if text.trim().is_empty() {
+                    ServerMessage::Error {
+                        code: ErrorCode::InvalidMessage,
+                        message: "Comment text cannot be empty".to_string(),
+                        details: None,
+                    }
+                } else {
+                    match state
+                        .delegation_manager
+                        .add_comment(work_item_id, participant_id, text)
+                        .await
+                    {
+                        Ok(comment) => ServerMessage::WorkCommentAdded { comment },
+                        Err(e) => ServerMessage::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                            details: None,
+                        },
+                    }
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
             }
-            ClientMessage::ClaimWork { work_item_id } => {
+            ClientMessage::GetWorkComments { work_item_id } => {
                 let conn = conn_state.lock().await;
                 let participant_id = conn.delegation_registrations.values().next().copied();
                 drop(conn);
@@ -841,6 +2300,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     Some(id) => id,
                     None => {
                         let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
                             message: "Not registered with delegation system".to_string(),
                             details: None,
                         };
@@ -852,45 +2312,317 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                 };
 
-                match state
+                let msg = match state
                     .delegation_manager
-                    .claim_work(work_item_id, participant_id)
+                    .get_comments(work_item_id, participant_id)
                     .await
                 {
-                    Ok(_) => {
-                        let msg = ServerMessage::WorkClaimed {
-                            work_item_id,
-                            claimed_by: participant_id,
-                        };
-                        let mut sender = sender.lock().await;
-                        let _ = sender
-                            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                            .await;
-                    }
-                    Err(e) => {
+                    Ok(comments) => ServerMessage::WorkComments {
+                        work_item_id,
+                        comments,
+                    },
+                    Err(e) => ServerMessage::Error {
+                        code: e.code(),
+                        message: e.to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::GetAuditLog { journal_id } => {
+                let conn = conn_state.lock().await;
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let participant_id = match participant_id {
+                    Some(id) => id,
+                    None => {
                         let error = ServerMessage::Error {
-                            message: e.to_string(),
+                            code: ErrorCode::NotRegistered,
+                            message: "Not registered with delegation system".to_string(),
                             details: None,
                         };
                         let mut sender = sender.lock().await;
                         let _ = sender
                             .send(Message::Text(serde_json::to_string(&error).unwrap()))
                             .await;
+                        continue;
                     }
-                }
+                };
+
+                let allowed = state
+                    .delegation_manager
+                    .get_participant(participant_id)
+                    .await
+                    .is_some_and(|p| p.has_capability(Capability::Approve));
+                let msg = if !allowed {
+                    ServerMessage::Error {
+                        code: ErrorCode::InsufficientCapability,
+                        message: "Reading the audit log requires the approve capability"
+                            .to_string(),
+                        details: None,
+                    }
+                } else {
+                    match state
+                        .delegation_manager
+                        .get_audit_log(journal_id, AUDIT_LOG_LIMIT)
+                        .await
+                    {
+                        Ok(entries) => ServerMessage::AuditLog {
+                            journal_id,
+                            entries,
+                        },
+                        Err(e) => ServerMessage::Error {
+                            code: e.code(),
+                            message: e.to_string(),
+                            details: None,
+                        },
+                    }
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::SubscribeDelegation => {
+                let mut conn = conn_state.lock().await;
+                let msg = if conn.delegation_registrations.is_empty() {
+                    ServerMessage::Error {
+                        code: ErrorCode::NotRegistered,
+                        message: "Not registered with delegation system".to_string(),
+                        details: None,
+                    }
+                } else {
+                    // Subscribing again keeps the one forwarder
+                    if conn.delegation_forwarder.is_none() {
+                        conn.delegation_forwarder = Some(spawn_delegation_forwarder(
+                            Arc::clone(&sender),
+                            Arc::clone(&state),
+                            Arc::clone(&conn_state),
+                        ));
+                    }
+                    ServerMessage::DelegationSubscribed
+                };
+                drop(conn);
+
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::GetWorkQueue { sorted, tag } => {
+                let conn = conn_state.lock().await;
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let items = if let Some(id) = participant_id {
+                    let mut items = if sorted {
+                        state.delegation_manager.get_work_queue_sorted(id).await
+                    } else {
+                        state.delegation_manager.get_work_queue(id).await
+                    };
+                    if let Some(tag) = tag {
+                        items.retain(|item| item.has_tag(&tag));
+                    }
+                    items
+                } else {
+                    vec![]
+                };
+
+                let msg = ServerMessage::WorkQueue { items };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
             }
-            ClientMessage::GetWorkQueue => {
+            ClientMessage::GetOverdueWork => {
                 let conn = conn_state.lock().await;
                 let participant_id = conn.delegation_registrations.values().next().copied();
                 drop(conn);
 
                 let items = if let Some(id) = participant_id {
-                    state.delegation_manager.get_work_queue(id).await
+                    state.delegation_manager.get_overdue_items(id).await
                 } else {
                     vec![]
                 };
 
-                let msg = ServerMessage::WorkQueue { items };
+                let msg = ServerMessage::OverdueWork { items };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::GetMyCapabilities => {
+                let participant_id = conn_state
+                    .lock()
+                    .await
+                    .delegation_registrations
+                    .values()
+                    .next()
+                    .copied();
+
+                let registered = match participant_id {
+                    Some(id) => state.delegation_manager.get_participant(id).await,
+                    None => None,
+                };
+                let msg = match registered {
+                    Some(registered) => ServerMessage::MyCapabilities {
+                        participant_id: registered.id(),
+                        capabilities: capability_names(&registered.capabilities),
+                    },
+                    None => ServerMessage::Error {
+                        code: ErrorCode::NotRegistered,
+                        message: "Not registered with delegation system".to_string(),
+                        details: None,
+                    },
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::GetParticipantCapabilities { participant_id } => {
+                let registered = conn_state
+                    .lock()
+                    .await
+                    .delegation_registrations
+                    .values()
+                    .next()
+                    .is_some();
+
+                let msg = if !registered {
+                    ServerMessage::Error {
+                        code: ErrorCode::NotRegistered,
+                        message: "Not registered with delegation system".to_string(),
+                        details: None,
+                    }
+                } else {
+                    match state
+                        .delegation_manager
+                        .get_participant(participant_id)
+                        .await
+                    {
+                        Some(participant) => ServerMessage::ParticipantCapabilities {
+                            participant_id,
+                            capabilities: capability_names(&participant.capabilities),
+                        },
+                        None => {
+                            let e = DelegationError::ParticipantNotFound(participant_id);
+                            ServerMessage::Error {
+                                code: e.code(),
+                                message: e.to_string(),
+                                details: None,
+                            }
+                        }
+                    }
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::GetWorkItem { work_item_id } => {
+                let registered = conn_state
+                    .lock()
+                    .await
+                    .delegation_registrations
+                    .values()
+                    .next()
+                    .is_some();
+
+                let msg = if !registered {
+                    ServerMessage::Error {
+                        code: ErrorCode::NotRegistered,
+                        message: "Not registered with delegation system".to_string(),
+                        details: None,
+                    }
+                } else {
+                    match state.delegation_manager.get_work_item(work_item_id).await {
+                        Some(work_item) => ServerMessage::WorkItem { work_item },
+                        None => ServerMessage::Error {
+                            code: ErrorCode::NotFound,
+                            message: format!("Work item not found: {}", work_item_id),
+                            details: None,
+                        },
+                    }
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::GetParticipantStats { participant_id } => {
+                let stats = state
+                    .delegation_manager
+                    .get_participant_stats(participant_id)
+                    .await;
+
+                let msg = ServerMessage::ParticipantStats {
+                    participant_id,
+                    stats,
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::GetJournalWork {
+                journal_id,
+                include_completed,
+            } => {
+                let items = state
+                    .delegation_manager
+                    .get_journal_work_items(journal_id, include_completed)
+                    .await;
+
+                let msg = ServerMessage::JournalWork { journal_id, items };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::GetUnassignedWork { journal_id } => {
+                let items = state
+                    .delegation_manager
+                    .get_unassigned_work(journal_id)
+                    .await;
+
+                let msg = ServerMessage::UnassignedWork { journal_id, items };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::SearchWork { query, journal_id } => {
+                let conn = conn_state.lock().await;
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let participant_id = match participant_id {
+                    Some(id) => id,
+                    None => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
+                            message: "Not registered with delegation system".to_string(),
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
+
+                let items = state
+                    .delegation_manager
+                    .search_work_items(participant_id, &query, journal_id)
+                    .await;
+
+                let msg = ServerMessage::WorkSearchResults { items };
                 let mut sender = sender.lock().await;
                 let _ = sender
                     .send(Message::Text(serde_json::to_string(&msg).unwrap()))
@@ -922,6 +2654,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     Some(id) => id,
                     None => {
                         let error = ServerMessage::Error {
+                            code: ErrorCode::NotRegistered,
                             message: "Not registered with delegation system".to_string(),
                             details: None,
                         };
@@ -950,6 +2683,7 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                     Err(e) => {
                         let error = ServerMessage::Error {
+                            code: e.code(),
                             message: e.to_string(),
                             details: None,
                         };
@@ -960,8 +2694,77 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     }
                 }
             }
-            ClientMessage::GetParticipants { journal_id: _ } => {
-                let participants = state.delegation_manager.list_available_participants().await;
+            ClientMessage::BulkSetAcceptingWork {
+                participant_ids,
+                accepting,
+            } => {
+                let conn = conn_state.lock().await;
+                let participant_id = conn.delegation_registrations.values().next().copied();
+                drop(conn);
+
+                let is_coordinator = match participant_id {
+                    Some(id) => state
+                        .delegation_manager
+                        .get_participant(id)
+                        .await
+                        .is_some_and(|p| p.has_capability(Capability::Coordinate)),
+                    None => false,
+                };
+
+                let msg = if !is_coordinator {
+                    ServerMessage::Error {
+                        code: ErrorCode::InsufficientCapability,
+                        message: "Bulk availability changes require the coordinate capability"
+                            .to_string(),
+                        details: None,
+                    }
+                } else {
+                    let unknown = state
+                        .delegation_manager
+                        .bulk_set_accepting_work(&participant_ids, accepting)
+                        .await;
+                    let updated = participant_ids
+                        .into_iter()
+                        .filter(|id| !unknown.contains(id))
+                        .collect();
+                    ServerMessage::BulkAcceptingWorkChanged {
+                        accepting,
+                        updated,
+                        unknown,
+                    }
+                };
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+            ClientMessage::GetParticipants {
+                journal_id: _,
+                required_capability,
+            } => {
+                let required = match required_capability
+                    .as_deref()
+                    .map(str::parse::<Capability>)
+                    .transpose()
+                {
+                    Ok(required) => required,
+                    Err(message) => {
+                        let error = ServerMessage::Error {
+                            code: ErrorCode::InvalidMessage,
+                            message,
+                            details: None,
+                        };
+                        let mut sender = sender.lock().await;
+                        let _ = sender
+                            .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                            .await;
+                        continue;
+                    }
+                };
+                let participants = state
+                    .delegation_manager
+                    .list_available_participants(required)
+                    .await;
                 let msg = ServerMessage::AvailableParticipants { participants };
                 let mut sender = sender.lock().await;
                 let _ = sender
@@ -971,54 +2774,447 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
         }
     }
 
-    // Cleanup: Leave all subscribed rooms and unregister from delegation
+    // Cleanup: Stop forwarding and leave all subscribed rooms. Delegation
+    // registrations stay so a reconnecting client keeps its work.
     let conn = conn_state.lock().await;
+    for forwarder in conn.forwarders.values() {
+        forwarder.abort();
+    }
+    if let Some(forwarder) = &conn.delegation_forwarder {
+        forwarder.abort();
+    }
     for (journal_id, participant_id) in conn.subscriptions.iter() {
         if let Some(room) = state.room_manager.get(*journal_id).await {
             room.leave(*participant_id).await;
         }
     }
-    // Unregister from delegation system
-    for (_, participant_id) in conn.delegation_registrations.iter() {
-        state
-            .delegation_manager
-            .unregister_participant(*participant_id)
+    // Release their work if they don't come back within the grace period
+    if let Some(grace) = state.delegation_manager.release_grace() {
+        let departed_at = chrono::Utc::now();
+        for &participant_id in conn.delegation_registrations.values() {
+            let state = state.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(grace).await;
+                state
+                    .delegation_manager
+                    .unregister_if_departed(participant_id, departed_at)
+                    .await;
+            });
+        }
+    }
+}
+
+/// Mirror an assignee's delegated workload into their journal presence
+///
+/// The assignee shows as busy while any of their work in the journal is
+/// active. Assignees that are registered for delegation but not subscribed
+/// to the journal have no presence to update and are skipped.
+async fn update_work_presence(state: &Arc<AppState>, work_item: &WorkItem) {
+    update_presence_for(state, work_item.journal_id, work_item.assignee_id).await;
+}
+
+/// Mark `participant_id` busy in `journal_id` while it has active work there
+async fn update_presence_for(state: &Arc<AppState>, journal_id: Uuid, participant_id: Uuid) {
+    let Some(room) = state.room_manager.get(journal_id).await else {
+        return;
+    };
+    let Some(presence) = room.get_participant(participant_id).await else {
+        return;
+    };
+
+    let busy = state
+        .delegation_manager
+        .get_work_queue(participant_id)
+        .await
+        .iter()
+        .any(|item| item.journal_id == journal_id && item.status.is_active());
+
+    if busy {
+        room.set_status(presence.id, ParticipantStatus::Busy).await;
+    } else if presence.status == ParticipantStatus::Busy {
+        room.set_status(presence.id, ParticipantStatus::Active)
             .await;
     }
 }
 
+/// Capability names as sent to clients
+fn capability_names(capabilities: &CapabilitySet) -> Vec<String> {
+    capabilities
+        .to_vec()
+        .iter()
+        .map(|c| c.as_str().to_string())
+        .collect()
+}
+
+/// Work out which participants joined and left relative to a client's known set.
+///
+/// The subscriber itself is never reported. Returns `None` when the client
+/// knows nobody or its view is stale enough that the diff would be no smaller
+/// than the full list.
+fn presence_diff(
+    known: &[Uuid],
+    current: &[Participant],
+    self_id: Uuid,
+) -> Option<(Vec<Participant>, Vec<Uuid>)> {
+    if known.is_empty() {
+        return None;
+    }
+
+    let known: std::collections::HashSet<Uuid> =
+        known.iter().copied().filter(|id| *id != self_id).collect();
+    let current_ids: std::collections::HashSet<Uuid> = current.iter().map(|p| p.id).collect();
+
+    let joined: Vec<Participant> = current
+        .iter()
+        .filter(|p| p.id != self_id && !known.contains(&p.id))
+        .cloned()
+        .collect();
+    let left: Vec<Uuid> = known
+        .iter()
+        .filter(|id| !current_ids.contains(id))
+        .copied()
+        .collect();
+
+    if joined.len() + left.len() >= current.len() {
+        return None;
+    }
+
+    Some((joined, left))
+}
+
+/// Participants to report in a presence snapshot
+///
+/// With `collapse_observers`, observers are left out and only counted; everyone
+/// else is always sent in full.
+fn presence_snapshot(
+    participants: Vec<Participant>,
+    collapse_observers: bool,
+) -> (Vec<Participant>, Option<usize>) {
+    if !collapse_observers {
+        return (participants, None);
+    }
+
+    let (observers, others): (Vec<_>, Vec<_>) = participants
+        .into_iter()
+        .partition(|p| p.kind == ParticipantKind::Observer);
+    (others, Some(observers.len()))
+}
+
+/// Make sure a journal exists before a room is opened for it
+///
+/// Unknown journals are rejected unless `create` is set, in which case the
+/// journal is created with the requested ID.
+async fn ensure_journal(
+    state: &Arc<AppState>,
+    journal_id: Uuid,
+    create: bool,
+) -> error::Result<()> {
+    match state.store.get_journal(journal_id).await {
+        Err(error::AppError::NotFound(_)) if create => state
+            .store
+            .create_journal_with_id(journal_id, None)
+            .await
+            .map(|_| ()),
+        result => result.map(|_| ()),
+    }
+}
+
+/// Refuse unless this connection's role in the journal passes `allowed`
+///
+/// The role comes from the connection's subscription, so a connection that
+/// hasn't subscribed to the journal may not change it.
+async fn check_journal_role(
+    state: &AppState,
+    conn_state: &Mutex<ConnectionState>,
+    journal_id: Uuid,
+    allowed: fn(&JournalRole) -> bool,
+) -> error::Result<()> {
+    let participant_id = conn_state
+        .lock()
+        .await
+        .subscriptions
+        .get(&journal_id)
+        .copied();
+    let role = match (participant_id, state.room_manager.get(journal_id).await) {
+        (Some(participant_id), Some(room)) => room.role(participant_id),
+        _ => None,
+    };
+    match role {
+        Some(role) if allowed(&role) => Ok(()),
+        Some(role) => Err(error::AppError::Forbidden(format!(
+            "A {} of journal {} may not do that",
+            role.as_str(),
+            journal_id
+        ))),
+        None => Err(error::AppError::Forbidden(format!(
+            "Subscribe to journal {} before changing it",
+            journal_id
+        ))),
+    }
+}
+
+/// Give `participant_id` a new role, if this connection owns the journal
+async fn set_journal_role(
+    state: &AppState,
+    conn_state: &Mutex<ConnectionState>,
+    journal_id: Uuid,
+    participant_id: Uuid,
+    role: JournalRole,
+) -> Result<(), ServerMessage> {
+    let caller = conn_state
+        .lock()
+        .await
+        .subscriptions
+        .get(&journal_id)
+        .copied();
+    let room = state.room_manager.get(journal_id).await;
+    let (Some(caller), Some(room)) = (caller, room) else {
+        return Err(ServerMessage::Error {
+            code: ErrorCode::NotAuthorized,
+            message: format!("Subscribe to journal {} before changing roles", journal_id),
+            details: None,
+        });
+    };
+    if !room.role(caller).is_some_and(|r| r.can_manage()) {
+        return Err(ServerMessage::Error {
+            code: ErrorCode::NotAuthorized,
+            message: format!("Only an owner of journal {} may change roles", journal_id),
+            details: None,
+        });
+    }
+
+    let to_message = |e: error::AppError| ServerMessage::Error {
+        code: e.code(),
+        message: e.to_string(),
+        details: None,
+    };
+    room.set_role(participant_id, role, Some(caller))
+        .await
+        .map_err(to_message)?;
+    if let Some(member) = room.member(participant_id) {
+        state
+            .store
+            .set_journal_role(journal_id, &member, role)
+            .await
+            .map_err(to_message)?;
+    }
+    Ok(())
+}
+
+/// Push delegation events to a connection as they happen
+fn spawn_delegation_forwarder(
+    sender: Arc<Mutex<ClientSink>>,
+    state: Arc<AppState>,
+    conn_state: Arc<Mutex<ConnectionState>>,
+) -> tokio::task::AbortHandle {
+    let mut events = state.delegation_manager.subscribe();
+    let task = tokio::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                    tracing::warn!("Delegation subscriber missed {} events", missed);
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+
+            let participants: Vec<Uuid> = conn_state
+                .lock()
+                .await
+                .delegation_registrations
+                .values()
+                .copied()
+                .collect();
+            let work_item = match event.work_item_id() {
+                Some(id) => state.delegation_manager.get_work_item(id).await,
+                None => None,
+            };
+            if !delegation_event_concerns(&event, work_item.as_ref(), &participants) {
+                continue;
+            }
+
+            let msg = match work_item {
+                Some(work_item) if matches!(event, DelegationEvent::WorkDelegated { .. }) => {
+                    ServerMessage::WorkDelegated { work_item }
+                }
+                _ => ServerMessage::DelegationEvent { event },
+            };
+            let mut sender = sender.lock().await;
+            if sender
+                .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+    task.abort_handle()
+}
+
+/// Whether a connection with `participants` registered should hear about `event`
+///
+/// It should if one of them is delegator, assignee or approver of the work,
+/// was just taken off it, or had their capabilities changed. Actions they
+/// took themselves are left out, since the request already got a reply.
+fn delegation_event_concerns(
+    event: &DelegationEvent,
+    work_item: Option<&WorkItem>,
+    participants: &[Uuid],
+) -> bool {
+    let ours = |id: &Uuid| participants.contains(id);
+    match event {
+        DelegationEvent::CapabilitiesChanged { participant_id, .. } => return ours(participant_id),
+        DelegationEvent::ApproverReassigned {
+            previous_approver_id,
+            ..
+        } if ours(previous_approver_id) => return true,
+        DelegationEvent::WorkReassigned {
+            previous_assignee_id,
+            reassigned_by,
+            ..
+        } if ours(previous_assignee_id) && !ours(reassigned_by) => return true,
+        _ if event.actor_id().is_some_and(|actor| ours(&actor)) => return false,
+        _ => {}
+    }
+    work_item.is_some_and(|item| participants.iter().any(|p| item.involves(*p)))
+}
+
+/// What a client asked for when subscribing to a journal
+struct SubscribeOptions {
+    name: String,
+    participant_kind: ParticipantKind,
+    /// Participants the client already knows about, for a presence diff
+    known_participants: Vec<Uuid>,
+    collapse_observers: bool,
+}
+
 /// Handle subscription to a journal
 async fn handle_subscribe(
-    sender: Arc<Mutex<futures::stream::SplitSink<WebSocket, Message>>>,
+    sender: Arc<Mutex<ClientSink>>,
     state: &Arc<AppState>,
     conn_state: Arc<Mutex<ConnectionState>>,
     journal_id: Uuid,
-    name: String,
-    kind: Option<String>,
+    options: SubscribeOptions,
 ) {
-    let participant_kind = kind
-        .as_deref()
-        .and_then(|k| k.parse().ok())
-        .unwrap_or(ParticipantKind::User);
+    let SubscribeOptions {
+        name,
+        participant_kind,
+        known_participants,
+        collapse_observers,
+    } = options;
+    // Share the delegation identity if already registered for this journal,
+    // and fall back to the token's owner when the client gives no name
+    let (registered_id, identity, name) = {
+        let conn = conn_state.lock().await;
+        let name = match &conn.identity {
+            Some(identity) if name.trim().is_empty() => identity.clone(),
+            _ => name,
+        };
+        (
+            conn.delegation_registrations.get(&journal_id).copied(),
+            conn.identity.clone(),
+            name,
+        )
+    };
 
-    let room = state.room_manager.get_or_create(journal_id).await;
-    let participant = room.join(name, participant_kind).await;
+    let room = match state.room_manager.get_or_create(journal_id).await {
+        Ok(room) => room,
+        Err(e) => {
+            let error = ServerMessage::Error {
+                code: ErrorCode::Internal,
+                message: e.to_string(),
+                details: None,
+            };
+            let mut sender_guard = sender.lock().await;
+            let _ = sender_guard
+                .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                .await;
+            return;
+        }
+    };
+    let joined = match registered_id {
+        Some(id) => {
+            room.rejoin(Participant::with_id(id, name, participant_kind))
+                .await
+        }
+        None => room.join(name, participant_kind).await,
+    };
+    let participant = match joined {
+        Ok(participant) => participant,
+        Err(e) => {
+            let error = ServerMessage::Error {
+                code: ErrorCode::NameTaken,
+                message: e.to_string(),
+                details: None,
+            };
+            let mut sender_guard = sender.lock().await;
+            let _ = sender_guard
+                .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                .await;
+            return;
+        }
+    };
     let participant_id = participant.id;
 
+    // Roles are kept per token identity, so they follow the person across
+    // connections; without auth the participant ID is all there is
+    let member = identity.unwrap_or_else(|| participant_id.to_string());
+    let joined_as = room.role(participant_id).unwrap_or(JournalRole::Guest);
+    let role = match state
+        .store
+        .claim_journal_role(journal_id, &member, joined_as)
+        .await
+    {
+        Ok(role) => role,
+        Err(e) => {
+            tracing::error!("Failed to record role in journal {}: {}", journal_id, e);
+            JournalRole::Guest
+        }
+    };
+    room.adopt_role(participant_id, member, role).await;
+
     // Store subscription
     {
         let mut conn = conn_state.lock().await;
         conn.subscriptions.insert(journal_id, participant_id);
+        if collapse_observers {
+            conn.collapsed_presence.insert(journal_id);
+        } else {
+            conn.collapsed_presence.remove(&journal_id);
+        }
     }
 
     // Get current participants
     let participants = room.participants().await;
 
-    // Send subscribed confirmation
-    let msg = ServerMessage::Subscribed {
-        journal_id,
-        participant: participant.clone(),
-        participants,
+    // Send subscribed confirmation, as a diff when the client's view is usable.
+    // Collapsed presence is always sent whole; without observers it stays small.
+    let diff = if collapse_observers {
+        None
+    } else {
+        presence_diff(&known_participants, &participants, participant_id)
+    };
+    let msg = match diff {
+        Some((joined, left)) => ServerMessage::SubscribedDiff {
+            journal_id,
+            participant: participant.clone(),
+            role,
+            joined,
+            left,
+        },
+        None => {
+            let (participants, observer_count) =
+                presence_snapshot(participants, collapse_observers);
+            ServerMessage::Subscribed {
+                journal_id,
+                participant: participant.clone(),
+                role,
+                participants,
+                observer_count,
+            }
+        }
     };
     {
         let mut sender_guard = sender.lock().await;
@@ -1033,9 +3229,49 @@ async fn handle_subscribe(
     // Spawn task to forward room events to this client
     let mut room_rx = room.subscribe();
     let sender_clone = Arc::clone(&sender);
-
-    tokio::spawn(async move {
-        while let Ok(event) = room_rx.recv().await {
+    let (binary_crdt, connection_id, crdt_ack) = {
+        let mut conn = conn_state.lock().await;
+        let crdt_ack = Arc::new(AtomicU64::new(0));
+        conn.crdt_acks.insert(journal_id, Arc::clone(&crdt_ack));
+        (Arc::clone(&conn.binary_crdt), conn.id, crdt_ack)
+    };
+    let sync_chunk_bytes = state.room_manager.sync_chunk_bytes();
+    // Weak so the forwarder doesn't keep the room (and its channel) alive
+    let weak_room = Arc::downgrade(&room);
+
+    let forwarder = tokio::spawn(async move {
+        loop {
+            let event = match room_rx.recv().await {
+                Ok(event) => event,
+                // Streaming floods the channel; skip what was missed rather than
+                // stop, but resend CRDT updates the client hasn't acknowledged
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                    tracing::warn!(
+                        "Subscriber to journal {} missed {} events",
+                        journal_id,
+                        missed
+                    );
+                    let Some(room) = weak_room.upgrade() else {
+                        break;
+                    };
+                    let from_seq = crdt_ack.load(Ordering::Relaxed) + 1;
+                    let mut sender_guard = sender_clone.lock().await;
+                    if send_crdt_replay(
+                        &mut sender_guard,
+                        &room,
+                        journal_id,
+                        from_seq,
+                        sync_chunk_bytes,
+                    )
+                    .await
+                    .is_err()
+                    {
+                        break;
+                    }
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
             let server_msg = match event {
                 RoomEvent::ParticipantJoined(p) => {
                     // Don't send our own join event
@@ -1057,6 +3293,8 @@ async fn handle_subscribe(
                     participant_id: pid,
                     block_id,
                     offset,
+                    anchor,
+                    head,
                 } => {
                     // Don't echo our own cursor moves
                     if pid == participant_id {
@@ -1067,6 +3305,8 @@ async fn handle_subscribe(
                         participant_id: pid,
                         block_id,
                         offset,
+                        anchor,
+                        head,
                     })
                 }
                 RoomEvent::StatusChanged {
@@ -1077,47 +3317,213 @@ async fn handle_subscribe(
                     participant_id: pid,
                     status,
                 }),
-                RoomEvent::CrdtUpdate { source, update } => {
+                RoomEvent::TypingChanged {
+                    participant_id: pid,
+                    typing,
+                } => {
+                    if pid == participant_id {
+                        continue;
+                    }
+                    Some(ServerMessage::TypingChanged {
+                        journal_id,
+                        participant_id: pid,
+                        typing,
+                    })
+                }
+                RoomEvent::CrdtUpdate {
+                    source,
+                    seq,
+                    update,
+                } => {
                     // Don't echo our own updates
                     if source == Some(participant_id) {
                         continue;
                     }
+                    if binary_crdt.load(Ordering::Relaxed) {
+                        let frame = BinaryFrame::new(Opcode::CrdtUpdate, journal_id, update);
+                        let mut sender_guard = sender_clone.lock().await;
+                        if sender_guard
+                            .send(Message::Binary(frame.encode()))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                        continue;
+                    }
                     Some(ServerMessage::CrdtUpdate {
                         journal_id,
                         source,
+                        seq,
                         update: base64_encode(&update),
                     })
                 }
-                RoomEvent::SyncState { state } => Some(ServerMessage::SyncState {
-                    journal_id,
-                    state: base64_encode(&state),
-                }),
+                RoomEvent::BlockEvent { origin, event } => {
+                    // The originating connection was sent it directly
+                    if origin == connection_id {
+                        continue;
+                    }
+                    Some(ServerMessage::from(event))
+                }
+                RoomEvent::RoleChanged {
+                    source,
+                    participant_id: changed,
+                    role,
+                } => {
+                    // The owner who changed it already got a direct reply
+                    if source == Some(participant_id) {
+                        continue;
+                    }
+                    Some(ServerMessage::RoleChanged {
+                        journal_id,
+                        participant_id: changed,
+                        role,
+                    })
+                }
+                RoomEvent::JournalRenamed { origin, title } => {
+                    // The renaming connection already got a direct reply
+                    if origin == Some(connection_id) {
+                        continue;
+                    }
+                    Some(ServerMessage::JournalRenamed { journal_id, title })
+                }
+                RoomEvent::SyncState { state } => {
+                    if binary_crdt.load(Ordering::Relaxed) {
+                        let frame = BinaryFrame::new(Opcode::SyncState, journal_id, state);
+                        let mut sender_guard = sender_clone.lock().await;
+                        if sender_guard
+                            .send(Message::Binary(frame.encode()))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                        continue;
+                    }
+                    let mut sender_guard = sender_clone.lock().await;
+                    if send_sync_state(&mut sender_guard, journal_id, &state, sync_chunk_bytes)
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                    continue;
+                }
+            };
+
+            if let Some(msg) = server_msg {
+                let mut sender_guard = sender_clone.lock().await;
+                if sender_guard
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await
+                    .is_err()
+                {
+                    // Connection closed
+                    break;
+                }
+            }
+        }
+    });
+
+    // A re-subscribe replaces the previous forwarder rather than doubling events
+    if let Some(previous) = conn_state
+        .lock()
+        .await
+        .forwarders
+        .insert(journal_id, forwarder.abort_handle())
+    {
+        previous.abort();
+    }
+}
+
+/// Handle a binary CRDT frame (see [`crate::frame`] for the wire format)
+async fn handle_binary_frame(
+    sender: &Arc<Mutex<ClientSink>>,
+    state: &Arc<AppState>,
+    conn_state: &Arc<Mutex<ConnectionState>>,
+    data: &[u8],
+) {
+    let opted_in = conn_state.lock().await.binary_crdt.load(Ordering::Relaxed);
+    let frame = if opted_in {
+        BinaryFrame::decode(data)
+    } else {
+        Err("Binary frames require a hello with binary_crdt enabled".to_string())
+    };
+
+    let frame = match frame {
+        Ok(frame) => frame,
+        Err(message) => {
+            let error = ServerMessage::Error {
+                code: ErrorCode::InvalidMessage,
+                message,
+                details: None,
+            };
+            let mut sender = sender.lock().await;
+            let _ = sender
+                .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                .await;
+            return;
+        }
+    };
+
+    let Some(room) = state.room_manager.get(frame.journal_id).await else {
+        return;
+    };
+
+    match frame.opcode {
+        Opcode::CrdtUpdate => {
+            let participant_id = {
+                let conn = conn_state.lock().await;
+                conn.subscriptions.get(&frame.journal_id).copied()
+            };
+            if let Err(e) = room.apply_update(participant_id, &frame.payload).await {
+                tracing::error!("Failed to apply CRDT update: {:?}", e);
+            }
+        }
+        Opcode::SyncRequest => {
+            let state_data = if frame.payload.is_empty() {
+                Some(room.get_sync_state())
+            } else {
+                room.doc().encode_diff(&frame.payload).ok()
             };
 
-            if let Some(msg) = server_msg {
-                let mut sender_guard = sender_clone.lock().await;
-                if sender_guard
-                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                    .await
-                    .is_err()
-                {
-                    // Connection closed
-                    break;
+            if let Some(data) = state_data {
+                let reply = BinaryFrame::new(Opcode::SyncState, frame.journal_id, data);
+                let mut sender = sender.lock().await;
+                if let Err(e) = sender.send(Message::Binary(reply.encode())).await {
+                    tracing::error!("Failed to send sync state: {}", e);
                 }
             }
         }
-    });
+        Opcode::SyncState => {
+            let error = ServerMessage::Error {
+                code: ErrorCode::InvalidMessage,
+                message: "Sync state frames are server-to-client only".to_string(),
+                details: None,
+            };
+            let mut sender = sender.lock().await;
+            let _ = sender
+                .send(Message::Text(serde_json::to_string(&error).unwrap()))
+                .await;
+        }
+    }
 }
 
 /// Handle unsubscription from a journal
 async fn handle_unsubscribe(
-    sender: Arc<Mutex<futures::stream::SplitSink<WebSocket, Message>>>,
+    sender: Arc<Mutex<ClientSink>>,
     state: &Arc<AppState>,
     conn_state: Arc<Mutex<ConnectionState>>,
     journal_id: Uuid,
 ) {
     let participant_id = {
         let mut conn = conn_state.lock().await;
+        if let Some(forwarder) = conn.forwarders.remove(&journal_id) {
+            forwarder.abort();
+        }
+        conn.crdt_acks.remove(&journal_id);
+        conn.partial_updates.remove(&journal_id);
+        conn.collapsed_presence.remove(&journal_id);
         conn.subscriptions.remove(&journal_id)
     };
 
@@ -1134,10 +3540,127 @@ async fn handle_unsubscribe(
         .await;
 }
 
+/// Resend the CRDT updates numbered `from_seq` onwards as text frames
+///
+/// Falls back to the full sync state if the room no longer holds them all.
+async fn send_crdt_replay(
+    sender: &mut ClientSink,
+    room: &JournalRoom,
+    journal_id: Uuid,
+    from_seq: u64,
+    chunk_bytes: usize,
+) -> Result<(), axum::Error> {
+    let Some(updates) = room.updates_since(from_seq) else {
+        return send_sync_state(sender, journal_id, &room.get_sync_state(), chunk_bytes).await;
+    };
+    for update in updates {
+        let msg = ServerMessage::CrdtUpdate {
+            journal_id,
+            source: update.source,
+            seq: update.seq,
+            update: base64_encode(&update.update),
+        };
+        sender
+            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Send a sync state as text frames
+///
+/// States up to `chunk_bytes` go out as a single `SyncState`, as before. Larger
+/// ones are split into numbered chunks, the last marked `final`, each encoded
+/// into the same buffer.
+async fn send_sync_state(
+    sender: &mut ClientSink,
+    journal_id: Uuid,
+    state: &[u8],
+    chunk_bytes: usize,
+) -> Result<(), axum::Error> {
+    if state.len() <= chunk_bytes {
+        let msg = ServerMessage::SyncState {
+            journal_id,
+            state: base64_encode(state),
+            seq: None,
+            last: None,
+        };
+        return sender
+            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+            .await;
+    }
+
+    let count = state.len().div_ceil(chunk_bytes);
+    let mut buf = String::with_capacity(chunk_bytes.div_ceil(3) * 4);
+    for (i, chunk) in state.chunks(chunk_bytes).enumerate() {
+        buf.clear();
+        base64_encode_into(chunk, &mut buf);
+        let msg = ServerMessage::SyncState {
+            journal_id,
+            state: std::mem::take(&mut buf),
+            seq: Some(i as u32),
+            last: Some(i + 1 == count),
+        };
+        let text = serde_json::to_string(&msg).unwrap();
+        if let ServerMessage::SyncState { state, .. } = msg {
+            buf = state;
+        }
+        sender.send(Message::Text(text)).await?;
+    }
+    Ok(())
+}
+
+/// Add one chunk of a client's chunked CRDT update
+///
+/// Returns the whole update once the final chunk arrives. Chunks must arrive
+/// in order starting from 0 and add up to no more than `max_bytes`; anything
+/// else discards what was collected.
+fn reassemble_update(
+    partial: &mut std::collections::HashMap<Uuid, (u32, Vec<u8>)>,
+    journal_id: Uuid,
+    seq: u32,
+    last: Option<bool>,
+    bytes: Vec<u8>,
+    max_bytes: usize,
+) -> Result<Option<Vec<u8>>, String> {
+    let expected = partial.get(&journal_id).map_or(0, |(next, _)| *next);
+    if seq != expected {
+        partial.remove(&journal_id);
+        return Err(format!(
+            "Out of order CRDT update chunk: expected {}, got {}",
+            expected, seq
+        ));
+    }
+
+    let (next, buffer) = partial.entry(journal_id).or_default();
+    if buffer.len() + bytes.len() > max_bytes {
+        partial.remove(&journal_id);
+        return Err(format!(
+            "Chunked CRDT update exceeds {} bytes; chunks discarded",
+            max_bytes
+        ));
+    }
+    *next += 1;
+    buffer.extend_from_slice(&bytes);
+
+    if last.unwrap_or(false) {
+        Ok(partial.remove(&journal_id).map(|(_, bytes)| bytes))
+    } else {
+        Ok(None)
+    }
+}
+
 /// Base64 encode helper
 fn base64_encode(data: &[u8]) -> String {
-    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
     let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    base64_encode_into(data, &mut result);
+    result
+}
+
+/// Base64 encode, appending to `result`
+fn base64_encode_into(data: &[u8], result: &mut String) {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    result.reserve(data.len().div_ceil(3) * 4);
 
     for chunk in data.chunks(3) {
         let b0 = chunk[0] as usize;
@@ -1159,8 +3682,6 @@ fn base64_encode(data: &[u8]) -> String {
             result.push('=');
         }
     }
-
-    result
 }
 
 /// Base64 decode helper
@@ -1210,139 +3731,446 @@ fn base64_decode(data: &str) -> Result<Vec<u8>, String> {
     Ok(result)
 }
 
-async fn handle_submit(
-    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
-    state: &Arc<AppState>,
+/// Reuse `session_id`, or create a session running `model`
+async fn resolve_session(
     opencode: &OpenCodeClient,
-    journal_id: Uuid,
-    content: String,
     session_id: Option<String>,
-) -> error::Result<()> {
-    // Create user block
-    let user_block = state
-        .store
-        .create_block(journal_id, BlockType::User, &content)
+    model: Option<String>,
+    system_prompt: Option<String>,
+) -> error::Result<String> {
+    if let Some(id) = session_id {
+        if let Some(model) = model {
+            tracing::warn!(
+                "Ignoring model {} for existing session {}; models are chosen when a session is created",
+                model,
+                id
+            );
+        }
+        if system_prompt.is_some() {
+            tracing::warn!(
+                "Existing session {} keeps its system prompt; the new one is only recorded in the journal",
+                id
+            );
+        }
+        return Ok(id);
+    }
+
+    let session = opencode
+        .create_session(crate::opencode::CreateSessionRequest {
+            model,
+            system_prompt,
+        })
         .await?;
+    Ok(session.id)
+}
 
-    // Send block created
-    let msg = ServerMessage::BlockCreated {
-        block: user_block.clone(),
+/// Pass `result` through, first failing `block` if OpenCode timed out
+///
+/// Without this a timed-out block would be left pending or streaming forever.
+async fn fail_on_timeout<T>(
+    result: error::Result<T>,
+    sender: &mut ClientSink,
+    state: &AppState,
+    connection_id: Uuid,
+    block: &crate::models::Block,
+    version: i64,
+) -> error::Result<T> {
+    let Err(err @ error::AppError::OpenCodeTimeout(_)) = &result else {
+        return result;
     };
-    sender
-        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-        .await
-        .map_err(|e| error::AppError::Internal(e.to_string()))?;
+    tracing::warn!("{} on block {}", err, block.id);
 
-    // Create assistant block (pending)
-    let assistant_block = state
+    state
+        .store
+        .update_block_content(block.id, OPENCODE_TIMEOUT_MESSAGE, version)
+        .await?;
+    state
         .store
-        .create_block(journal_id, BlockType::Assistant, "")
+        .update_block_status(block.id, BlockStatus::Error)
         .await?;
+    send_block_event(
+        sender,
+        state,
+        connection_id,
+        block.journal_id,
+        BlockEvent::StatusChanged {
+            block_id: block.id,
+            status: BlockStatus::Error,
+        },
+    )
+    .await;
 
-    let msg = ServerMessage::BlockCreated {
-        block: assistant_block.clone(),
-    };
-    sender
+    result
+}
+
+/// Send a block event to this client and relay it to the journal's other subscribers
+///
+/// A failed write to this client is only logged: the client may have gone
+/// while its response streams, and the room still needs the event.
+async fn send_block_event(
+    sender: &mut ClientSink,
+    state: &AppState,
+    connection_id: Uuid,
+    journal_id: Uuid,
+    event: BlockEvent,
+) {
+    if let Some(room) = state.room_manager.get(journal_id).await {
+        room.broadcast_block_event(connection_id, event.clone());
+    }
+
+    let msg = ServerMessage::from(event);
+    if let Err(e) = sender
         .send(Message::Text(serde_json::to_string(&msg).unwrap()))
         .await
-        .map_err(|e| error::AppError::Internal(e.to_string()))?;
+    {
+        tracing::debug!(
+            "Failed to send block event to connection {}: {}",
+            connection_id,
+            e
+        );
+    }
+}
 
-    // Get or create session
-    let session_id = match session_id {
-        Some(id) => id,
-        None => {
-            let session = opencode
-                .create_session(crate::opencode::CreateSessionRequest {
-                    model: None,
-                    system_prompt: None,
-                })
-                .await?;
-            session.id
+/// Save the token counts OpenCode reported and tell the journal about them
+///
+/// Failing to save them isn't worth failing the response over.
+async fn record_usage(
+    sender: &mut ClientSink,
+    state: &AppState,
+    connection_id: Uuid,
+    journal_id: Uuid,
+    block_id: Uuid,
+    usage: UsageEvent,
+) {
+    let usage = BlockUsage {
+        prompt_tokens: usage.prompt_tokens,
+        completion_tokens: usage.completion_tokens,
+    };
+    if let Err(e) = state.store.set_block_usage(block_id, usage).await {
+        tracing::warn!("Failed to save token usage for block {}: {}", block_id, e);
+    }
+    send_block_event(
+        sender,
+        state,
+        connection_id,
+        journal_id,
+        BlockEvent::Usage { block_id, usage },
+    )
+    .await;
+}
+
+/// Answer a repeated submit by re-sending the blocks the first one created
+///
+/// Blocks are sent as they are now, so a retry that arrives after the response
+/// finished gets the full content rather than a pending block.
+async fn replay_submit(
+    sender: &mut ClientSink,
+    state: &AppState,
+    block_ids: &[Uuid],
+) -> error::Result<()> {
+    for &block_id in block_ids {
+        let block = state.store.get_block(block_id).await?;
+        let msg = ServerMessage::BlockCreated { block };
+        sender
+            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+            .await
+            .map_err(|e| error::AppError::Internal(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// A prompt and the response that completed it
+struct Exchange {
+    prompt: String,
+    response: String,
+}
+
+/// Run a submit through to the end of its response
+///
+/// Returns the exchange if the response completed, so the journal can be titled from it.
+#[allow(clippy::too_many_arguments)]
+async fn handle_submit(
+    sender: &Arc<Mutex<ClientSink>>,
+    state: &Arc<AppState>,
+    opencode: &OpenCodeClient,
+    connection_id: Uuid,
+    journal_id: Uuid,
+    content: String,
+    session_id: Option<String>,
+    model: Option<String>,
+    system_prompt: Option<String>,
+    idempotency_key: Option<String>,
+) -> error::Result<Option<Exchange>> {
+    if let Some(key) = &idempotency_key {
+        if let Some(block_ids) = state.submit_keys.claim(journal_id, key) {
+            return replay_submit(&mut *sender.lock().await, state, &block_ids)
+                .await
+                .map(|()| None);
+        }
+    }
+    // Note each block under the key as soon as it exists, so a retry that
+    // arrives mid-stream still finds it
+    let record = |block_id| {
+        if let Some(key) = &idempotency_key {
+            state.submit_keys.record(journal_id, key, block_id);
+        }
+    };
+
+    let system_prompt = system_prompt.filter(|prompt| !prompt.trim().is_empty());
+
+    // Show the system prompt in the timeline ahead of the message it applies to
+    if let Some(prompt) = &system_prompt {
+        let system_block = state
+            .store
+            .create_block(journal_id, BlockType::System, prompt)
+            .await?;
+        record(system_block.id);
+
+        send_block_event(
+            &mut *sender.lock().await,
+            state,
+            connection_id,
+            journal_id,
+            BlockEvent::Created(system_block),
+        )
+        .await;
+    }
+
+    // Create the user block and its pending assistant block as a pair
+    let (user_block, assistant_block) = state.store.begin_submit(journal_id, &content).await?;
+    record(user_block.id);
+    record(assistant_block.id);
+
+    // Send block created
+    send_block_event(
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        journal_id,
+        BlockEvent::Created(user_block.clone()),
+    )
+    .await;
+
+    autotitle_from_prompt(
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        journal_id,
+        &content,
+    )
+    .await;
+
+    send_block_event(
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        journal_id,
+        BlockEvent::Created(assistant_block.clone()),
+    )
+    .await;
+
+    // Wait for a free slot if the journal is at its concurrency limit
+    let _permit = match state.submit_limiter.acquire(journal_id, assistant_block.id) {
+        Acquire::Ready(permit) => permit,
+        Acquire::Queued(ticket) => {
+            wait_in_queue(
+                sender,
+                state,
+                connection_id,
+                journal_id,
+                assistant_block.id,
+                ticket,
+            )
+            .await?
         }
     };
 
+    // Carry on the journal's conversation unless the client picked a session
+    let (session_id, remember) = match session_id {
+        Some(id) => (Some(id), false),
+        None => match state.store.get_journal_session(journal_id).await? {
+            Some(id) => (Some(id), false),
+            None => (None, true),
+        },
+    };
+    let mut session_id = fail_on_timeout(
+        resolve_session(opencode, session_id, model, system_prompt).await,
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        &assistant_block,
+        assistant_block.version,
+    )
+    .await?;
+    if remember {
+        session_id = state
+            .store
+            .get_or_set_journal_session(journal_id, &session_id)
+            .await?;
+    }
+
     // Update block to streaming
     state
         .store
         .update_block_status(assistant_block.id, BlockStatus::Streaming)
         .await?;
 
-    let msg = ServerMessage::BlockStatusChanged {
-        block_id: assistant_block.id,
-        status: BlockStatus::Streaming,
-    };
-    sender
-        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-        .await
-        .map_err(|e| error::AppError::Internal(e.to_string()))?;
+    send_block_event(
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        journal_id,
+        BlockEvent::StatusChanged {
+            block_id: assistant_block.id,
+            status: BlockStatus::Streaming,
+        },
+    )
+    .await;
+
+    // Lets a `Cancel` from any connection stop this stream
+    let active = state.streams.register(assistant_block.id);
 
     // Stream response from OpenCode
-    let mut stream = opencode
-        .send_message(&session_id, SendMessageRequest { content })
-        .await?;
+    let stream = opencode
+        .send_message(
+            &session_id,
+            SendMessageRequest {
+                content: content.clone(),
+            },
+        )
+        .await;
+    let mut stream = fail_on_timeout(
+        stream,
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        &assistant_block,
+        assistant_block.version,
+    )
+    .await?;
 
     let mut full_content = String::new();
+    let mut version = assistant_block.version;
+    let mut completed = false;
+
+    loop {
+        let event = tokio::select! {
+            biased;
+            _ = active.cancelled() => {
+                // Keep what arrived before the cancel; `handle_cancel` marks the block errored
+                state
+                    .store
+                    .update_block_content(assistant_block.id, &full_content, version)
+                    .await?;
+                break;
+            }
+            event = stream.next() => match event {
+                Some(event) => event,
+                None => break,
+            },
+        };
 
-    while let Some(event) = stream.next().await {
         match event {
             Ok(StreamEvent::Content(content_event)) => {
+                let offset = full_content.len();
                 full_content.push_str(&content_event.text);
+                active.push(&content_event.text);
 
                 // Send streaming update
-                let msg = ServerMessage::BlockContentDelta {
-                    block_id: assistant_block.id,
-                    delta: content_event.text,
-                };
-                sender
-                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                    .await
-                    .map_err(|e| error::AppError::Internal(e.to_string()))?;
+                send_block_event(
+                    &mut *sender.lock().await,
+                    state,
+                    connection_id,
+                    journal_id,
+                    BlockEvent::ContentDelta {
+                        block_id: assistant_block.id,
+                        delta: content_event.text,
+                        offset,
+                    },
+                )
+                .await;
+            }
+            Ok(StreamEvent::Usage(usage)) => {
+                record_usage(
+                    &mut *sender.lock().await,
+                    state,
+                    connection_id,
+                    journal_id,
+                    assistant_block.id,
+                    usage,
+                )
+                .await;
             }
             Ok(StreamEvent::Done) => {
                 // Update block to complete
-                state
+                version = state
                     .store
-                    .update_block_content(assistant_block.id, &full_content)
+                    .update_block_content(assistant_block.id, &full_content, version)
                     .await?;
                 state
                     .store
                     .update_block_status(assistant_block.id, BlockStatus::Complete)
                     .await?;
 
-                let msg = ServerMessage::BlockStatusChanged {
-                    block_id: assistant_block.id,
-                    status: BlockStatus::Complete,
-                };
-                sender
-                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                    .await
-                    .map_err(|e| error::AppError::Internal(e.to_string()))?;
+                send_block_event(
+                    &mut *sender.lock().await,
+                    state,
+                    connection_id,
+                    journal_id,
+                    BlockEvent::StatusChanged {
+                        block_id: assistant_block.id,
+                        status: BlockStatus::Complete,
+                    },
+                )
+                .await;
+                completed = true;
             }
             Ok(StreamEvent::Error(error_event)) => {
+                state.metrics.record_opencode_error();
                 // Update block to error
-                state
+                version = state
                     .store
-                    .update_block_content(assistant_block.id, &error_event.message)
+                    .update_block_content(
+                        assistant_block.id,
+                        &error_block_content(state, &error_event),
+                        version,
+                    )
                     .await?;
                 state
                     .store
                     .update_block_status(assistant_block.id, BlockStatus::Error)
                     .await?;
 
-                let msg = ServerMessage::BlockStatusChanged {
-                    block_id: assistant_block.id,
-                    status: BlockStatus::Error,
-                };
-                sender
-                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                    .await
-                    .map_err(|e| error::AppError::Internal(e.to_string()))?;
+                send_block_event(
+                    &mut *sender.lock().await,
+                    state,
+                    connection_id,
+                    journal_id,
+                    BlockEvent::StatusChanged {
+                        block_id: assistant_block.id,
+                        status: BlockStatus::Error,
+                    },
+                )
+                .await;
             }
             Ok(StreamEvent::Unknown { .. }) => {
                 // Ignore unknown events
             }
+            Err(e @ error::AppError::OpenCodeTimeout(_)) => {
+                return fail_on_timeout(
+                    Err(e),
+                    &mut *sender.lock().await,
+                    state,
+                    connection_id,
+                    &assistant_block,
+                    version,
+                )
+                .await
+                .map(|()| None);
+            }
             Err(e) => {
                 tracing::error!("Stream error: {}", e);
+                state.metrics.record_error(&e);
                 // Update block to error
                 state
                     .store
@@ -1352,28 +4180,201 @@ async fn handle_submit(
         }
     }
 
-    Ok(())
+    Ok(completed.then_some(Exchange {
+        prompt: content,
+        response: full_content,
+    }))
+}
+
+/// Report a queued block's position until it reaches the front and is given a slot
+async fn wait_in_queue<'a>(
+    sender: &Mutex<ClientSink>,
+    state: &AppState,
+    connection_id: Uuid,
+    journal_id: Uuid,
+    block_id: Uuid,
+    mut ticket: QueueTicket<'a>,
+) -> error::Result<SubmitPermit<'a>> {
+    send_block_event(
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        journal_id,
+        BlockEvent::StatusChanged {
+            block_id,
+            status: BlockStatus::Queued,
+        },
+    )
+    .await;
+
+    let mut position = ticket.position();
+    while position > 0 {
+        let msg = ServerMessage::QueuePosition {
+            journal_id,
+            block_id,
+            position,
+        };
+        if let Err(e) = sender
+            .lock()
+            .await
+            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+            .await
+        {
+            tracing::debug!("Failed to send queue position: {}", e);
+        }
+        position = ticket.changed().await;
+    }
+
+    ticket
+        .into_permit()
+        .map_err(|_| error::AppError::Internal("Queued submit was not granted".to_string()))
+}
+
+/// Block content for a failed response; the raw upstream error is always logged
+fn error_block_content(state: &AppState, error_event: &ErrorEvent) -> String {
+    tracing::warn!(
+        "OpenCode reported an error (code {:?}): {}",
+        error_event.code,
+        error_event.message
+    );
+    if state.raw_errors() {
+        error_event.message.clone()
+    } else {
+        error_event.user_message().to_string()
+    }
+}
+
+/// Whether `title` is one nobody chose: a default, or the one taken from `prompt`
+fn is_placeholder_title(title: &str, prompt: &str) -> bool {
+    store::DEFAULT_JOURNAL_TITLES.contains(&title)
+        || store::title_from_content(prompt).is_some_and(|derived| derived == title)
+}
+
+/// Title a journal that still has a default title after the prompt just sent to it
+///
+/// Best-effort like [`auto_title_journal`], which may later replace this
+/// title with a generated one.
+async fn autotitle_from_prompt(
+    sender: &mut ClientSink,
+    state: &AppState,
+    connection_id: Uuid,
+    journal_id: Uuid,
+    prompt: &str,
+) {
+    match state
+        .store
+        .maybe_autotitle_journal(journal_id, prompt)
+        .await
+    {
+        Ok(Some(journal)) => announce_title(sender, state, connection_id, journal).await,
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to title journal {}: {}", journal_id, e),
+    }
+}
+
+/// Give an untitled journal a title generated from its first exchange.
+///
+/// Best-effort: journals that already have a title or more than one exchange
+/// are left alone, and failures are only logged. Runs in the submit's task
+/// once the response completes, without holding the connection's sink.
+async fn auto_title_journal(
+    sender: Arc<Mutex<ClientSink>>,
+    state: Arc<AppState>,
+    opencode: OpenCodeClient,
+    connection_id: Uuid,
+    journal_id: Uuid,
+    exchange: Exchange,
+) {
+    let (journal, blocks) = match state.store.get_journal_with_blocks(journal_id).await {
+        Ok(found) => found,
+        Err(e) => {
+            tracing::warn!("Skipping auto title for journal {}: {}", journal_id, e);
+            return;
+        }
+    };
+
+    let assistant_blocks = blocks
+        .iter()
+        .filter(|b| b.block_type == BlockType::Assistant)
+        .count();
+    if !is_placeholder_title(&journal.title, &exchange.prompt) || assistant_blocks != 1 {
+        return;
+    }
+
+    let title = match opencode
+        .generate_title(&exchange.prompt, &exchange.response)
+        .await
+    {
+        Ok(title) => title,
+        Err(e) => {
+            tracing::warn!("Failed to generate title for journal {}: {}", journal_id, e);
+            return;
+        }
+    };
+
+    // Someone may have renamed the journal while the title was generated
+    match state
+        .store
+        .replace_placeholder_title(journal_id, &exchange.prompt, &title)
+        .await
+    {
+        Ok(Some(journal)) => {
+            let mut sender = sender.lock().await;
+            announce_title(&mut sender, &state, connection_id, journal).await;
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to set title for journal {}: {}", journal_id, e),
+    }
+}
+
+/// Tell the submitter, and everyone in the journal's room, about an automatic title
+async fn announce_title(
+    sender: &mut ClientSink,
+    state: &AppState,
+    connection_id: Uuid,
+    journal: crate::models::Journal,
+) {
+    if let Some(room) = state.room_manager.get(journal.id).await {
+        room.broadcast_rename(journal.title.clone(), Some(connection_id));
+    }
+    let msg = ServerMessage::JournalRenamed {
+        journal_id: journal.id,
+        title: journal.title,
+    };
+    if let Err(e) = sender
+        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+        .await
+    {
+        tracing::error!("Failed to send journal rename: {}", e);
+    }
 }
 
 async fn handle_fork(
-    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
+    sender: &Arc<Mutex<ClientSink>>,
     state: &Arc<AppState>,
     opencode: &OpenCodeClient,
+    connection_id: Uuid,
     block_id: Uuid,
     session_id: Option<String>,
+    model: Option<String>,
 ) -> error::Result<()> {
+    ensure_final(&state.store.get_block(block_id).await?, "forked")?;
+
     // Fork creates a new user block with the same content, branching from the original
     let forked_block = state.store.fork_block(block_id).await?;
 
     // Send block forked notification
-    let msg = ServerMessage::BlockForked {
-        original_block_id: block_id,
-        new_block: forked_block.clone(),
-    };
-    sender
-        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-        .await
-        .map_err(|e| error::AppError::Internal(e.to_string()))?;
+    send_block_event(
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        forked_block.journal_id,
+        BlockEvent::Forked {
+            original_block_id: block_id,
+            new_block: forked_block.clone(),
+        },
+    )
+    .await;
 
     // Now execute the fork by sending to OpenCode (reusing submit logic)
     // Create assistant block for the response
@@ -1388,58 +4389,75 @@ async fn handle_fork(
         )
         .await?;
 
-    let msg = ServerMessage::BlockCreated {
-        block: assistant_block.clone(),
-    };
-    sender
-        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-        .await
-        .map_err(|e| error::AppError::Internal(e.to_string()))?;
+    send_block_event(
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        forked_block.journal_id,
+        BlockEvent::Created(assistant_block.clone()),
+    )
+    .await;
 
-    // Get or create session
-    let session_id = match session_id {
-        Some(id) => id,
-        None => {
-            let session = opencode
-                .create_session(crate::opencode::CreateSessionRequest {
-                    model: None,
-                    system_prompt: None,
-                })
-                .await?;
-            session.id
-        }
-    };
+    let session_id = fail_on_timeout(
+        resolve_session(opencode, session_id, model, None).await,
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        &assistant_block,
+        assistant_block.version,
+    )
+    .await?;
 
     // Stream response from OpenCode
     stream_response(
         sender,
         state,
         opencode,
+        connection_id,
         &session_id,
         assistant_block,
         &forked_block.content,
     )
     .await
+    .map(|_| ())
+}
+
+/// Refuse to branch off a block whose content may still change
+fn ensure_final(block: &crate::models::Block, action: &str) -> error::Result<()> {
+    if block.is_terminal_status() {
+        return Ok(());
+    }
+    Err(error::AppError::BlockNotReady(format!(
+        "Block {} is {} and can't be {} until it finishes",
+        block.id,
+        block.status.as_str(),
+        action
+    )))
 }
 
 async fn handle_rerun(
-    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
+    sender: &Arc<Mutex<ClientSink>>,
     state: &Arc<AppState>,
     opencode: &OpenCodeClient,
+    connection_id: Uuid,
     block_id: Uuid,
     session_id: Option<String>,
+    model: Option<String>,
 ) -> error::Result<()> {
+    ensure_final(&state.store.get_block(block_id).await?, "re-run")?;
+
     // Rerun creates a new execution of the same prompt
     let rerun_block = state.store.rerun_block(block_id).await?;
 
     // Send block created notification
-    let msg = ServerMessage::BlockCreated {
-        block: rerun_block.clone(),
-    };
-    sender
-        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-        .await
-        .map_err(|e| error::AppError::Internal(e.to_string()))?;
+    send_block_event(
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        rerun_block.journal_id,
+        BlockEvent::Created(rerun_block.clone()),
+    )
+    .await;
 
     // Create assistant block for the response
     let assistant_block = state
@@ -1453,153 +4471,563 @@ async fn handle_rerun(
         )
         .await?;
 
-    let msg = ServerMessage::BlockCreated {
-        block: assistant_block.clone(),
-    };
-    sender
-        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-        .await
-        .map_err(|e| error::AppError::Internal(e.to_string()))?;
+    send_block_event(
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        rerun_block.journal_id,
+        BlockEvent::Created(assistant_block.clone()),
+    )
+    .await;
 
-    // Get or create session
-    let session_id = match session_id {
-        Some(id) => id,
-        None => {
-            let session = opencode
-                .create_session(crate::opencode::CreateSessionRequest {
-                    model: None,
-                    system_prompt: None,
-                })
-                .await?;
-            session.id
-        }
-    };
+    let session_id = fail_on_timeout(
+        resolve_session(opencode, session_id, model, None).await,
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        &assistant_block,
+        assistant_block.version,
+    )
+    .await?;
 
     // Stream response from OpenCode
     stream_response(
         sender,
         state,
         opencode,
+        connection_id,
         &session_id,
         assistant_block,
         &rerun_block.content,
     )
     .await
+    .map(|_| ())
+}
+
+async fn handle_edit_block(
+    sender: &mut ClientSink,
+    state: &Arc<AppState>,
+    connection_id: Uuid,
+    block_id: Uuid,
+    content: &str,
+) -> error::Result<()> {
+    let block = state.store.edit_user_block(block_id, content).await?;
+    let journal_id = block.journal_id;
+    send_block_event(
+        sender,
+        state,
+        connection_id,
+        journal_id,
+        BlockEvent::Edited(block),
+    )
+    .await;
+    Ok(())
+}
+
+/// Toggle a reaction for the participant this connection subscribed to the block's journal as
+async fn handle_react(
+    sender: &mut ClientSink,
+    state: &Arc<AppState>,
+    connection_id: Uuid,
+    subscriptions: &std::collections::HashMap<Uuid, Uuid>,
+    block_id: Uuid,
+    emoji: &str,
+) -> error::Result<()> {
+    let block = state.store.get_block(block_id).await?;
+    let participant_id = subscriptions
+        .get(&block.journal_id)
+        .copied()
+        .ok_or_else(|| {
+            error::AppError::BadRequest("Subscribe to the journal before reacting".to_string())
+        })?;
+
+    let reaction = state
+        .store
+        .toggle_reaction(block_id, participant_id, emoji)
+        .await?;
+    send_block_event(
+        sender,
+        state,
+        connection_id,
+        block.journal_id,
+        BlockEvent::ReactionChanged { block_id, reaction },
+    )
+    .await;
+    Ok(())
+}
+
+async fn handle_delete_block(
+    sender: &mut ClientSink,
+    state: &Arc<AppState>,
+    conn_state: &Mutex<ConnectionState>,
+    connection_id: Uuid,
+    block_id: Uuid,
+    cascade: bool,
+) -> error::Result<()> {
+    let block = state.store.get_block(block_id).await?;
+    check_journal_role(
+        state,
+        conn_state,
+        block.journal_id,
+        JournalRole::can_delete_blocks,
+    )
+    .await?;
+    let deleted = state.store.delete_block(block_id, cascade).await?;
+    state.delegation_manager.unlink_blocks(&deleted).await;
+
+    let room = state.room_manager.get(block.journal_id).await;
+    for id in deleted {
+        // Nothing should keep streaming into a block that's gone
+        state.streams.cancel(id);
+        if let Some(room) = &room {
+            room.delete_block(id, None).await;
+        }
+
+        send_block_event(
+            sender,
+            state,
+            connection_id,
+            block.journal_id,
+            BlockEvent::Deleted { block_id: id },
+        )
+        .await;
+    }
+
+    Ok(())
 }
 
 async fn handle_cancel(
-    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
+    sender: &mut ClientSink,
     state: &Arc<AppState>,
+    connection_id: Uuid,
     block_id: Uuid,
 ) -> error::Result<()> {
+    let block = state.store.get_block(block_id).await?;
+
+    // Stop the stream writing to this block, if one is still running
+    state.streams.cancel(block_id);
+
     // Update block status to error (cancelled)
     state
         .store
         .update_block_status(block_id, BlockStatus::Error)
         .await?;
 
-    let msg = ServerMessage::BlockCancelled { block_id };
-    sender
-        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+    send_block_event(
+        sender,
+        state,
+        connection_id,
+        block.journal_id,
+        BlockEvent::Cancelled { block_id },
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Catch a client up on a block it may have missed deltas for
+///
+/// A block that is still streaming gets everything written so far as one
+/// delta, then the rest as it arrives: from the room if this connection is
+/// subscribed to the journal, otherwise from a task that follows the stream
+/// to its end. A finished block gets its final content and status.
+async fn handle_resume(
+    sender: &Arc<Mutex<ClientSink>>,
+    state: &Arc<AppState>,
+    conn_state: &Arc<Mutex<ConnectionState>>,
+    block_id: Uuid,
+) -> error::Result<()> {
+    let block = state.store.get_block(block_id).await?;
+
+    let Some((content, mut deltas)) = state.streams.resume(block_id) else {
+        let mut sender = sender.lock().await;
+        for msg in [
+            ServerMessage::BlockContentDelta {
+                block_id,
+                delta: block.content,
+                offset: 0,
+            },
+            ServerMessage::BlockStatusChanged {
+                block_id,
+                status: block.status,
+            },
+        ] {
+            let _ = sender
+                .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                .await;
+        }
+        return Ok(());
+    };
+
+    {
+        let msg = ServerMessage::BlockContentDelta {
+            block_id,
+            delta: content,
+            offset: 0,
+        };
+        let mut sender = sender.lock().await;
+        let _ = sender
+            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+            .await;
+    }
+
+    let subscribed = conn_state
+        .lock()
         .await
-        .map_err(|e| error::AppError::Internal(e.to_string()))?;
+        .subscriptions
+        .contains_key(&block.journal_id);
+    if subscribed {
+        return Ok(());
+    }
+
+    let sender = Arc::clone(sender);
+    let state = Arc::clone(state);
+    tokio::spawn(async move {
+        loop {
+            let delta = match deltas.recv().await {
+                Ok(delta) => delta,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+            let msg = ServerMessage::BlockContentDelta {
+                block_id,
+                delta: delta.delta,
+                offset: delta.offset,
+            };
+            let mut sender = sender.lock().await;
+            if sender
+                .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+
+        // The stream has ended; report how
+        if let Ok(block) = state.store.get_block(block_id).await {
+            let msg = ServerMessage::BlockStatusChanged {
+                block_id,
+                status: block.status,
+            };
+            let mut sender = sender.lock().await;
+            let _ = sender
+                .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                .await;
+        }
+    });
 
     Ok(())
 }
 
+/// Message describing the outcome of a work submission
+async fn submitted_work_message(state: &AppState, work_item: &WorkItem) -> Option<ServerMessage> {
+    if work_item.status == WorkItemStatus::AwaitingApproval {
+        // Get the approval request
+        let approvals = state
+            .delegation_manager
+            .get_approval_queue(work_item.get_approver_id())
+            .await;
+        approvals
+            .into_iter()
+            .find(|a| a.work_item_id == work_item.id)
+            .map(|approval| ServerMessage::ApprovalRequested {
+                approval,
+                work_item: work_item.clone(),
+            })
+    } else {
+        Some(ServerMessage::WorkApproved {
+            work_item_id: work_item.id,
+            approver_id: work_item.delegator_id,
+            feedback: None,
+        })
+    }
+}
+
+/// Carry out auto-execute work on behalf of its agent assignee.
+///
+/// Accepts the item, streams a completion for its description into a new
+/// assistant block (after the designated block, if any) and submits the
+/// response as the work result. Progress goes to the delegator's connection;
+/// on failure the item is left in progress for the agent to finish by hand.
+async fn auto_execute_work(
+    sender: Arc<Mutex<ClientSink>>,
+    state: Arc<AppState>,
+    opencode: OpenCodeClient,
+    connection_id: Uuid,
+    work_item: WorkItem,
+) {
+    let work_item_id = work_item.id;
+    let assignee_id = work_item.assignee_id;
+
+    let assistant_block = match state
+        .store
+        .create_block_with_lineage(
+            work_item.journal_id,
+            BlockType::Assistant,
+            "",
+            work_item.block_id,
+            None,
+        )
+        .await
+    {
+        Ok(block) => block,
+        Err(e) => {
+            tracing::warn!("Cannot auto-execute work {}: {}", work_item_id, e);
+            return;
+        }
+    };
+
+    match state
+        .delegation_manager
+        .accept_work(work_item_id, assignee_id)
+        .await
+    {
+        Ok(item) => update_work_presence(&state, &item).await,
+        Err(e) => {
+            tracing::warn!("Cannot auto-execute work {}: {}", work_item_id, e);
+            return;
+        }
+    }
+
+    send_block_event(
+        &mut *sender.lock().await,
+        &state,
+        connection_id,
+        assistant_block.journal_id,
+        BlockEvent::Created(assistant_block.clone()),
+    )
+    .await;
+
+    let response = match opencode
+        .create_session(crate::opencode::CreateSessionRequest {
+            model: None,
+            system_prompt: None,
+        })
+        .await
+    {
+        Ok(session) => {
+            stream_response(
+                &sender,
+                &state,
+                &opencode,
+                connection_id,
+                &session.id,
+                assistant_block,
+                &work_item.description,
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    let result = match response {
+        Ok(Some(result)) => result,
+        Ok(None) => {
+            tracing::warn!("Auto-executed work {} did not complete", work_item_id);
+            return;
+        }
+        Err(e) => {
+            tracing::warn!("Auto-executed work {} failed: {}", work_item_id, e);
+            return;
+        }
+    };
+
+    match state
+        .delegation_manager
+        .submit_work(work_item_id, assignee_id, result)
+        .await
+    {
+        Ok(item) => {
+            update_work_presence(&state, &item).await;
+            if let Some(msg) = submitted_work_message(&state, &item).await {
+                let mut sender = sender.lock().await;
+                let _ = sender
+                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
+                    .await;
+            }
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Failed to submit auto-executed work {}: {}",
+                work_item_id,
+                e
+            );
+        }
+    }
+}
+
 /// Helper function to stream response from OpenCode
+///
+/// Returns the full response once the stream completes, or `None` if it ended
+/// in an error.
 async fn stream_response(
-    sender: &mut futures::stream::SplitSink<WebSocket, Message>,
+    sender: &Arc<Mutex<ClientSink>>,
     state: &Arc<AppState>,
     opencode: &OpenCodeClient,
+    connection_id: Uuid,
     session_id: &str,
     assistant_block: crate::models::Block,
     content: &str,
-) -> error::Result<()> {
+) -> error::Result<Option<String>> {
     // Update block to streaming
     state
         .store
         .update_block_status(assistant_block.id, BlockStatus::Streaming)
         .await?;
 
-    let msg = ServerMessage::BlockStatusChanged {
-        block_id: assistant_block.id,
-        status: BlockStatus::Streaming,
-    };
-    sender
-        .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-        .await
-        .map_err(|e| error::AppError::Internal(e.to_string()))?;
+    send_block_event(
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        assistant_block.journal_id,
+        BlockEvent::StatusChanged {
+            block_id: assistant_block.id,
+            status: BlockStatus::Streaming,
+        },
+    )
+    .await;
+
+    // Lets a `Cancel` from any connection stop this stream
+    let active = state.streams.register(assistant_block.id);
 
     // Stream response from OpenCode
-    let mut stream = opencode
+    let stream = opencode
         .send_message(
             session_id,
             SendMessageRequest {
                 content: content.to_string(),
             },
         )
-        .await?;
+        .await;
+    let mut stream = fail_on_timeout(
+        stream,
+        &mut *sender.lock().await,
+        state,
+        connection_id,
+        &assistant_block,
+        assistant_block.version,
+    )
+    .await?;
 
     let mut full_content = String::new();
+    let mut version = assistant_block.version;
+    let mut completed = false;
+
+    loop {
+        let event = tokio::select! {
+            biased;
+            _ = active.cancelled() => {
+                // Keep what arrived before the cancel; `handle_cancel` marks the block errored
+                state
+                    .store
+                    .update_block_content(assistant_block.id, &full_content, version)
+                    .await?;
+                break;
+            }
+            event = stream.next() => match event {
+                Some(event) => event,
+                None => break,
+            },
+        };
 
-    while let Some(event) = stream.next().await {
         match event {
             Ok(StreamEvent::Content(content_event)) => {
+                let offset = full_content.len();
                 full_content.push_str(&content_event.text);
-
-                let msg = ServerMessage::BlockContentDelta {
-                    block_id: assistant_block.id,
-                    delta: content_event.text,
-                };
-                sender
-                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                    .await
-                    .map_err(|e| error::AppError::Internal(e.to_string()))?;
+                active.push(&content_event.text);
+
+                send_block_event(
+                    &mut *sender.lock().await,
+                    state,
+                    connection_id,
+                    assistant_block.journal_id,
+                    BlockEvent::ContentDelta {
+                        block_id: assistant_block.id,
+                        delta: content_event.text,
+                        offset,
+                    },
+                )
+                .await;
+            }
+            Ok(StreamEvent::Usage(usage)) => {
+                record_usage(
+                    &mut *sender.lock().await,
+                    state,
+                    connection_id,
+                    assistant_block.journal_id,
+                    assistant_block.id,
+                    usage,
+                )
+                .await;
             }
             Ok(StreamEvent::Done) => {
-                state
+                version = state
                     .store
-                    .update_block_content(assistant_block.id, &full_content)
+                    .update_block_content(assistant_block.id, &full_content, version)
                     .await?;
                 state
                     .store
                     .update_block_status(assistant_block.id, BlockStatus::Complete)
                     .await?;
 
-                let msg = ServerMessage::BlockStatusChanged {
-                    block_id: assistant_block.id,
-                    status: BlockStatus::Complete,
-                };
-                sender
-                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                    .await
-                    .map_err(|e| error::AppError::Internal(e.to_string()))?;
+                send_block_event(
+                    &mut *sender.lock().await,
+                    state,
+                    connection_id,
+                    assistant_block.journal_id,
+                    BlockEvent::StatusChanged {
+                        block_id: assistant_block.id,
+                        status: BlockStatus::Complete,
+                    },
+                )
+                .await;
+                completed = true;
             }
             Ok(StreamEvent::Error(error_event)) => {
-                state
+                state.metrics.record_opencode_error();
+                version = state
                     .store
-                    .update_block_content(assistant_block.id, &error_event.message)
+                    .update_block_content(
+                        assistant_block.id,
+                        &error_block_content(state, &error_event),
+                        version,
+                    )
                     .await?;
                 state
                     .store
                     .update_block_status(assistant_block.id, BlockStatus::Error)
                     .await?;
 
-                let msg = ServerMessage::BlockStatusChanged {
-                    block_id: assistant_block.id,
-                    status: BlockStatus::Error,
-                };
-                sender
-                    .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                    .await
-                    .map_err(|e| error::AppError::Internal(e.to_string()))?;
+                send_block_event(
+                    &mut *sender.lock().await,
+                    state,
+                    connection_id,
+                    assistant_block.journal_id,
+                    BlockEvent::StatusChanged {
+                        block_id: assistant_block.id,
+                        status: BlockStatus::Error,
+                    },
+                )
+                .await;
             }
             Ok(StreamEvent::Unknown { .. }) => {
                 // Ignore unknown events
             }
+            Err(e @ error::AppError::OpenCodeTimeout(_)) => {
+                return fail_on_timeout(
+                    Err(e),
+                    &mut *sender.lock().await,
+                    state,
+                    connection_id,
+                    &assistant_block,
+                    version,
+                )
+                .await;
+            }
             Err(e) => {
                 tracing::error!("Stream error: {}", e);
+                state.metrics.record_error(&e);
                 state
                     .store
                     .update_block_status(assistant_block.id, BlockStatus::Error)
@@ -1608,52 +5036,200 @@ async fn stream_response(
         }
     }
 
-    Ok(())
+    Ok(completed.then_some(full_content))
 }
 
 /// Messages from client to server
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClientMessage {
+    /// Negotiate optional protocol features
+    Hello {
+        /// Opt in to binary CRDT frames
+        #[serde(default)]
+        binary_crdt: bool,
+        /// Have replies sent as MessagePack binary frames, starting with the welcome
+        #[serde(default)]
+        msgpack: bool,
+    },
     /// Submit a prompt
     Submit {
         journal_id: Uuid,
-        content: String,
-        session_id: Option<String>,
+        content: String,
+        /// Session to send to; defaults to the one the journal's earlier submits used
+        session_id: Option<String>,
+        /// Model for a new session; ignored when the submit reuses one
+        model: Option<String>,
+        /// Recorded as a system block ahead of the message, and used as the
+        /// system prompt when a new session is created
+        #[serde(default)]
+        system_prompt: Option<String>,
+        /// Client-chosen key; a repeat within a few minutes replays the
+        /// blocks from the first submit instead of creating new ones
+        #[serde(default)]
+        idempotency_key: Option<String>,
+    },
+    /// Create a new journal
+    CreateJournal { title: Option<String> },
+    /// Copy a journal and all its blocks into a new journal
+    CloneJournal {
+        journal_id: Uuid,
+        /// Defaults to the original's title with " (copy)"
+        #[serde(default)]
+        title: Option<String>,
+    },
+    /// Get a journal with its blocks
+    GetJournal {
+        journal_id: Uuid,
+        /// `"html"` to also send assistant blocks rendered as `content_html`
+        #[serde(default)]
+        render: Option<String>,
+    },
+    /// Get a journal's block counts and latest activity without its blocks
+    GetJournalSummary { journal_id: Uuid },
+    /// Get a single block
+    GetBlock {
+        block_id: Uuid,
+        /// `"html"` to also send the block rendered as `content_html` if it's an assistant block
+        #[serde(default)]
+        render: Option<String>,
+    },
+    /// Get a single block's current status and content, answered with
+    /// `block_fetched` (e.g. to look up the new block named by `block_forked`)
+    FetchBlock { block_id: Uuid },
+    /// Page backwards through a journal's blocks, newest first
+    GetBlocksPage {
+        journal_id: Uuid,
+        /// Only blocks created before this; omit for the newest page
+        #[serde(default)]
+        before: Option<chrono::DateTime<chrono::Utc>>,
+        limit: u32,
+    },
+    /// Full-text search over block content, best matches first
+    SearchBlocks {
+        query: String,
+        /// Only search this journal; omit to search all of them
+        #[serde(default)]
+        journal_id: Option<Uuid>,
+        #[serde(default)]
+        limit: Option<u32>,
+    },
+    /// Line diff from block `a` to block `b` (e.g. an answer and its rerun)
+    DiffBlocks { a: Uuid, b: Uuid },
+    /// Export the path from the root down to `block_id` as a transcript
+    ExportBranch {
+        block_id: Uuid,
+        format: crate::export::ExportFormat,
+    },
+    /// List all journals
+    ListJournals {
+        /// Also return soft-deleted journals
+        #[serde(default)]
+        include_deleted: bool,
+        /// Also return archived journals
+        #[serde(default)]
+        include_archived: bool,
+        /// Only journals whose title contains this, ignoring case
+        #[serde(default)]
+        query: Option<String>,
+        /// Defaults to most recently updated first
+        #[serde(default)]
+        sort: Option<crate::models::JournalSort>,
+    },
+    /// Recently updated journals, each with its newest block
+    GetRecentActivity {
+        #[serde(default)]
+        limit: Option<u32>,
+    },
+    /// Soft-delete a journal, hiding it from listings
+    DeleteJournal { journal_id: Uuid },
+    /// Change a journal's title
+    RenameJournal { journal_id: Uuid, title: String },
+    /// Archive (or unarchive) a journal, hiding it from listings
+    ArchiveJournal { journal_id: Uuid, archived: bool },
+    /// Change a participant's role in a journal (owners only)
+    SetRole {
+        journal_id: Uuid,
+        participant_id: Uuid,
+        role: JournalRole,
     },
-    /// Create a new journal
-    CreateJournal { title: Option<String> },
-    /// Get a journal with its blocks
-    GetJournal { journal_id: Uuid },
-    /// List all journals
-    ListJournals,
+    /// Storage usage figures (requires the admin capability)
+    GetStorageStats,
+    /// List the models OpenCode can run
+    ListModels,
     /// Fork a block (create new session from a branch point)
     Fork {
         block_id: Uuid,
         session_id: Option<String>,
+        /// Model for a new session; ignored when `session_id` is given
+        model: Option<String>,
     },
     /// Re-run a block (same prompt, new execution)
     Rerun {
         block_id: Uuid,
         session_id: Option<String>,
+        /// Model for a new session; ignored when `session_id` is given
+        model: Option<String>,
     },
     /// Cancel a streaming block
     Cancel { block_id: Uuid },
+    /// Catch up on a block's content, e.g. after reconnecting mid-stream
+    ResumeBlock { block_id: Uuid },
+    /// Move a block to a manual position within its journal
+    ReorderBlock { block_id: Uuid, position: f64 },
+    /// Delete a block; blocks forked or continued from it go too if `cascade`
+    DeleteBlock {
+        block_id: Uuid,
+        #[serde(default)]
+        cascade: bool,
+    },
+    /// Rewrite the content of a user block
+    EditBlock { block_id: Uuid, content: String },
+    /// Get the earlier contents of an edited block
+    GetBlockHistory { block_id: Uuid },
+    /// Add a reaction to a block, or take it back if already left
+    React { block_id: Uuid, emoji: String },
+    /// Get the reactions on a block
+    GetReactions { block_id: Uuid },
     /// Subscribe to a journal for real-time updates
     Subscribe {
         journal_id: Uuid,
+        /// Display name; defaults to the authenticated identity when empty or missing
+        #[serde(default)]
         name: String,
         #[serde(default)]
         kind: Option<String>,
+        /// Participants the client already knows about (e.g. when reconnecting);
+        /// if set, the server replies with a presence diff instead of the full list
+        #[serde(default)]
+        known_participants: Vec<Uuid>,
+        /// Create the journal if it doesn't exist (otherwise unknown journals are rejected)
+        #[serde(default)]
+        create: bool,
+        /// Report observers as an `observer_count` instead of full records in presence
+        #[serde(default)]
+        collapse_observers: bool,
     },
     /// Unsubscribe from a journal
     Unsubscribe { journal_id: Uuid },
     /// Update cursor position
+    ///
+    /// `anchor` and `head` report a selection; a lone `offset` is a cursor
+    /// with nothing selected.
     Cursor {
         journal_id: Uuid,
         block_id: Option<Uuid>,
         offset: Option<u32>,
+        #[serde(default)]
+        anchor: Option<u32>,
+        #[serde(default)]
+        head: Option<u32>,
     },
+    /// Show or hide this connection's typing indicator; refresh it every few
+    /// seconds while typing or it is cleared
+    Typing { journal_id: Uuid, typing: bool },
+    /// Keep this connection's presence in a journal alive
+    Heartbeat { journal_id: Uuid },
     /// Request presence information for a journal
     GetPresence { journal_id: Uuid },
     /// Apply a CRDT update
@@ -1661,6 +5237,12 @@ pub enum ClientMessage {
         journal_id: Uuid,
         /// Base64-encoded update data
         update: String,
+        /// Chunk index when a large update is split across messages
+        #[serde(default)]
+        seq: Option<u32>,
+        /// Set on the last chunk of a split update
+        #[serde(default, rename = "final")]
+        last: Option<bool>,
     },
     /// Request sync state for a journal
     SyncRequest {
@@ -1668,6 +5250,12 @@ pub enum ClientMessage {
         /// Base64-encoded state vector (optional, for diff sync)
         state_vector: Option<String>,
     },
+    /// Confirm every broadcast CRDT update up to `seq` has been applied
+    CrdtAck { journal_id: Uuid, seq: u64 },
+    /// Ask for the broadcast CRDT updates numbered `from_seq` onwards again
+    ///
+    /// Answered with a full `sync_state` if they are no longer all held.
+    CrdtResync { journal_id: Uuid, from_seq: u64 },
     // --- Delegation messages ---
     /// Register as a participant with the delegation system
     RegisterParticipant {
@@ -1677,7 +5265,21 @@ pub enum ClientMessage {
         kind: Option<String>,
         #[serde(default)]
         capabilities: Option<Vec<String>>,
+        /// URL to POST work assigned to this participant to
+        #[serde(default)]
+        webhook_url: Option<String>,
+        /// ID from an earlier registration to register under again (e.g.
+        /// after reconnecting or a server restart), so its work and
+        /// approvals carry over
+        #[serde(default)]
+        participant_id: Option<Uuid>,
     },
+    /// Leave the delegation system for a journal, handing unfinished work back
+    /// to the unassigned pool
+    ///
+    /// Closing the socket keeps the registration, so a client can reconnect
+    /// under the same `participant_id` and carry on.
+    UnregisterParticipant { journal_id: Uuid },
     /// Delegate work to another participant
     Delegate {
         journal_id: Uuid,
@@ -1691,13 +5293,45 @@ pub enum ClientMessage {
         requires_approval: bool,
         #[serde(default)]
         approver_id: Option<Uuid>,
+        /// Further approvers, each asked to review the submission
+        #[serde(default)]
+        approver_ids: Vec<Uuid>,
+        /// Approvals needed before the work is approved (default 1)
+        #[serde(default)]
+        required_approvals: Option<u32>,
+        /// Have the server do the work via OpenCode (agent assignees only)
+        #[serde(default)]
+        auto_execute: bool,
+        #[serde(default)]
+        due_at: Option<chrono::DateTime<chrono::Utc>>,
+        /// Labels for filtering, normalized to lowercase without repeats
+        #[serde(default)]
+        tags: Vec<String>,
+    },
+    /// Delegate the same work to several participants; all or none are assigned
+    DelegateMany {
+        journal_id: Uuid,
+        description: String,
+        assignee_ids: Vec<Uuid>,
+        #[serde(default)]
+        priority: Option<String>,
+        #[serde(default)]
+        requires_approval: bool,
+        #[serde(default)]
+        approver_id: Option<Uuid>,
     },
     /// Accept delegated work
     AcceptWork { work_item_id: Uuid },
     /// Decline delegated work
     DeclineWork { work_item_id: Uuid },
+    /// Pause accepted work without declining it
+    PauseWork { work_item_id: Uuid },
+    /// Resume paused work
+    ResumeWork { work_item_id: Uuid },
     /// Submit completed work (optionally for approval)
     SubmitWork { work_item_id: Uuid, result: String },
+    /// Withdraw a submission that is still awaiting approval
+    WithdrawWork { work_item_id: Uuid },
     /// Approve completed work
     ApproveWork {
         approval_id: Uuid,
@@ -1710,22 +5344,92 @@ pub enum ClientMessage {
     CancelWork { work_item_id: Uuid },
     /// Claim unassigned work
     ClaimWork { work_item_id: Uuid },
+    /// Move delegated work to a different assignee (by delegator)
+    ReassignWork {
+        work_item_id: Uuid,
+        new_assignee_id: Uuid,
+    },
+    /// Hand a pending approval to someone else (by its current approver)
+    ReassignApprover {
+        approval_id: Uuid,
+        new_approver_id: Uuid,
+    },
+    /// Comment on a work item (delegator, assignee or approver only)
+    AddWorkComment { work_item_id: Uuid, text: String },
+    /// Get the comments on a work item, oldest first
+    GetWorkComments { work_item_id: Uuid },
+    /// Get the delegation audit trail for a journal (requires approve capability)
+    GetAuditLog { journal_id: Uuid },
+    /// Have delegation events involving this connection's participants pushed as they happen
+    SubscribeDelegation,
     /// Get participant's work queue
-    GetWorkQueue,
+    GetWorkQueue {
+        /// Highest priority first instead of the order work arrived in
+        #[serde(default)]
+        sorted: bool,
+        /// Only items carrying this tag
+        #[serde(default)]
+        tag: Option<String>,
+    },
+    /// Get the overdue items in the participant's work queue
+    GetOverdueWork,
+    /// Get the capabilities this connection's participant currently holds
+    GetMyCapabilities,
+    /// Get another registered participant's capabilities
+    GetParticipantCapabilities { participant_id: Uuid },
+    /// Get a single work item by ID
+    GetWorkItem { work_item_id: Uuid },
+    /// Get counts of a participant's delegated, assigned and completed work
+    GetParticipantStats { participant_id: Uuid },
+    /// Get all work in a journal, whoever it's assigned to
+    GetJournalWork {
+        journal_id: Uuid,
+        /// Also list approved, declined and cancelled work
+        #[serde(default)]
+        include_completed: bool,
+    },
+    /// List work in a journal whose assignee left, which anyone may claim
+    GetUnassignedWork { journal_id: Uuid },
+    /// Search work items the participant is party to
+    SearchWork {
+        query: String,
+        #[serde(default)]
+        journal_id: Option<Uuid>,
+    },
     /// Get participant's pending approvals
     GetApprovalQueue,
     /// Set whether accepting work
     SetAcceptingWork { accepting: bool },
+    /// Set whether several participants are accepting work (requires the coordinate capability)
+    BulkSetAcceptingWork {
+        participant_ids: Vec<Uuid>,
+        accepting: bool,
+    },
     /// Get list of available participants for delegation
-    GetParticipants { journal_id: Uuid },
+    GetParticipants {
+        journal_id: Uuid,
+        /// Only list participants holding this capability (e.g. `"approve"`)
+        #[serde(default)]
+        required_capability: Option<String>,
+    },
 }
 
 /// Messages from server to client
 #[derive(Debug, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ServerMessage {
+    /// Reply to `Hello` confirming negotiated features
+    Welcome { binary_crdt: bool, msgpack: bool },
     /// Journal was created
     JournalCreated { journal_id: Uuid, title: String },
+    /// A journal was copied; `journal` is the new one
+    JournalCloned { journal: crate::models::Journal },
+    /// Journal metadata changed (e.g. it was given a title)
+    JournalUpdated { journal: crate::models::Journal },
+    /// Size and latest activity of a journal
+    JournalSummary {
+        summary: crate::models::JournalSummary,
+    },
     /// Journal with blocks
     Journal {
         journal: crate::models::Journal,
@@ -1735,12 +5439,43 @@ pub enum ServerMessage {
     Journals {
         journals: Vec<crate::models::Journal>,
     },
+    /// Recently updated journals, most recent first
+    RecentActivity {
+        entries: Vec<crate::models::ActivityEntry>,
+    },
+    /// A journal was soft-deleted
+    JournalDeleted { journal_id: Uuid },
+    /// A journal's title changed (sent to every subscriber)
+    JournalRenamed { journal_id: Uuid, title: String },
+    /// A participant's role in a journal changed (sent to every subscriber)
+    RoleChanged {
+        journal_id: Uuid,
+        participant_id: Uuid,
+        role: JournalRole,
+    },
+    /// Storage usage figures
+    StorageStats { stats: crate::models::StorageStats },
+    /// Models available for new sessions
+    Models {
+        models: Vec<crate::opencode::ModelInfo>,
+    },
     /// Block was created
     BlockCreated { block: crate::models::Block },
     /// Block content delta (streaming)
-    BlockContentDelta { block_id: Uuid, delta: String },
+    BlockContentDelta {
+        block_id: Uuid,
+        delta: String,
+        /// Byte offset of `delta` within the block's accumulated content
+        offset: usize,
+    },
     /// Block status changed
     BlockStatusChanged { block_id: Uuid, status: BlockStatus },
+    /// A queued block's place in its journal's submit queue (1 = next to start)
+    QueuePosition {
+        journal_id: Uuid,
+        block_id: Uuid,
+        position: usize,
+    },
     /// Block was forked
     BlockForked {
         original_block_id: Uuid,
@@ -1748,8 +5483,65 @@ pub enum ServerMessage {
     },
     /// Block was cancelled
     BlockCancelled { block_id: Uuid },
+    /// Block was deleted
+    BlockDeleted { block_id: Uuid },
+    /// A user block's content was edited
+    BlockEdited { block: crate::models::Block },
+    /// Tokens OpenCode reported for an assistant block (may be sent more than once)
+    BlockUsage {
+        block_id: Uuid,
+        prompt_tokens: u32,
+        completion_tokens: u32,
+    },
+    /// Someone toggled a reaction; `reactors` is everyone who now has it
+    ReactionChanged {
+        block_id: Uuid,
+        emoji: String,
+        count: usize,
+        reactors: Vec<Uuid>,
+    },
+    /// Reactions on a block, in the order each emoji was first used
+    Reactions {
+        block_id: Uuid,
+        reactions: Vec<crate::models::BlockReaction>,
+    },
+    /// Earlier contents of a block, oldest first
+    BlockHistory {
+        block_id: Uuid,
+        edits: Vec<crate::models::BlockEdit>,
+    },
+    /// Block was moved to a new manual position
+    BlockReordered { block: crate::models::Block },
+    /// A single block
+    Block { block: crate::models::Block },
+    /// Reply to `fetch_block`
+    BlockFetched { block: crate::models::Block },
+    /// One page of blocks, newest first
+    BlocksPage {
+        blocks: Vec<crate::models::Block>,
+        has_more: bool,
+    },
+    /// Blocks matching a search, best first
+    SearchResults { blocks: Vec<crate::models::Block> },
+    /// Line diff between two blocks
+    BlockDiff {
+        a: Uuid,
+        b: Uuid,
+        hunks: Vec<crate::diff::DiffHunk>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        warning: Option<String>,
+    },
+    /// A branch rendered as a transcript
+    BranchExport {
+        content: String,
+        format: crate::export::ExportFormat,
+    },
+    /// Non-fatal problem with the preceding response
+    Warning { message: String },
     /// Error occurred
     Error {
+        /// What kind of failure this was, for clients to branch on
+        code: ErrorCode,
         message: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         details: Option<String>,
@@ -1758,8 +5550,23 @@ pub enum ServerMessage {
     Subscribed {
         journal_id: Uuid,
         participant: Participant,
+        /// What this participant may do to the journal
+        role: JournalRole,
         /// Current participants in the room
         participants: Vec<Participant>,
+        /// Number of observers left out of `participants`, if they were collapsed
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        observer_count: Option<usize>,
+    },
+    /// Subscribed to a journal; presence relative to the client's `known_participants`
+    SubscribedDiff {
+        journal_id: Uuid,
+        participant: Participant,
+        role: JournalRole,
+        /// Participants present now that the client did not know about
+        joined: Vec<Participant>,
+        /// Known participants that are no longer present
+        left: Vec<Uuid>,
     },
     /// Unsubscribed from a journal
     Unsubscribed { journal_id: Uuid },
@@ -1774,11 +5581,15 @@ pub enum ServerMessage {
         participant_id: Uuid,
     },
     /// A participant's cursor moved
+    ///
+    /// `offset` is the selection's head, for clients that only draw a caret.
     CursorMoved {
         journal_id: Uuid,
         participant_id: Uuid,
         block_id: Option<Uuid>,
         offset: Option<u32>,
+        anchor: Option<u32>,
+        head: Option<u32>,
     },
     /// A participant's status changed
     ParticipantStatusChanged {
@@ -1786,15 +5597,27 @@ pub enum ServerMessage {
         participant_id: Uuid,
         status: ParticipantStatus,
     },
+    /// A participant started or stopped typing
+    TypingChanged {
+        journal_id: Uuid,
+        participant_id: Uuid,
+        typing: bool,
+    },
     /// Presence information for a journal
     Presence {
         journal_id: Uuid,
         participants: Vec<Participant>,
+        /// Number of observers left out of `participants`, if they were collapsed
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        observer_count: Option<usize>,
     },
     /// CRDT update to apply
     CrdtUpdate {
         journal_id: Uuid,
         source: Option<Uuid>,
+        /// Position in the journal's update sequence; a gap means an update
+        /// was missed (or was this client's own, which isn't echoed)
+        seq: u64,
         /// Base64-encoded update data
         update: String,
     },
@@ -1803,6 +5626,12 @@ pub enum ServerMessage {
         journal_id: Uuid,
         /// Base64-encoded state data
         state: String,
+        /// Chunk index when a large state is split across messages
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u32>,
+        /// Set on the last chunk; clients concatenate the decoded chunks
+        #[serde(default, rename = "final", skip_serializing_if = "Option::is_none")]
+        last: Option<bool>,
     },
     // --- Delegation messages ---
     /// Participant was registered with delegation system
@@ -1812,10 +5641,24 @@ pub enum ServerMessage {
         kind: String,
         capabilities: Vec<String>,
     },
+    /// Participant left the delegation system
+    ParticipantUnregistered { participant_id: Uuid },
     /// Work was delegated
     WorkDelegated {
         work_item: crate::delegation::WorkItem,
     },
+    /// Delegation events will now be pushed to this connection
+    DelegationSubscribed,
+    /// A delegation event involving one of this connection's participants
+    ///
+    /// New work arrives as `work_delegated` instead, with the full item.
+    DelegationEvent {
+        event: crate::delegation::DelegationEvent,
+    },
+    /// The same work was delegated to several participants
+    WorkBatchDelegated {
+        work_items: Vec<crate::delegation::WorkItem>,
+    },
     /// Work was accepted
     WorkAccepted {
         work_item_id: Uuid,
@@ -1826,11 +5669,32 @@ pub enum ServerMessage {
         work_item_id: Uuid,
         assignee_id: Uuid,
     },
+    /// Work was paused
+    WorkPaused {
+        work_item_id: Uuid,
+        assignee_id: Uuid,
+    },
+    /// Paused work was resumed
+    WorkResumed {
+        work_item_id: Uuid,
+        assignee_id: Uuid,
+    },
+    /// A submission awaiting approval was withdrawn
+    SubmissionWithdrawn {
+        work_item_id: Uuid,
+        assignee_id: Uuid,
+    },
     /// Approval was requested
     ApprovalRequested {
         approval: crate::delegation::ApprovalRequest,
         work_item: crate::delegation::WorkItem,
     },
+    /// An approval was recorded but the work still needs more approvers
+    ApprovalGranted {
+        approval_id: Uuid,
+        work_item_id: Uuid,
+        approver_id: Uuid,
+    },
     /// Work was approved
     WorkApproved {
         work_item_id: Uuid,
@@ -1853,10 +5717,74 @@ pub enum ServerMessage {
         work_item_id: Uuid,
         claimed_by: Uuid,
     },
+    /// Work was moved to a different assignee
+    WorkReassigned {
+        work_item_id: Uuid,
+        previous_assignee_id: Uuid,
+        new_assignee_id: Uuid,
+    },
+    /// A pending approval was handed to a different approver
+    ApproverReassigned {
+        approval_id: Uuid,
+        work_item_id: Uuid,
+        previous_approver_id: Uuid,
+        new_approver_id: Uuid,
+    },
+    /// A comment was left on a work item
+    WorkCommentAdded {
+        comment: crate::delegation::WorkItemComment,
+    },
+    /// Comments on a work item, oldest first
+    WorkComments {
+        work_item_id: Uuid,
+        comments: Vec<crate::delegation::WorkItemComment>,
+    },
+    /// Delegation audit trail for a journal, oldest first
+    AuditLog {
+        journal_id: Uuid,
+        entries: Vec<crate::delegation::DelegationAuditEntry>,
+    },
     /// Work queue response
     WorkQueue {
         items: Vec<crate::delegation::WorkItem>,
     },
+    /// Work counts for a participant
+    ParticipantStats {
+        participant_id: Uuid,
+        stats: crate::delegation::ParticipantStats,
+    },
+    /// Work in a journal, oldest first
+    JournalWork {
+        journal_id: Uuid,
+        items: Vec<crate::delegation::WorkItem>,
+    },
+    /// Claimable work in a journal left behind by unregistered participants
+    UnassignedWork {
+        journal_id: Uuid,
+        items: Vec<crate::delegation::WorkItem>,
+    },
+    /// Overdue work in the participant's queue
+    OverdueWork {
+        items: Vec<crate::delegation::WorkItem>,
+    },
+    /// The connection's own participant and its current capabilities
+    MyCapabilities {
+        participant_id: Uuid,
+        capabilities: Vec<String>,
+    },
+    /// Another participant's current capabilities
+    ParticipantCapabilities {
+        participant_id: Uuid,
+        capabilities: Vec<String>,
+    },
+    /// A single work item
+    WorkItem {
+        work_item: crate::delegation::WorkItem,
+    },
+    /// Work search response
+    WorkSearchResults {
+        items: Vec<crate::delegation::WorkItem>,
+    },
     /// Approval queue response
     ApprovalQueue {
         items: Vec<crate::delegation::ApprovalRequest>,
@@ -1870,6 +5798,54 @@ pub enum ServerMessage {
         participant_id: Uuid,
         accepting: bool,
     },
+    /// Result of a bulk accepting-work change
+    BulkAcceptingWorkChanged {
+        accepting: bool,
+        updated: Vec<Uuid>,
+        /// Requested IDs that aren't registered
+        unknown: Vec<Uuid>,
+    },
+}
+
+impl From<BlockEvent> for ServerMessage {
+    fn from(event: BlockEvent) -> Self {
+        match event {
+            BlockEvent::Created(block) => ServerMessage::BlockCreated { block },
+            BlockEvent::ContentDelta {
+                block_id,
+                delta,
+                offset,
+            } => ServerMessage::BlockContentDelta {
+                block_id,
+                delta,
+                offset,
+            },
+            BlockEvent::StatusChanged { block_id, status } => {
+                ServerMessage::BlockStatusChanged { block_id, status }
+            }
+            BlockEvent::Forked {
+                original_block_id,
+                new_block,
+            } => ServerMessage::BlockForked {
+                original_block_id,
+                new_block,
+            },
+            BlockEvent::Cancelled { block_id } => ServerMessage::BlockCancelled { block_id },
+            BlockEvent::Deleted { block_id } => ServerMessage::BlockDeleted { block_id },
+            BlockEvent::Edited(block) => ServerMessage::BlockEdited { block },
+            BlockEvent::Usage { block_id, usage } => ServerMessage::BlockUsage {
+                block_id,
+                prompt_tokens: usage.prompt_tokens,
+                completion_tokens: usage.completion_tokens,
+            },
+            BlockEvent::ReactionChanged { block_id, reaction } => ServerMessage::ReactionChanged {
+                block_id,
+                emoji: reaction.emoji,
+                count: reaction.count,
+                reactors: reaction.reactors,
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1890,10 +5866,16 @@ mod tests {
                 journal_id: jid,
                 content,
                 session_id,
+                model,
+                system_prompt,
+                idempotency_key,
             } => {
                 assert_eq!(jid, journal_id);
                 assert_eq!(content, "Hello");
                 assert_eq!(session_id, Some("sess_123".to_string()));
+                assert_eq!(model, None);
+                assert_eq!(system_prompt, None);
+                assert_eq!(idempotency_key, None);
             }
             _ => panic!("Expected Submit message"),
         }
@@ -1908,13 +5890,57 @@ mod tests {
         );
         let msg: ClientMessage = serde_json::from_str(&json).unwrap();
         match msg {
-            ClientMessage::Submit { session_id, .. } => {
+            ClientMessage::Submit {
+                session_id, model, ..
+            } => {
+                assert_eq!(session_id, None);
+                assert_eq!(model, None);
+            }
+            _ => panic!("Expected Submit message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_submit_with_model() {
+        let journal_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "submit", "journal_id": "{}", "content": "Test", "model": "anthropic/claude-sonnet"}}"#,
+            journal_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::Submit {
+                session_id, model, ..
+            } => {
                 assert_eq!(session_id, None);
+                assert_eq!(model.as_deref(), Some("anthropic/claude-sonnet"));
             }
             _ => panic!("Expected Submit message"),
         }
     }
 
+    #[test]
+    fn test_client_message_fork_and_rerun_with_model() {
+        let block_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "fork", "block_id": "{}", "model": "openai/gpt-4o"}}"#,
+            block_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        assert!(
+            matches!(msg, ClientMessage::Fork { model: Some(ref m), .. } if m == "openai/gpt-4o")
+        );
+
+        let json = format!(
+            r#"{{"type": "rerun", "block_id": "{}", "model": "openai/gpt-4o"}}"#,
+            block_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        assert!(
+            matches!(msg, ClientMessage::Rerun { model: Some(ref m), .. } if m == "openai/gpt-4o")
+        );
+    }
+
     #[test]
     fn test_client_message_create_journal() {
         let json = r#"{"type": "create_journal", "title": "My Journal"}"#;
@@ -1927,6 +5953,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_client_message_get_journal_summary() {
+        let journal_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "get_journal_summary", "journal_id": "{}"}}"#,
+            journal_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::GetJournalSummary { journal_id: id } if id == journal_id
+        ));
+    }
+
+    #[test]
+    fn test_client_message_clone_journal() {
+        let journal_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "clone_journal", "journal_id": "{}"}}"#,
+            journal_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::CloneJournal {
+                journal_id: id,
+                title,
+            } => {
+                assert_eq!(id, journal_id);
+                assert_eq!(title, None);
+            }
+            _ => panic!("Expected CloneJournal message"),
+        }
+    }
+
     #[test]
     fn test_client_message_create_journal_no_title() {
         let json = r#"{"type": "create_journal"}"#;
@@ -1948,7 +6008,9 @@ mod tests {
         );
         let msg: ClientMessage = serde_json::from_str(&json).unwrap();
         match msg {
-            ClientMessage::GetJournal { journal_id: jid } => {
+            ClientMessage::GetJournal {
+                journal_id: jid, ..
+            } => {
                 assert_eq!(jid, journal_id);
             }
             _ => panic!("Expected GetJournal message"),
@@ -1959,7 +6021,94 @@ mod tests {
     fn test_client_message_list_journals() {
         let json = r#"{"type": "list_journals"}"#;
         let msg: ClientMessage = serde_json::from_str(json).unwrap();
-        assert!(matches!(msg, ClientMessage::ListJournals));
+        assert!(matches!(
+            msg,
+            ClientMessage::ListJournals {
+                include_deleted: false,
+                include_archived: false,
+                query: None,
+                sort: None
+            }
+        ));
+
+        let json = r#"{"type": "list_journals", "query": "plan", "sort": "title"}"#;
+        let msg: ClientMessage = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::ListJournals {
+                query: Some(q),
+                sort: Some(crate::models::JournalSort::Title),
+                ..
+            } if q == "plan"
+        ));
+    }
+
+    #[test]
+    fn test_client_message_delete_journal() {
+        let id = Uuid::new_v4();
+        let json = format!(r#"{{"type": "delete_journal", "journal_id": "{}"}}"#, id);
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        assert!(matches!(msg, ClientMessage::DeleteJournal { journal_id } if journal_id == id));
+
+        let json = r#"{"type": "list_journals", "include_deleted": true}"#;
+        let msg: ClientMessage = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::ListJournals {
+                include_deleted: true,
+                include_archived: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_client_message_set_role() {
+        let journal = Uuid::new_v4();
+        let participant = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "set_role", "journal_id": "{}", "participant_id": "{}", "role": "guest"}}"#,
+            journal, participant
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::SetRole { journal_id, participant_id, role: JournalRole::Guest }
+                if journal_id == journal && participant_id == participant
+        ));
+
+        let json = json.replace("guest", "admin");
+        assert!(serde_json::from_str::<ClientMessage>(&json).is_err());
+    }
+
+    #[test]
+    fn test_client_message_archive_journal() {
+        let id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "archive_journal", "journal_id": "{}", "archived": true}}"#,
+            id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::ArchiveJournal {
+                journal_id,
+                archived,
+            } => {
+                assert_eq!(journal_id, id);
+                assert!(archived);
+            }
+            _ => panic!("Expected ArchiveJournal message"),
+        }
+
+        let json = r#"{"type": "list_journals", "include_archived": true}"#;
+        assert!(matches!(
+            serde_json::from_str::<ClientMessage>(json).unwrap(),
+            ClientMessage::ListJournals {
+                include_deleted: false,
+                include_archived: true,
+                ..
+            }
+        ));
     }
 
     #[test]
@@ -1988,6 +6137,8 @@ mod tests {
             title: "Test Journal".to_string(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            deleted_at: None,
+            archived_at: None,
         };
         let msg = ServerMessage::Journal {
             journal,
@@ -2015,8 +6166,11 @@ mod tests {
             status: BlockStatus::Complete,
             parent_id: None,
             forked_from_id: None,
+            position: None,
+            version: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            content_html: None,
         };
         let msg = ServerMessage::BlockCreated { block };
         let json = serde_json::to_string(&msg).unwrap();
@@ -2030,10 +6184,12 @@ mod tests {
         let msg = ServerMessage::BlockContentDelta {
             block_id,
             delta: "new content".to_string(),
+            offset: 12,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("block_content_delta"));
         assert!(json.contains("new content"));
+        assert!(json.contains(r#""offset":12"#));
     }
 
     #[test]
@@ -2051,6 +6207,7 @@ mod tests {
     #[test]
     fn test_server_message_error() {
         let msg = ServerMessage::Error {
+            code: ErrorCode::Internal,
             message: "Something went wrong".to_string(),
             details: None,
         };
@@ -2062,6 +6219,7 @@ mod tests {
     #[test]
     fn test_server_message_debug() {
         let msg = ServerMessage::Error {
+            code: ErrorCode::Internal,
             message: "test".to_string(),
             details: None,
         };
@@ -2071,7 +6229,12 @@ mod tests {
 
     #[test]
     fn test_client_message_debug() {
-        let msg = ClientMessage::ListJournals;
+        let msg = ClientMessage::ListJournals {
+            include_deleted: false,
+            include_archived: false,
+            query: None,
+            sort: None,
+        };
         let debug_str = format!("{:?}", msg);
         assert!(debug_str.contains("ListJournals"));
     }
@@ -2104,6 +6267,7 @@ mod tests {
             ClientMessage::Fork {
                 block_id: bid,
                 session_id,
+                ..
             } => {
                 assert_eq!(bid, block_id);
                 assert_eq!(session_id, Some("sess_123".to_string()));
@@ -2137,6 +6301,7 @@ mod tests {
             ClientMessage::Rerun {
                 block_id: bid,
                 session_id,
+                ..
             } => {
                 assert_eq!(bid, block_id);
                 assert_eq!(session_id, Some("sess_456".to_string()));
@@ -2151,23 +6316,61 @@ mod tests {
         let json = format!(r#"{{"type": "rerun", "block_id": "{}"}}"#, block_id);
         let msg: ClientMessage = serde_json::from_str(&json).unwrap();
         match msg {
-            ClientMessage::Rerun { session_id, .. } => {
-                assert_eq!(session_id, None);
+            ClientMessage::Rerun { session_id, .. } => {
+                assert_eq!(session_id, None);
+            }
+            _ => panic!("Expected Rerun message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_cancel() {
+        let block_id = Uuid::new_v4();
+        let json = format!(r#"{{"type": "cancel", "block_id": "{}"}}"#, block_id);
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::Cancel { block_id: bid } => {
+                assert_eq!(bid, block_id);
+            }
+            _ => panic!("Expected Cancel message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_export_branch() {
+        let block_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "export_branch", "block_id": "{}", "format": "markdown"}}"#,
+            block_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::ExportBranch {
+                block_id: bid,
+                format,
+            } => {
+                assert_eq!(bid, block_id);
+                assert_eq!(format, crate::export::ExportFormat::Markdown);
             }
-            _ => panic!("Expected Rerun message"),
+            _ => panic!("Expected ExportBranch message"),
         }
+
+        // Only known formats are accepted
+        let json = format!(
+            r#"{{"type": "export_branch", "block_id": "{}", "format": "pdf"}}"#,
+            block_id
+        );
+        assert!(serde_json::from_str::<ClientMessage>(&json).is_err());
     }
 
     #[test]
-    fn test_client_message_cancel() {
+    fn test_client_message_resume_block() {
         let block_id = Uuid::new_v4();
-        let json = format!(r#"{{"type": "cancel", "block_id": "{}"}}"#, block_id);
+        let json = format!(r#"{{"type": "resume_block", "block_id": "{}"}}"#, block_id);
         let msg: ClientMessage = serde_json::from_str(&json).unwrap();
         match msg {
-            ClientMessage::Cancel { block_id: bid } => {
-                assert_eq!(bid, block_id);
-            }
-            _ => panic!("Expected Cancel message"),
+            ClientMessage::ResumeBlock { block_id: bid } => assert_eq!(bid, block_id),
+            _ => panic!("Expected ResumeBlock message"),
         }
     }
 
@@ -2182,8 +6385,11 @@ mod tests {
             status: BlockStatus::Pending,
             parent_id: Some(original_block_id),
             forked_from_id: Some(original_block_id),
+            position: None,
+            version: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            content_html: None,
         };
         let msg = ServerMessage::BlockForked {
             original_block_id,
@@ -2220,6 +6426,7 @@ mod tests {
                 journal_id: jid,
                 name,
                 kind,
+                ..
             } => {
                 assert_eq!(jid, journal_id);
                 assert_eq!(name, "Alice");
@@ -2229,6 +6436,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_presence_diff() {
+        let me = Participant::new("Me", ParticipantKind::User);
+        let alice = Participant::new("Alice", ParticipantKind::User);
+        let bob = Participant::new("Bob", ParticipantKind::Agent);
+        let carol = Participant::new("Carol", ParticipantKind::User);
+        let gone = Uuid::new_v4();
+        let current = vec![me.clone(), alice.clone(), bob.clone(), carol.clone()];
+
+        let (joined, left) =
+            presence_diff(&[alice.id, bob.id, gone], &current, me.id).expect("diff");
+        assert_eq!(joined.len(), 1);
+        assert_eq!(joined[0].id, carol.id);
+        assert_eq!(left, vec![gone]);
+
+        // Nothing known, or nothing still relevant, falls back to the full list
+        assert!(presence_diff(&[], &current, me.id).is_none());
+        assert!(presence_diff(&[gone], &current, me.id).is_none());
+    }
+
     #[test]
     fn test_client_message_subscribe_no_kind() {
         let journal_id = Uuid::new_v4();
@@ -2275,10 +6502,39 @@ mod tests {
                 journal_id: jid,
                 block_id: bid,
                 offset,
+                anchor,
+                head,
             } => {
                 assert_eq!(jid, journal_id);
                 assert_eq!(bid, Some(block_id));
                 assert_eq!(offset, Some(42));
+                assert_eq!((anchor, head), (None, None));
+            }
+            _ => panic!("Expected Cursor message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_cursor_selection() {
+        let journal_id = Uuid::new_v4();
+        let block_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "cursor", "journal_id": "{}", "block_id": "{}", "anchor": 12, "head": 4}}"#,
+            journal_id, block_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::Cursor {
+                block_id: bid,
+                offset,
+                anchor,
+                head,
+                ..
+            } => {
+                assert_eq!(bid, Some(block_id));
+                assert_eq!(offset, None);
+                assert_eq!(anchor, Some(12));
+                assert_eq!(head, Some(4));
             }
             _ => panic!("Expected Cursor message"),
         }
@@ -2300,6 +6556,59 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_client_message_edit_block() {
+        let block_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "edit_block", "block_id": "{}", "content": "Fixed typo"}}"#,
+            block_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::EditBlock { block_id: id, content } if id == block_id && content == "Fixed typo"
+        ));
+    }
+
+    #[test]
+    fn test_client_message_react() {
+        let block_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "react", "block_id": "{}", "emoji": "👍"}}"#,
+            block_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::React { block_id: id, emoji } if id == block_id && emoji == "👍"
+        ));
+    }
+
+    #[test]
+    fn test_client_message_typing() {
+        let journal_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "typing", "journal_id": "{}", "typing": true}}"#,
+            journal_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::Typing { journal_id: id, typing: true } if id == journal_id
+        ));
+    }
+
+    #[test]
+    fn test_client_message_heartbeat() {
+        let journal_id = Uuid::new_v4();
+        let json = format!(r#"{{"type": "heartbeat", "journal_id": "{}"}}"#, journal_id);
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::Heartbeat { journal_id: jid } => assert_eq!(jid, journal_id),
+            _ => panic!("Expected Heartbeat message"),
+        }
+    }
+
     #[test]
     fn test_client_message_get_presence() {
         let journal_id = Uuid::new_v4();
@@ -2328,9 +6637,12 @@ mod tests {
             ClientMessage::CrdtUpdate {
                 journal_id: jid,
                 update,
+                seq,
+                ..
             } => {
                 assert_eq!(jid, journal_id);
                 assert_eq!(update, "SGVsbG8=");
+                assert_eq!(seq, None);
             }
             _ => panic!("Expected CrdtUpdate message"),
         }
@@ -2379,7 +6691,9 @@ mod tests {
         let msg = ServerMessage::Subscribed {
             journal_id,
             participant: participant.clone(),
+            role: JournalRole::Owner,
             participants: vec![participant],
+            observer_count: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("subscribed"));
@@ -2431,6 +6745,8 @@ mod tests {
             participant_id,
             block_id: Some(block_id),
             offset: Some(100),
+            anchor: Some(90),
+            head: Some(100),
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("cursor_moved"));
@@ -2457,9 +6773,11 @@ mod tests {
         let msg = ServerMessage::Presence {
             journal_id,
             participants: vec![],
+            observer_count: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("presence"));
+        assert!(!json.contains("observer_count"));
         assert!(json.contains("participants"));
     }
 
@@ -2470,6 +6788,7 @@ mod tests {
         let msg = ServerMessage::CrdtUpdate {
             journal_id,
             source: Some(source),
+            seq: 7,
             update: "SGVsbG8=".to_string(),
         };
         let json = serde_json::to_string(&msg).unwrap();
@@ -2477,18 +6796,385 @@ mod tests {
         assert!(json.contains("SGVsbG8="));
     }
 
+    #[test]
+    fn test_client_message_reorder_block() {
+        let block_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "reorder_block", "block_id": "{}", "position": 1.5}}"#,
+            block_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::ReorderBlock {
+                block_id: id,
+                position,
+            } => {
+                assert_eq!(id, block_id);
+                assert_eq!(position, 1.5);
+            }
+            _ => panic!("Expected ReorderBlock message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_delete_block() {
+        let block_id = Uuid::new_v4();
+        let json = format!(r#"{{"type": "delete_block", "block_id": "{}"}}"#, block_id);
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::DeleteBlock {
+                block_id: id,
+                cascade,
+            } => {
+                assert_eq!(id, block_id);
+                assert!(!cascade);
+            }
+            _ => panic!("Expected DeleteBlock message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_get_block() {
+        let block_id = Uuid::new_v4();
+        let json = format!(r#"{{"type": "get_block", "block_id": "{}"}}"#, block_id);
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::GetBlock {
+                block_id: id,
+                render,
+            } => {
+                assert_eq!(id, block_id);
+                assert_eq!(render, None);
+            }
+            _ => panic!("Expected GetBlock message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_fetch_block() {
+        let block_id = Uuid::new_v4();
+        let json = format!(r#"{{"type": "fetch_block", "block_id": "{}"}}"#, block_id);
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::FetchBlock { block_id: id } => assert_eq!(id, block_id),
+            _ => panic!("Expected FetchBlock message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_search_blocks() {
+        let json = r#"{"type": "search_blocks", "query": "serde"}"#;
+        let msg: ClientMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ClientMessage::SearchBlocks {
+                query,
+                journal_id,
+                limit,
+            } => {
+                assert_eq!(query, "serde");
+                assert!(journal_id.is_none());
+                assert!(limit.is_none());
+            }
+            _ => panic!("Expected SearchBlocks message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_get_blocks_page() {
+        let journal_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "get_blocks_page", "journal_id": "{}", "limit": 20}}"#,
+            journal_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::GetBlocksPage {
+                journal_id: id,
+                before,
+                limit,
+            } => {
+                assert_eq!(id, journal_id);
+                assert!(before.is_none());
+                assert_eq!(limit, 20);
+            }
+            _ => panic!("Expected GetBlocksPage message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_reassign_work() {
+        let work_item_id = Uuid::new_v4();
+        let new_assignee_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "reassign_work", "work_item_id": "{}", "new_assignee_id": "{}"}}"#,
+            work_item_id, new_assignee_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::ReassignWork {
+                work_item_id: id,
+                new_assignee_id: assignee,
+            } => {
+                assert_eq!(id, work_item_id);
+                assert_eq!(assignee, new_assignee_id);
+            }
+            _ => panic!("Expected ReassignWork message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_reassign_approver() {
+        let approval_id = Uuid::new_v4();
+        let new_approver_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "reassign_approver", "approval_id": "{}", "new_approver_id": "{}"}}"#,
+            approval_id, new_approver_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::ReassignApprover {
+                approval_id: id,
+                new_approver_id: approver,
+            } => {
+                assert_eq!(id, approval_id);
+                assert_eq!(approver, new_approver_id);
+            }
+            _ => panic!("Expected ReassignApprover message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_add_work_comment() {
+        let work_item_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "add_work_comment", "work_item_id": "{}", "text": "Looks good"}}"#,
+            work_item_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::AddWorkComment {
+                work_item_id: id,
+                text,
+            } => {
+                assert_eq!(id, work_item_id);
+                assert_eq!(text, "Looks good");
+            }
+            _ => panic!("Expected AddWorkComment message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_get_audit_log() {
+        let journal_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "get_audit_log", "journal_id": "{}"}}"#,
+            journal_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::GetAuditLog { journal_id: id } => assert_eq!(id, journal_id),
+            _ => panic!("Expected GetAuditLog message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_get_work_item() {
+        let work_item_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "get_work_item", "work_item_id": "{}"}}"#,
+            work_item_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::GetWorkItem { work_item_id: id } => assert_eq!(id, work_item_id),
+            _ => panic!("Expected GetWorkItem message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_unregister_participant() {
+        let journal_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "unregister_participant", "journal_id": "{}"}}"#,
+            journal_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::UnregisterParticipant { journal_id: id } if id == journal_id
+        ));
+    }
+
+    #[test]
+    fn test_client_message_get_unassigned_work() {
+        let journal_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "get_unassigned_work", "journal_id": "{}"}}"#,
+            journal_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::GetUnassignedWork { journal_id: id } if id == journal_id
+        ));
+    }
+
+    #[test]
+    fn test_client_message_get_work_queue_sorted() {
+        let msg: ClientMessage = serde_json::from_str(r#"{"type": "get_work_queue"}"#).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::GetWorkQueue {
+                sorted: false,
+                tag: None
+            }
+        ));
+
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type": "get_work_queue", "sorted": true}"#).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::GetWorkQueue {
+                sorted: true,
+                tag: None
+            }
+        ));
+    }
+
+    #[test]
+    fn test_client_message_hello() {
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type": "hello", "binary_crdt": true}"#).unwrap();
+        match msg {
+            ClientMessage::Hello {
+                binary_crdt,
+                msgpack,
+            } => {
+                assert!(binary_crdt);
+                assert!(!msgpack);
+            }
+            _ => panic!("Expected Hello message"),
+        }
+
+        let msg: ClientMessage = serde_json::from_str(r#"{"type": "hello"}"#).unwrap();
+        match msg {
+            ClientMessage::Hello { binary_crdt, .. } => assert!(!binary_crdt),
+            _ => panic!("Expected Hello message"),
+        }
+
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type": "hello", "msgpack": true}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Hello { msgpack: true, .. }));
+    }
+
+    #[test]
+    fn test_server_message_welcome() {
+        let msg = ServerMessage::Welcome {
+            binary_crdt: true,
+            msgpack: false,
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("welcome"));
+        assert!(json.contains(r#""binary_crdt":true"#));
+    }
+
     #[test]
     fn test_server_message_sync_state() {
         let journal_id = Uuid::new_v4();
         let msg = ServerMessage::SyncState {
             journal_id,
             state: "AQAAAQ==".to_string(),
+            seq: None,
+            last: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("sync_state"));
+        assert!(!json.contains("final"));
         assert!(json.contains("AQAAAQ=="));
     }
 
+    #[test]
+    fn test_reassemble_chunked_update() {
+        const LIMIT: usize = 1024;
+        let journal_id = Uuid::new_v4();
+        let mut partial = std::collections::HashMap::new();
+
+        assert_eq!(
+            reassemble_update(&mut partial, journal_id, 0, None, vec![1, 2], LIMIT),
+            Ok(None)
+        );
+        assert_eq!(
+            reassemble_update(&mut partial, journal_id, 1, Some(false), vec![3], LIMIT),
+            Ok(None)
+        );
+        assert_eq!(
+            reassemble_update(&mut partial, journal_id, 2, Some(true), vec![4, 5], LIMIT),
+            Ok(Some(vec![1, 2, 3, 4, 5]))
+        );
+        assert!(partial.is_empty());
+
+        // A skipped chunk discards the partial update
+        reassemble_update(&mut partial, journal_id, 0, None, vec![1], LIMIT).unwrap();
+        assert!(
+            reassemble_update(&mut partial, journal_id, 2, Some(true), vec![3], LIMIT).is_err()
+        );
+        assert!(partial.is_empty());
+    }
+
+    #[test]
+    fn test_reassemble_update_refuses_oversized_updates() {
+        let journal_id = Uuid::new_v4();
+        let mut partial = std::collections::HashMap::new();
+
+        reassemble_update(&mut partial, journal_id, 0, None, vec![0; 6], 10).unwrap();
+        // The chunk that would pass the limit is refused and the rest dropped
+        assert!(reassemble_update(&mut partial, journal_id, 1, None, vec![0; 6], 10).is_err());
+        assert!(partial.is_empty());
+    }
+
+    #[test]
+    fn test_client_message_chunked_crdt_update() {
+        let json = format!(
+            r#"{{"type": "crdt_update", "journal_id": "{}", "update": "AQ==", "seq": 3, "final": true}}"#,
+            Uuid::new_v4()
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::CrdtUpdate { seq, last, .. } => {
+                assert_eq!(seq, Some(3));
+                assert_eq!(last, Some(true));
+            }
+            _ => panic!("Expected CrdtUpdate message"),
+        }
+    }
+
+    #[test]
+    fn test_client_message_crdt_resync() {
+        let journal_id = Uuid::new_v4();
+        let json = format!(
+            r#"{{"type": "crdt_resync", "journal_id": "{}", "from_seq": 12}}"#,
+            journal_id
+        );
+        let msg: ClientMessage = serde_json::from_str(&json).unwrap();
+        match msg {
+            ClientMessage::CrdtResync {
+                journal_id: jid,
+                from_seq,
+            } => {
+                assert_eq!(jid, journal_id);
+                assert_eq!(from_seq, 12);
+            }
+            _ => panic!("Expected CrdtResync message"),
+        }
+    }
+
+    #[test]
+    fn test_base64_encode_into_appends() {
+        let mut buf = String::from("x");
+        base64_encode_into(b"abc", &mut buf);
+        assert_eq!(buf, "xYWJj");
+    }
+
     #[test]
     fn test_base64_encode_decode_roundtrip() {
         let original = b"Hello, CRDT World!";
@@ -19,7 +19,9 @@ async fn setup_app() -> (Router, sqlx::SqlitePool) {
             id TEXT PRIMARY KEY NOT NULL,
             title TEXT NOT NULL,
             created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            deleted_at DATETIME,
+            archived_at DATETIME
         )
         "#,
     )
@@ -32,11 +34,13 @@ async fn setup_app() -> (Router, sqlx::SqlitePool) {
         CREATE TABLE IF NOT EXISTS blocks (
             id TEXT PRIMARY KEY NOT NULL,
             journal_id TEXT NOT NULL REFERENCES journals(id),
-            block_type TEXT NOT NULL CHECK (block_type IN ('user', 'assistant')),
+            block_type TEXT NOT NULL CHECK (block_type IN ('user', 'assistant', 'system')),
             content TEXT NOT NULL DEFAULT '',
             status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'streaming', 'complete', 'error')),
             parent_id TEXT REFERENCES blocks(id),
             forked_from_id TEXT REFERENCES blocks(id),
+            position REAL,
+            version INTEGER NOT NULL DEFAULT 0,
             created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
             updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
         )
@@ -46,6 +50,20 @@ async fn setup_app() -> (Router, sqlx::SqlitePool) {
     .await
     .expect("Failed to create blocks table");
 
+    sqlx::query(include_str!(
+        "../migrations/20260110000021_journal_sessions.sql"
+    ))
+    .execute(&pool)
+    .await
+    .expect("Failed to create journal sessions table");
+
+    sqlx::query(include_str!(
+        "../migrations/20260110000023_journal_roles.sql"
+    ))
+    .execute(&pool)
+    .await
+    .expect("Failed to create journal roles table");
+
     let state = AppState::new(pool.clone());
 
     let app = Router::new()
@@ -77,6 +95,50 @@ async fn test_health_endpoint() {
     assert_eq!(response.status(), hyper::StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_health_reports_unreachable_database() {
+    // The parent directory doesn't exist, so every connection attempt fails
+    let pool = SqlitePoolOptions::new()
+        .acquire_timeout(std::time::Duration::from_secs(1))
+        .connect_lazy("sqlite:///nonexistent/outer-health/test.db")
+        .unwrap();
+    let app = Router::new()
+        .route("/health", get(outer::health::handler))
+        .with_state(AppState::new(pool));
+
+    let response = app
+        .oneshot(
+            hyper::Request::builder()
+                .uri("/health")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), hyper::StatusCode::SERVICE_UNAVAILABLE);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["db"], "error");
+}
+
+#[tokio::test]
+async fn test_health_check_with_reachable_dependencies() {
+    let (_app, pool) = setup_app().await;
+    let state = AppState::new(pool);
+
+    let opencode = wiremock::MockServer::start().await;
+    wiremock::Mock::given(wiremock::matchers::method("GET"))
+        .respond_with(wiremock::ResponseTemplate::new(200))
+        .mount(&opencode)
+        .await;
+
+    let report = outer::health::check(&state, &opencode.uri()).await;
+    assert!(report.is_healthy(), "{:?}", report);
+}
+
 #[tokio::test]
 async fn test_app_state_new() {
     let pool = SqlitePoolOptions::new()
@@ -4,13 +4,20 @@
 
 use axum::{routing::get, Router};
 use futures::{SinkExt, StreamExt};
+use outer::delegation::Capability;
 use outer::AppState;
 use sqlx::sqlite::SqlitePoolOptions;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
 async fn setup_server() -> (SocketAddr, sqlx::SqlitePool) {
+    let (addr, pool, _state) = setup_server_with_state().await;
+    (addr, pool)
+}
+
+async fn setup_server_with_state() -> (SocketAddr, sqlx::SqlitePool, Arc<AppState>) {
     let pool = SqlitePoolOptions::new()
         .max_connections(1)
         .connect("sqlite::memory:")
@@ -24,7 +31,9 @@ async fn setup_server() -> (SocketAddr, sqlx::SqlitePool) {
             id TEXT PRIMARY KEY NOT NULL,
             title TEXT NOT NULL,
             created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            deleted_at DATETIME,
+            archived_at DATETIME
         )
         "#,
     )
@@ -37,11 +46,13 @@ async fn setup_server() -> (SocketAddr, sqlx::SqlitePool) {
         CREATE TABLE IF NOT EXISTS blocks (
             id TEXT PRIMARY KEY NOT NULL,
             journal_id TEXT NOT NULL REFERENCES journals(id),
-            block_type TEXT NOT NULL CHECK (block_type IN ('user', 'assistant')),
+            block_type TEXT NOT NULL CHECK (block_type IN ('user', 'assistant', 'system')),
             content TEXT NOT NULL DEFAULT '',
             status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'streaming', 'complete', 'error')),
             parent_id TEXT REFERENCES blocks(id),
             forked_from_id TEXT REFERENCES blocks(id),
+            position REAL,
+            version INTEGER NOT NULL DEFAULT 0,
             created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
             updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
         )
@@ -51,11 +62,25 @@ async fn setup_server() -> (SocketAddr, sqlx::SqlitePool) {
     .await
     .expect("Failed to create blocks table");
 
+    sqlx::query(include_str!(
+        "../migrations/20260110000021_journal_sessions.sql"
+    ))
+    .execute(&pool)
+    .await
+    .expect("Failed to create journal sessions table");
+
+    sqlx::query(include_str!(
+        "../migrations/20260110000023_journal_roles.sql"
+    ))
+    .execute(&pool)
+    .await
+    .expect("Failed to create journal roles table");
+
     let state = AppState::new(pool.clone());
 
     let app = Router::new()
         .route("/ws", get(outer::websocket::handler))
-        .with_state(state);
+        .with_state(Arc::clone(&state));
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -66,7 +91,7 @@ async fn setup_server() -> (SocketAddr, sqlx::SqlitePool) {
 
     tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
 
-    (addr, pool)
+    (addr, pool, state)
 }
 
 async fn connect_ws(
@@ -150,6 +175,46 @@ async fn test_register_participant_agent() {
     assert!(!caps.iter().any(|c| c == "approve"));
 }
 
+#[tokio::test]
+async fn test_register_participant_kind_defaults_to_user() {
+    let (addr, _pool) = setup_server().await;
+    let mut ws = connect_ws(addr).await;
+
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": Uuid::new_v4().to_string(),
+        "name": "Alice"
+    });
+    send_msg(&mut ws, msg).await;
+
+    let response = recv_msg(&mut ws).await;
+    assert_eq!(response["type"], "participant_registered");
+    assert_eq!(response["kind"], "user");
+}
+
+#[tokio::test]
+async fn test_register_participant_rejects_unknown_kind() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    let mut ws = connect_ws(addr).await;
+
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": Uuid::new_v4().to_string(),
+        "name": "Bot",
+        "kind": "agnet"
+    });
+    send_msg(&mut ws, msg).await;
+
+    let response = recv_msg(&mut ws).await;
+    assert_eq!(response["type"], "error");
+    assert_eq!(response["code"], "invalid_kind");
+    assert!(state
+        .delegation_manager
+        .list_participants()
+        .await
+        .is_empty());
+}
+
 #[tokio::test]
 async fn test_human_to_agent_delegation() {
     let (addr, _pool) = setup_server().await;
@@ -198,6 +263,95 @@ async fn test_human_to_agent_delegation() {
     assert_eq!(work_item["status"], "pending");
 }
 
+#[tokio::test]
+async fn test_subscribed_participant_is_pushed_delegated_work() {
+    let (addr, _pool) = setup_server().await;
+    let journal_id = Uuid::new_v4();
+
+    // Bob registers and subscribes before any work exists
+    let mut ws_bob = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Bob",
+        "kind": "user"
+    });
+    send_msg(&mut ws_bob, msg).await;
+    let bob_id = recv_msg(&mut ws_bob).await["participant_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    send_msg(
+        &mut ws_bob,
+        serde_json::json!({"type": "subscribe_delegation"}),
+    )
+    .await;
+    assert_eq!(recv_msg(&mut ws_bob).await["type"], "delegation_subscribed");
+
+    let mut ws_alice = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Alice",
+        "kind": "user"
+    });
+    send_msg(&mut ws_alice, msg).await;
+    recv_msg(&mut ws_alice).await;
+    send_msg(
+        &mut ws_alice,
+        serde_json::json!({"type": "subscribe_delegation"}),
+    )
+    .await;
+    assert_eq!(
+        recv_msg(&mut ws_alice).await["type"],
+        "delegation_subscribed"
+    );
+
+    let msg = serde_json::json!({
+        "type": "delegate",
+        "journal_id": journal_id.to_string(),
+        "description": "Review the draft",
+        "assignee_id": bob_id
+    });
+    send_msg(&mut ws_alice, msg).await;
+    // Alice gets her reply once, not an echo of her own action as well
+    let response = recv_msg(&mut ws_alice).await;
+    assert_eq!(response["type"], "work_delegated");
+    let work_item_id = response["work_item"]["id"].as_str().unwrap().to_string();
+
+    // Bob hears about it without asking
+    let pushed = tokio::time::timeout(tokio::time::Duration::from_secs(5), recv_msg(&mut ws_bob))
+        .await
+        .expect("Bob was not sent the delegated work");
+    assert_eq!(pushed["type"], "work_delegated");
+    assert_eq!(pushed["work_item"]["id"], work_item_id.as_str());
+    assert_eq!(pushed["work_item"]["description"], "Review the draft");
+
+    // And Alice hears when Bob accepts
+    send_msg(
+        &mut ws_bob,
+        serde_json::json!({"type": "accept_work", "work_item_id": work_item_id}),
+    )
+    .await;
+    let pushed = tokio::time::timeout(tokio::time::Duration::from_secs(5), recv_msg(&mut ws_alice))
+        .await
+        .expect("Alice was not told the work was accepted");
+    assert_eq!(pushed["type"], "delegation_event");
+    assert_eq!(pushed["event"]["type"], "work_accepted");
+    assert_eq!(pushed["event"]["work_item_id"], work_item_id.as_str());
+}
+
+#[tokio::test]
+async fn test_subscribe_delegation_requires_registration() {
+    let (addr, _pool) = setup_server().await;
+    let mut ws = connect_ws(addr).await;
+
+    send_msg(&mut ws, serde_json::json!({"type": "subscribe_delegation"})).await;
+    let response = recv_msg(&mut ws).await;
+    assert_eq!(response["type"], "error");
+    assert_eq!(response["code"], "not_registered");
+}
+
 #[tokio::test]
 async fn test_agent_to_human_delegation() {
     let (addr, _pool) = setup_server().await;
@@ -474,6 +628,207 @@ async fn test_get_work_queue() {
     assert_eq!(queue_response["items"].as_array().unwrap().len(), 3);
 }
 
+#[tokio::test]
+async fn test_reconnecting_assignee_keeps_work() {
+    let (addr, _pool) = setup_server().await;
+    let journal_id = Uuid::new_v4();
+
+    let mut ws_alice = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Alice",
+        "kind": "user"
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let _ = recv_msg(&mut ws_alice).await;
+
+    let mut ws_bot = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Bot",
+        "kind": "agent"
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let bot_response = recv_msg(&mut ws_bot).await;
+    let bot_id = bot_response["participant_id"].as_str().unwrap().to_string();
+
+    let msg = serde_json::json!({
+        "type": "delegate",
+        "journal_id": journal_id.to_string(),
+        "description": "Task",
+        "assignee_id": bot_id
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let work_item_id = recv_msg(&mut ws_alice).await["work_item"]["id"].clone();
+
+    // Bot drops its connection and comes back under the same ID
+    ws_bot.close(None).await.unwrap();
+    drop(ws_bot);
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let msg = serde_json::json!({
+        "type": "get_unassigned_work",
+        "journal_id": journal_id.to_string()
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let response = recv_msg(&mut ws_alice).await;
+    assert_eq!(response["type"], "unassigned_work");
+    assert!(response["items"].as_array().unwrap().is_empty());
+
+    let mut ws_bot = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Bot",
+        "kind": "agent",
+        "participant_id": bot_id
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let _ = recv_msg(&mut ws_bot).await;
+
+    send_msg(&mut ws_bot, serde_json::json!({"type": "get_work_queue"})).await;
+    let queue_response = recv_msg(&mut ws_bot).await;
+    let items = queue_response["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["id"], work_item_id);
+    assert_eq!(items[0]["assignee_id"], bot_id);
+}
+
+#[tokio::test]
+async fn test_unregister_releases_work() {
+    let (addr, _pool) = setup_server().await;
+    let journal_id = Uuid::new_v4();
+
+    let mut ws_alice = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Alice",
+        "kind": "user"
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let _ = recv_msg(&mut ws_alice).await;
+
+    let mut ws_bot = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Bot",
+        "kind": "agent"
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let bot_response = recv_msg(&mut ws_bot).await;
+    let bot_id = bot_response["participant_id"].as_str().unwrap().to_string();
+
+    let msg = serde_json::json!({
+        "type": "delegate",
+        "journal_id": journal_id.to_string(),
+        "description": "Task",
+        "assignee_id": bot_id
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let work_item_id = recv_msg(&mut ws_alice).await["work_item"]["id"].clone();
+
+    let msg = serde_json::json!({
+        "type": "unregister_participant",
+        "journal_id": journal_id.to_string()
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let response = recv_msg(&mut ws_bot).await;
+    assert_eq!(response["type"], "participant_unregistered");
+    assert_eq!(response["participant_id"], bot_id);
+
+    // Unregistering twice finds nothing to leave
+    let msg = serde_json::json!({
+        "type": "unregister_participant",
+        "journal_id": journal_id.to_string()
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let response = recv_msg(&mut ws_bot).await;
+    assert_eq!(response["type"], "error");
+    assert_eq!(response["code"], "not_registered");
+
+    let msg = serde_json::json!({
+        "type": "get_unassigned_work",
+        "journal_id": journal_id.to_string()
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let response = recv_msg(&mut ws_alice).await;
+    let items = response["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["id"], work_item_id);
+}
+
+#[tokio::test]
+async fn test_get_work_item() {
+    let (addr, _pool) = setup_server().await;
+    let journal_id = Uuid::new_v4();
+
+    let mut ws_alice = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Alice",
+        "kind": "user"
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let _ = recv_msg(&mut ws_alice).await;
+
+    let mut ws_bot = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Bot",
+        "kind": "agent"
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let bot_response = recv_msg(&mut ws_bot).await;
+    let bot_id = bot_response["participant_id"].as_str().unwrap();
+
+    let msg = serde_json::json!({
+        "type": "delegate",
+        "journal_id": journal_id.to_string(),
+        "description": "Review the PR",
+        "assignee_id": bot_id
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let delegated = recv_msg(&mut ws_alice).await;
+    let work_item_id = delegated["work_item"]["id"].as_str().unwrap();
+
+    // Carol is neither delegator nor assignee but is registered
+    let mut ws_carol = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Carol",
+        "kind": "user"
+    });
+    send_msg(&mut ws_carol, msg).await;
+    let _ = recv_msg(&mut ws_carol).await;
+
+    let msg = serde_json::json!({"type": "get_work_item", "work_item_id": work_item_id});
+    send_msg(&mut ws_carol, msg).await;
+    let response = recv_msg(&mut ws_carol).await;
+    assert_eq!(response["type"], "work_item");
+    assert_eq!(response["work_item"]["id"], work_item_id);
+    assert_eq!(response["work_item"]["description"], "Review the PR");
+
+    // Unknown IDs are an error
+    let msg = serde_json::json!({"type": "get_work_item", "work_item_id": Uuid::new_v4()});
+    send_msg(&mut ws_carol, msg).await;
+    let response = recv_msg(&mut ws_carol).await;
+    assert_eq!(response["type"], "error");
+
+    // Unregistered connections are refused
+    let mut ws_anon = connect_ws(addr).await;
+    let msg = serde_json::json!({"type": "get_work_item", "work_item_id": work_item_id});
+    send_msg(&mut ws_anon, msg).await;
+    let response = recv_msg(&mut ws_anon).await;
+    assert_eq!(response["type"], "error");
+}
+
 #[tokio::test]
 async fn test_cancel_work() {
     let (addr, _pool) = setup_server().await;
@@ -645,6 +1000,55 @@ async fn test_get_participants() {
     assert_eq!(response["participants"].as_array().unwrap().len(), 2);
 }
 
+#[tokio::test]
+async fn test_get_participants_with_capability() {
+    let (addr, _pool) = setup_server().await;
+    let journal_id = Uuid::new_v4();
+
+    let mut ws_alice = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Alice",
+        "kind": "user"
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let _ = recv_msg(&mut ws_alice).await;
+
+    let mut ws_bot = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Bot",
+        "kind": "agent"
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let _ = recv_msg(&mut ws_bot).await;
+
+    let msg = serde_json::json!({
+        "type": "get_participants",
+        "journal_id": journal_id.to_string(),
+        "required_capability": "approve"
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let response = recv_msg(&mut ws_alice).await;
+    assert_eq!(response["type"], "available_participants");
+    let participants = response["participants"].as_array().unwrap();
+    assert_eq!(participants.len(), 1);
+    assert_eq!(participants[0]["participant"]["name"], "Alice");
+
+    // A misspelled capability is an error, not an empty list
+    let msg = serde_json::json!({
+        "type": "get_participants",
+        "journal_id": journal_id.to_string(),
+        "required_capability": "aprove"
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let response = recv_msg(&mut ws_alice).await;
+    assert_eq!(response["type"], "error");
+    assert_eq!(response["code"], "invalid_message");
+}
+
 #[tokio::test]
 async fn test_set_accepting_work() {
     let (addr, _pool) = setup_server().await;
@@ -738,3 +1142,514 @@ async fn test_reject_work() {
     assert_eq!(reject_response["type"], "work_rejected");
     assert_eq!(reject_response["feedback"], "Please add more tests");
 }
+
+#[tokio::test]
+async fn test_search_work() {
+    let (addr, _pool) = setup_server().await;
+    let journal_id = Uuid::new_v4();
+
+    let mut ws_alice = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Alice",
+        "kind": "user"
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let _ = recv_msg(&mut ws_alice).await;
+
+    let mut ws_bot = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Bot",
+        "kind": "agent"
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let bot_response = recv_msg(&mut ws_bot).await;
+    let bot_id = bot_response["participant_id"].as_str().unwrap();
+
+    for description in ["Refactor the parser", "Update the changelog"] {
+        let msg = serde_json::json!({
+            "type": "delegate",
+            "journal_id": journal_id.to_string(),
+            "description": description,
+            "assignee_id": bot_id
+        });
+        send_msg(&mut ws_alice, msg).await;
+        let _ = recv_msg(&mut ws_alice).await;
+    }
+
+    // Bot is the assignee, so it can find the item
+    let msg = serde_json::json!({
+        "type": "search_work",
+        "query": "parser",
+        "journal_id": journal_id.to_string()
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let response = recv_msg(&mut ws_bot).await;
+    assert_eq!(response["type"], "work_search_results");
+    let items = response["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["description"], "Refactor the parser");
+
+    // A participant who is not party to the work sees nothing
+    let mut ws_carol = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Carol",
+        "kind": "user"
+    });
+    send_msg(&mut ws_carol, msg).await;
+    let _ = recv_msg(&mut ws_carol).await;
+
+    let msg = serde_json::json!({
+        "type": "search_work",
+        "query": "parser"
+    });
+    send_msg(&mut ws_carol, msg).await;
+    let response = recv_msg(&mut ws_carol).await;
+    assert_eq!(response["type"], "work_search_results");
+    assert!(response["items"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_accepted_work_marks_presence_busy() {
+    let (addr, _pool) = setup_server().await;
+
+    let mut ws_observer = connect_ws(addr).await;
+    send_msg(
+        &mut ws_observer,
+        serde_json::json!({"type": "create_journal", "title": "Busy"}),
+    )
+    .await;
+    let created = recv_msg(&mut ws_observer).await;
+    let journal_id = created["journal_id"].as_str().unwrap().to_string();
+
+    // Bot subscribes first, then registers; both share one identity
+    let mut ws_bot = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "subscribe",
+        "journal_id": journal_id,
+        "name": "Bot",
+        "kind": "agent"
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let subscribed = recv_msg(&mut ws_bot).await;
+    assert_eq!(subscribed["type"], "subscribed");
+    let presence_id = subscribed["participant"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id,
+        "name": "Bot",
+        "kind": "agent"
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let registered = recv_msg(&mut ws_bot).await;
+    assert_eq!(registered["participant_id"], presence_id.as_str());
+
+    let mut ws_alice = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id,
+        "name": "Alice",
+        "kind": "user"
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let _ = recv_msg(&mut ws_alice).await;
+
+    let msg = serde_json::json!({
+        "type": "delegate",
+        "journal_id": journal_id,
+        "description": "Summarize the thread",
+        "assignee_id": presence_id
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let delegated = recv_msg(&mut ws_alice).await;
+    let work_item_id = delegated["work_item"]["id"].as_str().unwrap().to_string();
+
+    let presence_status = |presence: &serde_json::Value| {
+        presence["participants"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["id"] == presence_id.as_str())
+            .map(|p| p["status"].clone())
+            .unwrap()
+    };
+
+    // Accept flips presence to busy
+    send_msg(
+        &mut ws_bot,
+        serde_json::json!({"type": "accept_work", "work_item_id": work_item_id}),
+    )
+    .await;
+    loop {
+        if recv_msg(&mut ws_bot).await["type"] == "work_accepted" {
+            break;
+        }
+    }
+    send_msg(
+        &mut ws_observer,
+        serde_json::json!({"type": "get_presence", "journal_id": journal_id}),
+    )
+    .await;
+    let presence = recv_msg(&mut ws_observer).await;
+    assert_eq!(presence_status(&presence), "busy");
+
+    // Submitting returns it to active
+    send_msg(
+        &mut ws_bot,
+        serde_json::json!({
+            "type": "submit_work",
+            "work_item_id": work_item_id,
+            "result": "Done"
+        }),
+    )
+    .await;
+    loop {
+        if recv_msg(&mut ws_bot).await["type"] == "work_approved" {
+            break;
+        }
+    }
+    send_msg(
+        &mut ws_observer,
+        serde_json::json!({"type": "get_presence", "journal_id": journal_id}),
+    )
+    .await;
+    let presence = recv_msg(&mut ws_observer).await;
+    assert_eq!(presence_status(&presence), "active");
+}
+
+#[tokio::test]
+async fn test_withdraw_submission() {
+    let (addr, _pool) = setup_server().await;
+    let journal_id = Uuid::new_v4();
+
+    let mut ws_alice = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Alice",
+        "kind": "user"
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let _ = recv_msg(&mut ws_alice).await;
+
+    let mut ws_bot = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Bot",
+        "kind": "agent"
+    });
+    send_msg(&mut ws_bot, msg).await;
+    let bot_response = recv_msg(&mut ws_bot).await;
+    let bot_id = bot_response["participant_id"].as_str().unwrap();
+
+    let msg = serde_json::json!({
+        "type": "delegate",
+        "journal_id": journal_id.to_string(),
+        "description": "Draft the release notes",
+        "assignee_id": bot_id,
+        "requires_approval": true
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let delegate_response = recv_msg(&mut ws_alice).await;
+    let work_item_id = delegate_response["work_item"]["id"].as_str().unwrap();
+
+    send_msg(
+        &mut ws_bot,
+        serde_json::json!({"type": "accept_work", "work_item_id": work_item_id}),
+    )
+    .await;
+    let _ = recv_msg(&mut ws_bot).await;
+
+    send_msg(
+        &mut ws_bot,
+        serde_json::json!({
+            "type": "submit_work",
+            "work_item_id": work_item_id,
+            "result": "First draft"
+        }),
+    )
+    .await;
+    let submit_response = recv_msg(&mut ws_bot).await;
+    assert_eq!(submit_response["type"], "approval_requested");
+
+    // Bot spots a mistake and takes the submission back
+    send_msg(
+        &mut ws_bot,
+        serde_json::json!({"type": "withdraw_work", "work_item_id": work_item_id}),
+    )
+    .await;
+    let response = recv_msg(&mut ws_bot).await;
+    assert_eq!(response["type"], "submission_withdrawn");
+    assert_eq!(response["work_item_id"], work_item_id);
+    assert_eq!(response["assignee_id"], bot_id);
+
+    // Alice no longer has anything to review
+    send_msg(
+        &mut ws_alice,
+        serde_json::json!({"type": "get_approval_queue"}),
+    )
+    .await;
+    let response = recv_msg(&mut ws_alice).await;
+    assert_eq!(response["type"], "approval_queue");
+    assert!(response["items"].as_array().unwrap().is_empty());
+
+    // A second withdrawal has nothing to withdraw
+    send_msg(
+        &mut ws_bot,
+        serde_json::json!({"type": "withdraw_work", "work_item_id": work_item_id}),
+    )
+    .await;
+    let response = recv_msg(&mut ws_bot).await;
+    assert_eq!(response["type"], "error");
+}
+
+#[tokio::test]
+async fn test_get_storage_stats_requires_admin() {
+    let (addr, _pool) = setup_server().await;
+    let journal_id = Uuid::new_v4();
+
+    let mut ws_admin = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Ops",
+        "kind": "user",
+        "capabilities": ["read", "admin"]
+    });
+    send_msg(&mut ws_admin, msg).await;
+    let _ = recv_msg(&mut ws_admin).await;
+
+    send_msg(
+        &mut ws_admin,
+        serde_json::json!({"type": "create_journal", "title": "Stats"}),
+    )
+    .await;
+    let _ = recv_msg(&mut ws_admin).await;
+
+    send_msg(
+        &mut ws_admin,
+        serde_json::json!({"type": "get_storage_stats"}),
+    )
+    .await;
+    let response = recv_msg(&mut ws_admin).await;
+    assert_eq!(response["type"], "storage_stats");
+    assert_eq!(response["stats"]["journal_count"], 1);
+    assert_eq!(response["stats"]["block_count"], 0);
+
+    let mut ws_alice = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Alice",
+        "kind": "user"
+    });
+    send_msg(&mut ws_alice, msg).await;
+    let _ = recv_msg(&mut ws_alice).await;
+
+    send_msg(
+        &mut ws_alice,
+        serde_json::json!({"type": "get_storage_stats"}),
+    )
+    .await;
+    let response = recv_msg(&mut ws_alice).await;
+    assert_eq!(response["type"], "error");
+}
+
+#[tokio::test]
+async fn test_bulk_set_accepting_work_requires_coordinator() {
+    let (addr, _pool) = setup_server().await;
+    let journal_id = Uuid::new_v4();
+
+    let mut ws_lead = connect_ws(addr).await;
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id.to_string(),
+        "name": "Lead",
+        "kind": "user",
+        "capabilities": ["read", "delegate", "coordinate"]
+    });
+    send_msg(&mut ws_lead, msg).await;
+    let _ = recv_msg(&mut ws_lead).await;
+
+    let mut agent_ids = Vec::new();
+    let mut agent_sockets = Vec::new();
+    for name in ["Bot A", "Bot B", "Bot C"] {
+        let mut ws = connect_ws(addr).await;
+        let msg = serde_json::json!({
+            "type": "register_participant",
+            "journal_id": journal_id.to_string(),
+            "name": name,
+            "kind": "agent"
+        });
+        send_msg(&mut ws, msg).await;
+        let response = recv_msg(&mut ws).await;
+        agent_ids.push(response["participant_id"].as_str().unwrap().to_string());
+        agent_sockets.push(ws);
+    }
+
+    // An ordinary agent can't take the team off duty
+    send_msg(
+        &mut agent_sockets[0],
+        serde_json::json!({
+            "type": "bulk_set_accepting_work",
+            "participant_ids": [agent_ids[1]],
+            "accepting": false
+        }),
+    )
+    .await;
+    let response = recv_msg(&mut agent_sockets[0]).await;
+    assert_eq!(response["type"], "error");
+
+    let missing = Uuid::new_v4().to_string();
+    send_msg(
+        &mut ws_lead,
+        serde_json::json!({
+            "type": "bulk_set_accepting_work",
+            "participant_ids": [agent_ids[0], agent_ids[1], missing],
+            "accepting": false
+        }),
+    )
+    .await;
+    let response = recv_msg(&mut ws_lead).await;
+    assert_eq!(response["type"], "bulk_accepting_work_changed");
+    assert_eq!(response["accepting"], false);
+    assert_eq!(
+        response["updated"],
+        serde_json::json!([agent_ids[0], agent_ids[1]])
+    );
+    assert_eq!(response["unknown"], serde_json::json!([missing]));
+
+    // Paused agents refuse delegation; the unlisted one still accepts
+    for (agent_id, expected) in agent_ids.iter().zip(["error", "error", "work_delegated"]) {
+        send_msg(
+            &mut ws_lead,
+            serde_json::json!({
+                "type": "delegate",
+                "journal_id": journal_id.to_string(),
+                "description": "Nightly sweep",
+                "assignee_id": agent_id
+            }),
+        )
+        .await;
+        let response = recv_msg(&mut ws_lead).await;
+        assert_eq!(response["type"], expected);
+    }
+}
+
+#[tokio::test]
+async fn test_get_my_capabilities_reflects_updates() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    let mut ws_bot = connect_ws(addr).await;
+    let mut ws_alice = connect_ws(addr).await;
+    let journal_id = Uuid::new_v4();
+
+    // Unregistered connections have no capabilities to report
+    send_msg(
+        &mut ws_bot,
+        serde_json::json!({"type": "get_my_capabilities"}),
+    )
+    .await;
+    let response = recv_msg(&mut ws_bot).await;
+    assert_eq!(response["type"], "error");
+    assert_eq!(response["code"], "not_registered");
+
+    send_msg(
+        &mut ws_bot,
+        serde_json::json!({
+            "type": "register_participant",
+            "journal_id": journal_id.to_string(),
+            "name": "Bot",
+            "kind": "agent"
+        }),
+    )
+    .await;
+    let response = recv_msg(&mut ws_bot).await;
+    let bot_id: Uuid = response["participant_id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(!response["capabilities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|c| c == "approve"));
+
+    // Someone else grants the agent approval rights
+    let mut capabilities = state
+        .delegation_manager
+        .get_participant(bot_id)
+        .await
+        .unwrap()
+        .capabilities;
+    capabilities.add(Capability::Approve);
+    state
+        .delegation_manager
+        .update_capabilities(bot_id, capabilities)
+        .await
+        .unwrap();
+
+    send_msg(
+        &mut ws_bot,
+        serde_json::json!({"type": "get_my_capabilities"}),
+    )
+    .await;
+    let response = recv_msg(&mut ws_bot).await;
+    assert_eq!(response["type"], "my_capabilities");
+    assert_eq!(response["participant_id"], bot_id.to_string());
+    assert!(response["capabilities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|c| c == "approve"));
+
+    // Other participants can inspect the same grant
+    send_msg(
+        &mut ws_alice,
+        serde_json::json!({
+            "type": "register_participant",
+            "journal_id": journal_id.to_string(),
+            "name": "Alice",
+            "kind": "user"
+        }),
+    )
+    .await;
+    recv_msg(&mut ws_alice).await;
+    send_msg(
+        &mut ws_alice,
+        serde_json::json!({
+            "type": "get_participant_capabilities",
+            "participant_id": bot_id.to_string()
+        }),
+    )
+    .await;
+    let response = recv_msg(&mut ws_alice).await;
+    assert_eq!(response["type"], "participant_capabilities");
+    assert!(response["capabilities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|c| c == "approve"));
+
+    send_msg(
+        &mut ws_alice,
+        serde_json::json!({
+            "type": "get_participant_capabilities",
+            "participant_id": Uuid::new_v4().to_string()
+        }),
+    )
+    .await;
+    let response = recv_msg(&mut ws_alice).await;
+    assert_eq!(response["type"], "error");
+    assert_eq!(response["code"], "not_found");
+}
@@ -5,11 +5,17 @@ use futures::{SinkExt, StreamExt};
 use outer::AppState;
 use sqlx::sqlite::SqlitePoolOptions;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio_tungstenite::tungstenite::Message;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 async fn setup_server() -> (SocketAddr, sqlx::SqlitePool) {
+    let (addr, pool, _state) = setup_server_with_state().await;
+    (addr, pool)
+}
+
+async fn setup_server_with_state() -> (SocketAddr, sqlx::SqlitePool, Arc<AppState>) {
     let pool = SqlitePoolOptions::new()
         .max_connections(1)
         .connect("sqlite::memory:")
@@ -23,7 +29,9 @@ async fn setup_server() -> (SocketAddr, sqlx::SqlitePool) {
             id TEXT PRIMARY KEY NOT NULL,
             title TEXT NOT NULL,
             created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            deleted_at DATETIME,
+            archived_at DATETIME
         )
         "#,
     )
@@ -36,11 +44,13 @@ async fn setup_server() -> (SocketAddr, sqlx::SqlitePool) {
         CREATE TABLE IF NOT EXISTS blocks (
             id TEXT PRIMARY KEY NOT NULL,
             journal_id TEXT NOT NULL REFERENCES journals(id),
-            block_type TEXT NOT NULL CHECK (block_type IN ('user', 'assistant')),
+            block_type TEXT NOT NULL CHECK (block_type IN ('user', 'assistant', 'system')),
             content TEXT NOT NULL DEFAULT '',
             status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'streaming', 'complete', 'error')),
             parent_id TEXT REFERENCES blocks(id),
             forked_from_id TEXT REFERENCES blocks(id),
+            position REAL,
+            version INTEGER NOT NULL DEFAULT 0,
             created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
             updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
         )
@@ -50,11 +60,25 @@ async fn setup_server() -> (SocketAddr, sqlx::SqlitePool) {
     .await
     .expect("Failed to create blocks table");
 
+    sqlx::query(include_str!(
+        "../migrations/20260110000021_journal_sessions.sql"
+    ))
+    .execute(&pool)
+    .await
+    .expect("Failed to create journal sessions table");
+
+    sqlx::query(include_str!(
+        "../migrations/20260110000023_journal_roles.sql"
+    ))
+    .execute(&pool)
+    .await
+    .expect("Failed to create journal roles table");
+
     let state = AppState::new(pool.clone());
 
     let app = Router::new()
         .route("/ws", get(outer::websocket::handler))
-        .with_state(state);
+        .with_state(Arc::clone(&state));
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -66,7 +90,7 @@ async fn setup_server() -> (SocketAddr, sqlx::SqlitePool) {
     // Give the server a moment to start
     tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
 
-    (addr, pool)
+    (addr, pool, state)
 }
 
 #[tokio::test]
@@ -203,12 +227,111 @@ async fn test_websocket_get_journal_not_found() {
     if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
         let json: serde_json::Value = serde_json::from_str(&response).unwrap();
         assert_eq!(json["type"], "error");
+        assert_eq!(json["code"], "not_found");
+        assert!(json["message"].as_str().unwrap().contains("not found"));
+    } else {
+        panic!("Expected text message");
+    }
+}
+
+#[tokio::test]
+async fn test_websocket_get_block() {
+    let (addr, pool) = setup_server().await;
+
+    let store = outer::store::Store::new(pool);
+    let journal = store.create_journal(None).await.unwrap();
+    let block = store
+        .create_block(journal.id, outer::models::BlockType::User, "Hello")
+        .await
+        .unwrap();
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({"type": "get_block", "block_id": block.id});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+        let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(json["type"], "block");
+        assert_eq!(json["block"]["id"], block.id.to_string());
+        assert_eq!(json["block"]["journal_id"], journal.id.to_string());
+        assert_eq!(json["block"]["content"], "Hello");
+    } else {
+        panic!("Expected text message");
+    }
+}
+
+#[tokio::test]
+async fn test_websocket_get_block_not_found() {
+    let (addr, _pool) = setup_server().await;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({
+        "type": "get_block",
+        "block_id": "00000000-0000-0000-0000-000000000000"
+    });
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+        let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(json["type"], "error");
+        assert_eq!(json["code"], "not_found");
         assert!(json["message"].as_str().unwrap().contains("not found"));
     } else {
         panic!("Expected text message");
     }
 }
 
+#[tokio::test]
+async fn test_websocket_fetch_block() {
+    let (addr, pool) = setup_server().await;
+
+    let store = outer::store::Store::new(pool);
+    let journal = store.create_journal(None).await.unwrap();
+    let block = store
+        .create_block(journal.id, outer::models::BlockType::User, "Hello")
+        .await
+        .unwrap();
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    for block_id in [block.id, uuid::Uuid::nil()] {
+        let msg = serde_json::json!({"type": "fetch_block", "block_id": block_id});
+        ws_stream
+            .send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+    }
+
+    if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+        let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(json["type"], "block_fetched");
+        assert_eq!(json["block"]["id"], block.id.to_string());
+        assert_eq!(json["block"]["content"], "Hello");
+        assert_eq!(json["block"]["status"], "complete");
+    } else {
+        panic!("Expected text message");
+    }
+
+    if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+        let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(json["type"], "error");
+        assert_eq!(json["code"], "not_found");
+    } else {
+        panic!("Expected text message");
+    }
+}
+
 #[tokio::test]
 async fn test_websocket_list_journals_after_create() {
     let (addr, _pool) = setup_server().await;
@@ -266,6 +389,7 @@ async fn test_websocket_invalid_message() {
     if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
         let json: serde_json::Value = serde_json::from_str(&response).unwrap();
         assert_eq!(json["type"], "error");
+        assert_eq!(json["code"], "invalid_message");
         assert!(json["message"]
             .as_str()
             .unwrap()
@@ -304,7 +428,9 @@ async fn setup_server_with_opencode(mock_server_uri: &str) -> (SocketAddr, sqlx:
             id TEXT PRIMARY KEY NOT NULL,
             title TEXT NOT NULL,
             created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+            updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            deleted_at DATETIME,
+            archived_at DATETIME
         )
         "#,
     )
@@ -317,11 +443,13 @@ async fn setup_server_with_opencode(mock_server_uri: &str) -> (SocketAddr, sqlx:
         CREATE TABLE IF NOT EXISTS blocks (
             id TEXT PRIMARY KEY NOT NULL,
             journal_id TEXT NOT NULL REFERENCES journals(id),
-            block_type TEXT NOT NULL CHECK (block_type IN ('user', 'assistant')),
+            block_type TEXT NOT NULL CHECK (block_type IN ('user', 'assistant', 'system')),
             content TEXT NOT NULL DEFAULT '',
             status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'streaming', 'complete', 'error')),
             parent_id TEXT REFERENCES blocks(id),
             forked_from_id TEXT REFERENCES blocks(id),
+            position REAL,
+            version INTEGER NOT NULL DEFAULT 0,
             created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
             updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
         )
@@ -331,6 +459,20 @@ async fn setup_server_with_opencode(mock_server_uri: &str) -> (SocketAddr, sqlx:
     .await
     .expect("Failed to create blocks table");
 
+    sqlx::query(include_str!(
+        "../migrations/20260110000021_journal_sessions.sql"
+    ))
+    .execute(&pool)
+    .await
+    .expect("Failed to create journal sessions table");
+
+    sqlx::query(include_str!(
+        "../migrations/20260110000023_journal_roles.sql"
+    ))
+    .execute(&pool)
+    .await
+    .expect("Failed to create journal roles table");
+
     // Set environment variable for OpenCode URL
     std::env::set_var("OPENCODE_URL", mock_server_uri);
 
@@ -338,6 +480,8 @@ async fn setup_server_with_opencode(mock_server_uri: &str) -> (SocketAddr, sqlx:
 
     let app = Router::new()
         .route("/ws", get(outer::websocket::handler))
+        .route("/metrics", get(outer::metrics::handler))
+        .route("/journals/:id/stream", get(outer::sse::handler))
         .with_state(state);
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -443,13 +587,37 @@ async fn test_websocket_submit_with_new_session() {
 }
 
 #[tokio::test]
-async fn test_websocket_submit_create_session_fails() {
+async fn test_submit_survives_transient_session_failures() {
     let mock_server = MockServer::start().await;
 
-    // Mock create session to fail
+    // The first two attempts hit a server error, the third goes through
     Mock::given(method("POST"))
         .and(path("/session"))
         .respond_with(ResponseTemplate::new(500).set_body_string("Internal Server Error"))
+        .up_to_n_times(2)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_retry",
+            "version": "1.0.0",
+            "projectID": "proj_456"
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/event"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"Hello!\", \"part\": {\"sessionID\": \"sess_retry\"}}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_retry\"}}\n\n")
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/session/sess_retry/prompt_async"))
+        .respond_with(ResponseTemplate::new(204))
         .mount(&mock_server)
         .await;
 
@@ -458,14 +626,12 @@ async fn test_websocket_submit_create_session_fails() {
     let url = format!("ws://{}/ws", addr);
     let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
 
-    // Create a journal
-    let msg = serde_json::json!({"type": "create_journal", "title": "Test"});
+    let msg = serde_json::json!({"type": "create_journal", "title": "Retry"});
     ws_stream
         .send(Message::Text(msg.to_string().into()))
         .await
         .unwrap();
-
-    let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+    let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
         if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
             let json: serde_json::Value = serde_json::from_str(&response).unwrap();
             Some(json["journal_id"].as_str().unwrap().to_string())
@@ -477,7 +643,6 @@ async fn test_websocket_submit_create_session_fails() {
     .expect("Timeout")
     .expect("Expected journal_id");
 
-    // Submit (should fail because session creation fails)
     let msg = serde_json::json!({
         "type": "submit",
         "journal_id": journal_id,
@@ -488,74 +653,84 @@ async fn test_websocket_submit_create_session_fails() {
         .await
         .unwrap();
 
-    // Should get blocks created, then error - use longer timeout
-    let has_error_or_block = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
-        for _ in 0..10 {
-            if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
-                let json: serde_json::Value = serde_json::from_str(&response).unwrap();
-                // Accept either an error or a block_created as success
-                // The important thing is we get a response
-                if json["type"] == "error" || json["type"] == "block_created" {
-                    return true;
-                }
+    // The assistant block is created and finishes without an error
+    let assistant_id = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        let mut assistant_id = None;
+        while let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            assert_ne!(json["type"], "error", "Unexpected error: {}", json);
+            if json["type"] == "block_created" && json["block"]["block_type"] == "assistant" {
+                assistant_id = Some(json["block"]["id"].clone());
+            }
+            if json["type"] == "block_status_changed"
+                && json["status"] == "complete"
+                && Some(&json["block_id"]) == assistant_id.as_ref()
+            {
+                break;
             }
         }
-        false
+        assistant_id
     })
     .await
-    .unwrap_or(false);
+    .expect("Timeout waiting for the assistant block to complete");
+    assert!(assistant_id.is_some());
 
-    // Just verify we got some response (error or block)
-    assert!(has_error_or_block);
+    let session_requests = mock_server
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|r| r.url.path() == "/session")
+        .count();
+    assert_eq!(session_requests, 3);
 }
 
 #[tokio::test]
-async fn test_websocket_submit_full_flow() {
+async fn test_submit_records_token_usage() {
     let mock_server = MockServer::start().await;
 
-    // Mock create session
     Mock::given(method("POST"))
         .and(path("/session"))
         .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "id": "sess_full",
+            "id": "sess_usage",
             "version": "1.0.0",
             "projectID": "proj_456"
         })))
         .mount(&mock_server)
         .await;
-
-    // Mock event stream with full flow: content, more content, done
     Mock::given(method("GET"))
         .and(path("/event"))
         .respond_with(
             ResponseTemplate::new(200)
-                .set_body_string(
-                    "data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"Hello \", \"part\": {\"sessionID\": \"sess_full\"}}}\n\ndata: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"World\", \"part\": {\"sessionID\": \"sess_full\"}}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_full\"}}\n\n",
-                )
+                .set_body_string(concat!(
+                    "data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"Hi\", \"part\": {\"sessionID\": \"sess_usage\"}}}\n\n",
+                    "data: {\"type\": \"message.updated\", \"properties\": {\"info\": {\"sessionID\": \"sess_usage\", \"role\": \"assistant\", \"tokens\": {\"input\": 321, \"output\": 12}}}}\n\n",
+                    "data: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_usage\"}}\n\n",
+                ))
                 .insert_header("content-type", "text/event-stream"),
         )
         .mount(&mock_server)
         .await;
-
-    // Mock prompt_async
     Mock::given(method("POST"))
-        .and(path("/session/sess_full/prompt_async"))
+        .and(path("/session/sess_usage/prompt_async"))
         .respond_with(ResponseTemplate::new(204))
         .mount(&mock_server)
         .await;
 
-    let (addr, _pool) = setup_server_with_opencode(&mock_server.uri()).await;
+    let (addr, pool) = setup_server_with_opencode(&mock_server.uri()).await;
+    sqlx::query(include_str!("../migrations/20260110000018_block_usage.sql"))
+        .execute(&pool)
+        .await
+        .expect("Failed to add usage columns");
 
     let url = format!("ws://{}/ws", addr);
     let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
 
-    // Create a journal
-    let msg = serde_json::json!({"type": "create_journal", "title": "Full Flow Test"});
+    let msg = serde_json::json!({"type": "create_journal", "title": "Usage"});
     ws_stream
         .send(Message::Text(msg.to_string().into()))
         .await
         .unwrap();
-
     let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
         if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
             let json: serde_json::Value = serde_json::from_str(&response).unwrap();
@@ -568,71 +743,79 @@ async fn test_websocket_submit_full_flow() {
     .expect("Timeout")
     .expect("Expected journal_id");
 
-    // Submit a message
     let msg = serde_json::json!({
         "type": "submit",
         "journal_id": journal_id,
-        "content": "Test message"
+        "content": "Hello"
     });
     ws_stream
         .send(Message::Text(msg.to_string().into()))
         .await
         .unwrap();
 
-    // Collect several responses with timeout
-    let messages = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
-        let mut msgs = Vec::new();
-        // Try to collect up to 10 messages
-        for _ in 0..10 {
-            match tokio::time::timeout(tokio::time::Duration::from_millis(500), ws_stream.next())
-                .await
+    let (assistant_id, usage) = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        let mut assistant_id = None;
+        let mut usage = None;
+        while let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            assert_ne!(json["type"], "error", "Unexpected error: {}", json);
+            if json["type"] == "block_created" && json["block"]["block_type"] == "assistant" {
+                assistant_id = json["block"]["id"].as_str().map(String::from);
+            }
+            if json["type"] == "block_usage" {
+                usage = Some(json.clone());
+            }
+            if json["type"] == "block_status_changed"
+                && json["status"] == "complete"
+                && json["block_id"].as_str() == assistant_id.as_deref()
             {
-                Ok(Some(Ok(Message::Text(response)))) => {
-                    let json: serde_json::Value = serde_json::from_str(&response).unwrap();
-                    msgs.push(json);
-                }
-                _ => break,
+                break;
             }
         }
-        msgs
+        (assistant_id.expect("No assistant block"), usage)
     })
     .await
-    .expect("Timeout collecting messages");
-
-    // Verify we got various message types
-    let types: Vec<&str> = messages.iter().filter_map(|m| m["type"].as_str()).collect();
-    assert!(types.contains(&"block_created"), "Missing block_created");
+    .expect("Timeout waiting for the assistant block to complete");
+
+    let usage = usage.expect("Expected a block_usage message");
+    assert_eq!(usage["block_id"], assistant_id.as_str());
+    assert_eq!(usage["prompt_tokens"], 321);
+    assert_eq!(usage["completion_tokens"], 12);
+
+    let (prompt_tokens, completion_tokens): (Option<i64>, Option<i64>) =
+        sqlx::query_as("SELECT prompt_tokens, completion_tokens FROM blocks WHERE id = ?")
+            .bind(&assistant_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(prompt_tokens, Some(321));
+    assert_eq!(completion_tokens, Some(12));
 }
 
 #[tokio::test]
-async fn test_websocket_submit_with_unknown_event() {
+async fn test_journal_stream_receives_block_events() {
     let mock_server = MockServer::start().await;
 
-    // Mock create session
     Mock::given(method("POST"))
         .and(path("/session"))
         .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "id": "sess_unk",
+            "id": "sess_sse",
             "version": "1.0.0",
             "projectID": "proj_456"
         })))
         .mount(&mock_server)
         .await;
-
-    // Mock event stream with unknown event type (should be handled as Unknown)
     Mock::given(method("GET"))
         .and(path("/event"))
         .respond_with(
             ResponseTemplate::new(200)
-                .set_body_string("data: {\"type\": \"unknown_type\", \"properties\": {}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_unk\"}}\n\n")
+                .set_body_string("data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"Hi\", \"part\": {\"sessionID\": \"sess_sse\"}}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_sse\"}}\n\n")
                 .insert_header("content-type", "text/event-stream"),
         )
         .mount(&mock_server)
         .await;
-
-    // Mock prompt_async
     Mock::given(method("POST"))
-        .and(path("/session/sess_unk/prompt_async"))
+        .and(path("/session/sess_sse/prompt_async"))
         .respond_with(ResponseTemplate::new(204))
         .mount(&mock_server)
         .await;
@@ -642,13 +825,11 @@ async fn test_websocket_submit_with_unknown_event() {
     let url = format!("ws://{}/ws", addr);
     let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
 
-    // Create a journal
-    let msg = serde_json::json!({"type": "create_journal", "title": "Unknown Event Test"});
+    let msg = serde_json::json!({"type": "create_journal", "title": "Watched"});
     ws_stream
         .send(Message::Text(msg.to_string().into()))
         .await
         .unwrap();
-
     let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
         if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
             let json: serde_json::Value = serde_json::from_str(&response).unwrap();
@@ -661,31 +842,2953 @@ async fn test_websocket_submit_with_unknown_event() {
     .expect("Timeout")
     .expect("Expected journal_id");
 
-    // Submit
+    let missing = reqwest::get(format!(
+        "http://{}/journals/{}/stream",
+        addr,
+        uuid::Uuid::new_v4()
+    ))
+    .await
+    .unwrap();
+    assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let response = reqwest::get(format!("http://{}/journals/{}/stream", addr, journal_id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/event-stream"));
+    let mut events = response.bytes_stream();
+
     let msg = serde_json::json!({
         "type": "submit",
         "journal_id": journal_id,
-        "content": "Test"
+        "content": "Hello"
     });
     ws_stream
         .send(Message::Text(msg.to_string().into()))
         .await
         .unwrap();
 
-    // Should get at least block_created messages
-    let received = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
-        for _ in 0..5 {
-            if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
-                let json: serde_json::Value = serde_json::from_str(&response).unwrap();
-                if json["type"] == "block_created" {
-                    return true;
+    let data = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        let mut buffer = String::new();
+        while let Some(chunk) = events.next().await {
+            buffer.push_str(&String::from_utf8_lossy(&chunk.unwrap()));
+            while let Some(end) = buffer.find("\n\n") {
+                let frame: String = buffer.drain(..end + 2).collect();
+                if frame.lines().any(|line| line == "event: block_created") {
+                    let data = frame
+                        .lines()
+                        .find_map(|line| line.strip_prefix("data: "))
+                        .expect("Event without data");
+                    return serde_json::from_str::<serde_json::Value>(data).unwrap();
                 }
             }
         }
-        false
+        panic!("Stream ended before a block was created");
     })
     .await
-    .expect("Timeout");
+    .expect("Timeout waiting for a block_created event");
 
-    assert!(received);
+    assert_eq!(data["type"], "block_created");
+    assert_eq!(data["block"]["journal_id"], journal_id.as_str());
+    assert_eq!(data["block"]["content"], "Hello");
+}
+
+#[tokio::test]
+async fn test_submits_reuse_the_journal_session() {
+    let mock_server = MockServer::start().await;
+
+    // Only the first submit should create a session
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_reuse",
+            "version": "1.0.0",
+            "projectID": "proj_456"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/event"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"Hi\", \"part\": {\"sessionID\": \"sess_reuse\"}}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_reuse\"}}\n\n")
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/session/sess_reuse/prompt_async"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let (addr, pool) = setup_server_with_opencode(&mock_server.uri()).await;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({"type": "create_journal", "title": "Context"});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            Some(json["journal_id"].as_str().unwrap().to_string())
+        } else {
+            None
+        }
+    })
+    .await
+    .expect("Timeout")
+    .expect("Expected journal_id");
+
+    for content in ["First", "Second"] {
+        let msg = serde_json::json!({
+            "type": "submit",
+            "journal_id": journal_id,
+            "content": content
+        });
+        ws_stream
+            .send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+
+        tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+            let mut assistant_id = None;
+            while let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+                let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+                assert_ne!(json["type"], "error", "Unexpected error: {}", json);
+                if json["type"] == "block_created" && json["block"]["block_type"] == "assistant" {
+                    assistant_id = json["block"]["id"].as_str().map(String::from);
+                }
+                if json["type"] == "block_status_changed"
+                    && json["status"] == "complete"
+                    && json["block_id"].as_str() == assistant_id.as_deref()
+                {
+                    break;
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for the assistant block to complete");
+    }
+
+    let stored: String =
+        sqlx::query_scalar("SELECT session_id FROM journal_sessions WHERE journal_id = ?")
+            .bind(&journal_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(stored, "sess_reuse");
+    mock_server.verify().await;
+}
+
+#[tokio::test]
+async fn test_fork_completed_block() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_fork",
+            "version": "1.0.0",
+            "projectID": "proj_456"
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/event"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("data: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_fork\"}}\n\n")
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/session/sess_fork/prompt_async"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_server)
+        .await;
+
+    let (addr, pool) = setup_server_with_opencode(&mock_server.uri()).await;
+    let store = outer::store::Store::new(pool);
+    let journal = store.create_journal(None).await.unwrap();
+    let block = store
+        .create_block(journal.id, outer::models::BlockType::User, "Branch me")
+        .await
+        .unwrap();
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "fork", "block_id": block.id});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let forked = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        while let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            assert_ne!(json["type"], "error", "Unexpected error: {}", json);
+            if json["type"] == "block_forked" {
+                return json;
+            }
+        }
+        panic!("Connection closed before the fork was reported");
+    })
+    .await
+    .expect("Timeout waiting for block_forked");
+
+    assert_eq!(forked["original_block_id"], block.id.to_string());
+    assert_eq!(forked["new_block"]["content"], "Branch me");
+}
+
+#[tokio::test]
+async fn test_fork_streaming_block_is_rejected() {
+    let (addr, pool) = setup_server().await;
+    let store = outer::store::Store::new(pool);
+    let journal = store.create_journal(None).await.unwrap();
+    let block = store
+        .create_block(
+            journal.id,
+            outer::models::BlockType::Assistant,
+            "Half an answer",
+        )
+        .await
+        .unwrap();
+    store
+        .update_block_status(block.id, outer::models::BlockStatus::Streaming)
+        .await
+        .unwrap();
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    for kind in ["fork", "rerun"] {
+        let msg = serde_json::json!({"type": kind, "block_id": block.id});
+        ws_stream
+            .send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+
+        let response = tokio::time::timeout(tokio::time::Duration::from_secs(5), ws_stream.next())
+            .await
+            .expect("Timeout waiting for response")
+            .unwrap()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(response.to_text().unwrap()).unwrap();
+        assert_eq!(json["type"], "error");
+        assert_eq!(json["code"], "block_not_ready");
+    }
+
+    // Nothing was branched off the unfinished block
+    let blocks = store.get_blocks_for_journal(journal.id).await.unwrap();
+    assert_eq!(blocks.len(), 1);
+}
+
+/// Value of the unlabelled sample `name` in a Prometheus text body
+fn metric_value(body: &str, name: &str) -> Option<u64> {
+    body.lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+        .and_then(|value| value.parse().ok())
+}
+
+#[tokio::test]
+async fn test_metrics_count_submits() {
+    let mock_server = MockServer::start().await;
+
+    // Fail session creation so the submit also counts as an OpenCode error
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("Internal Server Error"))
+        .mount(&mock_server)
+        .await;
+
+    let (addr, _pool) = setup_server_with_opencode(&mock_server.uri()).await;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({"type": "create_journal", "title": "Metrics"});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            Some(json["journal_id"].as_str().unwrap().to_string())
+        } else {
+            None
+        }
+    })
+    .await
+    .expect("Timeout")
+    .expect("Expected journal_id");
+
+    let msg = serde_json::json!({
+        "type": "submit",
+        "journal_id": journal_id,
+        "content": "Hello"
+    });
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // Wait for the submit to finish failing
+    tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        while let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            if json["type"] == "error" {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for error");
+
+    let response = reqwest::get(format!("http://{}/metrics", addr))
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body = response.text().await.unwrap();
+
+    assert!(metric_value(&body, "outer_submits_total").unwrap() > 0);
+    assert!(metric_value(&body, "outer_opencode_errors_total").unwrap() > 0);
+    assert_eq!(metric_value(&body, "outer_active_connections"), Some(1));
+    assert!(body.contains("outer_work_items{status=\"pending\"} 0"));
+}
+
+#[tokio::test]
+async fn test_submit_retry_with_same_key_reuses_blocks() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("Internal Server Error"))
+        .mount(&mock_server)
+        .await;
+
+    let (addr, pool) = setup_server_with_opencode(&mock_server.uri()).await;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({"type": "create_journal", "title": "Retry"});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            Some(json["journal_id"].as_str().unwrap().to_string())
+        } else {
+            None
+        }
+    })
+    .await
+    .expect("Timeout")
+    .expect("Expected journal_id");
+
+    let submit = serde_json::json!({
+        "type": "submit",
+        "journal_id": journal_id,
+        "content": "Hello",
+        "idempotency_key": "retry-1"
+    });
+
+    // First attempt creates the blocks, then fails to reach OpenCode
+    ws_stream
+        .send(Message::Text(submit.to_string().into()))
+        .await
+        .unwrap();
+    let mut first_ids = Vec::new();
+    tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        while let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            match json["type"].as_str() {
+                Some("block_created") => first_ids.push(json["block"]["id"].clone()),
+                Some("error") => break,
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for first submit");
+    assert_eq!(first_ids.len(), 2);
+
+    // The retry replays the same blocks
+    ws_stream
+        .send(Message::Text(submit.to_string().into()))
+        .await
+        .unwrap();
+    let mut retry_ids = Vec::new();
+    tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        while retry_ids.len() < first_ids.len() {
+            if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+                let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+                if json["type"] == "block_created" {
+                    retry_ids.push(json["block"]["id"].clone());
+                }
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for replayed blocks");
+    assert_eq!(retry_ids, first_ids);
+
+    let (user_blocks,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM blocks WHERE journal_id = ? AND block_type = 'user'")
+            .bind(&journal_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(user_blocks, 1);
+}
+
+#[tokio::test]
+async fn test_websocket_submit_create_session_fails() {
+    let mock_server = MockServer::start().await;
+
+    // Mock create session to fail
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("Internal Server Error"))
+        .mount(&mock_server)
+        .await;
+
+    let (addr, _pool) = setup_server_with_opencode(&mock_server.uri()).await;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    // Create a journal
+    let msg = serde_json::json!({"type": "create_journal", "title": "Test"});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            Some(json["journal_id"].as_str().unwrap().to_string())
+        } else {
+            None
+        }
+    })
+    .await
+    .expect("Timeout")
+    .expect("Expected journal_id");
+
+    // Submit (should fail because session creation fails)
+    let msg = serde_json::json!({
+        "type": "submit",
+        "journal_id": journal_id,
+        "content": "Hello"
+    });
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // Should get blocks created, then error - use longer timeout
+    let has_error_or_block = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        for _ in 0..10 {
+            if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+                let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+                // Accept either an error or a block_created as success
+                // The important thing is we get a response
+                if json["type"] == "error" || json["type"] == "block_created" {
+                    return true;
+                }
+            }
+        }
+        false
+    })
+    .await
+    .unwrap_or(false);
+
+    // Just verify we got some response (error or block)
+    assert!(has_error_or_block);
+}
+
+#[tokio::test]
+async fn test_websocket_submit_full_flow() {
+    let mock_server = MockServer::start().await;
+
+    // Mock create session
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_full",
+            "version": "1.0.0",
+            "projectID": "proj_456"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    // Mock event stream with full flow: content, more content, done
+    Mock::given(method("GET"))
+        .and(path("/event"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(
+                    "data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"Hello \", \"part\": {\"sessionID\": \"sess_full\"}}}\n\ndata: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"World\", \"part\": {\"sessionID\": \"sess_full\"}}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_full\"}}\n\n",
+                )
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    // Mock prompt_async
+    Mock::given(method("POST"))
+        .and(path("/session/sess_full/prompt_async"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_server)
+        .await;
+
+    let (addr, _pool) = setup_server_with_opencode(&mock_server.uri()).await;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    // Create a journal
+    let msg = serde_json::json!({"type": "create_journal", "title": "Full Flow Test"});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            Some(json["journal_id"].as_str().unwrap().to_string())
+        } else {
+            None
+        }
+    })
+    .await
+    .expect("Timeout")
+    .expect("Expected journal_id");
+
+    // Submit a message
+    let msg = serde_json::json!({
+        "type": "submit",
+        "journal_id": journal_id,
+        "content": "Test message"
+    });
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // Collect several responses with timeout
+    let messages = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        let mut msgs = Vec::new();
+        // Try to collect up to 10 messages
+        for _ in 0..10 {
+            match tokio::time::timeout(tokio::time::Duration::from_millis(500), ws_stream.next())
+                .await
+            {
+                Ok(Some(Ok(Message::Text(response)))) => {
+                    let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+                    msgs.push(json);
+                }
+                _ => break,
+            }
+        }
+        msgs
+    })
+    .await
+    .expect("Timeout collecting messages");
+
+    // Verify we got various message types
+    let types: Vec<&str> = messages.iter().filter_map(|m| m["type"].as_str()).collect();
+    assert!(types.contains(&"block_created"), "Missing block_created");
+}
+
+#[tokio::test]
+async fn test_websocket_submit_with_unknown_event() {
+    let mock_server = MockServer::start().await;
+
+    // Mock create session
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_unk",
+            "version": "1.0.0",
+            "projectID": "proj_456"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    // Mock event stream with unknown event type (should be handled as Unknown)
+    Mock::given(method("GET"))
+        .and(path("/event"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("data: {\"type\": \"unknown_type\", \"properties\": {}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_unk\"}}\n\n")
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    // Mock prompt_async
+    Mock::given(method("POST"))
+        .and(path("/session/sess_unk/prompt_async"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_server)
+        .await;
+
+    let (addr, _pool) = setup_server_with_opencode(&mock_server.uri()).await;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    // Create a journal
+    let msg = serde_json::json!({"type": "create_journal", "title": "Unknown Event Test"});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            Some(json["journal_id"].as_str().unwrap().to_string())
+        } else {
+            None
+        }
+    })
+    .await
+    .expect("Timeout")
+    .expect("Expected journal_id");
+
+    // Submit
+    let msg = serde_json::json!({
+        "type": "submit",
+        "journal_id": journal_id,
+        "content": "Test"
+    });
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // Should get at least block_created messages
+    let received = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        for _ in 0..5 {
+            if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+                let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+                if json["type"] == "block_created" {
+                    return true;
+                }
+            }
+        }
+        false
+    })
+    .await
+    .expect("Timeout");
+
+    assert!(received);
+}
+
+#[tokio::test]
+async fn test_websocket_submit_delta_offsets_are_contiguous() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_offsets",
+            "version": "1.0.0",
+            "projectID": "proj_456"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    // Three deltas, one of them multi-byte, to exercise byte offsets
+    Mock::given(method("GET"))
+        .and(path("/event"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(
+                    "data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"Hello \", \"part\": {\"sessionID\": \"sess_offsets\"}}}\n\ndata: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"wörld\", \"part\": {\"sessionID\": \"sess_offsets\"}}}\n\ndata: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"!\", \"part\": {\"sessionID\": \"sess_offsets\"}}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_offsets\"}}\n\n",
+                )
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/session/sess_offsets/prompt_async"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_server)
+        .await;
+
+    let (addr, _pool) = setup_server_with_opencode(&mock_server.uri()).await;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({"type": "create_journal", "title": "Offsets"});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            Some(json["journal_id"].as_str().unwrap().to_string())
+        } else {
+            None
+        }
+    })
+    .await
+    .expect("Timeout")
+    .expect("Expected journal_id");
+
+    let msg = serde_json::json!({
+        "type": "submit",
+        "journal_id": journal_id,
+        "content": "Greet me"
+    });
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // Collect deltas until the assistant block completes
+    let deltas = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        let mut deltas = Vec::new();
+        while let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            if json["type"] == "block_content_delta" {
+                deltas.push((
+                    json["offset"].as_u64().unwrap() as usize,
+                    json["delta"].as_str().unwrap().to_string(),
+                ));
+            } else if json["type"] == "block_status_changed" && json["status"] == "complete" {
+                break;
+            }
+        }
+        deltas
+    })
+    .await
+    .expect("Timeout collecting deltas");
+
+    assert_eq!(deltas.len(), 3);
+
+    let mut reassembled = String::new();
+    for (offset, delta) in &deltas {
+        assert_eq!(*offset, reassembled.len(), "Deltas must be contiguous");
+        reassembled.push_str(delta);
+    }
+    assert_eq!(reassembled, "Hello wörld!");
+}
+
+#[tokio::test]
+async fn test_websocket_message_pack_negotiation() {
+    use outer::codec::Encoding;
+
+    let (addr, _pool) = setup_server().await;
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    async fn send_msgpack(
+        ws: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        msg: serde_json::Value,
+    ) {
+        let axum::extract::ws::Message::Binary(bytes) = Encoding::MessagePack.encode(&msg).unwrap()
+        else {
+            panic!("Expected a binary frame");
+        };
+        ws.send(Message::Binary(bytes)).await.unwrap();
+    }
+
+    async fn next_msgpack(
+        ws: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+    ) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+            loop {
+                match ws.next().await {
+                    Some(Ok(Message::Binary(bytes))) => {
+                        return Encoding::MessagePack.decode(&bytes).unwrap();
+                    }
+                    Some(Ok(Message::Text(text))) => panic!("Expected MessagePack, got {}", text),
+                    _ => continue,
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for message")
+    }
+
+    // Requests may be MessagePack at any time; replies follow once negotiated
+    send_msgpack(
+        &mut ws,
+        serde_json::json!({"type": "hello", "msgpack": true}),
+    )
+    .await;
+    let welcome = next_msgpack(&mut ws).await;
+    assert_eq!(welcome["type"], "welcome");
+    assert_eq!(welcome["msgpack"], true);
+
+    send_msgpack(
+        &mut ws,
+        serde_json::json!({"type": "create_journal", "title": "Packed"}),
+    )
+    .await;
+    let created = next_msgpack(&mut ws).await;
+    assert_eq!(created["type"], "journal_created");
+    assert_eq!(created["title"], "Packed");
+}
+
+#[tokio::test]
+async fn test_websocket_binary_crdt_update() {
+    use outer::crdt::JournalDoc;
+    use outer::frame::{BinaryFrame, Opcode};
+
+    let (addr, _pool) = setup_server().await;
+    let url = format!("ws://{}/ws", addr);
+
+    let (mut ws_a, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (mut ws_b, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    async fn next_json(
+        ws: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+    ) -> serde_json::Value {
+        loop {
+            if let Some(Ok(Message::Text(text))) = ws.next().await {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    let msg = serde_json::json!({"type": "create_journal", "title": "Binary"});
+    ws_a.send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let created = next_json(&mut ws_a).await;
+    let journal_id: uuid::Uuid = created["journal_id"].as_str().unwrap().parse().unwrap();
+
+    // A opts in to binary frames; B stays on the JSON text path
+    let msg = serde_json::json!({"type": "hello", "binary_crdt": true});
+    ws_a.send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let welcome = next_json(&mut ws_a).await;
+    assert_eq!(welcome["type"], "welcome");
+    assert_eq!(welcome["binary_crdt"], true);
+
+    for (ws, name) in [(&mut ws_a, "A"), (&mut ws_b, "B")] {
+        let msg = serde_json::json!({
+            "type": "subscribe",
+            "journal_id": journal_id.to_string(),
+            "name": name
+        });
+        ws.send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+    }
+    let subscribed = next_json(&mut ws_a).await;
+    let a_id = subscribed["participant"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(next_json(&mut ws_b).await["type"], "subscribed");
+
+    // Build a real Yrs update and send it raw
+    let block_id = uuid::Uuid::new_v4();
+    let local = JournalDoc::new(journal_id);
+    local.set_block_content(block_id, "sent as bytes");
+    let frame = BinaryFrame::new(Opcode::CrdtUpdate, journal_id, local.encode_state());
+    ws_a.send(Message::Binary(frame.encode().into()))
+        .await
+        .unwrap();
+
+    // The text subscriber receives it exactly like a base64 update
+    let forwarded = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        loop {
+            let json = next_json(&mut ws_b).await;
+            if json["type"] == "crdt_update" {
+                return json;
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for crdt_update");
+    assert_eq!(forwarded["source"], a_id.as_str());
+    assert!(!forwarded["update"].as_str().unwrap().is_empty());
+
+    // A binary sync request returns the applied state as a binary frame
+    let request = BinaryFrame::new(Opcode::SyncRequest, journal_id, vec![]);
+    ws_a.send(Message::Binary(request.encode().into()))
+        .await
+        .unwrap();
+    let reply = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        loop {
+            if let Some(Ok(Message::Binary(bytes))) = ws_a.next().await {
+                return BinaryFrame::decode(&bytes).unwrap();
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for sync state");
+    assert_eq!(reply.opcode, Opcode::SyncState);
+    assert_eq!(reply.journal_id, journal_id);
+
+    let synced = JournalDoc::from_update(journal_id, &reply.payload).unwrap();
+    assert_eq!(
+        synced.get_block_content(block_id),
+        Some("sent as bytes".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_websocket_binary_frame_requires_hello() {
+    use outer::frame::{BinaryFrame, Opcode};
+
+    let (addr, _pool) = setup_server().await;
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let frame = BinaryFrame::new(Opcode::CrdtUpdate, uuid::Uuid::new_v4(), vec![0, 0]);
+    ws_stream
+        .send(Message::Binary(frame.encode().into()))
+        .await
+        .unwrap();
+
+    if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+        let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(json["type"], "error");
+    } else {
+        panic!("Expected error message");
+    }
+}
+
+#[tokio::test]
+async fn test_websocket_untitled_journal_gets_generated_title() {
+    let mock_server = MockServer::start().await;
+
+    // First session serves the conversation, the second the title request
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_chat",
+            "version": "1.0.0",
+            "projectID": "proj_456"
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_title",
+            "version": "1.0.0",
+            "projectID": "proj_456"
+        })))
+        .with_priority(2)
+        .mount(&mock_server)
+        .await;
+
+    // Each subscriber filters the shared event stream down to its own session
+    Mock::given(method("GET"))
+        .and(path("/event"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(
+                    "data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"Borrowing lets you reference data without owning it.\", \"part\": {\"sessionID\": \"sess_chat\"}}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_chat\"}}\n\ndata: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"\\\"Rust Borrowing Basics\\\"\", \"part\": {\"sessionID\": \"sess_title\"}}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_title\"}}\n\n",
+                )
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/session/sess_chat/prompt_async"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/session/sess_title/prompt_async"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let (addr, _pool) = setup_server_with_opencode(&mock_server.uri()).await;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({"type": "create_journal"});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let journal_id = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        if let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            assert_eq!(json["title"], "Untitled");
+            Some(json["journal_id"].as_str().unwrap().to_string())
+        } else {
+            None
+        }
+    })
+    .await
+    .expect("Timeout")
+    .expect("Expected journal_id");
+
+    // Someone else in the journal hears about the title too
+    let (mut ws_watcher, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal_id, "name": "Bob"});
+    ws_watcher
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let msg = serde_json::json!({
+        "type": "submit",
+        "journal_id": journal_id,
+        "content": "Explain borrowing in Rust"
+    });
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // The prompt names the journal first, then the generated title replaces it
+    async fn generated_title(
+        ws: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+    ) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+            while let Some(Ok(Message::Text(response))) = ws.next().await {
+                let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+                if json["type"] == "journal_renamed" && json["title"] != "Explain borrowing in Rust"
+                {
+                    return Some(json);
+                }
+            }
+            None
+        })
+        .await
+        .expect("Timeout waiting for journal_renamed")
+        .expect("Expected journal_renamed")
+    }
+
+    let renamed = generated_title(&mut ws_stream).await;
+    assert_eq!(renamed["journal_id"], journal_id.as_str());
+    assert_eq!(renamed["title"], "Rust Borrowing Basics");
+
+    let renamed = generated_title(&mut ws_watcher).await;
+    assert_eq!(renamed["journal_id"], journal_id.as_str());
+    assert_eq!(renamed["title"], "Rust Borrowing Basics");
+
+    // The rename is persisted
+    let msg = serde_json::json!({"type": "get_journal", "journal_id": journal_id});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let journal = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        while let Some(Ok(Message::Text(response))) = ws_stream.next().await {
+            let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+            if json["type"] == "journal" {
+                return Some(json);
+            }
+        }
+        None
+    })
+    .await
+    .expect("Timeout")
+    .expect("Expected journal");
+
+    assert_eq!(journal["journal"]["title"], "Rust Borrowing Basics");
+
+    mock_server.verify().await;
+}
+
+#[tokio::test]
+async fn test_websocket_resubscribe_with_known_participants_gets_diff() {
+    let (addr, _pool) = setup_server().await;
+    let url = format!("ws://{}/ws", addr);
+
+    async fn next_of_type(
+        ws: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        msg_type: &str,
+    ) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+            loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if json["type"] == msg_type {
+                        return json;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for message")
+    }
+
+    let (mut ws_alice, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "create_journal", "title": "Presence"});
+    ws_alice
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let created = next_of_type(&mut ws_alice, "journal_created").await;
+    let journal_id = created["journal_id"].as_str().unwrap().to_string();
+
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal_id, "name": "Alice"});
+    ws_alice
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let subscribed = next_of_type(&mut ws_alice, "subscribed").await;
+    let alice_id = subscribed["participant"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let (mut ws_bob, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal_id, "name": "Bob"});
+    ws_bob
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let subscribed = next_of_type(&mut ws_bob, "subscribed").await;
+    let bob_id = subscribed["participant"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // Bob drops off; Alice sees him leave
+    ws_bob.close(None).await.unwrap();
+    let left = next_of_type(&mut ws_alice, "participant_left").await;
+    assert_eq!(left["participant_id"], bob_id.as_str());
+
+    // Carol joins while the reconnecting client is away
+    let (mut ws_carol, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal_id, "name": "Carol"});
+    ws_carol
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let subscribed = next_of_type(&mut ws_carol, "subscribed").await;
+    let carol_id = subscribed["participant"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // Reconnect knowing Alice and Bob: only Carol joined and Bob left
+    let (mut ws_dave, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({
+        "type": "subscribe",
+        "journal_id": journal_id,
+        "name": "Dave",
+        "known_participants": [alice_id, bob_id]
+    });
+    ws_dave
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let diff = next_of_type(&mut ws_dave, "subscribed_diff").await;
+
+    let joined = diff["joined"].as_array().unwrap();
+    assert_eq!(joined.len(), 1);
+    assert_eq!(joined[0]["id"], carol_id.as_str());
+    assert_eq!(diff["left"], serde_json::json!([bob_id]));
+    assert!(diff.get("participants").is_none());
+
+    // With nothing known, the full list is sent
+    let (mut ws_erin, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal_id, "name": "Erin"});
+    ws_erin
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let full = next_of_type(&mut ws_erin, "subscribed").await;
+    assert_eq!(full["participants"].as_array().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn test_websocket_auto_execute_delegated_work() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_auto",
+            "version": "1.0.0",
+            "projectID": "proj_456"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/event"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(
+                    "data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"All three \", \"part\": {\"sessionID\": \"sess_auto\"}}}\n\ndata: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"tests pass.\", \"part\": {\"sessionID\": \"sess_auto\"}}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_auto\"}}\n\n",
+                )
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/session/sess_auto/prompt_async"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let (addr, _pool) = setup_server_with_opencode(&mock_server.uri()).await;
+    let url = format!("ws://{}/ws", addr);
+
+    async fn next_json(
+        ws: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+    ) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+            loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    return serde_json::from_str(&text).unwrap();
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for message")
+    }
+
+    let (mut ws_alice, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "create_journal", "title": "Auto"});
+    ws_alice
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let created = next_json(&mut ws_alice).await;
+    let journal_id = created["journal_id"].as_str().unwrap().to_string();
+
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id,
+        "name": "Alice",
+        "kind": "user"
+    });
+    ws_alice
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let _ = next_json(&mut ws_alice).await;
+
+    let (mut ws_bot, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal_id,
+        "name": "Test Runner",
+        "kind": "agent"
+    });
+    ws_bot
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let registered = next_json(&mut ws_bot).await;
+    let bot_id = registered["participant_id"].as_str().unwrap().to_string();
+
+    let msg = serde_json::json!({
+        "type": "delegate",
+        "journal_id": journal_id,
+        "description": "Run the test suite and report",
+        "assignee_id": bot_id,
+        "auto_execute": true
+    });
+    ws_alice
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let delegated = next_json(&mut ws_alice).await;
+    assert_eq!(delegated["type"], "work_delegated");
+    assert_eq!(delegated["work_item"]["auto_execute"], true);
+    let work_item_id = delegated["work_item"]["id"].as_str().unwrap().to_string();
+
+    // The delegator watches the block stream and then sees the result land
+    let mut block_id = None;
+    let mut completed = false;
+    loop {
+        let msg = next_json(&mut ws_alice).await;
+        match msg["type"].as_str().unwrap() {
+            "block_created" => {
+                assert_eq!(msg["block"]["block_type"], "assistant");
+                block_id = Some(msg["block"]["id"].as_str().unwrap().to_string());
+            }
+            "block_status_changed" if msg["status"] == "complete" => {
+                assert_eq!(msg["block_id"].as_str(), block_id.as_deref());
+                completed = true;
+            }
+            "work_approved" => {
+                assert_eq!(msg["work_item_id"], work_item_id.as_str());
+                break;
+            }
+            _ => {}
+        }
+    }
+    assert!(
+        completed,
+        "Block should complete before the result is submitted"
+    );
+
+    // The block holds the response and the work item carries it as its result
+    let msg = serde_json::json!({"type": "get_journal", "journal_id": journal_id});
+    ws_alice
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let journal = next_json(&mut ws_alice).await;
+    let blocks = journal["blocks"].as_array().unwrap();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0]["status"], "complete");
+    assert_eq!(blocks[0]["content"], "All three tests pass.");
+
+    let msg = serde_json::json!({"type": "search_work", "query": "test suite"});
+    ws_alice
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let results = next_json(&mut ws_alice).await;
+    let items = results["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["status"], "approved");
+    assert_eq!(items[0]["result"], "All three tests pass.");
+
+    mock_server.verify().await;
+}
+
+#[tokio::test]
+async fn test_websocket_close_releases_room_subscriber() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    let journal_id = state.store.create_journal(None).await.unwrap().id;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({
+        "type": "subscribe",
+        "journal_id": journal_id,
+        "name": "Alice"
+    });
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    ws_stream.next().await; // subscribed
+
+    // Re-subscribing replaces the forwarder instead of adding a second one
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    ws_stream.next().await;
+
+    let room = state.room_manager.get(journal_id).await.unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    assert_eq!(room.subscriber_count(), 1);
+
+    ws_stream.close(None).await.unwrap();
+
+    let released = tokio::time::timeout(tokio::time::Duration::from_secs(1), async {
+        while room.subscriber_count() > 0 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    assert!(released.is_ok(), "forwarding task outlived its connection");
+}
+
+#[tokio::test]
+async fn test_websocket_subscribe_requires_existing_journal() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    async fn subscribe(
+        ws: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        msg: serde_json::Value,
+    ) -> serde_json::Value {
+        ws.send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+        match ws.next().await {
+            Some(Ok(Message::Text(response))) => serde_json::from_str(&response).unwrap(),
+            other => panic!("Expected text message, got {:?}", other),
+        }
+    }
+
+    // Unknown journal: rejected, and no phantom room is left behind
+    let missing = uuid::Uuid::new_v4();
+    let response = subscribe(
+        &mut ws_stream,
+        serde_json::json!({"type": "subscribe", "journal_id": missing, "name": "Alice"}),
+    )
+    .await;
+    assert_eq!(response["type"], "error");
+    assert!(response["message"].as_str().unwrap().contains("not found"));
+    assert!(state.room_manager.get(missing).await.is_none());
+
+    // Existing journal: subscribed as before
+    let journal = state.store.create_journal(None).await.unwrap();
+    let response = subscribe(
+        &mut ws_stream,
+        serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Alice"}),
+    )
+    .await;
+    assert_eq!(response["type"], "subscribed");
+
+    // Explicit create makes the journal with the requested ID
+    let fresh = uuid::Uuid::new_v4();
+    let response = subscribe(
+        &mut ws_stream,
+        serde_json::json!({
+            "type": "subscribe",
+            "journal_id": fresh,
+            "name": "Alice",
+            "create": true
+        }),
+    )
+    .await;
+    assert_eq!(response["type"], "subscribed");
+    assert_eq!(state.store.get_journal(fresh).await.unwrap().id, fresh);
+}
+
+#[tokio::test]
+async fn test_websocket_subscribe_rejects_unknown_kind() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    let journal = state.store.create_journal(None).await.unwrap();
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    async fn subscribe(
+        ws: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        msg: serde_json::Value,
+    ) -> serde_json::Value {
+        ws.send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+        match ws.next().await {
+            Some(Ok(Message::Text(response))) => serde_json::from_str(&response).unwrap(),
+            other => panic!("Expected text message, got {:?}", other),
+        }
+    }
+
+    // A misspelled kind is an error, not a quiet fallback to user
+    let response = subscribe(
+        &mut ws_stream,
+        serde_json::json!({
+            "type": "subscribe",
+            "journal_id": journal.id,
+            "name": "Bot",
+            "kind": "agnet"
+        }),
+    )
+    .await;
+    assert_eq!(response["type"], "error");
+    assert_eq!(response["code"], "invalid_kind");
+    assert!(state.room_manager.get(journal.id).await.is_none());
+
+    // Leaving the kind out still means a user
+    let response = subscribe(
+        &mut ws_stream,
+        serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Alice"}),
+    )
+    .await;
+    assert_eq!(response["type"], "subscribed");
+    assert_eq!(response["participant"]["kind"], "user");
+}
+
+#[tokio::test]
+async fn test_websocket_collapsed_presence_counts_observers() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    let journal = state.store.create_journal(None).await.unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    type Ws = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    async fn next_of_type(ws: &mut Ws, msg_type: &str) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+            loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if json["type"] == msg_type {
+                        return json;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for message")
+    }
+
+    async fn join(url: &str, msg: serde_json::Value) -> (Ws, serde_json::Value) {
+        let (mut ws, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+        ws.send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+        let subscribed = next_of_type(&mut ws, "subscribed").await;
+        (ws, subscribed)
+    }
+
+    let mut connections = Vec::new();
+    for i in 0..3 {
+        let msg = serde_json::json!({
+            "type": "subscribe",
+            "journal_id": journal.id,
+            "name": format!("Watcher {}", i),
+            "kind": "observer"
+        });
+        connections.push(join(&url, msg).await.0);
+    }
+    let mut editor_ids = Vec::new();
+    for name in ["Alice", "Bob"] {
+        let msg = serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": name});
+        let (ws, subscribed) = join(&url, msg).await;
+        editor_ids.push(subscribed["participant"]["id"].clone());
+        connections.push(ws);
+    }
+
+    // A collapsing spectator gets the editors in full and everyone else as a count
+    let msg = serde_json::json!({
+        "type": "subscribe",
+        "journal_id": journal.id,
+        "name": "Spectator",
+        "kind": "observer",
+        "collapse_observers": true
+    });
+    let (mut ws_spectator, subscribed) = join(&url, msg).await;
+    let ids: Vec<_> = subscribed["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["id"].clone())
+        .collect();
+    assert_eq!(ids.len(), 2);
+    assert!(editor_ids.iter().all(|id| ids.contains(id)));
+    assert_eq!(subscribed["observer_count"], 4);
+
+    let msg = serde_json::json!({"type": "get_presence", "journal_id": journal.id});
+    ws_spectator
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let presence = next_of_type(&mut ws_spectator, "presence").await;
+    assert_eq!(presence["participants"].as_array().unwrap().len(), 2);
+    assert_eq!(presence["observer_count"], 4);
+
+    // Without the flag, every record is sent and there is no count
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Carol"});
+    let (_ws_carol, subscribed) = join(&url, msg).await;
+    assert_eq!(subscribed["participants"].as_array().unwrap().len(), 7);
+    assert!(subscribed.get("observer_count").is_none());
+}
+
+#[tokio::test]
+async fn test_websocket_selection_reaches_other_subscribers() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    let journal = state.store.create_journal(None).await.unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    type Ws = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    async fn next_of_type(ws: &mut Ws, msg_type: &str) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+            loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if json["type"] == msg_type {
+                        return json;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for message")
+    }
+
+    let mut clients = Vec::new();
+    for name in ["Alice", "Bob"] {
+        let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let msg = serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": name});
+        ws.send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+        let subscribed = next_of_type(&mut ws, "subscribed").await;
+        clients.push((ws, subscribed["participant"]["id"].clone()));
+    }
+    let (mut ws_bob, _) = clients.pop().unwrap();
+    let (mut ws_alice, alice_id) = clients.pop().unwrap();
+
+    let block_id = uuid::Uuid::new_v4();
+    let msg = serde_json::json!({
+        "type": "cursor",
+        "journal_id": journal.id,
+        "block_id": block_id,
+        "anchor": 3,
+        "head": 11
+    });
+    ws_alice
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let moved = next_of_type(&mut ws_bob, "cursor_moved").await;
+    assert_eq!(moved["participant_id"], alice_id);
+    assert_eq!(moved["block_id"], block_id.to_string());
+    assert_eq!(moved["anchor"], 3);
+    assert_eq!(moved["head"], 11);
+    assert_eq!(moved["offset"], 11);
+
+    // The range is kept in presence for anyone who asks later
+    let msg = serde_json::json!({"type": "get_presence", "journal_id": journal.id});
+    ws_bob
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let presence = next_of_type(&mut ws_bob, "presence").await;
+    let alice = presence["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["id"] == alice_id)
+        .unwrap();
+    assert_eq!(alice["cursor_anchor"], 3);
+    assert_eq!(alice["cursor_head"], 11);
+
+    // A bare offset collapses the selection
+    let msg = serde_json::json!({
+        "type": "cursor",
+        "journal_id": journal.id,
+        "block_id": block_id,
+        "offset": 5
+    });
+    ws_alice
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let moved = next_of_type(&mut ws_bob, "cursor_moved").await;
+    assert_eq!(moved["anchor"], 5);
+    assert_eq!(moved["head"], 5);
+}
+
+#[tokio::test]
+async fn test_websocket_typing_reaches_other_subscribers() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    let journal = state.store.create_journal(None).await.unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    type Ws = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    async fn next_of_type(ws: &mut Ws, msg_type: &str) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+            loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if json["type"] == msg_type {
+                        return json;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for message")
+    }
+
+    let mut clients = Vec::new();
+    for name in ["Alice", "Bob"] {
+        let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let msg = serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": name});
+        ws.send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+        let subscribed = next_of_type(&mut ws, "subscribed").await;
+        clients.push((ws, subscribed["participant"]["id"].clone()));
+    }
+    let (mut ws_bob, _) = clients.pop().unwrap();
+    let (mut ws_alice, alice_id) = clients.pop().unwrap();
+
+    for typing in [true, false] {
+        let msg = serde_json::json!({"type": "typing", "journal_id": journal.id, "typing": typing});
+        ws_alice
+            .send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+
+        let changed = next_of_type(&mut ws_bob, "typing_changed").await;
+        assert_eq!(changed["journal_id"], journal.id.to_string());
+        assert_eq!(changed["participant_id"], alice_id);
+        assert_eq!(changed["typing"], typing);
+    }
+
+    // Alice isn't told about her own indicator
+    let msg = serde_json::json!({"type": "get_presence", "journal_id": journal.id});
+    ws_alice
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let echoed = tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        loop {
+            if let Some(Ok(Message::Text(text))) = ws_alice.next().await {
+                let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                match json["type"].as_str() {
+                    Some("typing_changed") => return true,
+                    Some("presence") => return false,
+                    _ => {}
+                }
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for presence");
+    assert!(!echoed);
+}
+
+#[tokio::test]
+async fn test_websocket_chunked_crdt_sync() {
+    use outer::crdt::JournalDoc;
+
+    fn b64(data: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut out = String::new();
+        for chunk in data.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, &b)| n | ((b as u32) << (16 - 8 * i)));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+
+    let (addr, _pool, state) = setup_server_with_state().await;
+    // A multiple of 3, so the chunks' base64 concatenates to the whole state's
+    state.room_manager.set_sync_chunk_bytes(15);
+    let journal = state.store.create_journal(None).await.unwrap();
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    async fn next_of_type(
+        ws: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        msg_type: &str,
+    ) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+            loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if json["type"] == msg_type {
+                        return json;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for message")
+    }
+
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Alice"});
+    ws.send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    next_of_type(&mut ws, "subscribed").await;
+
+    // Upload an update in 10-byte chunks
+    let block_id = uuid::Uuid::new_v4();
+    let local = JournalDoc::new(journal.id);
+    local.set_block_content(block_id, "a block long enough to need several chunks");
+    let update = local.encode_state();
+    let chunks: Vec<_> = update.chunks(10).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let msg = serde_json::json!({
+            "type": "crdt_update",
+            "journal_id": journal.id,
+            "update": b64(chunk),
+            "seq": i,
+            "final": i + 1 == chunks.len()
+        });
+        ws.send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+    }
+
+    // The full sync comes back chunked as well
+    let msg = serde_json::json!({"type": "sync_request", "journal_id": journal.id});
+    ws.send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let mut frames = Vec::new();
+    loop {
+        let frame = next_of_type(&mut ws, "sync_state").await;
+        let last = frame["final"] == true;
+        frames.push(frame);
+        if last {
+            break;
+        }
+    }
+
+    let room = state.room_manager.get(journal.id).await.unwrap();
+    assert_eq!(
+        room.doc().get_block_content(block_id),
+        Some("a block long enough to need several chunks".to_string())
+    );
+
+    let expected = room.get_sync_state();
+    assert_eq!(frames.len(), expected.len().div_ceil(15));
+    for (i, frame) in frames.iter().enumerate() {
+        assert_eq!(frame["seq"], i);
+    }
+    let joined: String = frames
+        .iter()
+        .map(|f| f["state"].as_str().unwrap())
+        .collect();
+    assert_eq!(joined, b64(&expected));
+}
+
+#[tokio::test]
+async fn test_websocket_rename_journal_reaches_subscribers() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    let journal = state
+        .store
+        .create_journal(Some("CLI Session".to_string()))
+        .await
+        .unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    type Ws = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    async fn next_of_type(ws: &mut Ws, msg_type: &str) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+            loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if json["type"] == msg_type {
+                        return json;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for message")
+    }
+
+    let (mut ws_watcher, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Bob"});
+    ws_watcher
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    next_of_type(&mut ws_watcher, "subscribed").await;
+
+    // Only subscribers hold a role that may rename the journal
+    let (mut ws_renamer, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Carol"});
+    ws_renamer
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    next_of_type(&mut ws_renamer, "subscribed").await;
+
+    // Blank titles are refused and nothing is broadcast
+    let msg =
+        serde_json::json!({"type": "rename_journal", "journal_id": journal.id, "title": "   "});
+    ws_renamer
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    next_of_type(&mut ws_renamer, "error").await;
+
+    let msg = serde_json::json!({
+        "type": "rename_journal",
+        "journal_id": journal.id,
+        "title": "Release planning"
+    });
+    ws_renamer
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let reply = next_of_type(&mut ws_renamer, "journal_renamed").await;
+    assert_eq!(reply["title"], "Release planning");
+
+    let renamed = next_of_type(&mut ws_watcher, "journal_renamed").await;
+    assert_eq!(renamed["journal_id"], journal.id.to_string());
+    assert_eq!(renamed["title"], "Release planning");
+
+    let stored = state.store.get_journal(journal.id).await.unwrap();
+    assert_eq!(stored.title, "Release planning");
+}
+
+#[tokio::test]
+async fn test_websocket_guest_cannot_rename_journal() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    let journal = state
+        .store
+        .create_journal(Some("Roadmap".to_string()))
+        .await
+        .unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    type Ws = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    async fn next_of_type(ws: &mut Ws, msg_type: &str) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+            loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if json["type"] == msg_type {
+                        return json;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for message")
+    }
+
+    async fn send(ws: &mut Ws, msg: serde_json::Value) {
+        ws.send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+    }
+
+    // The first to subscribe owns the journal; the next is a member
+    let (mut ws_owner, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    send(
+        &mut ws_owner,
+        serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Alice"}),
+    )
+    .await;
+    let subscribed = next_of_type(&mut ws_owner, "subscribed").await;
+    assert_eq!(subscribed["role"], "owner");
+
+    let (mut ws_guest, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    send(
+        &mut ws_guest,
+        serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Bob"}),
+    )
+    .await;
+    let subscribed = next_of_type(&mut ws_guest, "subscribed").await;
+    assert_eq!(subscribed["role"], "member");
+    let guest_id = subscribed["participant"]["id"].clone();
+
+    // Members can't hand out roles
+    send(
+        &mut ws_guest,
+        serde_json::json!({
+            "type": "set_role",
+            "journal_id": journal.id,
+            "participant_id": guest_id,
+            "role": "owner"
+        }),
+    )
+    .await;
+    let error = next_of_type(&mut ws_guest, "error").await;
+    assert_eq!(error["code"], "not_authorized");
+
+    send(
+        &mut ws_owner,
+        serde_json::json!({
+            "type": "set_role",
+            "journal_id": journal.id,
+            "participant_id": guest_id,
+            "role": "guest"
+        }),
+    )
+    .await;
+    next_of_type(&mut ws_owner, "role_changed").await;
+    let changed = next_of_type(&mut ws_guest, "role_changed").await;
+    assert_eq!(changed["role"], "guest");
+
+    let rename = |title: &str| serde_json::json!({"type": "rename_journal", "journal_id": journal.id, "title": title});
+    send(&mut ws_guest, rename("Guest title")).await;
+    let error = next_of_type(&mut ws_guest, "error").await;
+    assert_eq!(error["code"], "not_authorized");
+    assert_eq!(
+        state.store.get_journal(journal.id).await.unwrap().title,
+        "Roadmap"
+    );
+
+    send(&mut ws_owner, rename("Owner title")).await;
+    let reply = next_of_type(&mut ws_owner, "journal_renamed").await;
+    assert_eq!(reply["title"], "Owner title");
+}
+
+#[tokio::test]
+async fn test_websocket_unsubscribed_connection_cannot_change_journal() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    let journal = state
+        .store
+        .create_journal(Some("Roadmap".to_string()))
+        .await
+        .unwrap();
+    let block = state
+        .store
+        .create_block(journal.id, outer::models::BlockType::User, "Keep me")
+        .await
+        .unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    // Someone else owns the journal and is in the room
+    let (mut ws_owner, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Alice"});
+    ws_owner
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    ws_owner.next().await;
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    for msg in [
+        serde_json::json!({"type": "delete_journal", "journal_id": journal.id}),
+        serde_json::json!({"type": "rename_journal", "journal_id": journal.id, "title": "Mine"}),
+        serde_json::json!({"type": "archive_journal", "journal_id": journal.id, "archived": true}),
+        serde_json::json!({"type": "delete_block", "block_id": block.id}),
+    ] {
+        ws.send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+        let Some(Ok(Message::Text(response))) = ws.next().await else {
+            panic!("Expected text message");
+        };
+        let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(json["type"], "error", "{} was allowed", msg["type"]);
+        assert_eq!(json["code"], "not_authorized");
+    }
+
+    let stored = state.store.get_journal(journal.id).await.unwrap();
+    assert_eq!(stored.title, "Roadmap");
+    assert!(stored.deleted_at.is_none());
+    assert!(stored.archived_at.is_none());
+    assert!(state.store.get_block(block.id).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_websocket_ownership_follows_token_across_restart() {
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+    let (addr, pool, state) = setup_server_with_state().await;
+    sqlx::query(include_str!("../migrations/20260110000017_tokens.sql"))
+        .execute(&pool)
+        .await
+        .expect("Failed to create tokens table");
+    let alice = state.store.create_token("alice").await.unwrap();
+    let bob = state.store.create_token("bob").await.unwrap();
+    let journal = state
+        .store
+        .create_journal(Some("Roadmap".to_string()))
+        .await
+        .unwrap();
+
+    async fn subscribe_role(addr: SocketAddr, token: &str, journal_id: uuid::Uuid) -> String {
+        let mut request = format!("ws://{}/ws", addr).into_client_request().unwrap();
+        request.headers_mut().insert(
+            "Authorization",
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        let (mut ws, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+        let msg = serde_json::json!({"type": "subscribe", "journal_id": journal_id, "name": ""});
+        ws.send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+        let Some(Ok(Message::Text(response))) = ws.next().await else {
+            panic!("Expected text message");
+        };
+        let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(json["type"], "subscribed");
+        json["role"].as_str().unwrap().to_string()
+    }
+
+    state.auth.enable(None);
+    assert_eq!(subscribe_role(addr, &alice, journal.id).await, "owner");
+
+    // A fresh server over the same database starts with an empty room, yet
+    // the first to arrive there doesn't get to take ownership
+    let restarted = AppState::new(pool.clone());
+    restarted.auth.enable(None);
+    let app = Router::new()
+        .route("/ws", get(outer::websocket::handler))
+        .with_state(restarted);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    assert_eq!(subscribe_role(addr, &bob, journal.id).await, "member");
+    assert_eq!(subscribe_role(addr, &alice, journal.id).await, "owner");
+}
+
+#[tokio::test]
+async fn test_websocket_submit_streams_to_other_subscribers() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_shared",
+            "version": "1.0.0",
+            "projectID": "proj_shared"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/event"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(
+                    "data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"Hello \", \"part\": {\"sessionID\": \"sess_shared\"}}}\n\ndata: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"World\", \"part\": {\"sessionID\": \"sess_shared\"}}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_shared\"}}\n\n",
+                )
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/session/sess_shared/prompt_async"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_server)
+        .await;
+
+    let (addr, pool) = setup_server_with_opencode(&mock_server.uri()).await;
+    let journal = outer::store::Store::new(pool)
+        .create_journal(Some("Shared".to_string()))
+        .await
+        .unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    type Ws = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    async fn subscribe(url: &str, journal_id: uuid::Uuid, name: &str) -> Ws {
+        let (mut ws, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+        let msg = serde_json::json!({"type": "subscribe", "journal_id": journal_id, "name": name});
+        ws.send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+        next_of_type(&mut ws, "subscribed").await;
+        ws
+    }
+
+    async fn next_of_type(ws: &mut Ws, msg_type: &str) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+            loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if json["type"] == msg_type {
+                        return json;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for message")
+    }
+
+    /// Block messages up to and including the assistant block's completion
+    async fn block_messages(ws: &mut Ws) -> Vec<serde_json::Value> {
+        tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+            let mut msgs = Vec::new();
+            loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if !json["type"].as_str().unwrap_or("").starts_with("block_") {
+                        continue;
+                    }
+                    let done =
+                        json["type"] == "block_status_changed" && json["status"] == "complete";
+                    msgs.push(json);
+                    if done {
+                        return msgs;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for block messages")
+    }
+
+    let mut ws_a = subscribe(&url, journal.id, "Alice").await;
+    let mut ws_b = subscribe(&url, journal.id, "Bob").await;
+
+    let msg = serde_json::json!({
+        "type": "submit",
+        "journal_id": journal.id,
+        "content": "Say hello"
+    });
+    ws_a.send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let seen_by_b = block_messages(&mut ws_b).await;
+    let created: Vec<_> = seen_by_b
+        .iter()
+        .filter(|m| m["type"] == "block_created")
+        .map(|m| m["block"]["block_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(created, vec!["user", "assistant"]);
+    let streamed: String = seen_by_b
+        .iter()
+        .filter(|m| m["type"] == "block_content_delta")
+        .map(|m| m["delta"].as_str().unwrap())
+        .collect();
+    assert_eq!(streamed, "Hello World");
+
+    // The submitter gets each event once, not again through its own subscription
+    let seen_by_a = block_messages(&mut ws_a).await;
+    assert_eq!(seen_by_a, seen_by_b);
+}
+
+/// Deltas the slow fake OpenCode streams before finishing
+const SLOW_DELTAS: usize = 30;
+
+/// Start a fake OpenCode whose event stream trickles out one delta every 100ms
+async fn spawn_slow_opencode() -> String {
+    use axum::routing::post;
+
+    async fn events() -> axum::response::Response {
+        let body = async_stream::stream! {
+            for i in 0..SLOW_DELTAS {
+                let event = serde_json::json!({
+                    "type": "message.part.updated",
+                    "properties": {"delta": format!("chunk{} ", i), "part": {"sessionID": "sess_slow"}}
+                });
+                yield Ok::<_, std::convert::Infallible>(format!("data: {}\n\n", event));
+                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            }
+            yield Ok(
+                "data: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_slow\"}}\n\n"
+                    .to_string(),
+            );
+        };
+        axum::response::Response::builder()
+            .header("content-type", "text/event-stream")
+            .body(axum::body::Body::from_stream(body))
+            .unwrap()
+    }
+
+    let opencode = Router::new()
+        .route(
+            "/session",
+            post(|| async {
+                axum::Json(serde_json::json!({
+                    "id": "sess_slow",
+                    "version": "1.0.0",
+                    "projectID": "proj_slow"
+                }))
+            }),
+        )
+        .route("/event", get(events))
+        .route(
+            "/session/sess_slow/prompt_async",
+            post(|| async { axum::http::StatusCode::NO_CONTENT }),
+        );
+    let opencode_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let opencode_uri = format!("http://{}", opencode_listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(opencode_listener, opencode).await.unwrap();
+    });
+    opencode_uri
+}
+
+#[tokio::test]
+async fn test_websocket_cancel_stops_streaming() {
+    let opencode_uri = spawn_slow_opencode().await;
+    let (addr, pool) = setup_server_with_opencode(&opencode_uri).await;
+    let store = outer::store::Store::new(pool);
+    let journal = store.create_journal(None).await.unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    let (mut ws_writer, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (mut ws_canceller, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Writer"});
+    ws_writer
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let msg = serde_json::json!({"type": "submit", "journal_id": journal.id, "content": "Go"});
+    ws_writer
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // Read the writer's messages until the cancel reaches it, cancelling after the first delta
+    let (block_id, received) = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        let mut block_id = None;
+        let mut received = String::new();
+        loop {
+            let Some(Ok(Message::Text(text))) = ws_writer.next().await else {
+                continue;
+            };
+            let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+            match json["type"].as_str().unwrap() {
+                "block_content_delta" => {
+                    received.push_str(json["delta"].as_str().unwrap());
+                    if block_id.is_none() {
+                        let id = json["block_id"].as_str().unwrap().to_string();
+                        let cancel = serde_json::json!({"type": "cancel", "block_id": id});
+                        ws_canceller
+                            .send(Message::Text(cancel.to_string().into()))
+                            .await
+                            .unwrap();
+                        block_id = Some(id);
+                    }
+                }
+                "block_status_changed" => {
+                    assert_ne!(json["status"], "complete", "Stream ran to completion");
+                }
+                "block_cancelled" => return (block_id.unwrap(), received),
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for cancellation");
+
+    // The stream stopped well short of its end
+    assert!(received.split_whitespace().count() < SLOW_DELTAS);
+
+    // Nothing else is streamed once the cancel has landed
+    let late = tokio::time::timeout(tokio::time::Duration::from_millis(500), async {
+        loop {
+            if let Some(Ok(Message::Text(text))) = ws_writer.next().await {
+                let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                if json["type"] == "block_content_delta" {
+                    return json;
+                }
+            }
+        }
+    })
+    .await;
+    assert!(late.is_err(), "Received a delta after cancelling");
+
+    // The block keeps what streamed before the cancel
+    let block = store.get_block(block_id.parse().unwrap()).await.unwrap();
+    assert_eq!(block.status, outer::models::BlockStatus::Error);
+    assert_eq!(block.content, received);
+}
+
+#[tokio::test]
+async fn test_websocket_submitter_cancels_own_stream() {
+    let opencode_uri = spawn_slow_opencode().await;
+    let (addr, pool) = setup_server_with_opencode(&opencode_uri).await;
+    let store = outer::store::Store::new(pool);
+    let journal = store.create_journal(None).await.unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "submit", "journal_id": journal.id, "content": "Go"});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // The connection streaming the response is the one that cancels it
+    let (block_id, received) = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        let mut block_id = None;
+        let mut received = String::new();
+        loop {
+            let Some(Ok(Message::Text(text))) = ws_stream.next().await else {
+                continue;
+            };
+            let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+            match json["type"].as_str().unwrap() {
+                "block_content_delta" => {
+                    received.push_str(json["delta"].as_str().unwrap());
+                    if block_id.is_none() {
+                        let id = json["block_id"].as_str().unwrap().to_string();
+                        let cancel = serde_json::json!({"type": "cancel", "block_id": id});
+                        ws_stream
+                            .send(Message::Text(cancel.to_string().into()))
+                            .await
+                            .unwrap();
+                        block_id = Some(id);
+                    }
+                }
+                "block_status_changed" => {
+                    assert_ne!(json["status"], "complete", "Stream ran to completion");
+                }
+                "block_cancelled" => return (block_id.unwrap(), received),
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for cancellation");
+
+    assert!(received.split_whitespace().count() < SLOW_DELTAS);
+    let block = store.get_block(block_id.parse().unwrap()).await.unwrap();
+    assert_eq!(block.status, outer::models::BlockStatus::Error);
+}
+
+#[tokio::test]
+async fn test_websocket_cancel_during_fork() {
+    let opencode_uri = spawn_slow_opencode().await;
+    let (addr, pool) = setup_server_with_opencode(&opencode_uri).await;
+    let store = outer::store::Store::new(pool);
+    let journal = store.create_journal(None).await.unwrap();
+    let block = store
+        .create_block(journal.id, outer::models::BlockType::User, "Branch me")
+        .await
+        .unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "fork", "block_id": block.id});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // The connection streaming the fork's response can still cancel it
+    let (block_id, received) = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        let mut block_id = None;
+        let mut received = String::new();
+        loop {
+            let Some(Ok(Message::Text(text))) = ws_stream.next().await else {
+                continue;
+            };
+            let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+            match json["type"].as_str().unwrap() {
+                "block_content_delta" => {
+                    received.push_str(json["delta"].as_str().unwrap());
+                    if block_id.is_none() {
+                        let id = json["block_id"].as_str().unwrap().to_string();
+                        let cancel = serde_json::json!({"type": "cancel", "block_id": id});
+                        ws_stream
+                            .send(Message::Text(cancel.to_string().into()))
+                            .await
+                            .unwrap();
+                        block_id = Some(id);
+                    }
+                }
+                "block_status_changed" => {
+                    assert_ne!(json["status"], "complete", "Stream ran to completion");
+                }
+                "block_cancelled" => return (block_id.unwrap(), received),
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for cancellation");
+
+    assert!(received.split_whitespace().count() < SLOW_DELTAS);
+    let block = store.get_block(block_id.parse().unwrap()).await.unwrap();
+    assert_eq!(block.status, outer::models::BlockStatus::Error);
+    assert_eq!(block.content, received);
+}
+
+#[tokio::test]
+async fn test_websocket_stream_outlives_submitter() {
+    let opencode_uri = spawn_slow_opencode().await;
+    let (addr, pool) = setup_server_with_opencode(&opencode_uri).await;
+    let store = outer::store::Store::new(pool);
+    let journal = store.create_journal(None).await.unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    let (mut ws_submitter, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "submit", "journal_id": journal.id, "content": "Go"});
+    ws_submitter
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // The submitter goes away as soon as the response starts streaming
+    let block_id = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        loop {
+            let Some(Ok(Message::Text(text))) = ws_submitter.next().await else {
+                continue;
+            };
+            let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+            if json["type"] == "block_content_delta" {
+                return json["block_id"].as_str().unwrap().to_string();
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for the first delta");
+    drop(ws_submitter);
+
+    // Another connection picks the block up and follows it to the end
+    let (mut ws_reader, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "resume_block", "block_id": block_id});
+    ws_reader
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    let received = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        let mut received = String::new();
+        loop {
+            let Some(Ok(Message::Text(text))) = ws_reader.next().await else {
+                continue;
+            };
+            let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+            match json["type"].as_str().unwrap() {
+                "block_content_delta" => received.push_str(json["delta"].as_str().unwrap()),
+                "block_status_changed" => {
+                    assert_eq!(json["status"], "complete");
+                    return received;
+                }
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for the block to complete");
+
+    assert_eq!(received.split_whitespace().count(), SLOW_DELTAS);
+    let block = store.get_block(block_id.parse().unwrap()).await.unwrap();
+    assert_eq!(block.status, outer::models::BlockStatus::Complete);
+    assert_eq!(block.content, received);
+}
+
+#[tokio::test]
+async fn test_websocket_room_sees_stream_finish_after_submitter_leaves() {
+    let opencode_uri = spawn_slow_opencode().await;
+    let (addr, pool) = setup_server_with_opencode(&opencode_uri).await;
+    let store = outer::store::Store::new(pool);
+    let journal = store.create_journal(None).await.unwrap();
+    let url = format!("ws://{}/ws", addr);
+
+    let (mut ws_watcher, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Watcher"});
+    ws_watcher
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        loop {
+            if let Some(Ok(Message::Text(text))) = ws_watcher.next().await {
+                let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                if json["type"] == "subscribed" {
+                    return;
+                }
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for subscribed");
+
+    let (mut ws_submitter, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let msg =
+        serde_json::json!({"type": "subscribe", "journal_id": journal.id, "name": "Submitter"});
+    ws_submitter
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let msg = serde_json::json!({"type": "submit", "journal_id": journal.id, "content": "Go"});
+    ws_submitter
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // The watcher follows the response; the submitter closes after its first delta
+    let (block_id, received) = tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        let mut ws_submitter = Some(ws_submitter);
+        let mut received = String::new();
+        loop {
+            let Some(Ok(Message::Text(text))) = ws_watcher.next().await else {
+                continue;
+            };
+            let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+            match json["type"].as_str().unwrap() {
+                "block_content_delta" => {
+                    received.push_str(json["delta"].as_str().unwrap());
+                    if let Some(mut ws) = ws_submitter.take() {
+                        ws.close(None).await.unwrap();
+                    }
+                }
+                "block_status_changed" if json["status"] == "complete" => {
+                    return (json["block_id"].as_str().unwrap().to_string(), received);
+                }
+                "block_status_changed" => assert_ne!(json["status"], "error"),
+                _ => {}
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for the watcher to see the block complete");
+
+    assert_eq!(received.split_whitespace().count(), SLOW_DELTAS);
+    let block = store.get_block(block_id.parse().unwrap()).await.unwrap();
+    assert_eq!(block.status, outer::models::BlockStatus::Complete);
+    assert_eq!(block.content, received);
+}
+
+#[tokio::test]
+async fn test_websocket_resume_completed_block() {
+    use outer::models::{BlockStatus, BlockType};
+
+    let (addr, pool) = setup_server().await;
+    let store = outer::store::Store::new(pool);
+    let journal = store.create_journal(None).await.unwrap();
+    let block = store
+        .create_block(journal.id, BlockType::Assistant, "")
+        .await
+        .unwrap();
+    store
+        .update_block_content(block.id, "The whole answer", block.version)
+        .await
+        .unwrap();
+    store
+        .update_block_status(block.id, BlockStatus::Complete)
+        .await
+        .unwrap();
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({"type": "resume_block", "block_id": block.id});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+
+    // A finished block is replayed in full, then reported complete
+    let mut replies = Vec::new();
+    while replies.len() < 2 {
+        let Some(Ok(Message::Text(text))) = ws_stream.next().await else {
+            panic!("Expected text message");
+        };
+        replies.push(serde_json::from_str::<serde_json::Value>(&text).unwrap());
+    }
+    assert_eq!(replies[0]["type"], "block_content_delta");
+    assert_eq!(replies[0]["block_id"], block.id.to_string());
+    assert_eq!(replies[0]["delta"], "The whole answer");
+    assert_eq!(replies[0]["offset"], 0);
+    assert_eq!(replies[1]["type"], "block_status_changed");
+    assert_eq!(replies[1]["status"], "complete");
+}
+
+#[tokio::test]
+async fn test_websocket_submit_rate_limited() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/session"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "sess_rate",
+            "version": "1.0.0",
+            "projectID": "proj_rate"
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/event"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("data: {\"type\": \"message.part.updated\", \"properties\": {\"delta\": \"Hi\", \"part\": {\"sessionID\": \"sess_rate\"}}}\n\ndata: {\"type\": \"session.idle\", \"properties\": {\"sessionID\": \"sess_rate\"}}\n\n")
+                .insert_header("content-type", "text/event-stream"),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/session/sess_rate/prompt_async"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_server)
+        .await;
+    std::env::set_var("OPENCODE_URL", mock_server.uri());
+
+    let (addr, _pool, state) = setup_server_with_state().await;
+    state.submit_limiter.set_submits_per_minute(2);
+    let journal = state.store.create_journal(None).await.unwrap();
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    for i in 0..3 {
+        let msg = serde_json::json!({
+            "type": "submit",
+            "journal_id": journal.id,
+            "content": format!("Prompt {}", i)
+        });
+        ws_stream
+            .send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+    }
+
+    // The first two go through; the third is refused before reaching OpenCode
+    tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+        loop {
+            let Some(Ok(Message::Text(text))) = ws_stream.next().await else {
+                continue;
+            };
+            let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+            if json["type"] == "error" && json["code"] == "rate_limited" {
+                return;
+            }
+        }
+    })
+    .await
+    .expect("Timeout waiting for rate limit error");
+
+    let blocks = state
+        .store
+        .get_blocks_for_journal(journal.id)
+        .await
+        .unwrap();
+    let mut prompts: Vec<_> = blocks
+        .iter()
+        .filter(|b| b.block_type == outer::models::BlockType::User)
+        .map(|b| b.content.as_str())
+        .collect();
+    prompts.sort();
+    assert_eq!(prompts, vec!["Prompt 0", "Prompt 1"]);
+}
+
+#[tokio::test]
+async fn test_websocket_list_models() {
+    let mock_server = MockServer::start().await;
+
+    // Both requests below are served from one fetch
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"id": "anthropic/claude-sonnet-4", "name": "Claude Sonnet 4", "context_window": 200000},
+            {"id": "local/llama", "name": "Llama"}
+        ])))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let (addr, _pool) = setup_server_with_opencode(&mock_server.uri()).await;
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    for _ in 0..2 {
+        let msg = serde_json::json!({"type": "list_models"});
+        ws_stream
+            .send(Message::Text(msg.to_string().into()))
+            .await
+            .unwrap();
+
+        let Some(Ok(Message::Text(response))) = ws_stream.next().await else {
+            panic!("Expected text message");
+        };
+        let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(json["type"], "models");
+        let models = json["models"].as_array().unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0]["id"], "anthropic/claude-sonnet-4");
+        assert_eq!(models[0]["context_window"], 200000);
+        assert_eq!(models[1]["name"], "Llama");
+        assert!(models[1]["context_window"].is_null());
+    }
+}
+
+#[tokio::test]
+async fn test_websocket_observer_submit_rejected() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    state.set_require_submit_capability(true);
+    let journal = state.store.create_journal(None).await.unwrap();
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({
+        "type": "register_participant",
+        "journal_id": journal.id,
+        "name": "Watcher",
+        "kind": "observer"
+    });
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let Some(Ok(Message::Text(response))) = ws_stream.next().await else {
+        panic!("Expected text message");
+    };
+    let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(json["type"], "participant_registered");
+
+    let msg = serde_json::json!({
+        "type": "submit",
+        "journal_id": journal.id,
+        "content": "Write me a poem"
+    });
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let Some(Ok(Message::Text(response))) = ws_stream.next().await else {
+        panic!("Expected text message");
+    };
+    let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(json["type"], "error");
+    assert_eq!(json["code"], "insufficient_capability");
+
+    // Refused before anything was written
+    let blocks = state
+        .store
+        .get_blocks_for_journal(journal.id)
+        .await
+        .unwrap();
+    assert!(blocks.is_empty());
+}
+
+#[tokio::test]
+async fn test_websocket_oversized_frame_rejected() {
+    let (addr, _pool, state) = setup_server_with_state().await;
+    state.set_max_frame_bytes(1024);
+
+    let url = format!("ws://{}/ws", addr);
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+    let msg = serde_json::json!({"type": "create_journal", "title": "x".repeat(4096)});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let Some(Ok(Message::Text(response))) = ws_stream.next().await else {
+        panic!("Expected text message");
+    };
+    let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(json["type"], "error");
+    assert_eq!(json["code"], "message_too_large");
+
+    // The connection is still open for normal-sized messages
+    let msg = serde_json::json!({"type": "list_journals"});
+    ws_stream
+        .send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let Some(Ok(Message::Text(response))) = ws_stream.next().await else {
+        panic!("Expected text message");
+    };
+    let json: serde_json::Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(json["type"], "journals");
+    assert!(json["journals"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_websocket_rejects_upgrade_without_token() {
+    use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Error};
+
+    let (addr, pool, state) = setup_server_with_state().await;
+    sqlx::query(include_str!("../migrations/20260110000017_tokens.sql"))
+        .execute(&pool)
+        .await
+        .expect("Failed to create tokens table");
+    state.auth.enable(Some("s3cret".to_string()));
+    let url = format!("ws://{}/ws", addr);
+
+    async fn next_of_type(
+        ws: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        msg_type: &str,
+    ) -> serde_json::Value {
+        tokio::time::timeout(tokio::time::Duration::from_secs(5), async {
+            loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if json["type"] == msg_type {
+                        return json;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("Timeout waiting for message")
+    }
+
+    for url in [url.clone(), format!("{}?token=wrong", url)] {
+        match tokio_tungstenite::connect_async(&url).await {
+            Err(Error::Http(response)) => assert_eq!(response.status(), 401),
+            other => panic!("Expected 401, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    // The query parameter works for clients that can't set headers
+    let (mut ws, _) = tokio_tungstenite::connect_async(format!("{}?token=s3cret", url))
+        .await
+        .unwrap();
+    ws.close(None).await.unwrap();
+
+    // A token from the table names the participant when the client doesn't
+    let token = state.store.create_token("alice").await.unwrap();
+    let mut request = url.as_str().into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", token).parse().unwrap(),
+    );
+    let (mut ws, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+
+    let msg = serde_json::json!({"type": "create_journal", "title": "Authed"});
+    ws.send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let created = next_of_type(&mut ws, "journal_created").await;
+    let msg = serde_json::json!({"type": "subscribe", "journal_id": created["journal_id"]});
+    ws.send(Message::Text(msg.to_string().into()))
+        .await
+        .unwrap();
+    let subscribed = next_of_type(&mut ws, "subscribed").await;
+    assert_eq!(subscribed["participant"]["name"], "alice");
 }
@@ -202,6 +202,12 @@ export function initializeConnection(): Promise<void> {
 				]);
 				break;
 
+			case 'journal_updated':
+				journals.update((js) =>
+					js.map((j) => (j.id === message.journal.id ? message.journal : j))
+				);
+				break;
+
 			case 'journal':
 				journals.update((js) => {
 					const idx = js.findIndex((j) => j.id === message.journal.id);
@@ -379,6 +385,7 @@ export function initializeConnection(): Promise<void> {
 				approvalQueue.update((aq) => [...aq, message.approval]);
 				break;
 
+			case 'approval_granted':
 			case 'work_approved':
 			case 'work_rejected':
 				// Refresh approval queue
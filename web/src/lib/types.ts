@@ -5,28 +5,81 @@ export interface Journal {
 	title: string;
 	created_at: string;
 	updated_at: string;
+	deleted_at?: string;
+	archived_at?: string;
+}
+
+export interface JournalSummary {
+	journal_id: string;
+	block_count: number;
+	user_block_count: number;
+	assistant_block_count: number;
+	last_activity: string;
 }
 
 export interface Block {
 	id: string;
 	journal_id: string;
-	block_type: 'user' | 'assistant';
+	block_type: 'user' | 'assistant' | 'system';
 	content: string;
-	status: 'pending' | 'streaming' | 'complete' | 'error';
+	status: 'pending' | 'queued' | 'streaming' | 'complete' | 'error';
 	parent_id?: string;
 	forked_from_id?: string;
+	position?: number;
+	version: number;
 	created_at: string;
 	updated_at: string;
+	content_html?: string;
+}
+
+export interface BlockEdit {
+	block_id: string;
+	content: string;
+	version: number;
+	edited_at: string;
+}
+
+export interface BlockReaction {
+	emoji: string;
+	count: number;
+	reactors: string[];
+}
+
+export interface DelegationAuditEntry {
+	id: number;
+	event_type: string;
+	actor_id: string | null;
+	work_item_id: string | null;
+	details: Record<string, unknown>;
+	at: string;
 }
 
+export interface WorkItemComment {
+	id: string;
+	work_item_id: string;
+	author_id: string;
+	text: string;
+	created_at: string;
+}
+
+export interface ActivityEntry {
+	journal: Journal;
+	latest_block: Block | null;
+}
+
+export type JournalRole = 'owner' | 'member' | 'guest';
+
 export interface Participant {
 	id: string;
 	name: string;
 	kind: 'user' | 'agent';
-	status: 'active' | 'idle' | 'away';
+	status: 'active' | 'idle' | 'busy' | 'away';
 	color: string;
 	cursor_block_id?: string;
 	cursor_offset?: number;
+	cursor_anchor?: number;
+	cursor_head?: number;
+	typing: boolean;
 	joined_at: string;
 }
 
@@ -45,7 +98,12 @@ export interface WorkItem {
 		| 'rejected'
 		| 'cancelled';
 	priority: 'low' | 'normal' | 'high' | 'urgent';
+	approver_id?: string;
+	approver_ids?: string[];
+	required_approvals: number;
 	result?: string;
+	due_at?: string;
+	tags?: string[];
 	created_at: string;
 	updated_at: string;
 }
@@ -59,28 +117,137 @@ export interface ApprovalRequest {
 	created_at: string;
 }
 
+export interface ModelInfo {
+	id: string;
+	name: string;
+	context_window: number | null;
+}
+
+export interface DiffHunk {
+	op: 'equal' | 'delete' | 'insert';
+	lines: string[];
+}
+
+export interface ParticipantStats {
+	delegated_out: number;
+	assigned: number;
+	completed: number;
+	declined: number;
+	cancelled: number;
+	pending_approvals: number;
+}
+
+export type ExportFormat = 'markdown' | 'json';
+
+// Delegation events as the server's delegation manager emits them, tagged by `type`
+// (e.g. 'work_accepted', 'approval_requested')
+export interface DelegationEvent {
+	type: string;
+	work_item_id?: string;
+	[field: string]: unknown;
+}
+
+export type ErrorCode =
+	| 'not_found'
+	| 'invalid_message'
+	| 'invalid_kind'
+	| 'name_taken'
+	| 'not_registered'
+	| 'insufficient_capability'
+	| 'not_authorized'
+	| 'invalid_state'
+	| 'conflict'
+	| 'block_not_ready'
+	| 'rate_limited'
+	| 'timeout'
+	| 'message_too_large'
+	| 'internal';
+
 // Client -> Server messages
 export type ClientMessage =
-	| { type: 'submit'; journal_id: string; content: string; session_id?: string }
+	| {
+			type: 'submit';
+			journal_id: string;
+			content: string;
+			session_id?: string;
+			model?: string;
+			system_prompt?: string;
+			idempotency_key?: string;
+	  }
 	| { type: 'create_journal'; title?: string }
-	| { type: 'get_journal'; journal_id: string }
-	| { type: 'list_journals' }
-	| { type: 'fork'; block_id: string; session_id?: string }
-	| { type: 'rerun'; block_id: string; session_id?: string }
+	| { type: 'clone_journal'; journal_id: string; title?: string }
+	| { type: 'get_journal'; journal_id: string; render?: 'html' }
+	| { type: 'get_journal_summary'; journal_id: string }
+	| { type: 'get_block'; block_id: string; render?: 'html' }
+	| { type: 'fetch_block'; block_id: string }
+	| { type: 'get_blocks_page'; journal_id: string; before?: string; limit: number }
+	| { type: 'search_blocks'; query: string; journal_id?: string; limit?: number }
+	| { type: 'diff_blocks'; a: string; b: string }
+	| { type: 'export_branch'; block_id: string; format: ExportFormat }
+	| {
+			type: 'list_journals';
+			include_deleted?: boolean;
+			include_archived?: boolean;
+			query?: string;
+			sort?: 'updated' | 'created' | 'title';
+	  }
+	| { type: 'get_recent_activity'; limit?: number }
+	| { type: 'list_models' }
+	| { type: 'delete_journal'; journal_id: string }
+	| { type: 'rename_journal'; journal_id: string; title: string }
+	| { type: 'archive_journal'; journal_id: string; archived: boolean }
+	| { type: 'set_role'; journal_id: string; participant_id: string; role: JournalRole }
+	| { type: 'fork'; block_id: string; session_id?: string; model?: string }
+	| { type: 'rerun'; block_id: string; session_id?: string; model?: string }
 	| { type: 'cancel'; block_id: string }
-	| { type: 'subscribe'; journal_id: string; name: string; kind?: string }
+	| { type: 'resume_block'; block_id: string }
+	| { type: 'reorder_block'; block_id: string; position: number }
+	| { type: 'delete_block'; block_id: string; cascade?: boolean }
+	| { type: 'edit_block'; block_id: string; content: string }
+	| { type: 'get_block_history'; block_id: string }
+	| { type: 'react'; block_id: string; emoji: string }
+	| { type: 'get_reactions'; block_id: string }
+	| {
+			type: 'subscribe';
+			journal_id: string;
+			name: string;
+			kind?: string;
+			known_participants?: string[];
+			create?: boolean;
+			collapse_observers?: boolean;
+	  }
 	| { type: 'unsubscribe'; journal_id: string }
-	| { type: 'cursor'; journal_id: string; block_id?: string; offset?: number }
+	| {
+			type: 'cursor';
+			journal_id: string;
+			block_id?: string;
+			offset?: number;
+			anchor?: number;
+			head?: number;
+	  }
+	| { type: 'typing'; journal_id: string; typing: boolean }
+	| { type: 'heartbeat'; journal_id: string }
 	| { type: 'get_presence'; journal_id: string }
-	| { type: 'crdt_update'; journal_id: string; update: string }
+	| {
+			type: 'crdt_update';
+			journal_id: string;
+			update: string;
+			seq?: number;
+			final?: boolean;
+	  }
 	| { type: 'sync_request'; journal_id: string; state_vector?: string }
+	| { type: 'crdt_ack'; journal_id: string; seq: number }
+	| { type: 'crdt_resync'; journal_id: string; from_seq: number }
 	| {
 			type: 'register_participant';
 			journal_id: string;
 			name: string;
 			kind?: string;
 			capabilities?: string[];
+			webhook_url?: string;
+			participant_id?: string;
 	  }
+	| { type: 'unregister_participant'; journal_id: string }
 	| {
 			type: 'delegate';
 			journal_id: string;
@@ -90,6 +257,20 @@ export type ClientMessage =
 			priority?: string;
 			requires_approval?: boolean;
 			approver_id?: string;
+			approver_ids?: string[];
+			required_approvals?: number;
+			auto_execute?: boolean;
+			due_at?: string;
+			tags?: string[];
+	  }
+	| {
+			type: 'delegate_many';
+			journal_id: string;
+			description: string;
+			assignee_ids: string[];
+			priority?: string;
+			requires_approval?: boolean;
+			approver_id?: string;
 	  }
 	| { type: 'accept_work'; work_item_id: string }
 	| { type: 'decline_work'; work_item_id: string }
@@ -98,27 +279,80 @@ export type ClientMessage =
 	| { type: 'reject_work'; approval_id: string; feedback: string }
 	| { type: 'cancel_work'; work_item_id: string }
 	| { type: 'claim_work'; work_item_id: string }
-	| { type: 'get_work_queue' }
+	| { type: 'reassign_work'; work_item_id: string; new_assignee_id: string }
+	| { type: 'reassign_approver'; approval_id: string; new_approver_id: string }
+	| { type: 'add_work_comment'; work_item_id: string; text: string }
+	| { type: 'get_work_comments'; work_item_id: string }
+	| { type: 'get_audit_log'; journal_id: string }
+	| { type: 'subscribe_delegation' }
+	| { type: 'get_work_queue'; sorted?: boolean; tag?: string }
+	| { type: 'get_overdue_work' }
+	| { type: 'get_participant_stats'; participant_id: string }
+	| { type: 'get_journal_work'; journal_id: string; include_completed?: boolean }
+	| { type: 'get_unassigned_work'; journal_id: string }
+	| { type: 'get_my_capabilities' }
+	| { type: 'get_participant_capabilities'; participant_id: string }
+	| { type: 'get_work_item'; work_item_id: string }
 	| { type: 'get_approval_queue' }
 	| { type: 'set_accepting_work'; accepting: boolean }
-	| { type: 'get_participants'; journal_id: string };
+	| { type: 'bulk_set_accepting_work'; participant_ids: string[]; accepting: boolean }
+	| { type: 'get_participants'; journal_id: string; required_capability?: string };
 
 // Server -> Client messages
 export type ServerMessage =
 	| { type: 'journal_created'; journal_id: string; title: string }
+	| { type: 'journal_cloned'; journal: Journal }
+	| { type: 'journal_summary'; summary: JournalSummary }
+	| { type: 'journal_updated'; journal: Journal }
 	| { type: 'journal'; journal: Journal; blocks: Block[] }
 	| { type: 'journals'; journals: Journal[] }
+	| { type: 'recent_activity'; entries: ActivityEntry[] }
+	| { type: 'models'; models: ModelInfo[] }
+	| { type: 'journal_deleted'; journal_id: string }
+	| { type: 'journal_renamed'; journal_id: string; title: string }
+	| { type: 'role_changed'; journal_id: string; participant_id: string; role: JournalRole }
 	| { type: 'block_created'; block: Block }
-	| { type: 'block_content_delta'; block_id: string; delta: string }
+	| { type: 'block_content_delta'; block_id: string; delta: string; offset: number }
 	| { type: 'block_status_changed'; block_id: string; status: Block['status'] }
+	| { type: 'queue_position'; journal_id: string; block_id: string; position: number }
 	| { type: 'block_forked'; original_block_id: string; new_block: Block }
 	| { type: 'block_cancelled'; block_id: string }
-	| { type: 'error'; message: string; details?: string }
+	| { type: 'block_deleted'; block_id: string }
+	| { type: 'block_edited'; block: Block }
+	| { type: 'block_usage'; block_id: string; prompt_tokens: number; completion_tokens: number }
+	| {
+			type: 'reaction_changed';
+			block_id: string;
+			emoji: string;
+			count: number;
+			reactors: string[];
+	  }
+	| { type: 'reactions'; block_id: string; reactions: BlockReaction[] }
+	| { type: 'block_history'; block_id: string; edits: BlockEdit[] }
+	| { type: 'block_reordered'; block: Block }
+	| { type: 'block'; block: Block }
+	| { type: 'block_fetched'; block: Block }
+	| { type: 'blocks_page'; blocks: Block[]; has_more: boolean }
+	| { type: 'search_results'; blocks: Block[] }
+	| { type: 'block_diff'; a: string; b: string; hunks: DiffHunk[]; warning?: string }
+	| { type: 'branch_export'; content: string; format: ExportFormat }
+	| { type: 'warning'; message: string }
+	| { type: 'error'; code: ErrorCode; message: string; details?: string }
 	| {
 			type: 'subscribed';
 			journal_id: string;
 			participant: Participant;
+			role: JournalRole;
 			participants: Participant[];
+			observer_count?: number;
+	  }
+	| {
+			type: 'subscribed_diff';
+			journal_id: string;
+			participant: Participant;
+			role: JournalRole;
+			joined: Participant[];
+			left: string[];
 	  }
 	| { type: 'unsubscribed'; journal_id: string }
 	| { type: 'participant_joined'; journal_id: string; participant: Participant }
@@ -129,6 +363,8 @@ export type ServerMessage =
 			participant_id: string;
 			block_id?: string;
 			offset?: number;
+			anchor?: number;
+			head?: number;
 	  }
 	| {
 			type: 'participant_status_changed';
@@ -136,9 +372,22 @@ export type ServerMessage =
 			participant_id: string;
 			status: Participant['status'];
 	  }
-	| { type: 'presence'; journal_id: string; participants: Participant[] }
-	| { type: 'crdt_update'; journal_id: string; source?: string; update: string }
-	| { type: 'sync_state'; journal_id: string; state: string }
+	| { type: 'typing_changed'; journal_id: string; participant_id: string; typing: boolean }
+	| {
+			type: 'presence';
+			journal_id: string;
+			participants: Participant[];
+			observer_count?: number;
+	  }
+	| { type: 'crdt_update'; journal_id: string; source?: string; seq: number; update: string }
+	| {
+			type: 'sync_state';
+			journal_id: string;
+			state: string;
+			/** Present when a large state is split; concatenate decoded chunks until `final` */
+			seq?: number;
+			final?: boolean;
+	  }
 	| {
 			type: 'participant_registered';
 			participant_id: string;
@@ -146,18 +395,52 @@ export type ServerMessage =
 			kind: string;
 			capabilities: string[];
 	  }
+	| { type: 'participant_unregistered'; participant_id: string }
 	| { type: 'work_delegated'; work_item: WorkItem }
+	| { type: 'delegation_subscribed' }
+	| { type: 'delegation_event'; event: DelegationEvent }
+	| { type: 'work_batch_delegated'; work_items: WorkItem[] }
 	| { type: 'work_accepted'; work_item_id: string; assignee_id: string }
 	| { type: 'work_declined'; work_item_id: string; assignee_id: string }
 	| { type: 'approval_requested'; approval: ApprovalRequest; work_item: WorkItem }
+	| { type: 'approval_granted'; approval_id: string; work_item_id: string; approver_id: string }
 	| { type: 'work_approved'; work_item_id: string; approver_id: string; feedback?: string }
 	| { type: 'work_rejected'; work_item_id: string; approver_id: string; feedback: string }
 	| { type: 'work_cancelled'; work_item_id: string; cancelled_by: string }
 	| { type: 'work_claimed'; work_item_id: string; claimed_by: string }
+	| {
+			type: 'work_reassigned';
+			work_item_id: string;
+			previous_assignee_id: string;
+			new_assignee_id: string;
+	  }
+	| { type: 'work_comment_added'; comment: WorkItemComment }
+	| { type: 'work_comments'; work_item_id: string; comments: WorkItemComment[] }
+	| { type: 'audit_log'; journal_id: string; entries: DelegationAuditEntry[] }
+	| {
+			type: 'approver_reassigned';
+			approval_id: string;
+			work_item_id: string;
+			previous_approver_id: string;
+			new_approver_id: string;
+	  }
 	| { type: 'work_queue'; items: WorkItem[] }
+	| { type: 'overdue_work'; items: WorkItem[] }
+	| { type: 'participant_stats'; participant_id: string; stats: ParticipantStats }
+	| { type: 'journal_work'; journal_id: string; items: WorkItem[] }
+	| { type: 'unassigned_work'; journal_id: string; items: WorkItem[] }
+	| { type: 'my_capabilities'; participant_id: string; capabilities: string[] }
+	| { type: 'participant_capabilities'; participant_id: string; capabilities: string[] }
+	| { type: 'work_item'; work_item: WorkItem }
 	| { type: 'approval_queue'; items: ApprovalRequest[] }
 	| {
 			type: 'available_participants';
 			participants: Array<{ id: string; name: string; kind: string; capabilities: string[] }>;
 	  }
-	| { type: 'accepting_work_changed'; participant_id: string; accepting: boolean };
+	| { type: 'accepting_work_changed'; participant_id: string; accepting: boolean }
+	| {
+			type: 'bulk_accepting_work_changed';
+			accepting: boolean;
+			updated: string[];
+			unknown: string[];
+	  };
@@ -2,6 +2,30 @@ import type { ClientMessage, ServerMessage } from './types';
 
 export type MessageHandler = (message: ServerMessage) => void;
 
+// Local storage key for the auth token
+const AUTH_TOKEN_KEY = 'outer_auth_token';
+
+// Token from a `?token=` link to the page, remembered for later visits
+function loadAuthToken(): string | null {
+	try {
+		const params = new URLSearchParams(location.search);
+		const token = params.get('token');
+		if (!token) {
+			return localStorage.getItem(AUTH_TOKEN_KEY);
+		}
+		localStorage.setItem(AUTH_TOKEN_KEY, token);
+		// Keep the token out of the address bar and history
+		params.delete('token');
+		const query = params.toString();
+		const search = query ? `?${query}` : '';
+		history.replaceState(null, '', `${location.pathname}${search}${location.hash}`);
+		return token;
+	} catch (e) {
+		console.error('Failed to load auth token:', e);
+		return null;
+	}
+}
+
 export class WebSocketClient {
 	private ws: WebSocket | null = null;
 	private url: string;
@@ -17,7 +41,12 @@ export class WebSocketClient {
 
 	constructor(url?: string) {
 		// Default to same host, /ws path
-		this.url = url || `${location.protocol === 'https:' ? 'wss:' : 'ws:'}//${location.host}/ws`;
+		const base = url || `${location.protocol === 'https:' ? 'wss:' : 'ws:'}//${location.host}/ws`;
+		// Browsers can't set headers on a websocket, so the token goes in the query
+		const token = loadAuthToken();
+		this.url = token
+			? `${base}${base.includes('?') ? '&' : '?'}token=${encodeURIComponent(token)}`
+			: base;
 	}
 
 	onConnect(cb: () => void) {